@@ -0,0 +1,298 @@
+use axum::extract::State;
+use axum::{Json, extract::Path, http::StatusCode};
+use uuid::Uuid;
+
+use crate::api::routes::Services;
+use crate::domain::api_key::{ApiKey, CreatedApiKey};
+use crate::service::api_key::ApiKeyNew;
+use crate::service::application_error::ApplicationError;
+
+pub async fn api_key_post(
+    State(services): State<Services>,
+    api_key: Json<ApiKeyNew>,
+) -> Result<Json<CreatedApiKey>, ApplicationError> {
+    let new_api_key_dto: ApiKeyNew = api_key.0;
+    let created_api_key = services.api_key_service.create(new_api_key_dto)?;
+    Ok(Json(created_api_key))
+}
+
+pub async fn api_key_list(
+    State(services): State<Services>,
+) -> Result<Json<Vec<ApiKey>>, ApplicationError> {
+    let api_keys = services.api_key_service.list()?;
+    Ok(Json(api_keys))
+}
+
+pub async fn api_key_delete(
+    State(services): State<Services>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApplicationError> {
+    services.api_key_service.revoke(id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::routes::Services;
+    use crate::domain::api_key::ApiKey;
+    use crate::domain::audit::AuditRecord;
+    use crate::service::api_key::ApiKeyNew;
+    use crate::test_util::test_utility::{
+        TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services_with_authentication,
+    };
+    use axum::Router;
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    // the first key is created by the service directly, like the command line does
+    async fn setup_test_app() -> (Router, String) {
+        let services: Services = setup_test_services_with_authentication();
+        let created = services
+            .api_key_service
+            .create(ApiKeyNew {
+                name: String::from("admin"),
+                user: None,
+            })
+            .unwrap_or_else(|err| panic!("Failed to create api key: {}", err.message));
+        let app = crate::api::routes::setup_routing(services).await;
+        (app, created.key)
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+        authorization: Option<&str>,
+    ) -> Response<Body> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(authorization) = authorization {
+            request = request.header("authorization", authorization);
+        }
+        let request = request
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn act_json_request<T: DeserializeOwned>(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+        key: &str,
+    ) -> T {
+        let authorization = format!("ApiKey {key}");
+        let response = arrange_and_act_request(app, method, uri, body, Some(&authorization)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response.")
+    }
+
+    #[tokio::test]
+    async fn requests_without_valid_api_key_are_unauthorized() {
+        let (app, key) = setup_test_app().await;
+        let wrong_scheme = format!("Bearer {key}");
+
+        for authorization in [
+            None,
+            Some("ApiKey exk_unknown"),
+            Some(wrong_scheme.as_str()),
+        ] {
+            let response = arrange_and_act_request(
+                &app,
+                Method::GET,
+                &format!("{TEST_LEDGER_PATH}/expense_types"),
+                None,
+                authorization,
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn api_key_lifecycle() {
+        let (app, admin_key) = setup_test_app().await;
+
+        let created: Value = act_json_request(
+            &app,
+            Method::POST,
+            "/api_keys",
+            Some(json!({"name": "dashboard"})),
+            &admin_key,
+        )
+        .await;
+        let key = created["key"].as_str().unwrap().to_string();
+        let listed: Vec<Value> = act_json_request(&app, Method::GET, "/api_keys", None, &key).await;
+        let uri = format!("/api_keys/{}", created["id"].as_str().unwrap());
+        let authorization = format!("ApiKey {admin_key}");
+        let revoked =
+            arrange_and_act_request(&app, Method::DELETE, &uri, None, Some(&authorization)).await;
+        let authorization = format!("ApiKey {key}");
+        let rejected =
+            arrange_and_act_request(&app, Method::GET, "/api_keys", None, Some(&authorization))
+                .await;
+
+        assert!(key.starts_with(created["prefix"].as_str().unwrap()));
+        assert_eq!(listed.len(), 2);
+        assert!(listed.iter().all(|api_key| api_key.get("key").is_none()));
+        assert!(
+            listed
+                .iter()
+                .all(|api_key| api_key.get("key_hash").is_none())
+        );
+        assert_eq!(revoked.status(), StatusCode::NO_CONTENT);
+        assert_eq!(rejected.status(), StatusCode::UNAUTHORIZED);
+        let api_keys: Vec<ApiKey> =
+            act_json_request(&app, Method::GET, "/api_keys", None, &admin_key).await;
+        assert!(api_keys[1].revoked_at().is_some());
+    }
+
+    #[tokio::test]
+    async fn api_key_create_rejects_empty_name() {
+        let (app, key) = setup_test_app().await;
+        let authorization = format!("ApiKey {key}");
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            "/api_keys",
+            Some(json!({"name": " "})),
+            Some(&authorization),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn api_key_is_recorded_as_actor() {
+        let (app, key) = setup_test_app().await;
+        let authorization = format!("ApiKey {key}");
+        let request = Request::builder()
+            .method(Method::DELETE)
+            .uri(format!(
+                "{TEST_LEDGER_PATH}/expense_entries/{}",
+                TEST_VALID_UUID
+            ))
+            .header("authorization", authorization)
+            // an authenticated request cannot claim to be someone else
+            .header("x-actor", "mallory")
+            .body(Body::empty())
+            .expect("Failed to finalize request.");
+        let response = app
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.");
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/history",
+            TEST_VALID_UUID
+        );
+        let history: Vec<AuditRecord> = act_json_request(&app, Method::GET, &uri, None, &key).await;
+
+        assert_eq!(history.last().unwrap().actor, "api_key:admin");
+    }
+
+    // a key for bob, created by the operator
+    async fn arrange_user_key(app: &Router, admin_key: &str) -> Value {
+        act_json_request(
+            app,
+            Method::POST,
+            "/api_keys",
+            Some(json!({"name": "bob's phone", "user": "bob"})),
+            admin_key,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn api_key_bound_to_user_acts_as_the_user() {
+        let (app, admin_key) = setup_test_app().await;
+        let created = arrange_user_key(&app, &admin_key).await;
+        let authorization = format!("ApiKey {}", created["key"].as_str().unwrap());
+        let expense_types = format!("{TEST_LEDGER_PATH}/expense_types");
+
+        let principal: Value = act_json_request(
+            &app,
+            Method::GET,
+            "/me",
+            None,
+            created["key"].as_str().unwrap(),
+        )
+        .await;
+        assert_eq!(principal["user"], "bob");
+        let response = arrange_and_act_request(
+            &app,
+            Method::GET,
+            &expense_types,
+            None,
+            Some(&authorization),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let _: Value = act_json_request(
+            &app,
+            Method::PUT,
+            &format!("{TEST_LEDGER_PATH}/members/bob"),
+            Some(json!({"role": "viewer"})),
+            &admin_key,
+        )
+        .await;
+        let response = arrange_and_act_request(
+            &app,
+            Method::GET,
+            &expense_types,
+            None,
+            Some(&authorization),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &expense_types,
+            Some(json!({"name": "Groceries", "description": "Food"})),
+            Some(&authorization),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn api_key_management_is_left_to_the_operator() {
+        let (app, admin_key) = setup_test_app().await;
+        let created = arrange_user_key(&app, &admin_key).await;
+        let authorization = format!("ApiKey {}", created["key"].as_str().unwrap());
+        let uri = format!("/api_keys/{}", created["id"].as_str().unwrap());
+
+        for (method, uri, body) in [
+            (Method::POST, "/api_keys", Some(json!({"name": "another"}))),
+            (Method::GET, "/api_keys", None),
+            (Method::DELETE, uri.as_str(), None),
+        ] {
+            let response =
+                arrange_and_act_request(&app, method, uri, body, Some(&authorization)).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+    }
+}
@@ -0,0 +1,491 @@
+use axum::extract::multipart::MultipartError;
+use axum::extract::{Multipart, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::{Json, extract::Path, http::StatusCode};
+use uuid::Uuid;
+
+use crate::api::routes::Services;
+use crate::domain::attachment::Attachment;
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::attachment::AttachmentNew;
+
+impl From<MultipartError> for ApplicationError {
+    fn from(err: MultipartError) -> Self {
+        match err.status() {
+            StatusCode::PAYLOAD_TOO_LARGE => ApplicationError {
+                error_type: ApplicationErrorType::PayloadTooLarge,
+                message: "Upload exceeds the maximum size.".to_string(),
+            },
+            _ => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Malformed multipart upload.".to_string(),
+            },
+        }
+    }
+}
+
+pub async fn attachment_post(
+    State(services): State<Services>,
+    Path((ledger_id, expense_entry_id)): Path<(Uuid, Uuid)>,
+    mut multipart: Multipart,
+) -> Result<Json<Attachment>, ApplicationError> {
+    let max_size = services.attachment_service.max_size();
+
+    while let Some(mut field) = multipart.next_field().await? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = field.file_name().unwrap_or_default().to_string();
+
+        // read the upload chunk wise so oversized files are rejected early
+        let mut content = Vec::new();
+        while let Some(chunk) = field.chunk().await? {
+            if (content.len() + chunk.len()) as u64 > max_size {
+                return Err(ApplicationError {
+                    error_type: ApplicationErrorType::PayloadTooLarge,
+                    message: format!("Upload exceeds the maximum size of {max_size} bytes."),
+                });
+            }
+            content.extend_from_slice(&chunk);
+        }
+
+        let created_attachment = services.attachment_service.create(
+            ledger_id,
+            expense_entry_id,
+            AttachmentNew { file_name, content },
+        )?;
+        return Ok(Json(created_attachment));
+    }
+
+    Err(ApplicationError {
+        error_type: ApplicationErrorType::ValidationFailed,
+        message: "Upload without file content.".to_string(),
+    })
+}
+
+pub async fn attachment_list(
+    State(services): State<Services>,
+    Path((ledger_id, expense_entry_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<Attachment>>, ApplicationError> {
+    let found_attachments = services
+        .attachment_service
+        .list(ledger_id, expense_entry_id)?;
+    Ok(Json(found_attachments))
+}
+
+pub async fn attachment_get(
+    State(services): State<Services>,
+    Path((ledger_id, expense_entry_id, id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<Response, ApplicationError> {
+    let found = services
+        .attachment_service
+        .get(ledger_id, expense_entry_id, id)?;
+
+    // keep the header value plain ascii and free of quotes
+    let file_name: String = found
+        .attachment
+        .file_name()
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect();
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                found.attachment.content_type().to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        found.content,
+    )
+        .into_response())
+}
+
+pub async fn attachment_delete(
+    State(services): State<Services>,
+    Path((ledger_id, expense_entry_id, id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<StatusCode, ApplicationError> {
+    services
+        .attachment_service
+        .delete(ledger_id, expense_entry_id, id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::attachment::Attachment;
+    use crate::test_util::test_utility::{
+        TEST_INVALID_UUID, TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services,
+    };
+    use axum::{
+        Router,
+        body::Body,
+        http::{Method, Request, StatusCode, header},
+        response::Response,
+    };
+    use tower::ServiceExt;
+
+    const BOUNDARY: &str = "expenses-api-test-boundary";
+    const PNG_CONTENT: &[u8] = &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x01, 0x02];
+    const PDF_CONTENT: &[u8] = b"%PDF-1.7 receipt";
+
+    async fn setup_test_app() -> Router {
+        let services = setup_test_services();
+
+        crate::api::routes::setup_routing(services).await
+    }
+
+    fn multipart_body(file_name: &str, content: &[u8]) -> Vec<u8> {
+        let mut body = format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(content);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+        body
+    }
+
+    async fn arrange_and_act_post_request(
+        app: &Router,
+        expense_entry_id: &str,
+        file_name: &str,
+        content: &[u8],
+    ) -> Response<Body> {
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/attachments",
+            expense_entry_id
+        );
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(&uri)
+            .header(
+                "content-type",
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(Body::from(multipart_body(file_name, content)))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn arrange_and_act_request(app: &Router, method: Method, uri: &str) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn parse_attachment(response: Response<Body>) -> Attachment {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into Attachment struct.")
+    }
+
+    #[tokio::test]
+    async fn attachment_post() {
+        let app = setup_test_app().await;
+        let response = arrange_and_act_post_request(
+            &app,
+            &TEST_VALID_UUID.to_string(),
+            "receipt.png",
+            PNG_CONTENT,
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let attachment = parse_attachment(response).await;
+        assert_eq!(attachment.expense_entry_id(), TEST_VALID_UUID);
+        assert_eq!(attachment.file_name(), "receipt.png");
+        assert_eq!(attachment.content_type(), "image/png");
+        assert_eq!(attachment.size(), PNG_CONTENT.len() as u64);
+        assert_eq!(attachment.content_hash().len(), 64);
+    }
+
+    #[tokio::test]
+    async fn attachment_post_sniffs_content_type() {
+        let app = setup_test_app().await;
+        // the extension lies, the content decides
+        let response = arrange_and_act_post_request(
+            &app,
+            &TEST_VALID_UUID.to_string(),
+            "receipt.png",
+            PDF_CONTENT,
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let attachment = parse_attachment(response).await;
+        assert_eq!(attachment.content_type(), "application/pdf");
+    }
+
+    #[tokio::test]
+    async fn attachment_post_fails_unsupported_content_type() {
+        let app = setup_test_app().await;
+        let response = arrange_and_act_post_request(
+            &app,
+            &TEST_VALID_UUID.to_string(),
+            "receipt.txt",
+            b"just some text",
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Upload with unsupported content type.");
+    }
+
+    #[tokio::test]
+    async fn attachment_post_fails_too_large() {
+        let app = setup_test_app().await;
+        let mut content = PDF_CONTENT.to_vec();
+        content.resize(
+            crate::service::attachment::DEFAULT_MAX_ATTACHMENT_SIZE as usize + 1,
+            0,
+        );
+        let response = arrange_and_act_post_request(
+            &app,
+            &TEST_VALID_UUID.to_string(),
+            "receipt.pdf",
+            &content,
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn attachment_post_fails_expense_entry_not_found() {
+        let app = setup_test_app().await;
+        let response = arrange_and_act_post_request(
+            &app,
+            &TEST_INVALID_UUID.to_string(),
+            "receipt.png",
+            PNG_CONTENT,
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Expense entry not found.");
+    }
+
+    #[tokio::test]
+    async fn attachment_get() {
+        let app = setup_test_app().await;
+        let response = arrange_and_act_post_request(
+            &app,
+            &TEST_VALID_UUID.to_string(),
+            "receipt.pdf",
+            PDF_CONTENT,
+        )
+        .await;
+        let attachment = parse_attachment(response).await;
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/attachments/{}",
+            TEST_VALID_UUID,
+            attachment.id()
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"receipt.pdf\""
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        assert_eq!(body.as_ref(), PDF_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn attachment_get_fails_not_found() {
+        let app = setup_test_app().await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/attachments/{}",
+            TEST_VALID_UUID, TEST_INVALID_UUID
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Attachment not found.");
+    }
+
+    #[tokio::test]
+    async fn attachment_list() {
+        let app = setup_test_app().await;
+        let entry_id = TEST_VALID_UUID.to_string();
+        arrange_and_act_post_request(&app, &entry_id, "first.png", PNG_CONTENT).await;
+        arrange_and_act_post_request(&app, &entry_id, "second.pdf", PDF_CONTENT).await;
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/attachments",
+            entry_id
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let attachments: Vec<Attachment> =
+            serde_json::from_slice(&body).expect("Failed to parse response into Attachment list.");
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].file_name(), "first.png");
+        assert_eq!(attachments[1].file_name(), "second.pdf");
+    }
+
+    #[tokio::test]
+    async fn attachment_delete_keeps_deduplicated_content() {
+        let app = setup_test_app().await;
+        let entry_id = TEST_VALID_UUID.to_string();
+        let first = parse_attachment(
+            arrange_and_act_post_request(&app, &entry_id, "a.png", PNG_CONTENT).await,
+        )
+        .await;
+        let second = parse_attachment(
+            arrange_and_act_post_request(&app, &entry_id, "b.png", PNG_CONTENT).await,
+        )
+        .await;
+        assert_eq!(first.content_hash(), second.content_hash());
+
+        let first_uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/attachments/{}",
+            entry_id,
+            first.id()
+        );
+        let response = arrange_and_act_request(&app, Method::DELETE, &first_uri).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = arrange_and_act_request(&app, Method::GET, &first_uri).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // the shared content is still there for the second attachment
+        let second_uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/attachments/{}",
+            entry_id,
+            second.id()
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &second_uri).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        assert_eq!(body.as_ref(), PNG_CONTENT);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn attachment_concurrent_uploads_keep_shared_content() {
+        let app = setup_test_app().await;
+        let entry_id = TEST_VALID_UUID.to_string();
+        let first = parse_attachment(
+            arrange_and_act_post_request(&app, &entry_id, "a.png", PNG_CONTENT).await,
+        )
+        .await;
+        let first_uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/attachments/{}",
+            entry_id,
+            first.id()
+        );
+
+        // the last reference goes away while new ones to the same content come in
+        let delete = {
+            let app = app.clone();
+            tokio::spawn(
+                async move { arrange_and_act_request(&app, Method::DELETE, &first_uri).await },
+            )
+        };
+        let uploads: Vec<_> = (0..8)
+            .map(|index| {
+                let (app, entry_id) = (app.clone(), entry_id.clone());
+                tokio::spawn(async move {
+                    let file_name = format!("{index}.png");
+                    parse_attachment(
+                        arrange_and_act_post_request(&app, &entry_id, &file_name, PNG_CONTENT)
+                            .await,
+                    )
+                    .await
+                })
+            })
+            .collect();
+        let response = delete.await.expect("Delete panicked.");
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        for upload in uploads {
+            let attachment = upload.await.expect("Upload panicked.");
+            let uri = format!(
+                "{TEST_LEDGER_PATH}/expense_entries/{}/attachments/{}",
+                entry_id,
+                attachment.id()
+            );
+            let response = arrange_and_act_request(&app, Method::GET, &uri).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to recieve body from response.");
+            assert_eq!(body.as_ref(), PNG_CONTENT);
+        }
+    }
+
+    #[tokio::test]
+    async fn attachment_delete_with_expense_entry() {
+        let app = setup_test_app().await;
+        let entry_id = TEST_VALID_UUID.to_string();
+        let attachment = parse_attachment(
+            arrange_and_act_post_request(&app, &entry_id, "receipt.png", PNG_CONTENT).await,
+        )
+        .await;
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::DELETE,
+            &format!("{TEST_LEDGER_PATH}/expense_entries/{}", entry_id),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/attachments/{}",
+            entry_id,
+            attachment.id()
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Attachment not found.");
+    }
+}
@@ -0,0 +1,316 @@
+use axum::extract::State;
+use axum::{Json, extract::Path};
+use uuid::Uuid;
+
+use crate::api::routes::Services;
+use crate::domain::audit::{AuditRecord, AuditedEntity};
+use crate::service::application_error::ApplicationError;
+
+async fn history(
+    services: &Services,
+    ledger_id: Uuid,
+    entity: AuditedEntity,
+    id: Uuid,
+) -> Result<Json<Vec<AuditRecord>>, ApplicationError> {
+    let history = services.audit_service.history(ledger_id, entity, id)?;
+    Ok(Json(history))
+}
+
+pub async fn expense_entry_history(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<AuditRecord>>, ApplicationError> {
+    history(&services, ledger_id, AuditedEntity::ExpenseEntry, id).await
+}
+
+pub async fn attachment_history(
+    State(services): State<Services>,
+    Path((ledger_id, _expense_entry_id, attachment_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<Json<Vec<AuditRecord>>, ApplicationError> {
+    history(
+        &services,
+        ledger_id,
+        AuditedEntity::Attachment,
+        attachment_id,
+    )
+    .await
+}
+
+pub async fn cost_bearer_history(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<AuditRecord>>, ApplicationError> {
+    history(&services, ledger_id, AuditedEntity::CostBearer, id).await
+}
+
+pub async fn expense_type_history(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<AuditRecord>>, ApplicationError> {
+    history(&services, ledger_id, AuditedEntity::ExpenseType, id).await
+}
+
+pub async fn budget_history(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<AuditRecord>>, ApplicationError> {
+    history(&services, ledger_id, AuditedEntity::Budget, id).await
+}
+
+pub async fn recurring_expense_history(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<AuditRecord>>, ApplicationError> {
+    history(&services, ledger_id, AuditedEntity::RecurringExpense, id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::audit::{AuditAction, AuditRecord, AuditedEntity};
+    use crate::test_util::test_utility::{TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services};
+    use axum::Router;
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn setup_test_app() -> Router {
+        let services = setup_test_services();
+        crate::api::routes::setup_routing(services).await
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+        actor: Option<&str>,
+    ) -> Response<Body> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(actor) = actor {
+            request = request.header("x-actor", actor);
+        }
+        let request = request
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn act_history_request(app: &Router, uri: &str) -> Vec<AuditRecord> {
+        let response = arrange_and_act_request(app, Method::GET, uri, None, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into AuditRecord structs.")
+    }
+
+    #[tokio::test]
+    async fn expense_entry_history_records_changes() {
+        let app = setup_test_app().await;
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 10.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Hotel"
+            })),
+            Some("alice"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let entry: Value = serde_json::from_slice(&body).expect("Failed to parse expense entry.");
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}",
+            entry["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 42.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Hotel"
+            })),
+            Some("bob"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let history = act_history_request(&app, &format!("{}/history", uri)).await;
+
+        assert_eq!(history.len(), 3);
+        assert!(history.iter().all(|record| {
+            record.entity == AuditedEntity::ExpenseEntry
+                && record.entity_id.to_string() == entry["id"]
+        }));
+        assert_eq!(history[0].action, AuditAction::Created);
+        assert_eq!(history[0].actor, "alice");
+        assert!(history[0].before.is_none());
+        assert_eq!(history[0].after.as_ref().unwrap()["description"], "Hotel");
+        assert_eq!(history[1].action, AuditAction::Updated);
+        assert_eq!(history[1].actor, "bob");
+        assert_eq!(
+            history[1].before.as_ref().unwrap()["cost_shares"][0]["amount"],
+            10.0
+        );
+        assert_eq!(
+            history[1].after.as_ref().unwrap()["cost_shares"][0]["amount"],
+            42.0
+        );
+        assert_eq!(history[2].action, AuditAction::Deleted);
+        assert_eq!(history[2].actor, "anonymous");
+        assert!(history[2].after.is_none());
+        assert_ne!(history[0].request_id, history[1].request_id);
+    }
+
+    #[tokio::test]
+    async fn reassigning_delete_records_moved_entries() {
+        let app = setup_test_app().await;
+        let mut expense_type_ids = Vec::new();
+        for name in ["Travel", "Business Travel"] {
+            let response = arrange_and_act_request(
+                &app,
+                Method::POST,
+                &format!("{TEST_LEDGER_PATH}/expense_types"),
+                Some(json!({"name": name, "description": name})),
+                None,
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to receive body from response.");
+            let expense_type: Value =
+                serde_json::from_slice(&body).expect("Failed to parse expense type.");
+            expense_type_ids.push(expense_type["id"].as_str().unwrap().to_string());
+        }
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 10.0}],
+                "expense_type": expense_type_ids[0],
+                "description": "Train"
+            })),
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let entry: Value = serde_json::from_slice(&body).expect("Failed to parse expense entry.");
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::DELETE,
+            &format!(
+                "{TEST_LEDGER_PATH}/expense_types/{}?reassign_to={}",
+                expense_type_ids[0], expense_type_ids[1]
+            ),
+            None,
+            Some("alice"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let history = act_history_request(
+            &app,
+            &format!(
+                "{TEST_LEDGER_PATH}/expense_entries/{}/history",
+                entry["id"].as_str().unwrap()
+            ),
+        )
+        .await;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].action, AuditAction::Updated);
+        assert_eq!(history[1].actor, "alice");
+        assert_eq!(
+            history[1].before.as_ref().unwrap()["expense_type"],
+            expense_type_ids[0]
+        );
+        assert_eq!(
+            history[1].after.as_ref().unwrap()["expense_type"],
+            expense_type_ids[1]
+        );
+        let expense_type_history = act_history_request(
+            &app,
+            &format!(
+                "{TEST_LEDGER_PATH}/expense_types/{}/history",
+                expense_type_ids[0]
+            ),
+        )
+        .await;
+        assert_eq!(expense_type_history.len(), 2);
+        assert_eq!(expense_type_history[1].action, AuditAction::Deleted);
+        assert_eq!(expense_type_history[1].request_id, history[1].request_id);
+    }
+
+    #[tokio::test]
+    async fn history_uses_request_id_header() {
+        let app = setup_test_app().await;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("{TEST_LEDGER_PATH}/cost_bearers"))
+            .header("content-type", "application/json")
+            .header("x-request-id", "import-42")
+            .body(Body::from(
+                json!({"name": "Savings Account", "exists_from": "2025-01-01T00:00:00Z"})
+                    .to_string(),
+            ))
+            .expect("Failed to finalize request.");
+
+        let response = app
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-request-id"], "import-42");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let cost_bearer: Value =
+            serde_json::from_slice(&body).expect("Failed to parse cost bearer.");
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/cost_bearers/{}/history",
+            cost_bearer["id"].as_str().unwrap()
+        );
+        let history = act_history_request(&app, &uri).await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].request_id, "import-42");
+        assert_eq!(history[0].action, AuditAction::Created);
+    }
+
+    #[tokio::test]
+    async fn history_empty_unknown_entity() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/budgets/{}/history", Uuid::new_v4());
+
+        let history = act_history_request(&app, &uri).await;
+
+        assert!(history.is_empty());
+    }
+}
@@ -0,0 +1,305 @@
+use axum::Json;
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::request::Parts;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::api::routes::Services;
+use crate::domain::principal::Principal;
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::request_context::RequestContext;
+
+// keys are sent as `Authorization: ApiKey <key>`
+const API_KEY_SCHEME: &str = "ApiKey ";
+
+// tokens of the sso are sent as `Authorization: Bearer <token>`
+const BEARER_SCHEME: &str = "Bearer ";
+
+// lets only requests with an active api key or a valid bearer token through, changes they make
+// are recorded as done by the principal instead of the unverified actor header
+pub async fn authenticate(
+    State(services): State<Services>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !services.api_key_service.is_enforced() {
+        return next.run(request).await;
+    }
+    let authorization = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok());
+    let principal = match authorization {
+        Some(value) if value.starts_with(BEARER_SCHEME) => services
+            .token_service
+            .authenticate(value.strip_prefix(BEARER_SCHEME).map(str::trim)),
+        value => services
+            .api_key_service
+            .authenticate(
+                value
+                    .and_then(|value| value.strip_prefix(API_KEY_SCHEME))
+                    .map(str::trim),
+            )
+            .map(|api_key| Principal::api_key(api_key.id(), api_key.name(), api_key.user())),
+    };
+    match principal {
+        Ok(principal) => {
+            let context = RequestContext::current().authenticated(principal);
+            context.scope(next.run(request)).await
+        }
+        Err(err) => err.into_response(),
+    }
+}
+
+// handlers that need to know who sent the request take the principal as an argument
+impl<S: Send + Sync> FromRequestParts<S> for Principal {
+    type Rejection = ApplicationError;
+
+    async fn from_request_parts(_parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        RequestContext::current()
+            .principal
+            .ok_or_else(|| ApplicationError {
+                error_type: ApplicationErrorType::Unauthorized,
+                message: String::from("Request is not authenticated."),
+            })
+    }
+}
+
+pub async fn principal_get(principal: Principal) -> Json<Principal> {
+    Json(principal)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::routes::Services;
+    use crate::domain::audit::AuditRecord;
+    use crate::domain::ledger_member::LedgerRole;
+    use crate::domain::principal::{Principal, PrincipalKind};
+    use crate::service::api_key::ApiKeyNew;
+    use crate::service::ledger::LedgerMemberNew;
+    use crate::test_util::test_utility::{
+        TEST_ED25519_KEY_ID, TEST_ED25519_PRIVATE_KEY, TEST_JWT_AUDIENCE, TEST_JWT_ISSUER,
+        TEST_JWT_SECRET, TEST_LEDGER_ID, TEST_LEDGER_PATH, TEST_RSA_KEY_ID, TEST_RSA_PRIVATE_KEY,
+        TEST_VALID_UUID, setup_test_services_with_authentication,
+    };
+    use axum::Router;
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    async fn setup_test_app() -> (Router, Services) {
+        let services: Services = setup_test_services_with_authentication();
+        (
+            crate::api::routes::setup_routing(services.clone()).await,
+            services,
+        )
+    }
+
+    // claims of a token the test services accept, valid for the next five minutes
+    fn valid_claims(subject: &str) -> Value {
+        json!({
+            "sub": subject,
+            "iss": TEST_JWT_ISSUER,
+            "aud": TEST_JWT_AUDIENCE,
+            "exp": chrono::Utc::now().timestamp() + 300,
+        })
+    }
+
+    fn hs256_token(claims: &Value, secret: &str) -> String {
+        encode(
+            &Header::new(Algorithm::HS256),
+            claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .expect("Failed to sign token.")
+    }
+
+    fn rs256_token(claims: &Value) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(String::from(TEST_RSA_KEY_ID));
+        let key = EncodingKey::from_rsa_pem(TEST_RSA_PRIVATE_KEY.as_bytes())
+            .expect("Failed to read test rsa key.");
+        encode(&header, claims, &key).expect("Failed to sign token.")
+    }
+
+    fn ed25519_token(claims: &Value) -> String {
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(String::from(TEST_ED25519_KEY_ID));
+        let key = EncodingKey::from_ed_pem(TEST_ED25519_PRIVATE_KEY.as_bytes())
+            .expect("Failed to read test ed25519 key.");
+        encode(&header, claims, &key).expect("Failed to sign token.")
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        authorization: &str,
+    ) -> Response {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", authorization)
+            .body(Body::empty())
+            .expect("Failed to finalize request.");
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn act_json_request<T: DeserializeOwned>(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        authorization: &str,
+    ) -> T {
+        let response = arrange_and_act_request(app, method, uri, authorization).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read response body.");
+        serde_json::from_slice(&body).expect("Failed to parse response body.")
+    }
+
+    #[tokio::test]
+    async fn bearer_tokens_authenticate_users() {
+        let (app, _) = setup_test_app().await;
+        let claims = valid_claims("alice");
+
+        for token in [
+            hs256_token(&claims, TEST_JWT_SECRET),
+            rs256_token(&claims),
+            ed25519_token(&claims),
+        ] {
+            let authorization = format!("Bearer {token}");
+            let principal: Principal =
+                act_json_request(&app, Method::GET, "/me", &authorization).await;
+
+            assert_eq!(principal.kind(), PrincipalKind::User);
+            assert_eq!(principal.subject(), "alice");
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_bearer_tokens_are_unauthorized() {
+        let (app, _) = setup_test_app().await;
+        let expired = json!({
+            "sub": "alice",
+            "iss": TEST_JWT_ISSUER,
+            "aud": TEST_JWT_AUDIENCE,
+            "exp": chrono::Utc::now().timestamp() - 3600,
+        });
+        let mut foreign_audience = valid_claims("alice");
+        foreign_audience["aud"] = json!("another_api");
+        let mut foreign_issuer = valid_claims("alice");
+        foreign_issuer["iss"] = json!("https://sso.example.org");
+        let mut without_expiry = valid_claims("alice");
+        without_expiry.as_object_mut().unwrap().remove("exp");
+        let mut unknown_key = Header::new(Algorithm::RS256);
+        unknown_key.kid = Some(String::from("rotated-away"));
+        let unknown_key = encode(
+            &unknown_key,
+            &valid_claims("alice"),
+            &EncodingKey::from_rsa_pem(TEST_RSA_PRIVATE_KEY.as_bytes()).unwrap(),
+        )
+        .unwrap();
+        let unsupported_algorithm = encode(
+            &Header::new(Algorithm::HS512),
+            &valid_claims("alice"),
+            &EncodingKey::from_secret(TEST_JWT_SECRET.as_bytes()),
+        )
+        .unwrap();
+
+        for token in [
+            String::from("not-a-token"),
+            hs256_token(&valid_claims("alice"), "wrong-secret"),
+            hs256_token(&expired, TEST_JWT_SECRET),
+            hs256_token(&foreign_audience, TEST_JWT_SECRET),
+            rs256_token(&foreign_issuer),
+            ed25519_token(&without_expiry),
+            unknown_key,
+            unsupported_algorithm,
+        ] {
+            let authorization = format!("Bearer {token}");
+            let response = arrange_and_act_request(
+                &app,
+                Method::GET,
+                &format!("{TEST_LEDGER_PATH}/expense_types"),
+                &authorization,
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn api_keys_authenticate_as_principal() {
+        let (app, services) = setup_test_app().await;
+        let created = services
+            .api_key_service
+            .create(ApiKeyNew {
+                name: String::from("dashboard"),
+                user: None,
+            })
+            .unwrap_or_else(|err| panic!("Failed to create api key: {}", err.message));
+        let authorization = format!("ApiKey {}", created.key);
+
+        let principal: Principal = act_json_request(&app, Method::GET, "/me", &authorization).await;
+
+        assert_eq!(principal.kind(), PrincipalKind::ApiKey);
+        assert_eq!(principal.subject(), "dashboard");
+    }
+
+    #[tokio::test]
+    async fn token_user_is_recorded_as_actor() {
+        let (app, services) = setup_test_app().await;
+        services
+            .ledger_service
+            .set_member(
+                TEST_LEDGER_ID,
+                String::from("alice"),
+                LedgerMemberNew {
+                    role: LedgerRole::Owner,
+                },
+            )
+            .unwrap_or_else(|_| panic!("Failed to add ledger member."));
+        let authorization = format!("Bearer {}", ed25519_token(&valid_claims("alice")));
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, &authorization).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/history",
+            TEST_VALID_UUID
+        );
+        let history: Vec<AuditRecord> =
+            act_json_request(&app, Method::GET, &uri, &authorization).await;
+
+        assert_eq!(history.last().unwrap().actor, "user:alice");
+    }
+
+    #[tokio::test]
+    async fn token_users_cannot_manage_api_keys() {
+        let (app, _) = setup_test_app().await;
+        let authorization = format!(
+            "Bearer {}",
+            hs256_token(&valid_claims("alice"), TEST_JWT_SECRET)
+        );
+
+        let revoke = format!("/api_keys/{}", TEST_VALID_UUID);
+        for (method, uri) in [
+            (Method::GET, "/api_keys"),
+            (Method::DELETE, revoke.as_str()),
+        ] {
+            let response = arrange_and_act_request(&app, method, uri, &authorization).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+    }
+}
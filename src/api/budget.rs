@@ -0,0 +1,505 @@
+use axum::extract::{Query, State};
+use axum::{Json, extract::Path, http::StatusCode};
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::api::routes::Services;
+use crate::domain::budget::{Budget, BudgetStatus};
+use crate::service::application_error::ApplicationError;
+use crate::service::budget::BudgetNew;
+
+#[derive(serde::Deserialize)]
+pub struct BudgetStatusQuery {
+    date: Option<NaiveDate>,
+}
+
+pub async fn budget_post(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    budget: Json<BudgetNew>,
+) -> Result<Json<Budget>, ApplicationError> {
+    let new_budget_dto: BudgetNew = budget.0;
+    let created_budget = services.budget_service.create(ledger_id, new_budget_dto)?;
+    Ok(Json(created_budget))
+}
+
+pub async fn budget_delete(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApplicationError> {
+    services.budget_service.delete(ledger_id, id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn budget_restore(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Budget>, ApplicationError> {
+    let restored_budget = services.budget_service.restore(ledger_id, id)?;
+    Ok(Json(restored_budget))
+}
+
+pub async fn budget_get(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Budget>, ApplicationError> {
+    let found_budget = services.budget_service.get(ledger_id, id)?;
+    Ok(Json(found_budget))
+}
+
+pub async fn budget_status_get(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<BudgetStatusQuery>,
+) -> Result<Json<BudgetStatus>, ApplicationError> {
+    let status = services.budget_service.status(
+        ledger_id,
+        id,
+        query.date.unwrap_or_else(|| Utc::now().date_naive()),
+    )?;
+    Ok(Json(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::api::routes::Services;
+    use crate::domain::budget::{
+        Budget, BudgetAlert, BudgetPeriod, BudgetState, BudgetStatus, BudgetThreshold,
+    };
+    use crate::test_util::test_utility::{
+        TEST_INVALID_UUID, TEST_LEDGER_PATH, setup_test_services,
+        setup_test_services_with_alert_log,
+    };
+    use axum::{
+        Router,
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use chrono::{NaiveDate, Utc};
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn setup_test_app() -> Router {
+        let services = setup_test_services();
+
+        crate::api::routes::setup_routing(services).await
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn arrange_and_act_post_request(app: &Router, body: Value) -> Budget {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/budgets"),
+            Some(body),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into Budget.")
+    }
+
+    async fn arrange_and_act_status_request(app: &Router, id: Uuid, date: &str) -> BudgetStatus {
+        let uri = format!("{TEST_LEDGER_PATH}/budgets/{}/status?date={}", id, date);
+        let response = arrange_and_act_request(app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into BudgetStatus.")
+    }
+
+    async fn arrange_expense_entry(
+        app: &Router,
+        expense_type: Uuid,
+        expense_date: &str,
+        cost_shares: Value,
+    ) {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": cost_shares,
+                "expense_type": expense_type,
+                "description": "Groceries",
+                "expense_date": format!("{}T12:00:00Z", expense_date)
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    async fn arrange_expense_type(app: &Router, name: &str) -> Uuid {
+        arrange_entity(
+            app,
+            "expense_types",
+            json!({"name": name, "description": name}),
+        )
+        .await
+    }
+
+    async fn arrange_cost_bearer(app: &Router, name: &str) -> Uuid {
+        arrange_entity(
+            app,
+            "cost_bearers",
+            json!({"name": name, "exists_from": "2025-01-01T00:00:00Z"}),
+        )
+        .await
+    }
+
+    async fn arrange_entity(app: &Router, collection: &str, body: Value) -> Uuid {
+        let uri = format!("{TEST_LEDGER_PATH}/{collection}");
+        let response = arrange_and_act_request(app, Method::POST, &uri, Some(body)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let entity: Value = serde_json::from_slice(&body).unwrap();
+        Uuid::parse_str(entity["id"].as_str().unwrap()).unwrap()
+    }
+
+    async fn arrange_and_act_failing_post_request(app: &Router, body: Value) -> String {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/budgets"),
+            Some(body),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    // alerts are handed to the notifier by the outbox
+    fn read_alerts(services: &Services, alert_log: &Path) -> Vec<BudgetAlert> {
+        services
+            .outbox_service
+            .dispatch(Utc::now())
+            .unwrap_or_else(|err| panic!("Dispatching failed: {}", err.message));
+        std::fs::read_to_string(alert_log)
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("Failed to parse alert log line."))
+            .collect()
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn budget_post() {
+        let app = setup_test_app().await;
+        let expense_type = arrange_expense_type(&app, "Groceries").await;
+        let budget = arrange_and_act_post_request(
+            &app,
+            json!({"expense_type": expense_type, "period": "monthly", "limit": 400.0}),
+        )
+        .await;
+
+        assert_eq!(budget.expense_type(), expense_type);
+        assert_eq!(budget.cost_bearer_id(), None);
+        assert_eq!(budget.period(), BudgetPeriod::Monthly);
+        assert_eq!(budget.limit(), 400.0);
+
+        let uri = format!("{TEST_LEDGER_PATH}/budgets/{}", budget.id());
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn budget_post_fails_invalid_limit() {
+        let app = setup_test_app().await;
+        let error_message = arrange_and_act_failing_post_request(
+            &app,
+            json!({"expense_type": Uuid::new_v4(), "period": "weekly", "limit": 0.0}),
+        )
+        .await;
+        assert_eq!(error_message, "Json without valid limit.");
+    }
+
+    #[tokio::test]
+    async fn budget_post_fails_invalid_expense_type() {
+        let app = setup_test_app().await;
+        let error_message = arrange_and_act_failing_post_request(
+            &app,
+            json!({"expense_type": Uuid::nil(), "period": "yearly", "limit": 10.0}),
+        )
+        .await;
+        assert_eq!(error_message, "Json without valid expense id.");
+    }
+
+    #[tokio::test]
+    async fn budget_post_fails_unknown_expense_type() {
+        let app = setup_test_app().await;
+        let error_message = arrange_and_act_failing_post_request(
+            &app,
+            json!({"expense_type": Uuid::new_v4(), "period": "yearly", "limit": 10.0}),
+        )
+        .await;
+        assert_eq!(error_message, "Json without valid expense id.");
+    }
+
+    #[tokio::test]
+    async fn budget_get_fails_not_found() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/budgets/{}/status", TEST_INVALID_UUID);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Budget not found.");
+    }
+
+    #[tokio::test]
+    async fn budget_status() {
+        let app = setup_test_app().await;
+        let expense_type = arrange_expense_type(&app, "Groceries").await;
+        let (alice, bob) = (
+            arrange_cost_bearer(&app, "Alice").await,
+            arrange_cost_bearer(&app, "Bob").await,
+        );
+
+        arrange_expense_entry(
+            &app,
+            expense_type,
+            "2026-03-02",
+            json!([{"cost_bearer_id": alice, "amount": 30.0}, {"cost_bearer_id": bob, "amount": 10.0}]),
+        )
+        .await;
+        arrange_expense_entry(
+            &app,
+            expense_type,
+            "2026-03-05",
+            json!([{"cost_bearer_id": alice, "amount": 20.0}]),
+        )
+        .await;
+        // neither the previous month nor other expense types count
+        arrange_expense_entry(
+            &app,
+            expense_type,
+            "2026-02-28",
+            json!([{"cost_bearer_id": alice, "amount": 50.0}]),
+        )
+        .await;
+        arrange_expense_entry(
+            &app,
+            arrange_expense_type(&app, "Travel").await,
+            "2026-03-03",
+            json!([{"cost_bearer_id": alice, "amount": 99.0}]),
+        )
+        .await;
+
+        let budget = arrange_and_act_post_request(
+            &app,
+            json!({"expense_type": expense_type, "period": "monthly", "limit": 100.0}),
+        )
+        .await;
+        let status = arrange_and_act_status_request(&app, budget.id(), "2026-03-10").await;
+
+        assert_eq!(status.period_start, date("2026-03-01"));
+        assert_eq!(status.period_end, date("2026-03-31"));
+        assert_eq!(status.spent, 60.0);
+        assert_eq!(status.remaining, 40.0);
+        // 60 spent in 10 of 31 days
+        assert_eq!(status.projected, 186.0);
+        assert_eq!(status.state, BudgetState::AtRisk);
+
+        let status = arrange_and_act_status_request(&app, budget.id(), "2026-03-31").await;
+        assert_eq!(status.projected, 60.0);
+        assert_eq!(status.state, BudgetState::OnTrack);
+
+        // a cost bearer budget only counts that bearer's shares
+        let budget = arrange_and_act_post_request(
+            &app,
+            json!({"expense_type": expense_type, "cost_bearer_id": alice, "period": "monthly", "limit": 40.0}),
+        )
+        .await;
+        let status = arrange_and_act_status_request(&app, budget.id(), "2026-03-10").await;
+        assert_eq!(status.spent, 50.0);
+        assert_eq!(status.remaining, -10.0);
+        assert_eq!(status.state, BudgetState::Overspent);
+    }
+
+    #[tokio::test]
+    async fn budget_status_weekly_period() {
+        let app = setup_test_app().await;
+        let expense_type = arrange_expense_type(&app, "Groceries").await;
+        let budget = arrange_and_act_post_request(
+            &app,
+            json!({"expense_type": expense_type, "period": "weekly", "limit": 50.0}),
+        )
+        .await;
+
+        // 2026-03-04 is a wednesday, weeks start on monday
+        let status = arrange_and_act_status_request(&app, budget.id(), "2026-03-04").await;
+        assert_eq!(status.period_start, date("2026-03-02"));
+        assert_eq!(status.period_end, date("2026-03-08"));
+        assert_eq!(status.spent, 0.0);
+        assert_eq!(status.state, BudgetState::OnTrack);
+    }
+
+    #[tokio::test]
+    async fn budget_delete() {
+        let app = setup_test_app().await;
+        let expense_type = arrange_expense_type(&app, "Travel").await;
+        let budget = arrange_and_act_post_request(
+            &app,
+            json!({"expense_type": expense_type, "period": "yearly", "limit": 1200.0}),
+        )
+        .await;
+
+        let uri = format!("{TEST_LEDGER_PATH}/budgets/{}", budget.id());
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn budget_alerts_fire_once_per_threshold_and_period() {
+        let (services, alert_log) = setup_test_services_with_alert_log();
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        let expense_type = arrange_expense_type(&app, "Groceries").await;
+        let cost_bearer_id = arrange_cost_bearer(&app, "Alice").await;
+        let budget = arrange_and_act_post_request(
+            &app,
+            json!({"expense_type": expense_type, "period": "monthly", "limit": 100.0}),
+        )
+        .await;
+
+        for (expense_date, amount) in [
+            ("2026-03-02", 50.0),
+            ("2026-03-05", 35.0),
+            ("2026-03-09", 10.0),
+            ("2026-03-12", 10.0),
+            ("2026-03-20", 5.0),
+        ] {
+            arrange_expense_entry(
+                &app,
+                expense_type,
+                expense_date,
+                json!([{"cost_bearer_id": cost_bearer_id, "amount": amount}]),
+            )
+            .await;
+        }
+        // nothing is sent while the requests are answered
+        assert!(
+            std::fs::read_to_string(&alert_log)
+                .unwrap_or_default()
+                .is_empty()
+        );
+
+        let alerts = read_alerts(&services, &alert_log);
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].budget_id, budget.id());
+        assert_eq!(alerts[0].threshold, BudgetThreshold::Warning);
+        assert_eq!(alerts[0].spent, 85.0);
+        assert_eq!(alerts[1].threshold, BudgetThreshold::Exceeded);
+        assert_eq!(alerts[1].percent, 100);
+        assert_eq!(alerts[1].spent, 105.0);
+
+        // a new period starts over
+        arrange_expense_entry(
+            &app,
+            expense_type,
+            "2026-04-01",
+            json!([{"cost_bearer_id": cost_bearer_id, "amount": 90.0}]),
+        )
+        .await;
+        let alerts = read_alerts(&services, &alert_log);
+        assert_eq!(alerts.len(), 3);
+        assert_eq!(alerts[2].threshold, BudgetThreshold::Warning);
+        assert_eq!(alerts[2].period_start, date("2026-04-01"));
+    }
+
+    #[tokio::test]
+    async fn budget_alerts_on_expense_entry_update() {
+        let (services, alert_log) = setup_test_services_with_alert_log();
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        let expense_type = arrange_expense_type(&app, "Groceries").await;
+        let cost_bearer_id = arrange_cost_bearer(&app, "Alice").await;
+        arrange_and_act_post_request(
+            &app,
+            json!({"expense_type": expense_type, "period": "yearly", "limit": 1000.0}),
+        )
+        .await;
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": cost_bearer_id, "amount": 100.0}],
+                "expense_type": expense_type,
+                "description": "Flight",
+                "expense_date": "2026-06-01T12:00:00Z"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let entry: Value = serde_json::from_slice(&body).unwrap();
+        assert!(read_alerts(&services, &alert_log).is_empty());
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}",
+            entry["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": cost_bearer_id, "amount": 1100.0}],
+                "expense_type": expense_type,
+                "description": "Flight, business class"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let thresholds: Vec<BudgetThreshold> = read_alerts(&services, &alert_log)
+            .iter()
+            .map(|alert| alert.threshold)
+            .collect();
+        assert_eq!(
+            thresholds,
+            vec![BudgetThreshold::Warning, BudgetThreshold::Exceeded]
+        );
+    }
+}
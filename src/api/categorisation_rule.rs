@@ -0,0 +1,451 @@
+use axum::extract::{Query, State};
+use axum::{Json, extract::Path, http::StatusCode};
+use uuid::Uuid;
+
+use crate::api::expense_entry::ExpenseEntryQuery;
+use crate::api::routes::Services;
+use crate::domain::categorisation_rule::CategorisationRule;
+use crate::service::application_error::ApplicationError;
+use crate::service::categorisation_rule::CategorisationRuleNew;
+use crate::service::expense_entry::{Categorisation, ExpenseEntryFilter};
+
+pub async fn categorisation_rule_post(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    rule: Json<CategorisationRuleNew>,
+) -> Result<Json<CategorisationRule>, ApplicationError> {
+    let created_rule = services
+        .categorisation_rule_service
+        .create(ledger_id, rule.0)?;
+    Ok(Json(created_rule))
+}
+
+pub async fn categorisation_rule_list(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+) -> Result<Json<Vec<CategorisationRule>>, ApplicationError> {
+    let found_rules = services.categorisation_rule_service.list(ledger_id)?;
+    Ok(Json(found_rules))
+}
+
+pub async fn categorisation_rule_get(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<CategorisationRule>, ApplicationError> {
+    let found_rule = services.categorisation_rule_service.get(ledger_id, id)?;
+    Ok(Json(found_rule))
+}
+
+pub async fn categorisation_rule_update(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+    rule: Json<CategorisationRuleNew>,
+) -> Result<Json<CategorisationRule>, ApplicationError> {
+    let updated_rule = services
+        .categorisation_rule_service
+        .update(ledger_id, id, rule.0)?;
+    Ok(Json(updated_rule))
+}
+
+pub async fn categorisation_rule_delete(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApplicationError> {
+    services.categorisation_rule_service.delete(ledger_id, id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// the entries matching the filter the rules would change, nothing is written
+pub async fn categorisation_rule_dry_run(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    Query(query): Query<ExpenseEntryQuery>,
+) -> Result<Json<Vec<Categorisation>>, ApplicationError> {
+    let filter = ExpenseEntryFilter::try_from(query)?;
+    let categorisations = services
+        .expense_entry_service
+        .categorisations(ledger_id, &filter)?;
+    Ok(Json(categorisations))
+}
+
+pub async fn categorisation_rule_apply(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    Query(query): Query<ExpenseEntryQuery>,
+) -> Result<Json<Vec<Categorisation>>, ApplicationError> {
+    let filter = ExpenseEntryFilter::try_from(query)?;
+    let categorisations = services
+        .expense_entry_service
+        .categorise(ledger_id, &filter)?;
+    Ok(Json(categorisations))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::categorisation_rule::CategorisationRule;
+    use crate::domain::expense_entry::ExpenseEntry;
+    use crate::test_util::test_utility::{
+        TEST_INVALID_UUID, TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services,
+    };
+    use axum::{
+        Router,
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn setup_test_app() -> Router {
+        let services = setup_test_services();
+
+        crate::api::routes::setup_routing(services).await
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn read_body(response: Response<Body>) -> Vec<u8> {
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.")
+            .to_vec()
+    }
+
+    async fn arrange_rule(app: &Router, body: Value) -> CategorisationRule {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/rules"),
+            Some(body),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into CategorisationRule struct.")
+    }
+
+    async fn arrange_entry(app: &Router, amount: f64, description: &str) -> ExpenseEntry {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries?allow_duplicate=true"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": amount}],
+                "expense_type": TEST_VALID_UUID,
+                "description": description
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into ExpenseEntry struct.")
+    }
+
+    async fn arrange_expense_type(app: &Router, name: &str) -> Uuid {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_types"),
+            Some(json!({"name": name, "description": name})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let expense_type: Value = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into json value.");
+        Uuid::parse_str(expense_type["id"].as_str().unwrap()).unwrap()
+    }
+
+    async fn act_get_entry(app: &Router, id: Uuid) -> ExpenseEntry {
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{id}");
+        let response = arrange_and_act_request(app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into ExpenseEntry struct.")
+    }
+
+    #[tokio::test]
+    async fn categorisation_rule_post_get_and_list() {
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries").await;
+        let rule = arrange_rule(
+            &app,
+            json!({
+                "name": "Supermarkets",
+                "priority": 2,
+                "description_pattern": "(?i)rewe|edeka",
+                "set_expense_type": groceries,
+                "add_tags": ["Food", "food"]
+            }),
+        )
+        .await;
+        assert_eq!(rule.name(), "Supermarkets");
+        assert_eq!(rule.set_expense_type(), Some(groceries));
+        assert_eq!(rule.add_tags().len(), 1);
+        arrange_rule(
+            &app,
+            json!({"name": "Large", "priority": 1, "min_amount": 500.0, "add_tags": ["large"]}),
+        )
+        .await;
+
+        let uri = format!("{TEST_LEDGER_PATH}/rules/{}", rule.id());
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let uri = format!("{TEST_LEDGER_PATH}/rules");
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let rules: Vec<CategorisationRule> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into CategorisationRule structs.");
+        let names: Vec<&str> = rules.iter().map(|rule| rule.name()).collect();
+        assert_eq!(names, vec!["Large", "Supermarkets"]);
+    }
+
+    #[tokio::test]
+    async fn categorisation_rule_post_invalid() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/rules");
+        for (body, message) in [
+            (
+                json!({"name": "Broken", "description_pattern": "(rewe", "add_tags": ["food"]}),
+                "Json without valid description pattern.",
+            ),
+            (
+                // compiles to more than the size limit allows
+                json!({"name": "Huge", "description_pattern": "\\w{1000}", "add_tags": ["food"]}),
+                "Json without valid description pattern.",
+            ),
+            (
+                json!({"name": "Everything", "add_tags": ["food"]}),
+                "Json without valid conditions.",
+            ),
+            (
+                json!({"name": "Nothing", "description_pattern": "rewe"}),
+                "Json without valid actions.",
+            ),
+            (
+                json!({"name": "Range", "min_amount": 10.0, "max_amount": 5.0, "add_tags": ["food"]}),
+                "Json without valid amount range.",
+            ),
+        ] {
+            let response = arrange_and_act_request(&app, Method::POST, &uri, Some(body)).await;
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            let body = read_body(response).await;
+            assert_eq!(String::from_utf8(body).unwrap(), message);
+        }
+    }
+
+    #[tokio::test]
+    async fn categorisation_rule_post_fails_unknown_expense_type() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/rules");
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(json!({
+                "name": "Fuel",
+                "description_pattern": "(?i)shell|aral",
+                "set_expense_type": Uuid::new_v4()
+            })),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = read_body(response).await;
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "Json without valid expense id."
+        );
+    }
+
+    #[tokio::test]
+    async fn categorisation_rule_update_and_delete() {
+        let app = setup_test_app().await;
+        let rule = arrange_rule(
+            &app,
+            json!({"name": "Rent", "description_pattern": "rent", "add_tags": ["home"]}),
+        )
+        .await;
+        let uri = format!("{TEST_LEDGER_PATH}/rules/{}", rule.id());
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({"name": "Rent", "description_pattern": "(?i)rent", "add_tags": ["flat"]})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let updated_rule: CategorisationRule = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into CategorisationRule struct.");
+        assert_eq!(updated_rule.id(), rule.id());
+        assert_eq!(updated_rule.description_pattern(), Some("(?i)rent"));
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let uri = format!("{TEST_LEDGER_PATH}/rules/{TEST_INVALID_UUID}");
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn categorisation_rule_dry_run_and_apply() {
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries").await;
+        let entry = arrange_entry(&app, 42.0, "REWE SAGT DANKE 4711").await;
+        let untouched = arrange_entry(&app, 12.0, "Cinema").await;
+        arrange_rule(
+            &app,
+            json!({
+                "name": "Rewe",
+                "description_pattern": "(?i)^rewe.*?(\\d+)$",
+                "set_expense_type": groceries,
+                "rewrite_description": "Rewe Markt $1",
+                "add_tags": ["food"]
+            }),
+        )
+        .await;
+
+        let dry_run = format!("{TEST_LEDGER_PATH}/rules/dry_run");
+        let response = arrange_and_act_request(&app, Method::POST, &dry_run, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let categorisations: Vec<Value> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into json values.");
+        assert_eq!(categorisations.len(), 1);
+        assert_eq!(categorisations[0]["before"]["id"], json!(entry.id()));
+        assert_eq!(
+            categorisations[0]["after"]["description"],
+            json!("Rewe Markt 4711")
+        );
+        assert_eq!(
+            act_get_entry(&app, entry.id()).await.description(),
+            "REWE SAGT DANKE 4711"
+        );
+
+        let apply = format!("{TEST_LEDGER_PATH}/rules/apply");
+        let response = arrange_and_act_request(&app, Method::POST, &apply, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let categorisations: Vec<Value> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into json values.");
+        assert_eq!(categorisations.len(), 1);
+        let categorised_entry = act_get_entry(&app, entry.id()).await;
+        assert_eq!(categorised_entry.description(), "Rewe Markt 4711");
+        assert_eq!(categorised_entry.expense_type(), groceries);
+        assert_eq!(categorised_entry.tags().len(), 1);
+        let untouched_entry = act_get_entry(&app, untouched.id()).await;
+        assert_eq!(untouched_entry.expense_type(), untouched.expense_type());
+
+        // applied rules leave nothing more to change
+        let response = arrange_and_act_request(&app, Method::POST, &dry_run, None).await;
+        let categorisations: Vec<Value> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into json values.");
+        assert!(categorisations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn categorisation_rule_priority_decides() {
+        let app = setup_test_app().await;
+        let (shopping, electronics) = (
+            arrange_expense_type(&app, "Shopping").await,
+            arrange_expense_type(&app, "Electronics").await,
+        );
+        let entry = arrange_entry(&app, 899.0, "Amazon Marketplace").await;
+        arrange_rule(
+            &app,
+            json!({
+                "name": "Amazon",
+                "priority": 5,
+                "description_pattern": "Amazon",
+                "set_expense_type": shopping,
+                "add_tags": ["online"]
+            }),
+        )
+        .await;
+        arrange_rule(
+            &app,
+            json!({
+                "name": "Expensive",
+                "priority": 1,
+                "min_amount": 500.0,
+                "max_amount": 2000.0,
+                "set_expense_type": electronics,
+                "add_tags": ["large"]
+            }),
+        )
+        .await;
+
+        let apply = format!("{TEST_LEDGER_PATH}/rules/apply?from=2000-01-01");
+        let response = arrange_and_act_request(&app, Method::POST, &apply, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let categorised_entry = act_get_entry(&app, entry.id()).await;
+        assert_eq!(categorised_entry.expense_type(), electronics);
+        let tags: Vec<String> = categorised_entry
+            .tags()
+            .iter()
+            .map(|tag| tag.as_str().to_string())
+            .collect();
+        assert_eq!(tags, vec!["large", "online"]);
+    }
+
+    #[tokio::test]
+    async fn categorisation_rule_batch_import() {
+        let app = setup_test_app().await;
+        let fuel = arrange_expense_type(&app, "Fuel").await;
+        arrange_rule(
+            &app,
+            json!({"name": "Fuel", "description_pattern": "(?i)shell|aral", "set_expense_type": fuel}),
+        )
+        .await;
+        let operation = |description: &str| {
+            json!({
+                "op": "create",
+                "entry": {
+                    "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 60.0}],
+                    "expense_type": TEST_VALID_UUID,
+                    "description": description
+                }
+            })
+        };
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/batch");
+        for (apply_rules, categorised) in [(false, false), (true, true)] {
+            let response = arrange_and_act_request(
+                &app,
+                Method::POST,
+                &uri,
+                Some(json!({
+                    "operations": [operation("ARAL Station 12")],
+                    "apply_rules": apply_rules,
+                    "allow_duplicate": true
+                })),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let results: Vec<Value> = serde_json::from_slice(&read_body(response).await)
+                .expect("Failed to parse response into json values.");
+            assert_eq!(results[0]["status"], json!(200));
+            assert_eq!(
+                results[0]["entry"]["expense_type"] == json!(fuel),
+                categorised
+            );
+        }
+    }
+}
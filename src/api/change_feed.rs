@@ -0,0 +1,521 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::ws::{CloseFrame, Message, Utf8Bytes, WebSocket, WebSocketUpgrade, close_code};
+use axum::extract::{Path, Query, State};
+use axum::http::HeaderMap;
+use axum::response::Response;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::{Stream, stream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{Instant, MissedTickBehavior, timeout};
+use uuid::Uuid;
+
+use crate::api::routes::Services;
+use crate::domain::change_feed::{ChangeFilter, FeedEvent};
+use crate::domain::outbox::ChangeNotification;
+use crate::service::application_error::ApplicationErrorType;
+use crate::service::change_feed::ChangeFeedService;
+
+// clients are pinged this often and dropped after two periods without any sign of life
+const HEARTBEAT_PERIOD: Duration = Duration::from_secs(30);
+
+// a client that does not take a message within this time is too slow to keep
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+// close code asking the client to reconnect later
+const TRY_AGAIN_LATER: u16 = 1013;
+
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FeedMessage<'a> {
+    Subscribed {
+        filter: &'a ChangeFilter,
+    },
+    Change {
+        #[serde(flatten)]
+        notification: ChangeNotification<'a>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl FeedMessage<'_> {
+    fn to_message(&self) -> Message {
+        // serializing plain data into a string does not fail
+        Message::Text(serde_json::to_string(self).unwrap_or_default().into())
+    }
+}
+
+// clients only ever see the changes of the ledger they connected to
+fn scoped(filter: ChangeFilter, ledger_id: Uuid) -> ChangeFilter {
+    ChangeFilter {
+        ledger_id: Some(ledger_id),
+        ..filter
+    }
+}
+
+// the query sets the initial filter, every json filter the client sends replaces it
+pub async fn change_feed_ws(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    Query(filter): Query<ChangeFilter>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let service = services.change_feed_service.clone();
+    let subscriber = service.subscriber();
+    let changes = service.subscribe();
+    ws.on_upgrade(move |socket| {
+        let subscription = Subscription {
+            service,
+            subscriber,
+            ledger_id,
+        };
+        feed(socket, changes, subscription, scoped(filter, ledger_id))
+    })
+}
+
+// whom a connected client gets the changes for, every change is checked against the
+// subscriber's membership as it may be revoked while the client stays connected
+struct Subscription {
+    service: Arc<ChangeFeedService>,
+    subscriber: Option<String>,
+    ledger_id: Uuid,
+}
+
+// what a client streaming server-sent events still has to get
+struct EventStream {
+    missed: VecDeque<FeedEvent>,
+    reset: bool,
+    receiver: broadcast::Receiver<FeedEvent>,
+    filter: ChangeFilter,
+    subscription: Subscription,
+}
+
+fn sse_event(feed_event: &FeedEvent) -> Event {
+    let notification = ChangeNotification::from(&feed_event.change);
+    Event::default()
+        .id(feed_event.id())
+        .event(notification.event.clone())
+        // serializing plain data into a string does not fail
+        .data(serde_json::to_string(&notification).unwrap_or_default())
+}
+
+// the same changes as the websocket, a reconnecting client sends the id of the last event it
+// got as Last-Event-ID and first receives what it missed, a reset event tells it that changes
+// were lost in between and it has to reload
+pub async fn change_feed_sse(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    Query(filter): Query<ChangeFilter>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok());
+    let service = services.change_feed_service.clone();
+    let resume = service.resume(last_event_id);
+    let state = EventStream {
+        missed: resume.missed.into(),
+        reset: !resume.complete,
+        receiver: resume.receiver,
+        filter: scoped(filter, ledger_id),
+        subscription: Subscription {
+            subscriber: service.subscriber(),
+            service,
+            ledger_id,
+        },
+    };
+    let events = stream::unfold(state, |mut state| async move {
+        if state.reset {
+            state.reset = false;
+            return Some((Ok(Event::default().event("reset").data("{}")), state));
+        }
+        loop {
+            let feed_event = match state.missed.pop_front() {
+                Some(feed_event) => feed_event,
+                // a lagging client is dropped, it resumes from the buffer when it reconnects
+                None => state.receiver.recv().await.ok()?,
+            };
+            if state.filter.matches(&feed_event.change) {
+                // a subscriber who lost access is dropped, reconnecting is refused then
+                let feed_event = state
+                    .subscription
+                    .service
+                    .visible(state.subscription.subscriber.as_deref(), &feed_event)
+                    .ok()?;
+                return Some((Ok(sse_event(&feed_event)), state));
+            }
+        }
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+fn close(code: u16, reason: &'static str) -> Message {
+    Message::Close(Some(CloseFrame {
+        code,
+        reason: Utf8Bytes::from_static(reason),
+    }))
+}
+
+async fn feed(
+    mut socket: WebSocket,
+    mut changes: broadcast::Receiver<FeedEvent>,
+    subscription: Subscription,
+    mut filter: ChangeFilter,
+) {
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_PERIOD);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut last_seen = Instant::now();
+    if socket
+        .send(FeedMessage::Subscribed { filter: &filter }.to_message())
+        .await
+        .is_err()
+    {
+        return;
+    }
+    loop {
+        let outgoing = tokio::select! {
+            received = socket.recv() => {
+                last_seen = Instant::now();
+                match received {
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str(&text) {
+                        Ok(new_filter) => {
+                            filter = scoped(new_filter, subscription.ledger_id);
+                            FeedMessage::Subscribed { filter: &filter }.to_message()
+                        }
+                        Err(err) => FeedMessage::Error {
+                            message: format!("Invalid filter: {err}"),
+                        }
+                        .to_message(),
+                    },
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return,
+                    // pongs and pings only count as a sign of life
+                    Some(Ok(_)) => continue,
+                }
+            }
+            change = changes.recv() => match change {
+                Ok(feed_event) if filter.matches(&feed_event.change) => match subscription
+                    .service
+                    .visible(subscription.subscriber.as_deref(), &feed_event)
+                {
+                    Ok(feed_event) => FeedMessage::Change {
+                        notification: ChangeNotification::from(&feed_event.change),
+                    }
+                    .to_message(),
+                    Err(err) if matches!(err.error_type, ApplicationErrorType::Forbidden) => {
+                        let _ = socket
+                            .send(close(close_code::POLICY, "Missing permission for this ledger."))
+                            .await;
+                        return;
+                    }
+                    Err(_) => {
+                        let _ = socket.send(close(close_code::ERROR, "Server error.")).await;
+                        return;
+                    }
+                },
+                Ok(_) => continue,
+                Err(RecvError::Lagged(_)) => {
+                    let _ = socket.send(close(TRY_AGAIN_LATER, "Client too slow.")).await;
+                    return;
+                }
+                Err(RecvError::Closed) => {
+                    let _ = socket.send(close(close_code::AWAY, "Server shutting down.")).await;
+                    return;
+                }
+            },
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > 2 * HEARTBEAT_PERIOD {
+                    let _ = socket.send(close(close_code::POLICY, "Heartbeat missed.")).await;
+                    return;
+                }
+                Message::Ping(Default::default())
+            }
+        };
+        match timeout(SEND_TIMEOUT, socket.send(outgoing)).await {
+            Ok(Ok(())) => {}
+            // the connection is gone or stuck, a stuck client is dropped without a close frame
+            Ok(Err(_)) | Err(_) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::{
+        Router,
+        body::Body,
+        http::{Method, Request, StatusCode},
+    };
+    use chrono::Utc;
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{Value, json};
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+    use tower::ServiceExt;
+
+    use crate::api::routes::Services;
+    use crate::test_util::test_utility::{TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    // serves the app on a local port, websockets need a real connection to upgrade
+    async fn setup_test_server() -> (Router, Services, String) {
+        let services = setup_test_services();
+        // routes the seeded changes before anyone listens
+        services
+            .outbox_service
+            .dispatch(Utc::now())
+            .unwrap_or_else(|err| panic!("Dispatching failed: {}", err.message));
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind test server.");
+        let address = listener.local_addr().unwrap().to_string();
+        let server = app.clone();
+        tokio::spawn(async move { axum::serve(listener, server).await });
+        (app, services, address)
+    }
+
+    fn dispatch(services: &Services) {
+        services
+            .outbox_service
+            .dispatch(Utc::now())
+            .unwrap_or_else(|err| panic!("Dispatching failed: {}", err.message));
+    }
+
+    async fn arrange_and_act_request(app: &Router, method: Method, uri: &str, body: Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("Failed to finalize request.");
+        let response = app
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.");
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    async fn arrange_expense_entry(app: &Router) {
+        let body = json!({
+            "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 20.0}],
+            "expense_type": TEST_VALID_UUID,
+            "description": "Groceries",
+            "expense_date": "2026-03-15T12:00:00Z"
+        });
+        arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            body,
+        )
+        .await;
+    }
+
+    async fn arrange_cost_bearer_update(app: &Router) {
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", TEST_VALID_UUID);
+        let body = json!({"name": "Barclays Debit Card", "exists_from": "2025-01-01T00:00:00Z"});
+        arrange_and_act_request(app, Method::PATCH, &uri, body).await;
+    }
+
+    async fn arrange_expense_type_update(app: &Router) {
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", TEST_VALID_UUID);
+        let body = json!({"name": "Groceries", "description": "Food from the store"});
+        arrange_and_act_request(app, Method::PATCH, &uri, body).await;
+    }
+
+    async fn connect(address: &str, query: &str) -> Client {
+        let (mut client, _) = connect_async(format!("ws://{address}{TEST_LEDGER_PATH}/ws{query}"))
+            .await
+            .expect("Failed to connect to change feed.");
+        assert_eq!(act_receive(&mut client).await["type"], "subscribed");
+        client
+    }
+
+    // the next json message, heartbeats are skipped
+    async fn act_receive(client: &mut Client) -> Value {
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), client.next())
+                .await
+                .expect("No message from change feed.")
+                .expect("Change feed closed.")
+                .expect("Failed to receive message.");
+            if let Message::Text(text) = message {
+                return serde_json::from_str(&text).expect("Failed to parse message.");
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn change_feed_pushes_filtered_changes() {
+        let (app, services, address) = setup_test_server().await;
+        let mut client = connect(&address, "?entity=cost_bearer").await;
+
+        arrange_expense_entry(&app).await;
+        arrange_cost_bearer_update(&app).await;
+        dispatch(&services);
+        let bearer_change = act_receive(&mut client).await;
+        client
+            .send(Message::Text(
+                json!({"entity": "expense_entry"}).to_string().into(),
+            ))
+            .await
+            .expect("Failed to send filter.");
+        let resubscribed = act_receive(&mut client).await;
+        arrange_cost_bearer_update(&app).await;
+        arrange_expense_entry(&app).await;
+        dispatch(&services);
+        let entry_change = act_receive(&mut client).await;
+
+        assert_eq!(bearer_change["type"], "change");
+        assert_eq!(bearer_change["event"], "cost_bearer.updated");
+        assert_eq!(bearer_change["data"]["name"], "Barclays Debit Card");
+        assert_eq!(resubscribed["type"], "subscribed");
+        assert_eq!(resubscribed["filter"]["entity"], "expense_entry");
+        assert_eq!(entry_change["event"], "expense_entry.created");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn change_feed_filters_by_cost_bearer() {
+        let (app, services, address) = setup_test_server().await;
+        let query = format!("?cost_bearer_id={}", TEST_VALID_UUID);
+        let mut client = connect(&address, &query).await;
+
+        arrange_expense_type_update(&app).await;
+        arrange_expense_entry(&app).await;
+        dispatch(&services);
+        let change = act_receive(&mut client).await;
+
+        assert_eq!(change["event"], "expense_entry.created");
+        assert_eq!(
+            change["data"]["cost_shares"][0]["cost_bearer_id"],
+            TEST_VALID_UUID.to_string()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn change_feed_rejects_invalid_filter() {
+        let (_, _, address) = setup_test_server().await;
+        let mut client = connect(&address, "").await;
+
+        client
+            .send(Message::Text(
+                json!({"entity": "budget"}).to_string().into(),
+            ))
+            .await
+            .expect("Failed to send filter.");
+        let answer = act_receive(&mut client).await;
+
+        assert_eq!(answer["type"], "error");
+    }
+
+    type EventBody = axum::body::BodyDataStream;
+
+    async fn connect_events(app: &Router, last_event_id: Option<&str>) -> EventBody {
+        let mut request = Request::builder()
+            .method(Method::GET)
+            .uri(format!("{TEST_LEDGER_PATH}/events"));
+        if let Some(last_event_id) = last_event_id {
+            request = request.header("last-event-id", last_event_id);
+        }
+        let response = app
+            .clone()
+            .oneshot(
+                request
+                    .body(Body::empty())
+                    .expect("Failed to finalize request."),
+            )
+            .await
+            .expect("Failed to receive response.");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        response.into_body().into_data_stream()
+    }
+
+    // the id, name and data of the next event, keep alive comments are skipped
+    async fn act_receive_event(body: &mut EventBody) -> (String, String, String) {
+        let mut text = String::new();
+        loop {
+            let chunk = tokio::time::timeout(Duration::from_secs(5), body.next())
+                .await
+                .expect("No event from event stream.")
+                .expect("Event stream closed.")
+                .expect("Failed to receive event.");
+            text.push_str(std::str::from_utf8(&chunk).expect("Event is no text."));
+            let Some((event, rest)) = text.split_once("\n\n") else {
+                continue;
+            };
+            let field = |name: &str| {
+                event
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let received = (field("id: "), field("event: "), field("data: "));
+            if received.1.is_empty() {
+                text = rest.to_string();
+                continue;
+            }
+            return received;
+        }
+    }
+
+    #[tokio::test]
+    async fn events_stream_changes() {
+        let (app, services, _) = setup_test_server().await;
+        let mut body = connect_events(&app, None).await;
+
+        arrange_cost_bearer_update(&app).await;
+        dispatch(&services);
+        let (id, event, data) = act_receive_event(&mut body).await;
+
+        assert!(!id.is_empty());
+        assert_eq!(event, "cost_bearer.updated");
+        let data: Value = serde_json::from_str(&data).expect("Failed to parse event data.");
+        assert_eq!(data["data"]["name"], "Barclays Debit Card");
+    }
+
+    #[tokio::test]
+    async fn events_resume_after_last_event_id() {
+        let (app, services, _) = setup_test_server().await;
+        let mut body = connect_events(&app, None).await;
+        arrange_cost_bearer_update(&app).await;
+        arrange_expense_entry(&app).await;
+        dispatch(&services);
+        let (first_id, _, _) = act_receive_event(&mut body).await;
+        let (second_id, _, _) = act_receive_event(&mut body).await;
+        drop(body);
+
+        let mut resumed = connect_events(&app, Some(&first_id)).await;
+        let (resumed_id, resumed_event, _) = act_receive_event(&mut resumed).await;
+
+        assert_eq!(resumed_id, second_id);
+        assert_eq!(resumed_event, "expense_entry.created");
+    }
+
+    #[tokio::test]
+    async fn events_reset_unknown_last_event_id() {
+        let (app, services, _) = setup_test_server().await;
+        arrange_cost_bearer_update(&app).await;
+        dispatch(&services);
+
+        let mut body = connect_events(&app, Some("from-another-server:7")).await;
+        let (_, reset, _) = act_receive_event(&mut body).await;
+        arrange_expense_entry(&app).await;
+        dispatch(&services);
+        let (_, event, _) = act_receive_event(&mut body).await;
+
+        assert_eq!(reset, "reset");
+        assert_eq!(event, "expense_entry.created");
+    }
+}
@@ -1,48 +1,82 @@
+use axum::extract::{Query, State};
 use axum::{Json, extract::Path, http::StatusCode};
 use uuid::Uuid;
 
+use crate::api::expense_type::{MergeTarget, ReassignQuery};
+use crate::api::routes::Services;
 use crate::domain::cost_bearer::CostBearer;
 use crate::service::application_error::ApplicationError;
 use crate::service::cost_bearer::CostBearerNew;
 
 pub async fn cost_bearer_post(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
     entry: Json<CostBearerNew>,
 ) -> Result<Json<CostBearer>, ApplicationError> {
     let new_entry_dto: CostBearerNew = entry.0;
-    let created_entry = crate::service::command::cost_bearer::create(new_entry_dto)?;
+    let created_entry = services
+        .cost_bearer_service
+        .create(ledger_id, new_entry_dto)?;
     Ok(Json(created_entry))
 }
 
 pub async fn cost_bearer_update(
-    Path(id): Path<Uuid>,
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
     entry: Json<CostBearerNew>,
 ) -> Result<Json<CostBearer>, ApplicationError> {
     let update_entry_dto: CostBearerNew = entry.0;
-    let updated_entry = crate::service::command::cost_bearer::update(id, update_entry_dto)?;
+    let updated_entry = services
+        .cost_bearer_service
+        .update(ledger_id, id, update_entry_dto)?;
     Ok(Json(updated_entry))
 }
 
-pub async fn cost_bearer_delete(Path(id): Path<Uuid>) -> Result<StatusCode, ApplicationError> {
-    crate::service::command::cost_bearer::delete(id)?;
+pub async fn cost_bearer_delete(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<ReassignQuery>,
+) -> Result<StatusCode, ApplicationError> {
+    services
+        .cost_bearer_service
+        .delete(ledger_id, id, query.reassign_to)?;
     Ok(StatusCode::NO_CONTENT)
 }
 
-pub async fn cost_bearer_get(Path(id): Path<Uuid>) -> Result<Json<CostBearer>, ApplicationError> {
-    let found_entry = crate::service::query::cost_bearer::get(id)?;
+pub async fn cost_bearer_merge(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+    target: Json<MergeTarget>,
+) -> Result<Json<CostBearer>, ApplicationError> {
+    let merged_cost_bearer =
+        services
+            .cost_bearer_service
+            .merge(ledger_id, id, target.0.target_id)?;
+    Ok(Json(merged_cost_bearer))
+}
+
+pub async fn cost_bearer_restore(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<CostBearer>, ApplicationError> {
+    let restored_cost_bearer = services.cost_bearer_service.restore(ledger_id, id)?;
+    Ok(Json(restored_cost_bearer))
+}
+
+pub async fn cost_bearer_get(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<CostBearer>, ApplicationError> {
+    let found_entry = services.cost_bearer_service.get(ledger_id, id)?;
     Ok(Json(found_entry))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
-
-    use crate::{
-        api::routes::Services,
-        repository::sqliterepository::expense_entry::{
-            ExpenseEntryReadSqliteRepository, ExpenseEntryWriteSqliteRepository,
-        },
-        service::expense_entry::ExpenseEntryService,
-        test_util::test_utility::{TEST_INVALID_UUID, TEST_VALID_UUID},
+    use crate::domain::cost_bearer::CostBearer;
+    use crate::domain::expense_entry::ExpenseEntry;
+    use crate::test_util::test_utility::{
+        TEST_INVALID_UUID, TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services,
     };
     use axum::{
         Router,
@@ -51,25 +85,19 @@ mod tests {
         response::Response,
     };
     use chrono::{TimeZone, Utc};
-    use serde_json::json;
+    use serde_json::{Value, json};
     use tower::ServiceExt;
+    use uuid::Uuid;
 
     async fn setup_test_app() -> Router {
-        let read_repo = Arc::new(ExpenseEntryReadSqliteRepository::new());
-        let write_repo = Arc::new(ExpenseEntryWriteSqliteRepository::new());
-        let expense_entry_service = Arc::new(ExpenseEntryService::new(read_repo, write_repo));
-        let services = Services {
-            expense_entry_service: expense_entry_service.clone(),
-        };
-
-        crate::api::routes::setup_routing()
-            .await
-            .with_state(services)
+        let services = setup_test_services();
+
+        crate::api::routes::setup_routing(services).await
     }
 
     async fn arrange_and_act_get_request(id: &str) -> Response<Body> {
         let app = setup_test_app().await;
-        let uri = format!("/cost_bearers/{}", id);
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", id);
 
         let request = Request::builder()
             .method(Method::GET)
@@ -77,17 +105,14 @@ mod tests {
             .body(Body::empty())
             .expect("Failed to finalize request.");
 
-        let response = app
-            .oneshot(request)
+        app.oneshot(request)
             .await
-            .expect("Failed to receive response.");
-
-        response
+            .expect("Failed to receive response.")
     }
 
     async fn arrange_and_act_post_request(entry: String) -> Response<Body> {
         let app = setup_test_app().await;
-        let uri = "/cost_bearers";
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers");
         let body = Body::from(entry);
 
         let request = Request::builder()
@@ -97,17 +122,14 @@ mod tests {
             .body(body)
             .expect("Failed to finalize request.");
 
-        let response = app
-            .oneshot(request)
+        app.oneshot(request)
             .await
-            .expect("Failed to receive response.");
-
-        response
+            .expect("Failed to receive response.")
     }
 
     async fn arrange_and_act_delete_request(id: &str) -> Response<Body> {
         let app = setup_test_app().await;
-        let uri = format!("/cost_bearers/{}", id);
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", id);
 
         let request = Request::builder()
             .method(Method::DELETE)
@@ -115,12 +137,74 @@ mod tests {
             .body(Body::empty())
             .expect("Failed to finalize request.");
 
-        let response = app
+        app.oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
             .oneshot(request)
             .await
-            .expect("Failed to receive response.");
+            .expect("Failed to receive response.")
+    }
 
-        response
+    async fn arrange_cost_bearer(app: &Router, name: &str) -> CostBearer {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/cost_bearers"),
+            Some(json!({"name": name, "exists_from": Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into CostBearer struct.")
+    }
+
+    // an expense entry with one share per given cost bearer
+    async fn arrange_expense_entry(app: &Router, cost_bearer_ids: &[Uuid]) -> ExpenseEntry {
+        let cost_shares: Vec<Value> = cost_bearer_ids
+            .iter()
+            .map(|id| json!({"cost_bearer_id": id, "amount": 10.0}))
+            .collect();
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": cost_shares,
+                "expense_type": TEST_VALID_UUID,
+                "description": "Groceries"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into ExpenseEntry struct.")
+    }
+
+    async fn error_message(response: Response<Body>) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        String::from_utf8(body.to_vec()).unwrap()
     }
 
     #[tokio::test]
@@ -163,6 +247,41 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[tokio::test]
+    async fn cost_bearer_post_private() {
+        let new_cost_bearer = json!({
+            "name": "Alice's Savings",
+            "exists_from": Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            "private_to": [" alice ", "alice", "bob"]
+        });
+        let response = arrange_and_act_post_request(new_cost_bearer.to_string()).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let bearer: CostBearer = serde_json::from_slice(&body)
+            .expect("Failed to parse response into CostBearer struct.");
+        assert_eq!(bearer.private_to(), ["alice", "bob"]);
+    }
+
+    #[tokio::test]
+    async fn cost_bearer_post_fails_empty_private_user() {
+        let new_cost_bearer = json!({
+            "name": "Alice's Savings",
+            "exists_from": Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            "private_to": ["alice", " "]
+        });
+        let response = arrange_and_act_post_request(new_cost_bearer.to_string()).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Json without valid private users.");
+    }
+
     #[tokio::test]
     async fn cost_bearer_post() {
         let exists_from = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
@@ -319,14 +438,253 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn cost_bearer_update() {}
+    async fn cost_bearer_update() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", TEST_VALID_UUID);
+        let exists_from = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({"name": "Barclays Debit Card", "exists_from": exists_from})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let bearer: CostBearer = serde_json::from_slice(&body)
+            .expect("Failed to parse response into CostBearer struct.");
+        assert_eq!(bearer.id(), TEST_VALID_UUID);
+        assert_eq!(bearer.name(), "Barclays Debit Card");
+        assert_eq!(bearer.exists_from(), exists_from);
+        assert_eq!(bearer.exists_to(), None);
+    }
+
+    #[tokio::test]
+    async fn cost_bearer_update_fails() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", TEST_INVALID_UUID);
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({"name": "Barclays Debit Card", "exists_from": Utc::now()})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(error_message(response).await, "Cost Bearer not found.");
+    }
+
+    #[tokio::test]
+    async fn cost_bearer_delete_fails_referenced() {
+        let app = setup_test_app().await;
+        let cost_bearer = arrange_cost_bearer(&app, "Barclays Debit Card").await;
+        arrange_expense_entry(&app, &[cost_bearer.id()]).await;
+        arrange_expense_entry(&app, &[cost_bearer.id(), TEST_VALID_UUID]).await;
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", cost_bearer.id());
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            error_message(response).await,
+            "Cost bearer is still used by 2 expense entries."
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn cost_bearer_delete_with_reassign() {
+        let app = setup_test_app().await;
+        let entry = arrange_expense_entry(&app, &[TEST_VALID_UUID]).await;
+        let target = arrange_cost_bearer(&app, "Barclays Debit Card").await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/cost_bearers/{}?reassign_to={}",
+            TEST_VALID_UUID,
+            target.id()
+        );
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", entry.id());
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let entry: ExpenseEntry = serde_json::from_slice(&body)
+            .expect("Failed to parse response into ExpenseEntry struct.");
+        assert_eq!(entry.cost_shares()[0].cost_bearer_id, target.id());
+    }
+
+    #[tokio::test]
+    async fn cost_bearer_delete_with_reassign_fails_unknown_target() {
+        let app = setup_test_app().await;
+        arrange_expense_entry(&app, &[TEST_VALID_UUID]).await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/cost_bearers/{}?reassign_to={}",
+            TEST_VALID_UUID, TEST_INVALID_UUID
+        );
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error_message(response).await,
+            "Query without valid reassign target."
+        );
+    }
+
+    #[tokio::test]
+    async fn cost_bearer_merge_combines_shares() {
+        let app = setup_test_app().await;
+        let target = arrange_cost_bearer(&app, "Barclays Credit Card (new)").await;
+        let shared_entry = arrange_expense_entry(&app, &[TEST_VALID_UUID, target.id()]).await;
+        let single_entry = arrange_expense_entry(&app, &[TEST_VALID_UUID]).await;
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}/merge", TEST_VALID_UUID);
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(json!({"target_id": target.id()})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let merged: CostBearer = serde_json::from_slice(&body)
+            .expect("Failed to parse response into CostBearer struct.");
+        assert_eq!(merged.id(), target.id());
+        for (entry, amount) in [(shared_entry, 20.0), (single_entry, 10.0)] {
+            let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", entry.id());
+            let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to receive body from response.");
+            let entry: ExpenseEntry = serde_json::from_slice(&body)
+                .expect("Failed to parse response into ExpenseEntry struct.");
+            assert_eq!(entry.cost_shares().len(), 1);
+            assert_eq!(entry.cost_shares()[0].cost_bearer_id, target.id());
+            assert_eq!(entry.cost_shares()[0].amount, amount);
+        }
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    // a monthly recurring expense with one share per given cost bearer
+    async fn arrange_recurring_expense(app: &Router, cost_bearer_ids: &[Uuid]) -> Value {
+        let cost_shares: Vec<Value> = cost_bearer_ids
+            .iter()
+            .map(|id| json!({"cost_bearer_id": id, "amount": 10.0}))
+            .collect();
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/recurring_expenses"),
+            Some(json!({
+                "template": {
+                    "cost_shares": cost_shares,
+                    "expense_type": TEST_VALID_UUID,
+                    "description": "Rent"
+                },
+                "schedule": {"frequency": "monthly", "day_of_month": 1, "starts_on": "2026-01-01"}
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse recurring expense.")
+    }
+
+    #[tokio::test]
+    async fn cost_bearer_delete_fails_referenced_by_recurring_expense() {
+        let app = setup_test_app().await;
+        let cost_bearer = arrange_cost_bearer(&app, "Barclays Debit Card").await;
+        arrange_recurring_expense(&app, &[cost_bearer.id()]).await;
+
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", cost_bearer.id());
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            error_message(response).await,
+            "Cost bearer is still used by 1 budgets, recurring expenses or categorisation rules."
+        );
+    }
 
     #[tokio::test]
-    async fn cost_bearer_update_fails() {}
+    async fn cost_bearer_merge_combines_recurring_expense_shares() {
+        let app = setup_test_app().await;
+        let target = arrange_cost_bearer(&app, "Barclays Credit Card (new)").await;
+        let recurring_expense =
+            arrange_recurring_expense(&app, &[TEST_VALID_UUID, target.id()]).await;
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}/merge", TEST_VALID_UUID);
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(json!({"target_id": target.id()})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/recurring_expenses/{}",
+            recurring_expense["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let recurring_expense: Value =
+            serde_json::from_slice(&body).expect("Failed to parse recurring expense.");
+        assert_eq!(
+            recurring_expense["template"]["cost_shares"],
+            json!([{"cost_bearer_id": target.id(), "amount": 20.0}])
+        );
+    }
+
+    #[tokio::test]
+    async fn cost_bearer_merge_fails_into_itself() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}/merge", TEST_VALID_UUID);
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(json!({"target_id": TEST_VALID_UUID})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error_message(response).await,
+            "Json without valid target id."
+        );
+    }
 
     #[tokio::test]
     async fn cost_bearer_delete() {
-        let response = arrange_and_act_delete_request(&String::from(TEST_VALID_UUID)).await;
+        let app = setup_test_app().await;
+        let cost_bearer = arrange_cost_bearer(&app, "Barclays Debit Card").await;
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", cost_bearer.id());
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
 
         assert_eq!(response.status(), StatusCode::NO_CONTENT);
         let body = axum::body::to_bytes(response.into_body(), usize::MAX)
@@ -5,13 +5,21 @@ use axum::{
 
 use crate::service::application_error::{ApplicationError, ApplicationErrorType};
 
+// also used for the results of single operations within a batch
+pub fn status_code(error_type: &ApplicationErrorType) -> StatusCode {
+    match error_type {
+        ApplicationErrorType::NotFound => StatusCode::NOT_FOUND,
+        ApplicationErrorType::ValidationFailed => StatusCode::UNPROCESSABLE_ENTITY,
+        ApplicationErrorType::Conflict => StatusCode::CONFLICT,
+        ApplicationErrorType::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        ApplicationErrorType::Unauthorized => StatusCode::UNAUTHORIZED,
+        ApplicationErrorType::Forbidden => StatusCode::FORBIDDEN,
+        ApplicationErrorType::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 impl IntoResponse for ApplicationError {
     fn into_response(self) -> Response {
-        let http_status_code = match self.error_type {
-            ApplicationErrorType::NotFound => StatusCode::NOT_FOUND,
-            ApplicationErrorType::ValidationFailed => StatusCode::UNPROCESSABLE_ENTITY,
-        };
-
-        (http_status_code, self.message).into_response()
+        (status_code(&self.error_type), self.message).into_response()
     }
 }
@@ -1,63 +1,295 @@
-use axum::extract::State;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, HeaderValue};
 use axum::{Json, extract::Path, http::StatusCode};
+use chrono::{DateTime, Days, NaiveDate, Utc};
 use uuid::Uuid;
 
+use crate::api::error_code::status_code;
 use crate::api::routes::Services;
+use crate::domain::duplicate::DuplicatePair;
 use crate::domain::expense_entry::ExpenseEntry;
-use crate::service::application_error::ApplicationError;
-use crate::service::expense_entry::ExpenseEntryNew;
+use crate::domain::tag::{Tag, TagUsage};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::expense_entry::{
+    ExpenseEntryBatch, ExpenseEntryFilter, ExpenseEntryNew, ExpenseEntryOperationOutcome, TagFilter,
+};
+
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
+}
+
+// both dates are inclusive, tags are comma separated
+#[derive(serde::Deserialize)]
+pub struct ExpenseEntryQuery {
+    expense_type: Option<Uuid>,
+    cost_bearer_id: Option<Uuid>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    tags: Option<String>,
+    #[serde(default)]
+    tag_match: TagMatch,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ExpenseEntryCreateQuery {
+    #[serde(default)]
+    allow_duplicate: bool,
+}
+
+// ids of the existing entries a new one may duplicate, comma separated and best first
+const POSSIBLE_DUPLICATES_HEADER: &str = "possible-duplicates";
+
+#[derive(serde::Deserialize)]
+pub struct TagRename {
+    name: String,
+}
+
+// the outcome of one batch operation with the status code it would have got on its own
+#[derive(serde::Serialize)]
+pub struct ExpenseEntryOperationResult {
+    index: usize,
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry: Option<ExpenseEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    possible_duplicates: Vec<Uuid>,
+}
+
+impl ExpenseEntryOperationResult {
+    fn from_outcome(index: usize, outcome: ExpenseEntryOperationOutcome) -> Self {
+        let result = ExpenseEntryOperationResult {
+            index,
+            status: StatusCode::OK.as_u16(),
+            entry: None,
+            id: None,
+            error: None,
+            possible_duplicates: Vec::new(),
+        };
+        match outcome {
+            ExpenseEntryOperationOutcome::Created(entry)
+            | ExpenseEntryOperationOutcome::Updated(entry) => ExpenseEntryOperationResult {
+                id: Some(entry.id()),
+                entry: Some(entry),
+                ..result
+            },
+            ExpenseEntryOperationOutcome::Deleted(id) => ExpenseEntryOperationResult {
+                status: StatusCode::NO_CONTENT.as_u16(),
+                id: Some(id),
+                ..result
+            },
+            ExpenseEntryOperationOutcome::Failed(err) => ExpenseEntryOperationResult {
+                status: status_code(&err.error_type).as_u16(),
+                error: Some(err.message),
+                ..result
+            },
+            ExpenseEntryOperationOutcome::RolledBack => ExpenseEntryOperationResult {
+                status: StatusCode::FAILED_DEPENDENCY.as_u16(),
+                error: Some(String::from(
+                    "Not applied since another operation of the batch failed.",
+                )),
+                ..result
+            },
+        }
+    }
+}
+
+fn start_of(date: NaiveDate) -> Option<DateTime<Utc>> {
+    date.and_hms_opt(0, 0, 0).map(|start| start.and_utc())
+}
+
+impl TryFrom<ExpenseEntryQuery> for ExpenseEntryFilter {
+    type Error = ApplicationError;
+    fn try_from(query: ExpenseEntryQuery) -> Result<Self, ApplicationError> {
+        let tags = match query.tags {
+            Some(tags) => {
+                let tags = tags
+                    .split(',')
+                    .map(Tag::new)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| ApplicationError {
+                        error_type: ApplicationErrorType::ValidationFailed,
+                        message: String::from("Query without valid tags."),
+                    })?;
+                Some(match query.tag_match {
+                    TagMatch::Any => TagFilter::AnyOf(tags),
+                    TagMatch::All => TagFilter::AllOf(tags),
+                })
+            }
+            None => None,
+        };
+
+        Ok(ExpenseEntryFilter {
+            expense_type: query.expense_type,
+            cost_bearer_id: query.cost_bearer_id,
+            from: query.from.and_then(start_of),
+            to: query
+                .to
+                .and_then(|to| to.checked_add_days(Days::new(1)))
+                .and_then(start_of),
+            tags,
+        })
+    }
+}
+
+pub async fn expense_entry_list(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    Query(query): Query<ExpenseEntryQuery>,
+) -> Result<Json<Vec<ExpenseEntry>>, ApplicationError> {
+    let filter = ExpenseEntryFilter::try_from(query)?;
+    let found_entries = services.expense_entry_service.list(ledger_id, &filter)?;
+    Ok(Json(found_entries))
+}
+
+pub async fn tag_list(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+) -> Result<Json<Vec<TagUsage>>, ApplicationError> {
+    let tags = services.expense_entry_service.tags(ledger_id)?;
+    Ok(Json(tags))
+}
+
+pub async fn tag_update(
+    State(services): State<Services>,
+    Path((ledger_id, tag)): Path<(Uuid, String)>,
+    rename: Json<TagRename>,
+) -> Result<Json<TagUsage>, ApplicationError> {
+    let renamed_tag = services
+        .expense_entry_service
+        .rename_tag(ledger_id, &tag, &rename.0.name)?;
+    Ok(Json(renamed_tag))
+}
+
+// only looked up once the entry is stored, a failing lookup must not fail the request
+fn possible_duplicate_ids(services: &Services, ledger_id: Uuid, entry: &ExpenseEntry) -> Vec<Uuid> {
+    match services
+        .expense_entry_service
+        .possible_duplicates(ledger_id, entry)
+    {
+        Ok(pairs) => pairs.iter().map(|pair| pair.duplicate_of.id()).collect(),
+        Err(err) => {
+            eprintln!("Looking up possible duplicates failed: {}", err.message);
+            Vec::new()
+        }
+    }
+}
 
 pub async fn expense_entry_post(
     State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    Query(query): Query<ExpenseEntryCreateQuery>,
     entry: Json<ExpenseEntryNew>,
-) -> Result<Json<ExpenseEntry>, ApplicationError> {
+) -> Result<(HeaderMap, Json<ExpenseEntry>), ApplicationError> {
     let new_entry_dto: ExpenseEntryNew = entry.0;
-    let created_entry = services.expense_entry_service.create(new_entry_dto)?;
-    Ok(Json(created_entry))
+    let created_entry = match query.allow_duplicate {
+        true => services
+            .expense_entry_service
+            .create_allowing_duplicates(ledger_id, new_entry_dto)?,
+        false => services
+            .expense_entry_service
+            .create(ledger_id, new_entry_dto)?,
+    };
+    let mut headers = HeaderMap::new();
+    let duplicate_ids = possible_duplicate_ids(&services, ledger_id, &created_entry);
+    if !duplicate_ids.is_empty() {
+        let duplicate_ids: Vec<String> = duplicate_ids.iter().map(Uuid::to_string).collect();
+        if let Ok(value) = HeaderValue::from_str(&duplicate_ids.join(",")) {
+            headers.insert(POSSIBLE_DUPLICATES_HEADER, value);
+        }
+    }
+    Ok((headers, Json(created_entry)))
+}
+
+pub async fn expense_entry_duplicates(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    Query(query): Query<ExpenseEntryQuery>,
+) -> Result<Json<Vec<DuplicatePair>>, ApplicationError> {
+    let filter = ExpenseEntryFilter::try_from(query)?;
+    let pairs = services
+        .expense_entry_service
+        .duplicates(ledger_id, &filter)?;
+    Ok(Json(pairs))
+}
+
+pub async fn expense_entry_batch(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    batch: Json<ExpenseEntryBatch>,
+) -> Result<Json<Vec<ExpenseEntryOperationResult>>, ApplicationError> {
+    let outcomes = services
+        .expense_entry_service
+        .apply_batch(ledger_id, batch.0)?;
+    let mut results = Vec::with_capacity(outcomes.len());
+    for (index, outcome) in outcomes.into_iter().enumerate() {
+        let possible_duplicates = match &outcome {
+            ExpenseEntryOperationOutcome::Created(entry) => {
+                possible_duplicate_ids(&services, ledger_id, entry)
+            }
+            _ => Vec::new(),
+        };
+        results.push(ExpenseEntryOperationResult {
+            possible_duplicates,
+            ..ExpenseEntryOperationResult::from_outcome(index, outcome)
+        });
+    }
+    Ok(Json(results))
 }
 
 pub async fn expense_entry_update(
     State(services): State<Services>,
-    Path(id): Path<Uuid>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
     entry: Json<ExpenseEntryNew>,
 ) -> Result<Json<ExpenseEntry>, ApplicationError> {
     let update_entry_dto: ExpenseEntryNew = entry.0;
     let updated_entry = services
         .expense_entry_service
-        .update(id, update_entry_dto)?;
+        .update(ledger_id, id, update_entry_dto)?;
     Ok(Json(updated_entry))
 }
 
 pub async fn expense_entry_delete(
     State(services): State<Services>,
-    Path(id): Path<Uuid>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<StatusCode, ApplicationError> {
-    services.expense_entry_service.delete(id)?;
+    services.expense_entry_service.delete(ledger_id, id)?;
     Ok(StatusCode::NO_CONTENT)
 }
 
+pub async fn expense_entry_restore(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ExpenseEntry>, ApplicationError> {
+    let restored_expense_entry = services.expense_entry_service.restore(ledger_id, id)?;
+    Ok(Json(restored_expense_entry))
+}
+
 pub async fn expense_entry_get(
     State(services): State<Services>,
-    Path(id): Path<Uuid>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<Json<ExpenseEntry>, ApplicationError> {
-    let found_entry = services.expense_entry_service.get(id)?;
+    let found_entry = services.expense_entry_service.get(ledger_id, id)?;
     Ok(Json(found_entry))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
-
-    use crate::{
-        domain::cost_share::CostShare,
-        repository::sqliterepository::expense_entry::{
-            ExpenseEntryReadSqliteRepository, ExpenseEntryWriteSqliteRepository,
-        },
-        service::expense_entry::ExpenseEntryService,
-    };
+    use crate::domain::cost_share::CostShare;
 
     use super::*;
-    use crate::test_util::test_utility::{TEST_INVALID_UUID, TEST_VALID_UUID};
+    use crate::service::expense_entry::DuplicatePolicy;
+    use crate::test_util::test_utility::{
+        TEST_INVALID_UUID, TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services,
+        setup_test_services_with_duplicate_policy, setup_test_services_with_event_store,
+    };
     use axum::{
         Router,
         body::Body,
@@ -65,25 +297,87 @@ mod tests {
         response::Response,
     };
     use chrono::TimeZone;
-    use serde_json::json;
+    use serde_json::{Value, json};
     use tower::ServiceExt;
 
     async fn setup_test_app() -> Router {
-        let read_repo = Arc::new(ExpenseEntryReadSqliteRepository::new());
-        let write_repo = Arc::new(ExpenseEntryWriteSqliteRepository::new());
-        let expense_entry_service = Arc::new(ExpenseEntryService::new(read_repo, write_repo));
-        let services = Services {
-            expense_entry_service: expense_entry_service.clone(),
-        };
+        let services = setup_test_services();
+
+        crate::api::routes::setup_routing(services).await
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn arrange_tagged_expense_entry(app: &Router, tags: Value) -> ExpenseEntry {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 10.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Hotel",
+                "tags": tags
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into ExpenseEntry struct.")
+    }
 
-        crate::api::routes::setup_routing()
+    async fn arrange_cost_bearer(app: &Router, name: &str) -> Uuid {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/cost_bearers"),
+            Some(json!({"name": name, "exists_from": Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
             .await
-            .with_state(services)
+            .expect("Failed to recieve body from response.");
+        let cost_bearer: Value = serde_json::from_slice(&body).unwrap();
+        Uuid::parse_str(cost_bearer["id"].as_str().unwrap()).unwrap()
+    }
+
+    async fn arrange_and_act_list_request(app: &Router, query: &str) -> Vec<ExpenseEntry> {
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries?{}", query);
+        let response = arrange_and_act_request(app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into ExpenseEntry list.")
+    }
+
+    fn tag_names(entry: &ExpenseEntry) -> Vec<&str> {
+        entry.tags().iter().map(|tag| tag.as_str()).collect()
     }
 
     async fn arrange_and_act_get_request(id: &str) -> Response<Body> {
         let app = setup_test_app().await;
-        let uri = format!("/expense_entries/{}", id);
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", id);
 
         let request = Request::builder()
             .method(Method::GET)
@@ -91,17 +385,14 @@ mod tests {
             .body(Body::empty())
             .expect("Failed to finalize request.");
 
-        let response = app
-            .oneshot(request)
+        app.oneshot(request)
             .await
-            .expect("Failed to receive response.");
-
-        response
+            .expect("Failed to receive response.")
     }
 
     async fn arrange_and_act_post_request(entry: String) -> Response<Body> {
         let app = setup_test_app().await;
-        let uri = "/expense_entries";
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
         let body = Body::from(entry);
 
         let request = Request::builder()
@@ -111,17 +402,14 @@ mod tests {
             .body(body)
             .expect("Failed to finalize request.");
 
-        let response = app
-            .oneshot(request)
+        app.oneshot(request)
             .await
-            .expect("Failed to receive response.");
-
-        response
+            .expect("Failed to receive response.")
     }
 
     async fn arrange_and_act_delete_request(id: &str) -> Response<Body> {
         let app = setup_test_app().await;
-        let uri = format!("/expense_entries/{}", id);
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", id);
 
         let request = Request::builder()
             .method(Method::DELETE)
@@ -129,12 +417,9 @@ mod tests {
             .body(Body::empty())
             .expect("Failed to finalize request.");
 
-        let response = app
-            .oneshot(request)
+        app.oneshot(request)
             .await
-            .expect("Failed to receive response.");
-
-        response
+            .expect("Failed to receive response.")
     }
 
     #[tokio::test]
@@ -174,8 +459,10 @@ mod tests {
 
     #[tokio::test]
     async fn expense_entry_post() {
-        let cost_uuid = Uuid::new_v4();
-        let expense_type_uuid = Uuid::new_v4();
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+        let cost_uuid = TEST_VALID_UUID;
+        let expense_type_uuid = TEST_VALID_UUID;
 
         let new_expense_entry = ExpenseEntryNew {
             cost_shares: vec![CostShare {
@@ -185,8 +472,10 @@ mod tests {
             expense_type: expense_type_uuid,
             description: String::from("I bought something today."),
             expense_date: None,
+            tags: Vec::new(),
         };
-        let response = arrange_and_act_post_request(json!(new_expense_entry).to_string()).await;
+        let response =
+            arrange_and_act_request(&app, Method::POST, &uri, Some(json!(new_expense_entry))).await;
 
         assert_eq!(response.status(), StatusCode::OK);
         let body = axum::body::to_bytes(response.into_body(), usize::MAX)
@@ -201,7 +490,7 @@ mod tests {
         assert_eq!(entry.expense_type(), expense_type_uuid);
         assert_eq!(entry.description(), "I bought something today.");
 
-        let second_cost_uuid = Uuid::new_v4();
+        let second_cost_uuid = arrange_cost_bearer(&app, "Cash").await;
         let new_expense_entry = ExpenseEntryNew {
             cost_shares: vec![
                 CostShare {
@@ -216,8 +505,10 @@ mod tests {
             expense_type: expense_type_uuid,
             description: String::from("I bought something today."),
             expense_date: None,
+            tags: Vec::new(),
         };
-        let response = arrange_and_act_post_request(json!(new_expense_entry).to_string()).await;
+        let response =
+            arrange_and_act_request(&app, Method::POST, &uri, Some(json!(new_expense_entry))).await;
 
         assert_eq!(response.status(), StatusCode::OK);
         let body = axum::body::to_bytes(response.into_body(), usize::MAX)
@@ -238,8 +529,8 @@ mod tests {
 
     #[tokio::test]
     async fn expense_entry_post_with_explicit_expense_date() {
-        let cost_uuid = Uuid::new_v4();
-        let expense_type_uuid = Uuid::new_v4();
+        let cost_uuid = TEST_VALID_UUID;
+        let expense_type_uuid = TEST_VALID_UUID;
 
         // fixed date for deterministic assertion
         let explicit_date = chrono::Utc
@@ -254,6 +545,7 @@ mod tests {
             expense_type: expense_type_uuid,
             description: String::from("Dated explicitly"),
             expense_date: Some(explicit_date),
+            tags: Vec::new(),
         };
         let response =
             arrange_and_act_post_request(serde_json::to_string(&new_expense_entry).unwrap()).await;
@@ -275,8 +567,8 @@ mod tests {
 
     #[tokio::test]
     async fn expense_entry_post_with_none_uses_now() {
-        let cost_uuid = Uuid::new_v4();
-        let expense_type_uuid = Uuid::new_v4();
+        let cost_uuid = TEST_VALID_UUID;
+        let expense_type_uuid = TEST_VALID_UUID;
 
         let before = chrono::Utc::now();
         let new_expense_entry = ExpenseEntryNew {
@@ -287,6 +579,7 @@ mod tests {
             expense_type: expense_type_uuid,
             description: String::from("Implicit now date"),
             expense_date: None,
+            tags: Vec::new(),
         };
         let response =
             arrange_and_act_post_request(serde_json::to_string(&new_expense_entry).unwrap()).await;
@@ -339,23 +632,57 @@ mod tests {
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
     }
 
-    // this can only happen once the DB is implemented
-    /*#[tokio::test]
+    #[tokio::test]
     async fn expense_entry_post_fails_invalid_cost_bearer_id() {
-        let expense_uuid = Uuid::new_v4();
+        let expense_uuid = TEST_VALID_UUID;
         let cost_bearer_uuid = Uuid::new_v4();
         let invalid_uuid_json = format!(
             r#"{{"cost_shares":[{{"cost_bearer_id": "{}", "amount": 12.5}}], "expense_type": "{}", "description": "something something"}}"#,
-            cost_bearer_uuid,
-            expense_uuid
+            cost_bearer_uuid, expense_uuid
         );
         let response = arrange_and_act_post_request(invalid_uuid_json).await;
 
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
-        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.expect("Failed to receive body from response.");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Json without valid cost shares.");
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_fails_deleted_cost_bearer() {
+        let app = setup_test_app().await;
+        let cash = arrange_cost_bearer(&app, "Cash").await;
+        let response = arrange_and_act_request(
+            &app,
+            Method::DELETE,
+            &format!("{TEST_LEDGER_PATH}/cost_bearers/{cash}"),
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": cash, "amount": 10.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Taxi"
+            })),
+        )
+        .await;
+
+        // a trashed cost bearer can't be booked on until it is restored
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
         let error_message = String::from_utf8(body.to_vec()).unwrap();
         assert_eq!(error_message, "Json without valid cost shares.");
-    }*/
+    }
 
     #[tokio::test]
     async fn expense_entry_post_fails_nil_cost_bearer_id() {
@@ -428,10 +755,22 @@ mod tests {
         assert_eq!(error_message, "Json without valid cost shares.");
     }
 
-    /*#[tokio::test]
+    #[tokio::test]
     async fn expense_entry_post_fails_invalid_expense_id() {
-        // needs DB
-    }*/
+        let invalid_expense_type_json = format!(
+            r#"{{"cost_shares":[{{"cost_bearer_id": "{}", "amount": 12.5}}], "expense_type": "{}", "description": "Some description"}}"#,
+            TEST_VALID_UUID,
+            Uuid::new_v4()
+        );
+        let response = arrange_and_act_post_request(invalid_expense_type_json).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Json without valid expense id.");
+    }
 
     #[tokio::test]
     async fn expense_entry_post_fails_nil_expense_type() {
@@ -484,11 +823,69 @@ mod tests {
         assert_eq!(error_message, "Json without valid description.");
     }
 
+    async fn arrange_and_act_update_request(id: &str, entry: String) -> Response<Body> {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", id);
+        let body = Body::from(entry);
+
+        let request = Request::builder()
+            .method(Method::PATCH)
+            .uri(&uri)
+            .header("content-type", "application/json")
+            .body(body)
+            .expect("Failed to finalize request.");
+
+        app.oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
     #[tokio::test]
-    async fn expense_entry_update() {}
+    async fn expense_entry_update() {
+        let cost_bearer_id = TEST_VALID_UUID;
+        let expense_type = TEST_VALID_UUID;
+        let body = json!({
+            "cost_shares": [{"cost_bearer_id": cost_bearer_id, "amount": 42.0}],
+            "expense_type": expense_type,
+            "description": "Updated Description"
+        });
+        let response =
+            arrange_and_act_update_request(&String::from(TEST_VALID_UUID), body.to_string()).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let updated_entry: ExpenseEntry =
+            serde_json::from_slice(&body).expect("Failed to parse response into ExpenseEntry.");
+        assert_eq!(updated_entry.id(), TEST_VALID_UUID);
+        assert_eq!(updated_entry.expense_type(), expense_type);
+        assert_eq!(updated_entry.description(), "Updated Description");
+        assert_eq!(
+            updated_entry.cost_shares()[0].cost_bearer_id,
+            cost_bearer_id
+        );
+        assert_eq!(updated_entry.cost_shares()[0].amount, 42.0);
+    }
 
     #[tokio::test]
-    async fn expense_entry_update_fails() {}
+    async fn expense_entry_update_fails() {
+        let body = json!({
+            "cost_shares": [{"cost_bearer_id": Uuid::new_v4(), "amount": 42.0}],
+            "expense_type": Uuid::new_v4(),
+            "description": "Updated Description"
+        });
+        let response =
+            arrange_and_act_update_request(&String::from(TEST_INVALID_UUID), body.to_string())
+                .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Expense entry not found.");
+    }
 
     #[tokio::test]
     async fn expense_entry_delete() {
@@ -521,4 +918,555 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
+
+    #[tokio::test]
+    async fn expense_entry_post_normalises_tags() {
+        let app = setup_test_app().await;
+        let entry = arrange_tagged_expense_entry(
+            &app,
+            json!([" Tax  Deductible ", "tax-deductible", "Vacation-2026"]),
+        )
+        .await;
+
+        assert_eq!(tag_names(&entry), vec!["tax-deductible", "vacation-2026"]);
+
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", entry.id());
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let found_entry: ExpenseEntry = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            tag_names(&found_entry),
+            vec!["tax-deductible", "vacation-2026"]
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_fails_invalid_tags() {
+        let app = setup_test_app().await;
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 10.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Hotel",
+                "tags": ["vacation/2026"]
+            })),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Json without valid tags.");
+    }
+
+    #[tokio::test]
+    async fn expense_entry_list_by_tags() {
+        let app = setup_test_app().await;
+        let vacation = arrange_tagged_expense_entry(&app, json!(["vacation-2026"])).await;
+        let both =
+            arrange_tagged_expense_entry(&app, json!(["vacation-2026", "tax-deductible"])).await;
+        let deductible = arrange_tagged_expense_entry(&app, json!(["tax-deductible"])).await;
+
+        let mut ids: Vec<Uuid> =
+            arrange_and_act_list_request(&app, "tags=vacation-2026,tax-deductible")
+                .await
+                .iter()
+                .map(|entry| entry.id())
+                .collect();
+        ids.sort();
+        let mut expected = vec![vacation.id(), both.id(), deductible.id()];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        let entries =
+            arrange_and_act_list_request(&app, "tags=vacation-2026,Tax%20Deductible&tag_match=all")
+                .await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id(), both.id());
+
+        // without a tag filter the seeded entry is listed as well
+        assert_eq!(arrange_and_act_list_request(&app, "").await.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn tag_list_and_rename() {
+        let app = setup_test_app().await;
+        arrange_tagged_expense_entry(&app, json!(["vacation"])).await;
+        arrange_tagged_expense_entry(&app, json!(["vacation", "vacation-2026"])).await;
+        arrange_tagged_expense_entry(&app, json!(["vacation-2026"])).await;
+
+        // renaming onto an existing tag merges both
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &format!("{TEST_LEDGER_PATH}/tags/vacation"),
+            Some(json!({"name": "Vacation 2026"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let usage: TagUsage = serde_json::from_slice(&body).unwrap();
+        assert_eq!(usage.tag.as_str(), "vacation-2026");
+        assert_eq!(usage.expense_entries, 3);
+
+        let response =
+            arrange_and_act_request(&app, Method::GET, &format!("{TEST_LEDGER_PATH}/tags"), None)
+                .await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let tags: Vec<TagUsage> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(tags.len(), 1);
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &format!("{TEST_LEDGER_PATH}/tags/vacation"),
+            Some(json!({"name": "holiday"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    async fn setup_event_store_test_app() -> Router {
+        let services = setup_test_services_with_event_store();
+
+        crate::api::routes::setup_routing(services).await
+    }
+
+    async fn act_get_entry(app: &Router, id: Uuid) -> ExpenseEntry {
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", id);
+        let response = arrange_and_act_request(app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into ExpenseEntry struct.")
+    }
+
+    #[tokio::test]
+    async fn expense_entry_event_store_lifecycle() {
+        let app = setup_event_store_test_app().await;
+        let entry = arrange_tagged_expense_entry(&app, json!(["vacation"])).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", entry.id());
+        let cost_bearer_id = arrange_cost_bearer(&app, "Cash").await;
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": cost_bearer_id, "amount": 42.0}],
+                "expense_type": entry.expense_type(),
+                "description": "Hotel",
+                "tags": ["vacation"]
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": cost_bearer_id, "amount": 1.0}],
+                "expense_type": entry.expense_type(),
+                "description": "Hotel"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let restore_uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}/restore", entry.id());
+        let response = arrange_and_act_request(&app, Method::POST, &restore_uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let restored_entry = act_get_entry(&app, entry.id()).await;
+
+        assert_eq!(
+            restored_entry.cost_shares()[0].cost_bearer_id,
+            cost_bearer_id
+        );
+        assert_eq!(restored_entry.cost_shares()[0].amount, 42.0);
+        assert_eq!(restored_entry.description(), "Hotel");
+        assert_eq!(tag_names(&restored_entry), vec!["vacation"]);
+    }
+
+    #[tokio::test]
+    async fn expense_entry_event_store_keeps_reassigned_cost_bearer() {
+        let app = setup_event_store_test_app().await;
+        let source = act_get_entry(&app, TEST_VALID_UUID).await.cost_shares()[0].cost_bearer_id;
+        let cash_id = arrange_cost_bearer(&app, "Cash").await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": cash_id, "amount": 12.5}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Some Description"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(source, cash_id);
+
+        let merge_uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}/merge", cash_id);
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &merge_uri,
+            Some(json!({"target_id": TEST_VALID_UUID})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        // replaying the stream on delete and restore has to know about the merge
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let restore_uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/restore",
+            TEST_VALID_UUID
+        );
+        let response = arrange_and_act_request(&app, Method::POST, &restore_uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let entry = act_get_entry(&app, TEST_VALID_UUID).await;
+
+        assert_eq!(entry.cost_shares()[0].cost_bearer_id, TEST_VALID_UUID);
+    }
+
+    #[tokio::test]
+    async fn expense_entry_event_store_rename_tag() {
+        let app = setup_event_store_test_app().await;
+        let entry = arrange_tagged_expense_entry(&app, json!(["vacation", "hotel"])).await;
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &format!("{TEST_LEDGER_PATH}/tags/vacation"),
+            Some(json!({"name": "hotel"})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let renamed_entry = act_get_entry(&app, entry.id()).await;
+        assert_eq!(tag_names(&renamed_entry), vec!["hotel"]);
+    }
+
+    fn entry_json(description: &str) -> Value {
+        json!({
+            "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 10.0}],
+            "expense_type": TEST_VALID_UUID,
+            "description": description
+        })
+    }
+
+    async fn arrange_and_act_batch_request(app: &Router, batch: Value) -> Vec<Value> {
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/batch");
+        let response = arrange_and_act_request(app, Method::POST, &uri, Some(batch)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into result list.")
+    }
+
+    fn statuses(results: &[Value]) -> Vec<u64> {
+        results
+            .iter()
+            .map(|result| result["status"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn expense_entry_batch_transactional() {
+        let app = setup_test_app().await;
+        let updated = arrange_tagged_expense_entry(&app, json!([])).await;
+        let deleted = arrange_tagged_expense_entry(&app, json!([])).await;
+
+        let results = arrange_and_act_batch_request(
+            &app,
+            json!({"operations": [
+                {"op": "create", "entry": entry_json("Train")},
+                {"op": "update", "id": updated.id(), "entry": entry_json("Taxi")},
+                {"op": "delete", "id": deleted.id()}
+            ]}),
+        )
+        .await;
+
+        assert_eq!(statuses(&results), vec![200, 200, 204]);
+        assert_eq!(results[2]["index"], 2);
+        assert_eq!(results[2]["id"], json!(deleted.id()));
+        let entries = arrange_and_act_list_request(&app, "").await;
+        let descriptions: Vec<&str> = entries.iter().map(|entry| entry.description()).collect();
+        assert!(descriptions.contains(&"Taxi"));
+        assert!(descriptions.contains(&"Train"));
+        assert!(!entries.iter().any(|entry| entry.id() == deleted.id()));
+        assert_eq!(
+            results[0]["entry"]["description"],
+            json!("Train"),
+            "created entry is returned"
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_entry_batch_transactional_rolls_back() {
+        let app = setup_test_app().await;
+        let entry = arrange_tagged_expense_entry(&app, json!([])).await;
+        let entry_count = arrange_and_act_list_request(&app, "").await.len();
+
+        let results = arrange_and_act_batch_request(
+            &app,
+            json!({"operations": [
+                {"op": "create", "entry": entry_json("Train")},
+                {"op": "delete", "id": entry.id()},
+                {"op": "update", "id": entry.id(), "entry": entry_json("Taxi")},
+                {"op": "create", "entry": entry_json(" ")}
+            ]}),
+        )
+        .await;
+
+        assert_eq!(statuses(&results), vec![424, 424, 404, 422]);
+        assert_eq!(
+            results[3]["error"],
+            json!("Json without valid description.")
+        );
+        assert_eq!(
+            arrange_and_act_list_request(&app, "").await.len(),
+            entry_count
+        );
+        assert_eq!(act_get_entry(&app, entry.id()).await.description(), "Hotel");
+    }
+
+    #[tokio::test]
+    async fn expense_entry_batch_best_effort() {
+        let app = setup_test_app().await;
+        let entry_count = arrange_and_act_list_request(&app, "").await.len();
+
+        let results = arrange_and_act_batch_request(
+            &app,
+            json!({"mode": "best_effort", "operations": [
+                {"op": "create", "entry": entry_json("Train")},
+                {"op": "delete", "id": Uuid::new_v4()},
+                {"op": "create", "entry": entry_json("Taxi")}
+            ]}),
+        )
+        .await;
+
+        assert_eq!(statuses(&results), vec![200, 404, 200]);
+        assert_eq!(
+            arrange_and_act_list_request(&app, "").await.len(),
+            entry_count + 2
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_entry_batch_fails_without_operations() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/batch");
+
+        let response =
+            arrange_and_act_request(&app, Method::POST, &uri, Some(json!({"operations": []})))
+                .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn expense_entry_batch_event_store() {
+        let app = setup_event_store_test_app().await;
+        let entry = arrange_tagged_expense_entry(&app, json!(["vacation"])).await;
+        let entry_count = arrange_and_act_list_request(&app, "").await.len();
+
+        let results = arrange_and_act_batch_request(
+            &app,
+            json!({"operations": [
+                {"op": "update", "id": entry.id(), "entry": entry_json("Taxi")},
+                {"op": "update", "id": entry.id(), "entry": entry_json("Train")},
+                {"op": "create", "entry": entry_json("Bus")}
+            ]}),
+        )
+        .await;
+
+        assert_eq!(statuses(&results), vec![200, 200, 200]);
+        assert_eq!(act_get_entry(&app, entry.id()).await.description(), "Train");
+        assert_eq!(
+            arrange_and_act_list_request(&app, "").await.len(),
+            entry_count + 1
+        );
+    }
+
+    fn purchase_json(cost_bearer_id: Uuid, amount: f64, description: &str, day: u32) -> Value {
+        json!({
+            "cost_shares": [{"cost_bearer_id": cost_bearer_id, "amount": amount}],
+            "expense_type": TEST_VALID_UUID,
+            "description": description,
+            "expense_date": Utc.with_ymd_and_hms(2025, 3, day, 12, 0, 0).unwrap()
+        })
+    }
+
+    async fn arrange_purchase(app: &Router, purchase: Value) -> ExpenseEntry {
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+        let response = arrange_and_act_request(app, Method::POST, &uri, Some(purchase)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into ExpenseEntry struct.")
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_warns_possible_duplicate() {
+        let app = setup_test_app().await;
+        let cost_bearer_id = TEST_VALID_UUID;
+        let manual =
+            arrange_purchase(&app, purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 10)).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(purchase_json(cost_bearer_id, 42.5, "REWE MARKT", 11)),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get("possible-duplicates").unwrap(),
+            &manual.id().to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_without_duplicate() {
+        let app = setup_test_app().await;
+        let cost_bearer_id = TEST_VALID_UUID;
+        arrange_purchase(&app, purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 10)).await;
+        let cash = arrange_cost_bearer(&app, "Cash").await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+
+        for purchase in [
+            purchase_json(cash, 42.0, "Rewe Markt", 10),
+            purchase_json(cost_bearer_id, 50.0, "Rewe Markt", 10),
+            purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 20),
+            purchase_json(cost_bearer_id, 42.0, "Cinema", 12),
+        ] {
+            let response = arrange_and_act_request(&app, Method::POST, &uri, Some(purchase)).await;
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response.headers().get("possible-duplicates").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_blocks_duplicate() {
+        let services = setup_test_services_with_duplicate_policy(DuplicatePolicy::Block);
+        let app = crate::api::routes::setup_routing(services).await;
+        let cost_bearer_id = TEST_VALID_UUID;
+        let manual =
+            arrange_purchase(&app, purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 10)).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+        let imported = purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 10);
+
+        let blocked =
+            arrange_and_act_request(&app, Method::POST, &uri, Some(imported.clone())).await;
+        let allowed = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{uri}?allow_duplicate=true"),
+            Some(imported),
+        )
+        .await;
+
+        assert_eq!(blocked.status(), StatusCode::CONFLICT);
+        let body = axum::body::to_bytes(blocked.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        assert_eq!(
+            String::from_utf8(body.to_vec()).unwrap(),
+            format!("Expense entry looks like a duplicate of {}.", manual.id())
+        );
+        assert_eq!(allowed.status(), StatusCode::OK);
+        assert!(allowed.headers().get("possible-duplicates").is_some());
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_ignores_duplicate_when_off() {
+        let services = setup_test_services_with_duplicate_policy(DuplicatePolicy::Off);
+        let app = crate::api::routes::setup_routing(services).await;
+        let cost_bearer_id = TEST_VALID_UUID;
+        arrange_purchase(&app, purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 10)).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 10)),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get("possible-duplicates").is_none());
+    }
+
+    #[tokio::test]
+    async fn expense_entry_duplicates_review() {
+        let app = setup_test_app().await;
+        let cost_bearer_id = TEST_VALID_UUID;
+        let manual =
+            arrange_purchase(&app, purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 10)).await;
+        let imported = arrange_purchase(
+            &app,
+            purchase_json(cost_bearer_id, 42.0, "REWE Markt 1234", 11),
+        )
+        .await;
+        arrange_purchase(&app, purchase_json(cost_bearer_id, 42.0, "Cinema", 11)).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/duplicates?from=2025-03-01");
+
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let pairs: Vec<Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0]["entry"]["id"], json!(imported.id()));
+        assert_eq!(pairs[0]["duplicate_of"]["id"], json!(manual.id()));
+        let score = pairs[0]["score"].as_f64().unwrap();
+        assert!((0.75..1.0).contains(&score), "score {score}");
+    }
+
+    #[tokio::test]
+    async fn expense_entry_batch_reports_possible_duplicates() {
+        let app = setup_test_app().await;
+        let cost_bearer_id = TEST_VALID_UUID;
+        let manual =
+            arrange_purchase(&app, purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 10)).await;
+
+        let results = arrange_and_act_batch_request(
+            &app,
+            json!({"operations": [
+                {"op": "create", "entry": purchase_json(cost_bearer_id, 42.0, "Rewe Markt", 10)}
+            ]}),
+        )
+        .await;
+
+        assert_eq!(results[0]["possible_duplicates"], json!([manual.id()]));
+    }
 }
@@ -1,74 +1,122 @@
+use axum::extract::{Query, State};
 use axum::{Json, extract::Path, http::StatusCode};
 use uuid::Uuid;
 
+use crate::api::routes::Services;
 use crate::domain::expense_type::ExpenseType;
 use crate::service::application_error::ApplicationError;
 use crate::service::expense_type::ExpenseTypeNew;
 
 pub async fn expense_type_post(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
     expense_type: Json<ExpenseTypeNew>,
 ) -> Result<Json<ExpenseType>, ApplicationError> {
     let new_expense_type_dto: ExpenseTypeNew = expense_type.0;
-    let created_expense_type = crate::service::command::expense_type::create(new_expense_type_dto)?;
+    let created_expense_type = services
+        .expense_type_service
+        .create(ledger_id, new_expense_type_dto)?;
     Ok(Json(created_expense_type))
 }
 
 pub async fn expense_type_update(
-    Path(id): Path<Uuid>,
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
     expense_type: Json<ExpenseTypeNew>,
 ) -> Result<Json<ExpenseType>, ApplicationError> {
     let update_expense_type_dto: ExpenseTypeNew = expense_type.0;
     let updated_expense_type =
-        crate::service::command::expense_type::update(id, update_expense_type_dto)?;
+        services
+            .expense_type_service
+            .update(ledger_id, id, update_expense_type_dto)?;
     Ok(Json(updated_expense_type))
 }
 
-pub async fn expense_type_delete(Path(id): Path<Uuid>) -> Result<StatusCode, ApplicationError> {
-    crate::service::command::expense_type::delete(id)?;
+// moves the references of a deleted expense type or cost bearer to another one
+#[derive(serde::Deserialize)]
+pub struct ReassignQuery {
+    pub reassign_to: Option<Uuid>,
+}
+
+// the entity a merged expense type or cost bearer is merged into
+#[derive(serde::Deserialize)]
+pub struct MergeTarget {
+    pub target_id: Uuid,
+}
+
+pub async fn expense_type_merge(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+    target: Json<MergeTarget>,
+) -> Result<Json<ExpenseType>, ApplicationError> {
+    let merged_expense_type =
+        services
+            .expense_type_service
+            .merge(ledger_id, id, target.0.target_id)?;
+    Ok(Json(merged_expense_type))
+}
+
+pub async fn expense_type_delete(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<ReassignQuery>,
+) -> Result<StatusCode, ApplicationError> {
+    services
+        .expense_type_service
+        .delete(ledger_id, id, query.reassign_to)?;
     Ok(StatusCode::NO_CONTENT)
 }
 
-pub async fn expense_type_get(Path(id): Path<Uuid>) -> Result<Json<ExpenseType>, ApplicationError> {
-    let found_expense_type = crate::service::query::expense_type::get(id)?;
+pub async fn expense_type_restore(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ExpenseType>, ApplicationError> {
+    let restored_expense_type = services.expense_type_service.restore(ledger_id, id)?;
+    Ok(Json(restored_expense_type))
+}
+
+pub async fn expense_type_get(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ExpenseType>, ApplicationError> {
+    let found_expense_type = services.expense_type_service.get(ledger_id, id)?;
     Ok(Json(found_expense_type))
 }
 
+pub async fn expense_type_list(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+) -> Result<Json<Vec<ExpenseType>>, ApplicationError> {
+    let expense_types = services.expense_type_service.list(ledger_id)?;
+    Ok(Json(expense_types))
+}
+
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
-
-    use crate::api::routes::Services;
-    use crate::repository::sqliterepository::expense_entry::{
-        ExpenseEntryReadSqliteRepository, ExpenseEntryWriteSqliteRepository,
-    };
-    use crate::service::expense_entry::ExpenseEntryService;
+    use crate::domain::expense_type::{ExpenseType, ExpenseTypeTotal};
     use crate::service::expense_type::ExpenseTypeNew;
-    use crate::test_util::test_utility::{TEST_INVALID_UUID, TEST_VALID_UUID};
+    use crate::test_util::test_utility::{
+        TEST_INVALID_UUID, TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services,
+    };
     use axum::Router;
     use axum::{
         body::Body,
         http::{Method, Request, StatusCode},
         response::Response,
     };
-    use serde_json::json;
+    use serde_json::{Value, json};
     use tower::ServiceExt;
+    use uuid::Uuid;
 
     async fn setup_test_app() -> Router {
-        let read_repo = Arc::new(ExpenseEntryReadSqliteRepository::new());
-        let write_repo = Arc::new(ExpenseEntryWriteSqliteRepository::new());
-        let expense_entry_service = Arc::new(ExpenseEntryService::new(read_repo, write_repo));
-        let services = Services {
-            expense_entry_service: expense_entry_service.clone(),
-        };
+        let services = setup_test_services();
 
-        crate::api::routes::setup_routing()
-            .await
-            .with_state(services)
+        crate::api::routes::setup_routing(services).await
     }
 
     async fn arrange_and_act_get_request(id: &str) -> Response<Body> {
         let app = setup_test_app().await;
-        let uri = format!("/expense_types/{}", id);
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", id);
 
         let request = Request::builder()
             .method(Method::GET)
@@ -76,17 +124,14 @@ mod tests {
             .body(Body::empty())
             .expect("Failed to finalize request.");
 
-        let response = app
-            .oneshot(request)
+        app.oneshot(request)
             .await
-            .expect("Failed to receive response.");
-
-        response
+            .expect("Failed to receive response.")
     }
 
     async fn arrange_and_act_post_request(expense_type: String) -> Response<Body> {
         let app = setup_test_app().await;
-        let uri = "/expense_types";
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types");
         let body = Body::from(expense_type);
 
         let request = Request::builder()
@@ -96,17 +141,14 @@ mod tests {
             .body(body)
             .expect("Failed to finalize request.");
 
-        let response = app
-            .oneshot(request)
+        app.oneshot(request)
             .await
-            .expect("Failed to receive response.");
-
-        response
+            .expect("Failed to receive response.")
     }
 
     async fn arrange_and_act_delete_request(id: &str) -> Response<Body> {
         let app = setup_test_app().await;
-        let uri = format!("/expense_types/{}", id);
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", id);
 
         let request = Request::builder()
             .method(Method::DELETE)
@@ -114,12 +156,54 @@ mod tests {
             .body(Body::empty())
             .expect("Failed to finalize request.");
 
-        let response = app
+        app.oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
             .oneshot(request)
             .await
-            .expect("Failed to receive response.");
+            .expect("Failed to receive response.")
+    }
+
+    async fn arrange_expense_type(
+        app: &Router,
+        name: &str,
+        parent_id: Option<Uuid>,
+    ) -> ExpenseType {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_types"),
+            Some(json!({"name": name, "description": name, "parent_id": parent_id})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into ExpenseType struct.")
+    }
 
-        response
+    async fn error_message(response: Response<Body>) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        String::from_utf8(body.to_vec()).unwrap()
     }
 
     #[tokio::test]
@@ -164,6 +248,7 @@ mod tests {
         let new_expense_type = ExpenseTypeNew {
             name: String::from("Transportation"),
             description: String::from("Expenses related to transportation and travel"),
+            parent_id: None,
         };
         let response = arrange_and_act_post_request(json!(new_expense_type).to_string()).await;
 
@@ -250,44 +335,218 @@ mod tests {
         assert_eq!(error_message, "Json without valid description.");
     }
 
-    // this can only happen once the DB is implemented
-    /*#[tokio::test]
+    #[tokio::test]
     async fn expense_type_post_fails_duplicate_name() {
-        // First, create an expense type
-        let first_expense_type = ExpenseTypeNew{
-            name: String::from("Food"),
-            description: String::from("First food expense type")
+        // the seeded "Food" expense type already exists on the top level
+        let duplicate_expense_type = ExpenseTypeNew {
+            name: String::from("food"),
+            description: String::from("Second food expense type"),
+            parent_id: None,
         };
-        let response = arrange_and_act_post_request(json!(first_expense_type).to_string()).await;
+        let response =
+            arrange_and_act_post_request(json!(duplicate_expense_type).to_string()).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error_message(response).await,
+            "Expense type with this name already exists."
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_type_post_with_parent() {
+        let app = setup_test_app().await;
+        let transport = arrange_expense_type(&app, "Transport", None).await;
+        let fuel = arrange_expense_type(&app, "Fuel", Some(transport.id())).await;
+        assert_eq!(fuel.parent_id(), Some(transport.id()));
+
+        // names only have to be unique among siblings
+        let leisure = arrange_expense_type(&app, "Leisure", None).await;
+        arrange_expense_type(&app, "Fuel", Some(leisure.id())).await;
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_types"),
+            Some(json!({"name": "Fuel", "description": "Fuel", "parent_id": transport.id()})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error_message(response).await,
+            "Expense type with this name already exists."
+        );
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/expense_types"),
+            None,
+        )
+        .await;
         assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let expense_types: Vec<ExpenseType> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(expense_types.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn expense_type_post_fails_unknown_parent() {
+        let response = arrange_and_act_post_request(
+            json!({"name": "Fuel", "description": "Fuel", "parent_id": TEST_INVALID_UUID})
+                .to_string(),
+        )
+        .await;
 
-        // Then try to create another with the same name
-        let duplicate_expense_type = ExpenseTypeNew{
-            name: String::from("Food"),
-            description: String::from("Second food expense type")
-        };
-        let response = arrange_and_act_post_request(json!(duplicate_expense_type).to_string()).await;
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
-        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.expect("Failed to receive body from response.");
-        let error_message = String::from_utf8(body.to_vec()).unwrap();
-        assert_eq!(error_message, "Expense type with this name already exists.");
-    }*/
+        assert_eq!(
+            error_message(response).await,
+            "Json without valid parent id."
+        );
+    }
 
     #[tokio::test]
     async fn expense_type_update() {
-        // TODO: Implement once the update function is implemented in the service layer
-        // This test will verify that updating an expense type works correctly
+        let app = setup_test_app().await;
+        let transport = arrange_expense_type(&app, "Transport", None).await;
+
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({"name": "Snacks", "description": "Food on the road", "parent_id": transport.id()})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let expense_type: ExpenseType = serde_json::from_slice(&body).unwrap();
+        assert_eq!(expense_type.id(), TEST_VALID_UUID);
+        assert_eq!(expense_type.name(), "Snacks");
+        assert_eq!(expense_type.parent_id(), Some(transport.id()));
     }
 
     #[tokio::test]
     async fn expense_type_update_fails() {
-        // TODO: Implement once the update function is implemented in the service layer
-        // This test will verify that updating an expense type fails appropriately
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", TEST_INVALID_UUID);
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({"name": "Snacks", "description": "Snacks"})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(error_message(response).await, "Expense type not found.");
+    }
+
+    #[tokio::test]
+    async fn expense_type_update_fails_cyclic_parent() {
+        let app = setup_test_app().await;
+        let transport = arrange_expense_type(&app, "Transport", None).await;
+        let public_transit =
+            arrange_expense_type(&app, "Public transit", Some(transport.id())).await;
+        let train = arrange_expense_type(&app, "Train", Some(public_transit.id())).await;
+
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", transport.id());
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({"name": "Transport", "description": "Transport", "parent_id": train.id()})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error_message(response).await,
+            "Expense type cannot be its own ancestor."
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_type_delete_fails_with_sub_types() {
+        let app = setup_test_app().await;
+        let transport = arrange_expense_type(&app, "Transport", None).await;
+        arrange_expense_type(&app, "Fuel", Some(transport.id())).await;
+
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", transport.id());
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            error_message(response).await,
+            "Expense type still has sub types."
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_type_report_rolls_up() {
+        let app = setup_test_app().await;
+        let transport = arrange_expense_type(&app, "Transport", None).await;
+        let fuel = arrange_expense_type(&app, "Fuel", Some(transport.id())).await;
+        let public_transit =
+            arrange_expense_type(&app, "Public transit", Some(transport.id())).await;
+
+        for (expense_type, expense_date, amount) in [
+            (fuel.id(), "2026-05-03", 60.0),
+            (fuel.id(), "2026-05-20", 40.0),
+            (public_transit.id(), "2026-05-11", 25.0),
+            (transport.id(), "2026-05-12", 5.0),
+            (public_transit.id(), "2026-06-01", 99.0),
+        ] {
+            let response = arrange_and_act_request(
+                &app,
+                Method::POST,
+                &format!("{TEST_LEDGER_PATH}/expense_entries"),
+                Some(json!({
+                    "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": amount}],
+                    "expense_type": expense_type,
+                    "description": "Commute",
+                    "expense_date": format!("{}T08:00:00Z", expense_date)
+                })),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/reports/expense_types?from=2026-05-01&to=2026-05-31"),
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let report: Vec<ExpenseTypeTotal> = serde_json::from_slice(&body).unwrap();
+        let total_of = |id: Uuid| {
+            report
+                .iter()
+                .find(|total| total.expense_type_id == id)
+                .map(|total| (total.amount, total.total_amount))
+                .unwrap()
+        };
+
+        assert_eq!(total_of(fuel.id()), (100.0, 100.0));
+        assert_eq!(total_of(public_transit.id()), (25.0, 25.0));
+        assert_eq!(total_of(transport.id()), (5.0, 130.0));
     }
 
     #[tokio::test]
     async fn expense_type_delete() {
-        let response = arrange_and_act_delete_request(&String::from(TEST_VALID_UUID)).await;
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries", None).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", groceries.id());
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
 
         assert_eq!(response.status(), StatusCode::NO_CONTENT);
         let body = axum::body::to_bytes(response.into_body(), usize::MAX)
@@ -316,4 +575,289 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
+
+    #[tokio::test]
+    async fn expense_type_restore() {
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries", None).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", groceries.id());
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let restore_uri = format!(
+            "{TEST_LEDGER_PATH}/expense_types/{}/restore",
+            groceries.id()
+        );
+        let response = arrange_and_act_request(&app, Method::POST, &restore_uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // restoring twice finds nothing in the trash anymore
+        let response = arrange_and_act_request(&app, Method::POST, &restore_uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn expense_type_restore_fails_deleted_parent() {
+        let app = setup_test_app().await;
+        let transport = arrange_expense_type(&app, "Transport", None).await;
+        let fuel = arrange_expense_type(&app, "Fuel", Some(transport.id())).await;
+        for id in [fuel.id(), transport.id()] {
+            let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", id);
+            let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        }
+
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}/restore", fuel.id());
+        let response = arrange_and_act_request(&app, Method::POST, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            error_message(response).await,
+            "Parent expense type is deleted."
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_type_restore_fails_name_taken() {
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries", None).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", groceries.id());
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        // the name is free again while the original is in the trash
+        arrange_expense_type(&app, "Groceries", None).await;
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_types/{}/restore",
+            groceries.id()
+        );
+        let response = arrange_and_act_request(&app, Method::POST, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            error_message(response).await,
+            "Expense type with this name already exists."
+        );
+    }
+
+    async fn arrange_expense_entry(app: &Router, expense_type: Uuid) -> Value {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 10.0}],
+                "expense_type": expense_type,
+                "description": "Groceries"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse expense entry.")
+    }
+
+    #[tokio::test]
+    async fn expense_type_delete_fails_referenced() {
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries", None).await;
+        arrange_expense_entry(&app, groceries.id()).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", groceries.id());
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            error_message(response).await,
+            "Expense type is still used by 1 expense entry."
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_type_delete_with_reassign() {
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries", None).await;
+        let entry = arrange_expense_entry(&app, groceries.id()).await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_types/{}?reassign_to={}",
+            groceries.id(),
+            TEST_VALID_UUID
+        );
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}",
+            entry["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let entry: Value = serde_json::from_slice(&body).expect("Failed to parse expense entry.");
+        assert_eq!(entry["expense_type"], json!(TEST_VALID_UUID));
+    }
+
+    #[tokio::test]
+    async fn expense_type_delete_with_reassign_fails_self() {
+        let app = setup_test_app().await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_types/{}?reassign_to={}",
+            TEST_VALID_UUID, TEST_VALID_UUID
+        );
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error_message(response).await,
+            "Query without valid reassign target."
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_type_merge() {
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries", None).await;
+        let entry = arrange_expense_entry(&app, groceries.id()).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}/merge", groceries.id());
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(json!({"target_id": TEST_VALID_UUID})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let merged: ExpenseType = serde_json::from_slice(&body)
+            .expect("Failed to parse response into ExpenseType struct.");
+        assert_eq!(merged.id(), TEST_VALID_UUID);
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}",
+            entry["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let entry: Value = serde_json::from_slice(&body).expect("Failed to parse expense entry.");
+        assert_eq!(entry["expense_type"], json!(TEST_VALID_UUID));
+        // the source is in the trash
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", groceries.id());
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    async fn arrange_budget(app: &Router, expense_type: Uuid) -> Value {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/budgets"),
+            Some(json!({"expense_type": expense_type, "period": "monthly", "limit": 400.0})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse budget.")
+    }
+
+    #[tokio::test]
+    async fn expense_type_delete_fails_referenced_by_budget() {
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries", None).await;
+        arrange_budget(&app, groceries.id()).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", groceries.id());
+
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            error_message(response).await,
+            "Expense type is still used by 1 budgets, recurring expenses or categorisation rules."
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_type_merge_moves_budgets() {
+        let app = setup_test_app().await;
+        let groceries = arrange_expense_type(&app, "Groceries", None).await;
+        let budget = arrange_budget(&app, groceries.id()).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}/merge", groceries.id());
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(json!({"target_id": TEST_VALID_UUID})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/budgets/{}",
+            budget["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let budget: Value = serde_json::from_slice(&body).expect("Failed to parse budget.");
+        assert_eq!(budget["expense_type"], json!(TEST_VALID_UUID));
+    }
+
+    #[tokio::test]
+    async fn expense_type_merge_fails_with_sub_types() {
+        let app = setup_test_app().await;
+        let transport = arrange_expense_type(&app, "Transport", None).await;
+        arrange_expense_type(&app, "Fuel", Some(transport.id())).await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}/merge", transport.id());
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(json!({"target_id": TEST_VALID_UUID})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            error_message(response).await,
+            "Expense type still has sub types."
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_type_merge_fails_unknown_target() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}/merge", TEST_VALID_UUID);
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &uri,
+            Some(json!({"target_id": TEST_INVALID_UUID})),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error_message(response).await,
+            "Json without valid target id."
+        );
+    }
 }
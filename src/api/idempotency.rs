@@ -0,0 +1,395 @@
+use axum::body::{Body, to_bytes};
+use axum::extract::{Path, Request, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::api::ledger::LedgerPath;
+use crate::api::routes::Services;
+use crate::domain::idempotency::IdempotentResponse;
+use crate::domain::ledger_member::LedgerRole;
+use crate::service::idempotency::IdempotencyClaim;
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+// set on responses that were stored for an earlier request with the same key
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+
+// same as the default body limit of the json extractor behind it
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+fn replay(response: IdempotentResponse) -> Response {
+    let mut replayed = Response::new(Body::from(response.body));
+    *replayed.status_mut() =
+        StatusCode::from_u16(response.status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let headers = replayed.headers_mut();
+    if let Some(value) = response
+        .content_type
+        .and_then(|content_type| HeaderValue::from_str(&content_type).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, value);
+    }
+    headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    replayed
+}
+
+// without a stored response a retry would wait for the key to expire
+fn release(services: &Services, key: &str) {
+    if let Err(err) = services.idempotency_service.release(key) {
+        eprintln!("Releasing the idempotency key failed: {}", err.message);
+    }
+}
+
+// handles a request sent with an idempotency key only once, retries get the stored response if
+// the sender still has the role the route needs
+pub async fn idempotency(
+    State((services, role)): State<(Services, LedgerRole)>,
+    Path(path): Path<LedgerPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = request
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+    else {
+        return next.run(request).await;
+    };
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_BODY_SIZE).await else {
+        return (StatusCode::PAYLOAD_TOO_LARGE, "Request body is too large.").into_response();
+    };
+    if let Err(err) = services.ledger_service.authorize(path.ledger_id, role) {
+        return err.into_response();
+    }
+    let claim =
+        services
+            .idempotency_service
+            .claim(&key, parts.method.as_str(), parts.uri.path(), &body);
+    match claim {
+        Ok(IdempotencyClaim::New) => {}
+        Ok(IdempotencyClaim::Replay(response)) => return replay(response),
+        Err(err) => return err.into_response(),
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    // server errors are not kept, the client may retry them with the same key
+    if response.status().is_server_error() {
+        release(&services, &key);
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let Ok(body) = to_bytes(body, usize::MAX).await else {
+        release(&services, &key);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let stored = IdempotentResponse {
+        status_code: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from),
+        body: body.to_vec(),
+    };
+    if let Err(err) = services.idempotency_service.complete(&key, &stored) {
+        eprintln!("Storing the idempotent response failed: {}", err.message);
+        release(&services, &key);
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::routes::Services;
+    use crate::domain::ledger_member::LedgerRole;
+    use crate::service::api_key::ApiKeyNew;
+    use crate::service::ledger::LedgerMemberNew;
+    use crate::test_util::test_utility::{
+        TEST_LEDGER_ID, TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services,
+        setup_test_services_with_authentication,
+    };
+    use axum::{
+        Router,
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    async fn setup_test_app() -> Router {
+        let services = setup_test_services();
+
+        crate::api::routes::setup_routing(services).await
+    }
+
+    async fn arrange_and_act_post_request(
+        app: &Router,
+        uri: &str,
+        key: Option<&str>,
+        body: &Value,
+    ) -> Response<Body> {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(key) = key {
+            request = request.header("idempotency-key", key);
+        }
+        let request = request
+            .body(Body::from(body.to_string()))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn arrange_and_act_authorized_post_request(
+        app: &Router,
+        authorization: &str,
+        key: &str,
+        body: &Value,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("{TEST_LEDGER_PATH}/expense_entries"))
+            .header("content-type", "application/json")
+            .header("authorization", authorization)
+            .header("idempotency-key", key)
+            .body(Body::from(body.to_string()))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    fn arrange_api_key(services: &Services, name: &str, user: Option<&str>) -> String {
+        let created = services
+            .api_key_service
+            .create(ApiKeyNew {
+                name: String::from(name),
+                user: user.map(String::from),
+            })
+            .unwrap_or_else(|err| panic!("Failed to create api key: {}", err.message));
+        format!("ApiKey {}", created.key)
+    }
+
+    async fn body_json(response: Response<Body>) -> Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into json.")
+    }
+
+    async fn error_message(response: Response<Body>) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    fn expense_entry(description: &str) -> Value {
+        json!({
+            "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 10.0}],
+            "expense_type": TEST_VALID_UUID,
+            "description": description
+        })
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_retry_is_replayed() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+        let entry = expense_entry("Groceries");
+
+        let first = arrange_and_act_post_request(&app, &uri, Some("retry-1"), &entry).await;
+        let second = arrange_and_act_post_request(&app, &uri, Some("retry-1"), &entry).await;
+
+        assert_eq!(first.status(), StatusCode::OK);
+        assert!(first.headers().get("idempotent-replayed").is_none());
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(second.headers().get("idempotent-replayed").unwrap(), "true");
+        assert_eq!(
+            second.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        assert_eq!(body_json(first).await["id"], body_json(second).await["id"]);
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_without_key_creates_twice() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+        let entry = expense_entry("Groceries");
+
+        let first = arrange_and_act_post_request(&app, &uri, None, &entry).await;
+        let second = arrange_and_act_post_request(&app, &uri, None, &entry).await;
+
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_ne!(body_json(first).await["id"], body_json(second).await["id"]);
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_different_keys_create_twice() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+        let entry = expense_entry("Groceries");
+
+        let first = arrange_and_act_post_request(&app, &uri, Some("key-1"), &entry).await;
+        let second = arrange_and_act_post_request(&app, &uri, Some("key-2"), &entry).await;
+
+        assert_ne!(body_json(first).await["id"], body_json(second).await["id"]);
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_fails_key_reused_for_other_body() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+
+        arrange_and_act_post_request(&app, &uri, Some("reused"), &expense_entry("Groceries")).await;
+        let response =
+            arrange_and_act_post_request(&app, &uri, Some("reused"), &expense_entry("Rent")).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error_message(response).await,
+            "Idempotency-Key was already used for a different request."
+        );
+    }
+
+    #[tokio::test]
+    async fn expense_entry_post_fails_invalid_key() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+
+        let response =
+            arrange_and_act_post_request(&app, &uri, Some(""), &expense_entry("Groceries")).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error_message(response).await,
+            "Request without valid Idempotency-Key."
+        );
+    }
+
+    #[tokio::test]
+    async fn client_error_is_replayed() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries");
+        let entry = json!({"description": "Groceries"});
+
+        let first = arrange_and_act_post_request(&app, &uri, Some("invalid"), &entry).await;
+        let second = arrange_and_act_post_request(&app, &uri, Some("invalid"), &entry).await;
+
+        assert_eq!(first.status(), second.status());
+        assert!(first.status().is_client_error());
+        assert_eq!(second.headers().get("idempotent-replayed").unwrap(), "true");
+    }
+
+    #[tokio::test]
+    async fn cost_bearer_post_retry_is_replayed() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers");
+        let cost_bearer = json!({"name": "Alice", "exists_from": "2025-01-01T00:00:00Z"});
+
+        let first = arrange_and_act_post_request(&app, &uri, Some("bearer"), &cost_bearer).await;
+        let second = arrange_and_act_post_request(&app, &uri, Some("bearer"), &cost_bearer).await;
+
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(body_json(first).await["id"], body_json(second).await["id"]);
+    }
+
+    #[tokio::test]
+    async fn expense_type_post_retry_is_replayed() {
+        let app = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_types");
+        let expense_type = json!({"name": "Hobbies", "description": "Leisure"});
+
+        let first = arrange_and_act_post_request(&app, &uri, Some("type"), &expense_type).await;
+        let second = arrange_and_act_post_request(&app, &uri, Some("type"), &expense_type).await;
+
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(body_json(first).await["id"], body_json(second).await["id"]);
+    }
+
+    #[tokio::test]
+    async fn key_reused_on_other_route_fails() {
+        let app = setup_test_app().await;
+        let cost_bearer = json!({"name": "Hobbies", "exists_from": "2025-01-01T00:00:00Z"});
+
+        let first = arrange_and_act_post_request(
+            &app,
+            &format!("{TEST_LEDGER_PATH}/cost_bearers"),
+            Some("shared"),
+            &cost_bearer,
+        )
+        .await;
+        let second = arrange_and_act_post_request(
+            &app,
+            &format!("{TEST_LEDGER_PATH}/expense_types"),
+            Some("shared"),
+            &json!({"name": "Hobbies", "description": "Leisure"}),
+        )
+        .await;
+
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(second.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn api_keys_with_same_name_do_not_share_keys() {
+        let services = setup_test_services_with_authentication();
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        let first_key = arrange_api_key(&services, "importer", None);
+        let second_key = arrange_api_key(&services, "importer", None);
+        let entry = expense_entry("Groceries");
+
+        let first =
+            arrange_and_act_authorized_post_request(&app, &first_key, "import-1", &entry).await;
+        let second =
+            arrange_and_act_authorized_post_request(&app, &second_key, "import-1", &entry).await;
+
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(second.status(), StatusCode::OK);
+        assert!(second.headers().get("idempotent-replayed").is_none());
+        assert_ne!(body_json(first).await["id"], body_json(second).await["id"]);
+    }
+
+    #[tokio::test]
+    async fn replay_needs_the_role_of_the_route() {
+        let services = setup_test_services_with_authentication();
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        let set_role = |role: LedgerRole| {
+            services
+                .ledger_service
+                .set_member(
+                    TEST_LEDGER_ID,
+                    String::from("alice"),
+                    LedgerMemberNew { role },
+                )
+                .unwrap_or_else(|err| panic!("Failed to set ledger member: {}", err.message));
+        };
+        let authorization = arrange_api_key(&services, "alice's phone", Some("alice"));
+        let entry = expense_entry("Groceries");
+        set_role(LedgerRole::Editor);
+        let first =
+            arrange_and_act_authorized_post_request(&app, &authorization, "phone-1", &entry).await;
+        assert_eq!(first.status(), StatusCode::OK);
+
+        set_role(LedgerRole::Viewer);
+        let second =
+            arrange_and_act_authorized_post_request(&app, &authorization, "phone-1", &entry).await;
+
+        assert_eq!(second.status(), StatusCode::FORBIDDEN);
+        assert!(second.headers().get("idempotent-replayed").is_none());
+    }
+}
@@ -0,0 +1,925 @@
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::{Json, extract::Path};
+use uuid::Uuid;
+
+use crate::api::routes::Services;
+use crate::domain::ledger::Ledger;
+use crate::domain::ledger_member::LedgerMember;
+use crate::service::application_error::ApplicationError;
+use crate::service::ledger::{LedgerMemberNew, LedgerNew};
+
+// the ledger part of every scoped route, the other path parameters are left to the handlers
+#[derive(serde::Deserialize)]
+pub struct LedgerPath {
+    pub(crate) ledger_id: Uuid,
+}
+
+// lets only requests for an existing ledger through to the scoped routes
+pub async fn ledger_scope(
+    State(services): State<Services>,
+    Path(path): Path<LedgerPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match services.ledger_service.get(path.ledger_id) {
+        Ok(_) => next.run(request).await,
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn ledger_post(
+    State(services): State<Services>,
+    ledger: Json<LedgerNew>,
+) -> Result<Json<Ledger>, ApplicationError> {
+    let new_ledger_dto: LedgerNew = ledger.0;
+    let created_ledger = services.ledger_service.create(new_ledger_dto)?;
+    Ok(Json(created_ledger))
+}
+
+pub async fn ledger_list(
+    State(services): State<Services>,
+) -> Result<Json<Vec<Ledger>>, ApplicationError> {
+    let ledgers = services.ledger_service.list()?;
+    Ok(Json(ledgers))
+}
+
+pub async fn ledger_get(
+    State(services): State<Services>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Ledger>, ApplicationError> {
+    let found_ledger = services.ledger_service.get(id)?;
+    Ok(Json(found_ledger))
+}
+
+pub async fn ledger_member_list(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+) -> Result<Json<Vec<LedgerMember>>, ApplicationError> {
+    let members = services.ledger_service.members(ledger_id)?;
+    Ok(Json(members))
+}
+
+pub async fn ledger_member_put(
+    State(services): State<Services>,
+    Path((ledger_id, user)): Path<(Uuid, String)>,
+    member: Json<LedgerMemberNew>,
+) -> Result<Json<LedgerMember>, ApplicationError> {
+    let stored_member = services
+        .ledger_service
+        .set_member(ledger_id, user, member.0)?;
+    Ok(Json(stored_member))
+}
+
+pub async fn ledger_member_delete(
+    State(services): State<Services>,
+    Path((ledger_id, user)): Path<(Uuid, String)>,
+) -> Result<StatusCode, ApplicationError> {
+    services.ledger_service.remove_member(ledger_id, &user)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crate::api::routes::Services;
+    use crate::domain::cost_bearer::CostBearer;
+    use crate::domain::expense_entry::ExpenseEntry;
+    use crate::domain::expense_type::ExpenseType;
+    use crate::domain::ledger::Ledger;
+    use crate::domain::ledger_member::{LedgerMember, LedgerRole};
+    use crate::domain::projection::{CostBearerBalance, ExpenseEntryRow};
+    use crate::test_util::test_utility::{
+        TEST_JWT_AUDIENCE, TEST_JWT_ISSUER, TEST_JWT_SECRET, TEST_LEDGER_ID, TEST_LEDGER_PATH,
+        TEST_VALID_UUID, setup_test_services, setup_test_services_with_authentication,
+    };
+    use axum::{
+        Json, Router,
+        body::{Body, BodyDataStream},
+        extract::State,
+        http::{Method, Request, StatusCode},
+        response::Response,
+        routing::post,
+    };
+    use chrono::Utc;
+    use futures_util::StreamExt;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn setup_test_app() -> Router {
+        let services = setup_test_services();
+
+        crate::api::routes::setup_routing(services).await
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = match body {
+            Some(body) => Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string())),
+            None => Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty()),
+        }
+        .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    // sends the request as the sso user with the given subject
+    async fn arrange_and_act_user_request(
+        app: &Router,
+        user: &str,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let claims = json!({
+            "sub": user,
+            "iss": TEST_JWT_ISSUER,
+            "aud": TEST_JWT_AUDIENCE,
+            "exp": chrono::Utc::now().timestamp() + 300,
+        });
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(TEST_JWT_SECRET.as_bytes()),
+        )
+        .expect("Failed to sign token.");
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {token}"));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    // alice owns a new ledger, bob and carol join it with the given roles
+    async fn arrange_shared_ledger(app: &Router, bob: LedgerRole, carol: LedgerRole) -> Ledger {
+        let response = arrange_and_act_user_request(
+            app,
+            "alice",
+            Method::POST,
+            "/ledgers",
+            Some(json!({"name": "Flat Share"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let ledger: Ledger = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into Ledger struct.");
+        for (user, role) in [("bob", bob), ("carol", carol)] {
+            let uri = format!("/ledgers/{}/members/{user}", ledger.id());
+            let response = arrange_and_act_user_request(
+                app,
+                "alice",
+                Method::PUT,
+                &uri,
+                Some(json!({"role": role})),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = arrange_and_act_user_request(
+            app,
+            "alice",
+            Method::POST,
+            &format!("/ledgers/{}/expense_types", ledger.id()),
+            Some(json!({"name": "Gifts", "description": "Presents for friends"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        ledger
+    }
+
+    // the expense type every shared ledger starts with
+    async fn act_expense_type(app: &Router, base: &str) -> Uuid {
+        let uri = format!("{base}/expense_types");
+        let response = arrange_and_act_user_request(app, "alice", Method::GET, &uri, None).await;
+        let expense_types: Vec<ExpenseType> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into ExpenseType structs.");
+        expense_types[0].id()
+    }
+
+    async fn read_body(response: Response<Body>) -> Vec<u8> {
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.")
+            .to_vec()
+    }
+
+    async fn arrange_ledger(app: &Router, name: &str) -> Ledger {
+        let response =
+            arrange_and_act_request(app, Method::POST, "/ledgers", Some(json!({"name": name})))
+                .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into Ledger struct.")
+    }
+
+    // alice keeps the first cost bearer private, carol books an entry on each of them
+    async fn arrange_private_entries(app: &Router, base: &str) -> Vec<Uuid> {
+        let mut cost_bearers = Vec::new();
+        for private_to in [json!(["alice"]), json!([])] {
+            cost_bearers.push(arrange_cost_bearer(app, base, private_to).await);
+        }
+        let mut entries = Vec::new();
+        for cost_bearer_id in cost_bearers {
+            entries.push(arrange_entry(app, base, cost_bearer_id).await);
+        }
+        entries
+    }
+
+    async fn arrange_cost_bearer(app: &Router, base: &str, private_to: Value) -> Uuid {
+        let response = arrange_and_act_user_request(
+            app,
+            "alice",
+            Method::POST,
+            &format!("{base}/cost_bearers"),
+            Some(json!({
+                "name": "Account",
+                "exists_from": "2025-01-01T00:00:00Z",
+                "private_to": private_to
+            })),
+        )
+        .await;
+        let cost_bearer: CostBearer = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into CostBearer struct.");
+        cost_bearer.id()
+    }
+
+    async fn arrange_entry(app: &Router, base: &str, cost_bearer_id: Uuid) -> Uuid {
+        let expense_type = act_expense_type(app, base).await;
+        let response = arrange_and_act_user_request(
+            app,
+            "carol",
+            Method::POST,
+            &format!("{base}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": cost_bearer_id, "amount": 30.0}],
+                "expense_type": expense_type,
+                "description": "Birthday present",
+                "tags": ["gift"]
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let entry: ExpenseEntry = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into ExpenseEntry struct.");
+        entry.id()
+    }
+
+    async fn act_json_user_request(app: &Router, user: &str, uri: &str) -> Value {
+        let response = arrange_and_act_user_request(app, user, Method::GET, uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into json.")
+    }
+
+    // the sender blocks on the runtime, so dispatching happens off the async workers
+    async fn dispatch(services: &Services) {
+        let outbox_service = services.outbox_service.clone();
+        tokio::task::spawn_blocking(move || outbox_service.dispatch(Utc::now()))
+            .await
+            .expect("Dispatcher panicked.")
+            .unwrap_or_else(|err| panic!("Dispatching failed: {}", err.message));
+    }
+
+    // the data of the next change on the event stream, none once the stream ended
+    async fn act_receive_change(body: &mut BodyDataStream) -> Option<Value> {
+        let mut text = String::new();
+        loop {
+            let chunk = tokio::time::timeout(Duration::from_secs(5), body.next())
+                .await
+                .expect("No event from event stream.")?
+                .expect("Failed to receive event.");
+            text.push_str(std::str::from_utf8(&chunk).expect("Event is no text."));
+            while let Some((event, rest)) = text.split_once("\n\n") {
+                if let Some(data) = event.lines().find_map(|line| line.strip_prefix("data: ")) {
+                    return Some(serde_json::from_str(data).expect("Failed to parse event data."));
+                }
+                text = rest.to_string();
+            }
+        }
+    }
+
+    // a local webhook receiver that records every payload
+    async fn start_receiver() -> (String, Arc<Mutex<Vec<Value>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let router =
+            Router::new()
+                .route(
+                    "/hook",
+                    post(
+                        |State(received): State<Arc<Mutex<Vec<Value>>>>,
+                         Json(payload): Json<Value>| async move {
+                            received.lock().unwrap().push(payload);
+                            StatusCode::OK
+                        },
+                    ),
+                )
+                .with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind receiver.");
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (url, received)
+    }
+
+    #[tokio::test]
+    async fn ledger_post_and_list() {
+        let app = setup_test_app().await;
+        let ledger = arrange_ledger(&app, "  Holiday Home ").await;
+        assert_eq!(ledger.name(), "Holiday Home");
+
+        let response = arrange_and_act_request(&app, Method::GET, "/ledgers", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let ledgers: Vec<Ledger> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into Ledger structs.");
+        let ids: Vec<Uuid> = ledgers.iter().map(|l| l.id()).collect();
+        assert!(ids.contains(&TEST_LEDGER_ID));
+        assert!(ids.contains(&ledger.id()));
+
+        let uri = format!("/ledgers/{}", ledger.id());
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn ledger_post_without_name() {
+        let app = setup_test_app().await;
+        let response =
+            arrange_and_act_request(&app, Method::POST, "/ledgers", Some(json!({"name": " "})))
+                .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn ledger_scope_unknown_ledger() {
+        let app = setup_test_app().await;
+        let uri = format!("/ledgers/{}/cost_bearers", Uuid::new_v4());
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = read_body(response).await;
+        assert_eq!(String::from_utf8(body).unwrap(), "Ledger not found.");
+    }
+
+    #[tokio::test]
+    async fn ledger_scope_hides_other_ledgers() {
+        let app = setup_test_app().await;
+        let other = arrange_ledger(&app, "Holiday Home").await;
+
+        let uri = format!("/ledgers/{}/cost_bearers/{}", other.id(), TEST_VALID_UUID);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let uri = format!(
+            "/ledgers/{}/expense_entries/{}",
+            other.id(),
+            TEST_VALID_UUID
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let uri = format!("/ledgers/{}/expense_types", other.id());
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let expense_types: Vec<ExpenseType> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into ExpenseType structs.");
+        assert!(expense_types.is_empty());
+    }
+
+    #[tokio::test]
+    async fn ledger_scope_rejects_foreign_cost_bearer() {
+        let app = setup_test_app().await;
+        let other = arrange_ledger(&app, "Holiday Home").await;
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("/ledgers/{}/cost_bearers", other.id()),
+            Some(json!({"name": "Holiday Account", "exists_from": "2025-01-01T00:00:00Z"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let foreign: CostBearer = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into CostBearer struct.");
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": foreign.id(), "amount": 10.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Groceries"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = read_body(response).await;
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "Json references data of another ledger."
+        );
+    }
+
+    #[tokio::test]
+    async fn ledger_members_of_shared_ledger() {
+        let app =
+            crate::api::routes::setup_routing(setup_test_services_with_authentication()).await;
+        let ledger = arrange_shared_ledger(&app, LedgerRole::Viewer, LedgerRole::Editor).await;
+
+        let uri = format!("/ledgers/{}/members", ledger.id());
+        let response = arrange_and_act_user_request(&app, "bob", Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let members: Vec<LedgerMember> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into LedgerMember structs.");
+        let roles: Vec<(&str, LedgerRole)> = members
+            .iter()
+            .map(|member| (member.user(), member.role()))
+            .collect();
+        assert_eq!(
+            roles,
+            vec![
+                ("alice", LedgerRole::Owner),
+                ("bob", LedgerRole::Viewer),
+                ("carol", LedgerRole::Editor)
+            ]
+        );
+
+        // users only see their own ledgers
+        let response =
+            arrange_and_act_user_request(&app, "dave", Method::GET, "/ledgers", None).await;
+        let ledgers: Vec<Ledger> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into Ledger structs.");
+        assert!(ledgers.is_empty());
+        let response = arrange_and_act_user_request(&app, "dave", Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_user_request(&app, "bob", Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn ledger_scope_hides_existence_from_non_members() {
+        let app =
+            crate::api::routes::setup_routing(setup_test_services_with_authentication()).await;
+        let ledger = arrange_shared_ledger(&app, LedgerRole::Viewer, LedgerRole::Editor).await;
+
+        let mut answers = Vec::new();
+        for ledger_id in [ledger.id(), Uuid::new_v4()] {
+            for uri in [
+                format!("/ledgers/{ledger_id}"),
+                format!("/ledgers/{ledger_id}/cost_bearers"),
+            ] {
+                let response =
+                    arrange_and_act_user_request(&app, "dave", Method::GET, &uri, None).await;
+                answers.push((response.status(), read_body(response).await));
+            }
+        }
+
+        assert!(answers.iter().all(|answer| *answer == answers[0]));
+        assert_eq!(answers[0].0, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn ledger_roles_limit_commands() {
+        let app =
+            crate::api::routes::setup_routing(setup_test_services_with_authentication()).await;
+        let ledger = arrange_shared_ledger(&app, LedgerRole::Viewer, LedgerRole::Editor).await;
+        let cost_bearers = format!("/ledgers/{}/cost_bearers", ledger.id());
+        let expense_entries = format!("/ledgers/{}/expense_entries", ledger.id());
+        let cost_bearer = json!({"name": "Shared Account", "exists_from": "2025-01-01T00:00:00Z"});
+
+        // only owners manage cost bearers
+        for user in ["bob", "carol"] {
+            let response = arrange_and_act_user_request(
+                &app,
+                user,
+                Method::POST,
+                &cost_bearers,
+                Some(cost_bearer.clone()),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+            let body = read_body(response).await;
+            assert_eq!(
+                String::from_utf8(body).unwrap(),
+                "Missing permission for this ledger."
+            );
+        }
+        let response = arrange_and_act_user_request(
+            &app,
+            "alice",
+            Method::POST,
+            &cost_bearers,
+            Some(cost_bearer),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let cost_bearer: CostBearer = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into CostBearer struct.");
+
+        // editors manage expense entries, viewers only read them
+        let expense_type = act_expense_type(&app, &format!("/ledgers/{}", ledger.id())).await;
+        let entry = json!({
+            "cost_shares": [{"cost_bearer_id": cost_bearer.id(), "amount": 10.0}],
+            "expense_type": expense_type,
+            "description": "Groceries"
+        });
+        let response = arrange_and_act_user_request(
+            &app,
+            "bob",
+            Method::POST,
+            &expense_entries,
+            Some(entry.clone()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = arrange_and_act_user_request(
+            &app,
+            "carol",
+            Method::POST,
+            &expense_entries,
+            Some(entry),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response =
+            arrange_and_act_user_request(&app, "bob", Method::GET, &expense_entries, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn ledger_scope_covers_webhooks_and_feeds() {
+        let app =
+            crate::api::routes::setup_routing(setup_test_services_with_authentication()).await;
+        let ledger = arrange_shared_ledger(&app, LedgerRole::Viewer, LedgerRole::Editor).await;
+        let webhooks = format!("/ledgers/{}/webhooks", ledger.id());
+        let subscription = json!({
+            "url": "https://example.org/hook",
+            "events": ["*"],
+            "secret": "s3cret"
+        });
+
+        // only owners hand the ledger's changes to other systems
+        for user in ["bob", "carol", "dave"] {
+            let response = arrange_and_act_user_request(
+                &app,
+                user,
+                Method::POST,
+                &webhooks,
+                Some(subscription.clone()),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+        let response = arrange_and_act_user_request(
+            &app,
+            "alice",
+            Method::POST,
+            &webhooks,
+            Some(subscription),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response =
+            arrange_and_act_user_request(&app, "alice", Method::GET, "/webhooks", None).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // the live feed is open to members only
+        let events = format!("/ledgers/{}/events", ledger.id());
+        let response = arrange_and_act_user_request(&app, "dave", Method::GET, &events, None).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = arrange_and_act_user_request(&app, "bob", Method::GET, &events, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn ledger_users_cannot_reach_instance_data() {
+        let app =
+            crate::api::routes::setup_routing(setup_test_services_with_authentication()).await;
+        arrange_shared_ledger(&app, LedgerRole::Viewer, LedgerRole::Editor).await;
+
+        for (method, uri) in [
+            (Method::POST, "/views/rebuild"),
+            (Method::GET, "/outbox/dead_letters"),
+        ] {
+            let response = arrange_and_act_user_request(&app, "alice", method, uri, None).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+            let body = read_body(response).await;
+            assert_eq!(
+                String::from_utf8(body).unwrap(),
+                "Missing permission for this instance."
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn ledger_members_keep_an_owner() {
+        let app =
+            crate::api::routes::setup_routing(setup_test_services_with_authentication()).await;
+        let ledger = arrange_shared_ledger(&app, LedgerRole::Owner, LedgerRole::Editor).await;
+        let member_uri = |user: &str| format!("/ledgers/{}/members/{user}", ledger.id());
+
+        // editors cannot hand out roles
+        let response = arrange_and_act_user_request(
+            &app,
+            "carol",
+            Method::PUT,
+            &member_uri("carol"),
+            Some(json!({"role": "owner"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response =
+            arrange_and_act_user_request(&app, "alice", Method::DELETE, &member_uri("bob"), None)
+                .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response =
+            arrange_and_act_user_request(&app, "alice", Method::DELETE, &member_uri("alice"), None)
+                .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let response = arrange_and_act_user_request(
+            &app,
+            "alice",
+            Method::PUT,
+            &member_uri("alice"),
+            Some(json!({"role": "viewer"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = read_body(response).await;
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "A ledger needs at least one owner."
+        );
+    }
+
+    #[tokio::test]
+    async fn ledger_private_cost_bearer_redacts_entries() {
+        let app =
+            crate::api::routes::setup_routing(setup_test_services_with_authentication()).await;
+        let ledger = arrange_shared_ledger(&app, LedgerRole::Viewer, LedgerRole::Editor).await;
+        let base = format!("/ledgers/{}", ledger.id());
+        let mut cost_bearers = Vec::new();
+        for private_to in [json!(["alice"]), json!([])] {
+            let response = arrange_and_act_user_request(
+                &app,
+                "alice",
+                Method::POST,
+                &format!("{base}/cost_bearers"),
+                Some(json!({
+                    "name": "Account",
+                    "exists_from": "2025-01-01T00:00:00Z",
+                    "private_to": private_to
+                })),
+            )
+            .await;
+            let cost_bearer: CostBearer = serde_json::from_slice(&read_body(response).await)
+                .expect("Failed to parse response into CostBearer struct.");
+            cost_bearers.push(cost_bearer.id());
+        }
+        let (private, shared) = (cost_bearers[0], cost_bearers[1]);
+        let expense_type = act_expense_type(&app, &base).await;
+        let mut entries = Vec::new();
+        for cost_shares in [
+            json!([{"cost_bearer_id": private, "amount": 30.0}, {"cost_bearer_id": shared, "amount": 10.0}]),
+            json!([{"cost_bearer_id": shared, "amount": 5.0}]),
+        ] {
+            let response = arrange_and_act_user_request(
+                &app,
+                "carol",
+                Method::POST,
+                &format!("{base}/expense_entries"),
+                Some(json!({
+                    "cost_shares": cost_shares,
+                    "expense_type": expense_type,
+                    "description": "Birthday present",
+                    "tags": ["gift"]
+                })),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let entry: ExpenseEntry = serde_json::from_slice(&read_body(response).await)
+                .expect("Failed to parse response into ExpenseEntry struct.");
+            entries.push(entry.id());
+        }
+
+        // the private users see everything
+        let uri = format!("{base}/expense_entries/{}", entries[0]);
+        let response = arrange_and_act_user_request(&app, "alice", Method::GET, &uri, None).await;
+        let entry: ExpenseEntry = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into ExpenseEntry struct.");
+        assert!(!entry.is_redacted());
+        assert_eq!(entry.description(), "Birthday present");
+
+        // everyone else only the amounts
+        let response = arrange_and_act_user_request(&app, "bob", Method::GET, &uri, None).await;
+        let entry: ExpenseEntry = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into ExpenseEntry struct.");
+        assert!(entry.is_redacted());
+        assert_eq!(entry.description(), "");
+        assert!(entry.tags().is_empty());
+        assert_eq!(entry.cost_shares().len(), 2);
+        let response = arrange_and_act_user_request(
+            &app,
+            "bob",
+            Method::GET,
+            &format!("{uri}/attachments"),
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // the redacted rows still add up to the balances
+        let response = arrange_and_act_user_request(
+            &app,
+            "bob",
+            Method::GET,
+            &format!("{base}/views/expense_entries"),
+            None,
+        )
+        .await;
+        let rows: Vec<ExpenseEntryRow> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into ExpenseEntryRow structs.");
+        assert_eq!(rows.iter().filter(|row| row.redacted).count(), 1);
+        let response = arrange_and_act_user_request(
+            &app,
+            "bob",
+            Method::GET,
+            &format!("{base}/views/cost_bearer_balances"),
+            None,
+        )
+        .await;
+        let balances: Vec<CostBearerBalance> = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into CostBearerBalance structs.");
+        for balance in balances {
+            let listed: f64 = rows
+                .iter()
+                .flat_map(|row| row.cost_shares.iter())
+                .filter(|share| share.cost_bearer_id == balance.cost_bearer_id)
+                .map(|share| share.amount)
+                .sum();
+            assert_eq!(listed, balance.balance);
+        }
+
+        let response =
+            arrange_and_act_user_request(&app, "bob", Method::GET, &format!("{uri}/history"), None)
+                .await;
+        let history: Value = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into json.");
+        assert_eq!(history[0]["after"]["description"], "");
+        assert_eq!(history[0]["after"]["redacted"], true);
+    }
+
+    #[tokio::test]
+    async fn ledger_private_cost_bearer_redacts_trash_tags_and_restores() {
+        let app =
+            crate::api::routes::setup_routing(setup_test_services_with_authentication()).await;
+        let ledger = arrange_shared_ledger(&app, LedgerRole::Viewer, LedgerRole::Editor).await;
+        let base = format!("/ledgers/{}", ledger.id());
+        let entries = arrange_private_entries(&app, &base).await;
+
+        // entries on the private cost bearer are not counted for the others
+        let tags = act_json_user_request(&app, "bob", &format!("{base}/tags")).await;
+        assert_eq!(tags, json!([{"tag": "gift", "expense_entries": 1}]));
+        let tags = act_json_user_request(&app, "alice", &format!("{base}/tags")).await;
+        assert_eq!(tags, json!([{"tag": "gift", "expense_entries": 2}]));
+
+        let uri = format!("{base}/expense_entries/{}", entries[0]);
+        let response =
+            arrange_and_act_user_request(&app, "carol", Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let trash = act_json_user_request(&app, "bob", &format!("{base}/trash")).await;
+        assert_eq!(trash[0]["label"], "");
+        let trash = act_json_user_request(&app, "alice", &format!("{base}/trash")).await;
+        assert_eq!(trash[0]["label"], "Birthday present");
+
+        let response = arrange_and_act_user_request(
+            &app,
+            "carol",
+            Method::POST,
+            &format!("{uri}/restore"),
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let entry: ExpenseEntry = serde_json::from_slice(&read_body(response).await)
+            .expect("Failed to parse response into ExpenseEntry struct.");
+        assert!(entry.is_redacted());
+        assert_eq!(entry.description(), "");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ledger_live_feed_follows_membership_and_redacts() {
+        let services = setup_test_services_with_authentication();
+        dispatch(&services).await;
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        let ledger = arrange_shared_ledger(&app, LedgerRole::Viewer, LedgerRole::Editor).await;
+        let base = format!("/ledgers/{}", ledger.id());
+        dispatch(&services).await;
+
+        let events = format!("{base}/events?entity=expense_entry");
+        let response = arrange_and_act_user_request(&app, "bob", Method::GET, &events, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut body = response.into_body().into_data_stream();
+        arrange_private_entries(&app, &base).await;
+        dispatch(&services).await;
+        let private = act_receive_change(&mut body)
+            .await
+            .expect("Event stream closed.");
+        let shared = act_receive_change(&mut body)
+            .await
+            .expect("Event stream closed.");
+        assert_eq!(private["event"], "expense_entry.created");
+        assert_eq!(private["data"]["description"], "");
+        assert_eq!(private["data"]["redacted"], true);
+        assert_eq!(shared["data"]["description"], "Birthday present");
+
+        // once removed, bob gets no further changes
+        let response = arrange_and_act_user_request(
+            &app,
+            "alice",
+            Method::DELETE,
+            &format!("{base}/members/bob"),
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let cost_bearer_id = arrange_cost_bearer(&app, &base, json!([])).await;
+        arrange_entry(&app, &base, cost_bearer_id).await;
+        dispatch(&services).await;
+        assert!(act_receive_change(&mut body).await.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ledger_webhooks_follow_membership_and_redact() {
+        let services = setup_test_services_with_authentication();
+        dispatch(&services).await;
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        let ledger = arrange_shared_ledger(&app, LedgerRole::Owner, LedgerRole::Editor).await;
+        let base = format!("/ledgers/{}", ledger.id());
+        let (url, received) = start_receiver().await;
+        let response = arrange_and_act_user_request(
+            &app,
+            "bob",
+            Method::POST,
+            &format!("{base}/webhooks"),
+            Some(json!({"url": url, "events": ["expense_entry.*"], "secret": "s3cret"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        arrange_private_entries(&app, &base).await;
+        dispatch(&services).await;
+        let payloads = received.lock().unwrap().clone();
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[0]["data"]["description"], "");
+        assert_eq!(payloads[0]["data"]["redacted"], true);
+        assert_eq!(payloads[1]["data"]["description"], "Birthday present");
+
+        // the webhooks of a removed member stop receiving changes
+        let response = arrange_and_act_user_request(
+            &app,
+            "alice",
+            Method::DELETE,
+            &format!("{base}/members/bob"),
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let cost_bearer_id = arrange_cost_bearer(&app, &base, json!([])).await;
+        arrange_entry(&app, &base, cost_bearer_id).await;
+        dispatch(&services).await;
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+}
@@ -1,5 +1,21 @@
+mod api_key;
+mod attachment;
+mod audit;
+mod authentication;
+mod budget;
+mod categorisation_rule;
+mod change_feed;
 mod cost_bearer;
 mod error_code;
 mod expense_entry;
 mod expense_type;
+mod idempotency;
+mod ledger;
+mod outbox;
+mod projection;
+mod recurring_expense;
+mod report;
+mod request_context;
 pub mod routes;
+mod trash;
+mod webhook;
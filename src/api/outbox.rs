@@ -0,0 +1,263 @@
+use axum::Json;
+use axum::extract::State;
+
+use crate::api::routes::Services;
+use crate::domain::outbox::DeadLetter;
+use crate::service::application_error::ApplicationError;
+
+pub async fn dead_letter_list(
+    State(services): State<Services>,
+) -> Result<Json<Vec<DeadLetter>>, ApplicationError> {
+    let dead_letters = services.outbox_service.dead_letters()?;
+    Ok(Json(dead_letters))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::Router;
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use chrono::{Duration, Utc};
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use crate::api::routes::Services;
+    use crate::domain::outbox::{ChangeAction, ChangeEvent, ChangedEntity, DeadLetter};
+    use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+    use crate::service::outbox::{DISPATCH_BATCH_SIZE, MAX_DELIVERY_ATTEMPTS, OutboxHandlerPort};
+    use crate::test_util::test_utility::{TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services};
+
+    // records what it receives, failing the first given number of deliveries
+    struct RecordingHandler {
+        name: String,
+        failures: Mutex<u32>,
+        received: Mutex<Vec<ChangeEvent>>,
+    }
+
+    impl RecordingHandler {
+        fn new(name: &str, failures: u32) -> Arc<Self> {
+            Arc::new(RecordingHandler {
+                name: name.to_string(),
+                failures: Mutex::new(failures),
+                received: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn received(&self) -> Vec<(ChangedEntity, ChangeAction)> {
+            self.received
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|event| (event.entity, event.action))
+                .collect()
+        }
+    }
+
+    impl OutboxHandlerPort for RecordingHandler {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn handle(&self, event: &ChangeEvent) -> Result<(), ApplicationError> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(ApplicationError {
+                    error_type: ApplicationErrorType::Internal,
+                    message: String::from("Handler unavailable."),
+                });
+            }
+            self.received.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    async fn setup_test_app(services: &Services) -> Router {
+        crate::api::routes::setup_routing(services.clone()).await
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn arrange_expense_entry(app: &Router) -> Value {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 20.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Groceries",
+                "expense_date": "2026-03-15T12:00:00Z"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse expense entry.")
+    }
+
+    fn dispatch(services: &Services, now: chrono::DateTime<Utc>) -> usize {
+        services
+            .outbox_service
+            .dispatch(now)
+            .unwrap_or_else(|err| panic!("Dispatching failed: {}", err.message))
+    }
+
+    #[tokio::test]
+    async fn outbox_delivers_committed_changes_once() {
+        let services = setup_test_services();
+        // routes the seeded changes while nobody listens
+        dispatch(&services, Utc::now());
+        let handler = RecordingHandler::new("recorder", 0);
+        services.outbox_service.register(handler.clone());
+        let app = setup_test_app(&services).await;
+        let entry = arrange_expense_entry(&app).await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}",
+            entry["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({"name": "Barclays Debit Card", "exists_from": "2025-01-01T00:00:00Z"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_types"),
+            Some(json!({"name": "Travel", "description": "Trips"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        // a rejected change is never committed and so never published
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}",
+            uuid::Uuid::new_v4()
+        );
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        dispatch(&services, Utc::now());
+        let redelivered = dispatch(&services, Utc::now());
+
+        assert_eq!(redelivered, 0);
+        assert_eq!(
+            handler.received(),
+            vec![
+                (ChangedEntity::ExpenseEntry, ChangeAction::Created),
+                (ChangedEntity::ExpenseEntry, ChangeAction::Deleted),
+                (ChangedEntity::CostBearer, ChangeAction::Updated),
+                (ChangedEntity::ExpenseType, ChangeAction::Created),
+            ]
+        );
+        let created = &handler.received.lock().unwrap()[0];
+        assert_eq!(created.data.as_ref().unwrap()["description"], "Groceries");
+    }
+
+    #[tokio::test]
+    async fn outbox_retries_with_backoff() {
+        let services = setup_test_services();
+        // routes the seeded changes while nobody listens
+        dispatch(&services, Utc::now());
+        let handler = RecordingHandler::new("flaky", 1);
+        services.outbox_service.register(handler.clone());
+        let app = setup_test_app(&services).await;
+        arrange_expense_entry(&app).await;
+        let now = Utc::now();
+
+        dispatch(&services, now);
+        let after_failure = handler.received().len();
+        dispatch(&services, now);
+        let too_early = handler.received().len();
+        dispatch(&services, now + Duration::seconds(2));
+        let after_backoff = handler.received().len();
+
+        assert_eq!((after_failure, too_early, after_backoff), (0, 0, 1));
+    }
+
+    #[tokio::test]
+    async fn outbox_dead_letters_after_max_attempts() {
+        let services = setup_test_services();
+        // routes the seeded changes while nobody listens
+        dispatch(&services, Utc::now());
+        let handler = RecordingHandler::new("broken", u32::MAX);
+        services.outbox_service.register(handler.clone());
+        let app = setup_test_app(&services).await;
+        arrange_expense_entry(&app).await;
+
+        let mut now = Utc::now();
+        for _ in 0..MAX_DELIVERY_ATTEMPTS + 2 {
+            dispatch(&services, now);
+            now += Duration::days(1);
+        }
+        let response =
+            arrange_and_act_request(&app, Method::GET, "/outbox/dead_letters", None).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let dead_letters: Vec<DeadLetter> =
+            serde_json::from_slice(&body).expect("Failed to parse dead letters.");
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].handler, "broken");
+        assert_eq!(dead_letters[0].attempts, MAX_DELIVERY_ATTEMPTS);
+        assert_eq!(dead_letters[0].last_error, "Handler unavailable.");
+        assert_eq!(dead_letters[0].event.entity, ChangedEntity::ExpenseEntry);
+    }
+
+    #[tokio::test]
+    async fn outbox_skips_deliveries_of_unregistered_handlers() {
+        let services = setup_test_services();
+        let app = setup_test_app(&services).await;
+        for _ in 0..DISPATCH_BATCH_SIZE {
+            arrange_expense_entry(&app).await;
+        }
+        // routes the changes to the webhooks and live feeds without delivering them yet
+        dispatch(&services, Utc::now() - Duration::days(1));
+        let restarted = services.outbox_service.restarted();
+        let handler = RecordingHandler::new("recorder", 0);
+        restarted.register(handler.clone());
+        arrange_expense_entry(&app).await;
+
+        let delivered = restarted
+            .dispatch(Utc::now())
+            .unwrap_or_else(|err| panic!("Dispatching failed: {}", err.message));
+
+        assert_eq!(delivered, 1);
+        assert_eq!(
+            handler.received(),
+            vec![(ChangedEntity::ExpenseEntry, ChangeAction::Created)]
+        );
+    }
+}
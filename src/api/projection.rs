@@ -0,0 +1,261 @@
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use uuid::Uuid;
+
+use crate::api::expense_entry::ExpenseEntryQuery;
+use crate::api::routes::Services;
+use crate::domain::projection::{
+    CostBearerBalance, ExpenseEntryRow, MonthlyTotal, ProjectionRebuild,
+};
+use crate::service::application_error::ApplicationError;
+use crate::service::expense_entry::ExpenseEntryFilter;
+
+// accepts the same filters as the expense entry listing
+pub async fn expense_entry_view_get(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    Query(query): Query<ExpenseEntryQuery>,
+) -> Result<Json<Vec<ExpenseEntryRow>>, ApplicationError> {
+    let filter = ExpenseEntryFilter::try_from(query)?;
+    let rows = services
+        .projection_service
+        .expense_entry_rows(ledger_id, &filter)?;
+    Ok(Json(rows))
+}
+
+pub async fn monthly_total_view_get(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+) -> Result<Json<Vec<MonthlyTotal>>, ApplicationError> {
+    let totals = services.projection_service.monthly_totals(ledger_id)?;
+    Ok(Json(totals))
+}
+
+pub async fn cost_bearer_balance_view_get(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+) -> Result<Json<Vec<CostBearerBalance>>, ApplicationError> {
+    let balances = services
+        .projection_service
+        .cost_bearer_balances(ledger_id)?;
+    Ok(Json(balances))
+}
+
+pub async fn view_rebuild(
+    State(services): State<Services>,
+) -> Result<Json<ProjectionRebuild>, ApplicationError> {
+    let rebuild = services.projection_service.rebuild()?;
+    Ok(Json(rebuild))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::projection::{
+        CostBearerBalance, ExpenseEntryRow, MonthlyTotal, ProjectionRebuild,
+    };
+    use crate::test_util::test_utility::{TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services};
+    use axum::Router;
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    async fn setup_test_app() -> Router {
+        let services = setup_test_services();
+        crate::api::routes::setup_routing(services).await
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn act_view_request<T: DeserializeOwned>(app: &Router, method: Method, uri: &str) -> T {
+        let response = arrange_and_act_request(app, method, uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into view.")
+    }
+
+    async fn arrange_expense_entry(app: &Router, amount: f64) -> Value {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": amount}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Groceries",
+                "expense_date": "2026-03-15T12:00:00Z",
+                "tags": ["weekly"]
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse expense entry.")
+    }
+
+    fn food_total(totals: &[MonthlyTotal]) -> Option<&MonthlyTotal> {
+        totals
+            .iter()
+            .find(|total| total.expense_type == TEST_VALID_UUID && total.month == "2026-03")
+    }
+
+    fn barclays_balance(balances: &[CostBearerBalance]) -> Option<&CostBearerBalance> {
+        balances
+            .iter()
+            .find(|balance| balance.cost_bearer_id == TEST_VALID_UUID)
+    }
+
+    #[tokio::test]
+    async fn views_resolve_names_and_sum_up() {
+        let app = setup_test_app().await;
+        arrange_expense_entry(&app, 20.0).await;
+        arrange_expense_entry(&app, 5.5).await;
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/views/expense_entries?expense_type={}&tags=weekly",
+            TEST_VALID_UUID
+        );
+        let rows: Vec<ExpenseEntryRow> = act_view_request(&app, Method::GET, &uri).await;
+        let totals: Vec<MonthlyTotal> = act_view_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/views/monthly_totals"),
+        )
+        .await;
+        let balances: Vec<CostBearerBalance> = act_view_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/views/cost_bearer_balances"),
+        )
+        .await;
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].expense_type_name.as_deref(), Some("Food"));
+        assert_eq!(
+            rows[0].cost_shares[0].cost_bearer_name.as_deref(),
+            Some("Barclays Credit Card")
+        );
+        assert_eq!(rows[0].tags[0].as_str(), "weekly");
+        let food = food_total(&totals).expect("Missing monthly total.");
+        assert_eq!(food.expense_type_name.as_deref(), Some("Food"));
+        assert_eq!(food.total, 25.5);
+        // the seeded entry is booked on barclays as well
+        assert_eq!(barclays_balance(&balances).unwrap().balance, 38.0);
+    }
+
+    #[tokio::test]
+    async fn views_follow_changes() {
+        let app = setup_test_app().await;
+        let entry = arrange_expense_entry(&app, 20.0).await;
+        arrange_expense_entry(&app, 5.5).await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}",
+            entry["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({"name": "Barclays Debit Card", "exists_from": "2025-01-01T00:00:00Z"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let totals: Vec<MonthlyTotal> = act_view_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/views/monthly_totals"),
+        )
+        .await;
+        let balances: Vec<CostBearerBalance> = act_view_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/views/cost_bearer_balances"),
+        )
+        .await;
+
+        assert_eq!(food_total(&totals).unwrap().total, 5.5);
+        let barclays = barclays_balance(&balances).unwrap();
+        assert_eq!(barclays.balance, 18.0);
+        assert_eq!(
+            barclays.cost_bearer_name.as_deref(),
+            Some("Barclays Debit Card")
+        );
+    }
+
+    #[tokio::test]
+    async fn views_rebuild_replays_all_events() {
+        let app = setup_test_app().await;
+        let entry = arrange_expense_entry(&app, 20.0).await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}",
+            entry["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 30.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Groceries"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let rows_before: Vec<ExpenseEntryRow> = act_view_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/views/expense_entries"),
+        )
+        .await;
+
+        let rebuild: ProjectionRebuild =
+            act_view_request(&app, Method::POST, "/views/rebuild").await;
+
+        // the seeded entry, the creation and the changed cost shares and tags
+        assert_eq!(rebuild.replayed_events, 4);
+        let rows_after: Vec<ExpenseEntryRow> = act_view_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/views/expense_entries"),
+        )
+        .await;
+        assert_eq!(rows_after.len(), rows_before.len());
+        let totals: Vec<MonthlyTotal> = act_view_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/views/monthly_totals"),
+        )
+        .await;
+        assert_eq!(food_total(&totals).unwrap().total, 30.0);
+    }
+}
@@ -0,0 +1,504 @@
+use axum::extract::{Query, State};
+use axum::{Json, extract::Path, http::StatusCode};
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::api::routes::Services;
+use crate::domain::recurring_expense::{RecurringExpense, RecurringExpenseOccurrence};
+use crate::service::application_error::ApplicationError;
+use crate::service::expense_entry::ExpenseEntryNew;
+use crate::service::recurring_expense::RecurringExpenseNew;
+
+const DEFAULT_PREVIEW_OCCURRENCES: usize = 12;
+
+#[derive(serde::Deserialize)]
+pub struct OccurrencePreviewQuery {
+    from: Option<NaiveDate>,
+    count: Option<usize>,
+}
+
+pub async fn recurring_expense_post(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    recurring_expense: Json<RecurringExpenseNew>,
+) -> Result<Json<RecurringExpense>, ApplicationError> {
+    let new_recurring_expense_dto: RecurringExpenseNew = recurring_expense.0;
+    let created_recurring_expense = services
+        .recurring_expense_service
+        .create(ledger_id, new_recurring_expense_dto)?;
+    Ok(Json(created_recurring_expense))
+}
+
+pub async fn recurring_expense_delete(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApplicationError> {
+    services.recurring_expense_service.delete(ledger_id, id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn recurring_expense_restore(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<RecurringExpense>, ApplicationError> {
+    let restored_recurring_expense = services.recurring_expense_service.restore(ledger_id, id)?;
+    Ok(Json(restored_recurring_expense))
+}
+
+pub async fn recurring_expense_get(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<RecurringExpense>, ApplicationError> {
+    let found_recurring_expense = services.recurring_expense_service.get(ledger_id, id)?;
+    Ok(Json(found_recurring_expense))
+}
+
+pub async fn recurring_expense_occurrences_get(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<OccurrencePreviewQuery>,
+) -> Result<Json<Vec<RecurringExpenseOccurrence>>, ApplicationError> {
+    let occurrences = services.recurring_expense_service.preview(
+        ledger_id,
+        id,
+        query.from.unwrap_or_else(|| Utc::now().date_naive()),
+        query.count.unwrap_or(DEFAULT_PREVIEW_OCCURRENCES),
+    )?;
+    Ok(Json(occurrences))
+}
+
+pub async fn recurring_expense_occurrence_skip(
+    State(services): State<Services>,
+    Path((ledger_id, id, occurrence_date)): Path<(Uuid, Uuid, NaiveDate)>,
+) -> Result<StatusCode, ApplicationError> {
+    services
+        .recurring_expense_service
+        .skip_occurrence(ledger_id, id, occurrence_date)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn recurring_expense_occurrence_update(
+    State(services): State<Services>,
+    Path((ledger_id, id, occurrence_date)): Path<(Uuid, Uuid, NaiveDate)>,
+    entry: Json<ExpenseEntryNew>,
+) -> Result<Json<RecurringExpenseOccurrence>, ApplicationError> {
+    let override_entry_dto: ExpenseEntryNew = entry.0;
+    let overridden_occurrence = services.recurring_expense_service.override_occurrence(
+        ledger_id,
+        id,
+        occurrence_date,
+        override_entry_dto,
+    )?;
+    Ok(Json(overridden_occurrence))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::routes::Services;
+    use crate::domain::recurring_expense::{
+        OccurrenceStatus, RecurringExpense, RecurringExpenseOccurrence,
+    };
+    use crate::test_util::test_utility::{
+        TEST_INVALID_UUID, TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services,
+    };
+    use axum::{
+        Router,
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use chrono::NaiveDate;
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn setup_test_app() -> (Router, Services) {
+        let services = setup_test_services();
+
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        (app, services)
+    }
+
+    fn monthly_rent(day_of_month: u32, ends_on: Option<&str>) -> Value {
+        json!({
+            "template": {
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 950.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Rent"
+            },
+            "schedule": {
+                "frequency": "monthly",
+                "day_of_month": day_of_month,
+                "starts_on": "2026-01-01",
+                "ends_on": ends_on
+            }
+        })
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn arrange_and_act_post_request(app: &Router, body: Value) -> RecurringExpense {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/recurring_expenses"),
+            Some(body),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into RecurringExpense.")
+    }
+
+    async fn arrange_and_act_preview_request(
+        app: &Router,
+        id: Uuid,
+        query: &str,
+    ) -> Vec<RecurringExpenseOccurrence> {
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/recurring_expenses/{}/occurrences?{}",
+            id, query
+        );
+        let response = arrange_and_act_request(app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into occurrences.")
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_post() {
+        let (app, _) = setup_test_app().await;
+        let recurring_expense = arrange_and_act_post_request(&app, monthly_rent(1, None)).await;
+
+        assert_eq!(recurring_expense.template().description(), "Rent");
+        assert_eq!(recurring_expense.schedule().interval(), 1);
+        assert_eq!(recurring_expense.schedule().starts_on(), date("2026-01-01"));
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/recurring_expenses/{}",
+            recurring_expense.id()
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_post_fails_invalid_schedule() {
+        let (app, _) = setup_test_app().await;
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/recurring_expenses"),
+            Some(monthly_rent(32, None)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/recurring_expenses"),
+            Some(monthly_rent(1, Some("2025-12-31"))),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Json without valid date.");
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_post_fails_invalid_template() {
+        let (app, _) = setup_test_app().await;
+        let mut body = monthly_rent(1, None);
+        body["template"]["description"] = json!("  ");
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/recurring_expenses"),
+            Some(body),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Json without valid description.");
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_get_fails_not_found() {
+        let (app, _) = setup_test_app().await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/recurring_expenses/{}",
+            TEST_INVALID_UUID
+        );
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(error_message, "Recurring expense not found.");
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_occurrences_preview() {
+        let (app, _) = setup_test_app().await;
+        let recurring_expense =
+            arrange_and_act_post_request(&app, monthly_rent(31, Some("2026-04-30"))).await;
+
+        let occurrences = arrange_and_act_preview_request(
+            &app,
+            recurring_expense.id(),
+            "from=2026-01-15&count=10",
+        )
+        .await;
+
+        // short months book on their last day and the end date stops the series
+        let dates: Vec<NaiveDate> = occurrences.iter().map(|o| o.occurrence_date).collect();
+        assert_eq!(
+            dates,
+            vec![
+                date("2026-01-31"),
+                date("2026-02-28"),
+                date("2026-03-31"),
+                date("2026-04-30")
+            ]
+        );
+        assert!(
+            occurrences
+                .iter()
+                .all(|o| o.status == OccurrenceStatus::Pending)
+        );
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_occurrence_skip() {
+        let (app, services) = setup_test_app().await;
+        let recurring_expense = arrange_and_act_post_request(&app, monthly_rent(1, None)).await;
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/recurring_expenses/{}/occurrences/2026-02-01/skip",
+            recurring_expense.id()
+        );
+        let response = arrange_and_act_request(&app, Method::POST, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let occurrences = arrange_and_act_preview_request(
+            &app,
+            recurring_expense.id(),
+            "from=2026-01-01&count=3",
+        )
+        .await;
+        assert_eq!(occurrences[0].status, OccurrenceStatus::Pending);
+        assert_eq!(occurrences[1].status, OccurrenceStatus::Skipped);
+        assert_eq!(occurrences[2].status, OccurrenceStatus::Pending);
+
+        // the skipped month is never booked
+        let booked = services
+            .recurring_expense_service
+            .book_due_occurrences(date("2026-03-15"))
+            .unwrap_or_else(|err| panic!("{}", err.message));
+        assert_eq!(booked.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_occurrence_skip_fails_no_occurrence() {
+        let (app, _) = setup_test_app().await;
+        let recurring_expense = arrange_and_act_post_request(&app, monthly_rent(1, None)).await;
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/recurring_expenses/{}/occurrences/2026-02-02/skip",
+            recurring_expense.id()
+        );
+        let response = arrange_and_act_request(&app, Method::POST, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let error_message = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(
+            error_message,
+            "Date is not an occurrence of this recurring expense."
+        );
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_occurrence_update() {
+        let (app, services) = setup_test_app().await;
+        let recurring_expense = arrange_and_act_post_request(&app, monthly_rent(1, None)).await;
+
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/cost_bearers"),
+            Some(json!({"name": "Landlord Account", "exists_from": "2025-01-01T00:00:00Z"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to recieve body from response.");
+        let cost_bearer: Value = serde_json::from_slice(&body).unwrap();
+        let cost_bearer_id = Uuid::parse_str(cost_bearer["id"].as_str().unwrap()).unwrap();
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/recurring_expenses/{}/occurrences/2026-01-01",
+            recurring_expense.id()
+        );
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": cost_bearer_id, "amount": 1000.0}],
+                "expense_type": recurring_expense.template().expense_type(),
+                "description": "Rent incl. yearly adjustment"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let booked = services
+            .recurring_expense_service
+            .book_due_occurrences(date("2026-01-01"))
+            .unwrap_or_else(|err| panic!("{}", err.message));
+        assert_eq!(booked.len(), 1);
+        assert_eq!(booked[0].description(), "Rent incl. yearly adjustment");
+        assert_eq!(booked[0].cost_shares()[0].cost_bearer_id, cost_bearer_id);
+        assert_eq!(booked[0].expense_date().date_naive(), date("2026-01-01"));
+
+        // a booked occurrence can no longer be changed
+        let response =
+            arrange_and_act_request(&app, Method::POST, &format!("{}/skip", uri), None).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_booking_is_idempotent() {
+        let (app, services) = setup_test_app().await;
+        let recurring_expense = arrange_and_act_post_request(&app, monthly_rent(15, None)).await;
+
+        let booked = services
+            .recurring_expense_service
+            .book_due_occurrences(date("2026-03-20"))
+            .unwrap_or_else(|err| panic!("{}", err.message));
+        assert_eq!(booked.len(), 3);
+
+        // a second run, e.g. after a restart, books nothing new
+        let booked_again = services
+            .recurring_expense_service
+            .book_due_occurrences(date("2026-03-20"))
+            .unwrap_or_else(|err| panic!("{}", err.message));
+        assert!(booked_again.is_empty());
+
+        let occurrences = arrange_and_act_preview_request(
+            &app,
+            recurring_expense.id(),
+            "from=2026-01-01&count=4",
+        )
+        .await;
+        assert_eq!(occurrences[0].status, OccurrenceStatus::Booked);
+        assert_eq!(occurrences[0].expense_entry_id, Some(booked[0].id()));
+        assert_eq!(occurrences[2].status, OccurrenceStatus::Booked);
+        assert_eq!(occurrences[3].status, OccurrenceStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_booking_continues_after_last_booked() {
+        let (app, services) = setup_test_app().await;
+        let recurring_expense = arrange_and_act_post_request(&app, monthly_rent(15, None)).await;
+        let booked = services
+            .recurring_expense_service
+            .book_due_occurrences(date("2026-02-20"))
+            .unwrap_or_else(|err| panic!("{}", err.message));
+        assert_eq!(booked.len(), 2);
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/recurring_expenses/{}/occurrences",
+            recurring_expense.id()
+        );
+        let response =
+            arrange_and_act_request(&app, Method::POST, &format!("{uri}/2026-03-15/skip"), None)
+                .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &format!("{uri}/2026-04-15"),
+            Some(json!({
+                "cost_shares": recurring_expense.template().cost_shares(),
+                "expense_type": recurring_expense.template().expense_type(),
+                "description": "Rent April"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let booked = services
+            .recurring_expense_service
+            .book_due_occurrences(date("2026-04-20"))
+            .unwrap_or_else(|err| panic!("{}", err.message));
+
+        assert_eq!(booked.len(), 1);
+        assert_eq!(booked[0].description(), "Rent April");
+        let occurrences = arrange_and_act_preview_request(
+            &app,
+            recurring_expense.id(),
+            "from=2026-03-01&count=2",
+        )
+        .await;
+        assert_eq!(occurrences[0].status, OccurrenceStatus::Skipped);
+        assert_eq!(occurrences[1].status, OccurrenceStatus::Booked);
+        assert_eq!(occurrences[1].expense_entry_id, Some(booked[0].id()));
+    }
+
+    #[tokio::test]
+    async fn recurring_expense_delete() {
+        let (app, _) = setup_test_app().await;
+        let recurring_expense = arrange_and_act_post_request(&app, monthly_rent(1, None)).await;
+
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/recurring_expenses/{}",
+            recurring_expense.id()
+        );
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,20 @@
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use uuid::Uuid;
+
+use crate::api::expense_entry::ExpenseEntryQuery;
+use crate::api::routes::Services;
+use crate::domain::expense_type::ExpenseTypeTotal;
+use crate::service::application_error::ApplicationError;
+use crate::service::expense_entry::ExpenseEntryFilter;
+
+// accepts the same filters as the expense entry listing
+pub async fn expense_type_report_get(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    Query(query): Query<ExpenseEntryQuery>,
+) -> Result<Json<Vec<ExpenseTypeTotal>>, ApplicationError> {
+    let filter = ExpenseEntryFilter::try_from(query)?;
+    let report = services.expense_type_service.report(ledger_id, &filter)?;
+    Ok(Json(report))
+}
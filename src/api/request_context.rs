@@ -0,0 +1,32 @@
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::service::request_context::RequestContext;
+
+const ACTOR_HEADER: &str = "x-actor";
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+fn header_value(request: &Request, name: &str) -> Option<String> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
+
+// makes the actor and request id available to the services for the audit log
+pub async fn request_context(request: Request, next: Next) -> Response {
+    let context = RequestContext::new(
+        header_value(&request, ACTOR_HEADER),
+        header_value(&request, REQUEST_ID_HEADER),
+    );
+    let request_id = context.request_id.clone();
+    let mut response = context.scope(next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
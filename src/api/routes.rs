@@ -1,31 +1,113 @@
 use std::sync::Arc;
 
+use axum::extract::DefaultBodyLimit;
+use axum::handler::Handler;
 use axum::http::{StatusCode, Uri};
+use axum::middleware::{from_fn, from_fn_with_state};
 use axum::response::{IntoResponse, Response};
-use axum::{Router, routing::get, routing::post};
+use axum::{Router, routing::delete, routing::get, routing::patch, routing::post, routing::put};
 
+use crate::api::api_key::{api_key_delete, api_key_list, api_key_post};
+use crate::api::attachment::{attachment_delete, attachment_get, attachment_list, attachment_post};
+use crate::api::audit::{
+    attachment_history, budget_history, cost_bearer_history, expense_entry_history,
+    expense_type_history, recurring_expense_history,
+};
+use crate::api::authentication::{authenticate, principal_get};
+use crate::api::budget::{
+    budget_delete, budget_get, budget_post, budget_restore, budget_status_get,
+};
+use crate::api::categorisation_rule::{
+    categorisation_rule_apply, categorisation_rule_delete, categorisation_rule_dry_run,
+    categorisation_rule_get, categorisation_rule_list, categorisation_rule_post,
+    categorisation_rule_update,
+};
+use crate::api::change_feed::{change_feed_sse, change_feed_ws};
 use crate::api::cost_bearer::{
-    cost_bearer_delete, cost_bearer_get, cost_bearer_post, cost_bearer_update,
+    cost_bearer_delete, cost_bearer_get, cost_bearer_merge, cost_bearer_post, cost_bearer_restore,
+    cost_bearer_update,
 };
 use crate::api::expense_entry::{
-    expense_entry_delete, expense_entry_get, expense_entry_post, expense_entry_update,
+    expense_entry_batch, expense_entry_delete, expense_entry_duplicates, expense_entry_get,
+    expense_entry_list, expense_entry_post, expense_entry_restore, expense_entry_update, tag_list,
+    tag_update,
 };
 use crate::api::expense_type::{
-    expense_type_delete, expense_type_get, expense_type_post, expense_type_update,
+    expense_type_delete, expense_type_get, expense_type_list, expense_type_merge,
+    expense_type_post, expense_type_restore, expense_type_update,
+};
+use crate::api::idempotency::idempotency;
+use crate::api::ledger::{
+    ledger_get, ledger_list, ledger_member_delete, ledger_member_list, ledger_member_put,
+    ledger_post, ledger_scope,
+};
+use crate::api::outbox::dead_letter_list;
+use crate::api::projection::{
+    cost_bearer_balance_view_get, expense_entry_view_get, monthly_total_view_get, view_rebuild,
+};
+use crate::api::recurring_expense::{
+    recurring_expense_delete, recurring_expense_get, recurring_expense_occurrence_skip,
+    recurring_expense_occurrence_update, recurring_expense_occurrences_get, recurring_expense_post,
+    recurring_expense_restore,
 };
+use crate::api::report::expense_type_report_get;
+use crate::api::request_context::request_context;
+use crate::api::trash::trash_list;
+use crate::api::webhook::{
+    webhook_delete, webhook_deliveries_get, webhook_get, webhook_list, webhook_post,
+};
+use crate::domain::ledger_member::LedgerRole;
+use crate::service::api_key::ApiKeyService;
+use crate::service::attachment::AttachmentService;
+use crate::service::audit::AuditService;
+use crate::service::budget::BudgetService;
+use crate::service::categorisation_rule::CategorisationRuleService;
+use crate::service::change_feed::ChangeFeedService;
+use crate::service::cost_bearer::CostBearerService;
 use crate::service::expense_entry::ExpenseEntryService;
+use crate::service::expense_type::ExpenseTypeService;
+use crate::service::idempotency::IdempotencyService;
+use crate::service::ledger::LedgerService;
+use crate::service::outbox::OutboxService;
+use crate::service::projection::ProjectionService;
+use crate::service::recurring_expense::RecurringExpenseService;
+use crate::service::token::TokenService;
+use crate::service::trash::TrashService;
+use crate::service::webhook::WebhookService;
 
 #[derive(Clone)]
 pub struct Services {
     pub expense_entry_service: Arc<ExpenseEntryService>,
+    pub attachment_service: Arc<AttachmentService>,
+    pub recurring_expense_service: Arc<RecurringExpenseService>,
+    pub budget_service: Arc<BudgetService>,
+    pub categorisation_rule_service: Arc<CategorisationRuleService>,
+    pub expense_type_service: Arc<ExpenseTypeService>,
+    pub cost_bearer_service: Arc<CostBearerService>,
+    pub audit_service: Arc<AuditService>,
+    pub projection_service: Arc<ProjectionService>,
+    pub outbox_service: Arc<OutboxService>,
+    pub webhook_service: Arc<WebhookService>,
+    pub change_feed_service: Arc<ChangeFeedService>,
+    pub trash_service: Arc<TrashService>,
+    pub api_key_service: Arc<ApiKeyService>,
+    pub token_service: Arc<TokenService>,
+    pub ledger_service: Arc<LedgerService>,
+    pub idempotency_service: Arc<IdempotencyService>,
 }
 
-pub async fn setup_routing() -> Router<Services> {
+pub async fn setup_routing(services: Services) -> Router {
     Router::new()
-        .merge(route_expense_entry())
-        .merge(route_cost_bearer())
-        .merge(route_expense_type())
+        .merge(route_ledger())
+        .nest("/ledgers/{ledger_id}", route_ledger_scoped(&services))
+        .route("/views/rebuild", post(view_rebuild))
+        .merge(route_outbox())
+        .merge(route_api_key())
+        .merge(route_authentication())
         .fallback(handle_routing_error)
+        .layer(from_fn_with_state(services.clone(), authenticate))
+        .layer(from_fn(request_context))
+        .with_state(services)
 }
 
 async fn handle_routing_error(uri: Uri) -> Response {
@@ -36,7 +118,41 @@ async fn handle_routing_error(uri: Uri) -> Response {
         .into_response()
 }
 
-fn route_expense_entry() -> Router<Services> {
+fn route_ledger() -> Router<Services> {
+    Router::new()
+        .route("/ledgers/{ledger_id}", get(ledger_get))
+        .route("/ledgers", get(ledger_list).post(ledger_post))
+}
+
+fn route_ledger_member() -> Router<Services> {
+    Router::new()
+        .route(
+            "/members/{user}",
+            put(ledger_member_put).delete(ledger_member_delete),
+        )
+        .route("/members", get(ledger_member_list))
+}
+
+// all data of one household, unknown ledgers are rejected before any handler runs
+fn route_ledger_scoped(services: &Services) -> Router<Services> {
+    Router::new()
+        .merge(route_ledger_member())
+        .merge(route_expense_entry(services))
+        .merge(route_attachment())
+        .merge(route_cost_bearer(services))
+        .merge(route_expense_type(services))
+        .merge(route_recurring_expense())
+        .merge(route_budget())
+        .merge(route_categorisation_rule())
+        .merge(route_report())
+        .merge(route_trash())
+        .merge(route_view())
+        .merge(route_webhook())
+        .merge(route_change_feed())
+        .route_layer(from_fn_with_state(services.clone(), ledger_scope))
+}
+
+fn route_expense_entry(services: &Services) -> Router<Services> {
     Router::new()
         .route(
             "/expense_entries/{id}",
@@ -44,10 +160,47 @@ fn route_expense_entry() -> Router<Services> {
                 .patch(expense_entry_update)
                 .delete(expense_entry_delete),
         )
-        .route("/expense_entries", post(expense_entry_post))
+        .route("/expense_entries/{id}/restore", post(expense_entry_restore))
+        .route("/expense_entries/{id}/history", get(expense_entry_history))
+        // creations retried with the same idempotency key are only done once
+        .route(
+            "/expense_entries",
+            get(expense_entry_list).post(expense_entry_post.layer(from_fn_with_state(
+                (services.clone(), LedgerRole::Editor),
+                idempotency,
+            ))),
+        )
+        .route("/expense_entries/duplicates", get(expense_entry_duplicates))
+        .route(
+            "/expense_entries/batch",
+            post(expense_entry_batch.layer(from_fn_with_state(
+                (services.clone(), LedgerRole::Editor),
+                idempotency,
+            ))),
+        )
+        .route("/tags", get(tag_list))
+        .route("/tags/{tag}", patch(tag_update))
+}
+
+fn route_attachment() -> Router<Services> {
+    Router::new()
+        .route(
+            "/expense_entries/{id}/attachments/{attachment_id}",
+            get(attachment_get).delete(attachment_delete),
+        )
+        .route(
+            "/expense_entries/{id}/attachments/{attachment_id}/history",
+            get(attachment_history),
+        )
+        .route(
+            "/expense_entries/{id}/attachments",
+            get(attachment_list).post(attachment_post),
+        )
+        // the upload size is enforced by the attachment service
+        .layer(DefaultBodyLimit::disable())
 }
 
-fn route_cost_bearer() -> Router<Services> {
+fn route_cost_bearer(services: &Services) -> Router<Services> {
     Router::new()
         .route(
             "/cost_bearers/{id}",
@@ -55,10 +208,19 @@ fn route_cost_bearer() -> Router<Services> {
                 .patch(cost_bearer_update)
                 .delete(cost_bearer_delete),
         )
-        .route("/cost_bearers", post(cost_bearer_post))
+        .route("/cost_bearers/{id}/merge", post(cost_bearer_merge))
+        .route("/cost_bearers/{id}/restore", post(cost_bearer_restore))
+        .route("/cost_bearers/{id}/history", get(cost_bearer_history))
+        .route(
+            "/cost_bearers",
+            post(cost_bearer_post.layer(from_fn_with_state(
+                (services.clone(), LedgerRole::Owner),
+                idempotency,
+            ))),
+        )
 }
 
-fn route_expense_type() -> Router<Services> {
+fn route_expense_type(services: &Services) -> Router<Services> {
     Router::new()
         .route(
             "/expense_types/{id}",
@@ -66,5 +228,113 @@ fn route_expense_type() -> Router<Services> {
                 .patch(expense_type_update)
                 .delete(expense_type_delete),
         )
-        .route("/expense_types", post(expense_type_post))
+        .route("/expense_types/{id}/merge", post(expense_type_merge))
+        .route("/expense_types/{id}/restore", post(expense_type_restore))
+        .route("/expense_types/{id}/history", get(expense_type_history))
+        .route(
+            "/expense_types",
+            get(expense_type_list).post(expense_type_post.layer(from_fn_with_state(
+                (services.clone(), LedgerRole::Owner),
+                idempotency,
+            ))),
+        )
+}
+
+fn route_recurring_expense() -> Router<Services> {
+    Router::new()
+        .route(
+            "/recurring_expenses/{id}",
+            get(recurring_expense_get).delete(recurring_expense_delete),
+        )
+        .route(
+            "/recurring_expenses/{id}/restore",
+            post(recurring_expense_restore),
+        )
+        .route(
+            "/recurring_expenses/{id}/history",
+            get(recurring_expense_history),
+        )
+        .route(
+            "/recurring_expenses/{id}/occurrences",
+            get(recurring_expense_occurrences_get),
+        )
+        .route(
+            "/recurring_expenses/{id}/occurrences/{date}",
+            patch(recurring_expense_occurrence_update),
+        )
+        .route(
+            "/recurring_expenses/{id}/occurrences/{date}/skip",
+            post(recurring_expense_occurrence_skip),
+        )
+        .route("/recurring_expenses", post(recurring_expense_post))
+}
+
+fn route_budget() -> Router<Services> {
+    Router::new()
+        .route("/budgets/{id}", get(budget_get).delete(budget_delete))
+        .route("/budgets/{id}/status", get(budget_status_get))
+        .route("/budgets/{id}/restore", post(budget_restore))
+        .route("/budgets/{id}/history", get(budget_history))
+        .route("/budgets", post(budget_post))
+}
+
+fn route_categorisation_rule() -> Router<Services> {
+    Router::new()
+        .route(
+            "/rules/{id}",
+            get(categorisation_rule_get)
+                .patch(categorisation_rule_update)
+                .delete(categorisation_rule_delete),
+        )
+        .route("/rules/apply", post(categorisation_rule_apply))
+        .route("/rules/dry_run", post(categorisation_rule_dry_run))
+        .route(
+            "/rules",
+            get(categorisation_rule_list).post(categorisation_rule_post),
+        )
+}
+
+fn route_report() -> Router<Services> {
+    Router::new().route("/reports/expense_types", get(expense_type_report_get))
+}
+
+fn route_trash() -> Router<Services> {
+    Router::new().route("/trash", get(trash_list))
+}
+
+fn route_view() -> Router<Services> {
+    Router::new()
+        .route("/views/expense_entries", get(expense_entry_view_get))
+        .route("/views/monthly_totals", get(monthly_total_view_get))
+        .route(
+            "/views/cost_bearer_balances",
+            get(cost_bearer_balance_view_get),
+        )
+}
+
+fn route_outbox() -> Router<Services> {
+    Router::new().route("/outbox/dead_letters", get(dead_letter_list))
+}
+
+fn route_webhook() -> Router<Services> {
+    Router::new()
+        .route("/webhooks/{id}", get(webhook_get).delete(webhook_delete))
+        .route("/webhooks/{id}/deliveries", get(webhook_deliveries_get))
+        .route("/webhooks", get(webhook_list).post(webhook_post))
+}
+
+fn route_change_feed() -> Router<Services> {
+    Router::new()
+        .route("/ws", get(change_feed_ws))
+        .route("/events", get(change_feed_sse))
+}
+
+fn route_api_key() -> Router<Services> {
+    Router::new()
+        .route("/api_keys/{id}", delete(api_key_delete))
+        .route("/api_keys", get(api_key_list).post(api_key_post))
+}
+
+fn route_authentication() -> Router<Services> {
+    Router::new().route("/me", get(principal_get))
 }
@@ -0,0 +1,213 @@
+use axum::Json;
+use axum::extract::{Path, State};
+use uuid::Uuid;
+
+use crate::api::routes::Services;
+use crate::domain::trash::TrashItem;
+use crate::service::application_error::ApplicationError;
+
+pub async fn trash_list(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+) -> Result<Json<Vec<TrashItem>>, ApplicationError> {
+    let trash = services.trash_service.list(ledger_id)?;
+    Ok(Json(trash))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::routes::Services;
+    use crate::domain::trash::{TrashItem, TrashedEntity};
+    use crate::test_util::test_utility::{TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services};
+    use axum::Router;
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use chrono::{TimeDelta, Utc};
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn setup_test_app() -> (Router, Services) {
+        let services = setup_test_services();
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        (app, services)
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn act_trash_request(app: &Router) -> Vec<TrashItem> {
+        let response =
+            arrange_and_act_request(app, Method::GET, &format!("{TEST_LEDGER_PATH}/trash"), None)
+                .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response into TrashItem structs.")
+    }
+
+    #[tokio::test]
+    async fn trash_lists_deleted_entities() {
+        let (app, _) = setup_test_app().await;
+        assert!(act_trash_request(&app).await.is_empty());
+
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = arrange_and_act_request(
+            &app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/budgets"),
+            Some(json!({"expense_type": TEST_VALID_UUID, "period": "monthly", "limit": 100.0})),
+        )
+        .await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        let budget: Value = serde_json::from_slice(&body).expect("Failed to parse budget.");
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/budgets/{}",
+            budget["id"].as_str().unwrap()
+        );
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let trash = act_trash_request(&app).await;
+
+        assert_eq!(trash.len(), 2);
+        assert_eq!(trash[0].entity, TrashedEntity::ExpenseEntry);
+        assert_eq!(trash[0].id, TEST_VALID_UUID);
+        assert_eq!(trash[0].label, "Some Description");
+        assert_eq!(trash[1].entity, TrashedEntity::Budget);
+        assert_eq!(trash[1].label, "monthly budget of 100");
+    }
+
+    #[tokio::test]
+    async fn trash_restore_expense_entry() {
+        let (app, _) = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        // a deleted entry cannot be deleted again
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let restore_uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/restore",
+            TEST_VALID_UUID
+        );
+        let response = arrange_and_act_request(&app, Method::POST, &restore_uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(act_trash_request(&app).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn trash_restore_fails_not_deleted() {
+        let (app, _) = setup_test_app().await;
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/restore",
+            TEST_VALID_UUID
+        );
+
+        let response = arrange_and_act_request(&app, Method::POST, &uri, None).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn trash_purge_respects_retention() {
+        let (app, services) = setup_test_app().await;
+        let uri = format!("{TEST_LEDGER_PATH}/expense_entries/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        // deleted just now, so still within any retention
+        let purged = services
+            .trash_service
+            .purge(Utc::now() - TimeDelta::days(30))
+            .unwrap_or_else(|err| panic!("Failed to purge the trash: {}", err.message));
+        assert_eq!(purged, 0);
+        assert_eq!(act_trash_request(&app).await.len(), 1);
+
+        let purged = services
+            .trash_service
+            .purge(Utc::now() + TimeDelta::seconds(1))
+            .unwrap_or_else(|err| panic!("Failed to purge the trash: {}", err.message));
+
+        assert_eq!(purged, 1);
+        assert!(act_trash_request(&app).await.is_empty());
+        let restore_uri = format!(
+            "{TEST_LEDGER_PATH}/expense_entries/{}/restore",
+            TEST_VALID_UUID
+        );
+        let response = arrange_and_act_request(&app, Method::POST, &restore_uri, None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn trash_purge_keeps_parents_of_trashed_sub_types() {
+        let (app, services) = setup_test_app().await;
+        let mut parent_id = None;
+        let mut ids = Vec::new();
+        for name in ["Groceries", "Snacks"] {
+            let response = arrange_and_act_request(
+                &app,
+                Method::POST,
+                &format!("{TEST_LEDGER_PATH}/expense_types"),
+                Some(json!({"name": name, "description": name, "parent_id": parent_id})),
+            )
+            .await;
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to receive body from response.");
+            let expense_type: Value =
+                serde_json::from_slice(&body).expect("Failed to parse expense type.");
+            let id = Uuid::parse_str(expense_type["id"].as_str().unwrap()).unwrap();
+            parent_id = Some(id);
+            ids.push(id);
+        }
+        let groceries = ids[0];
+        for id in ids.iter().rev() {
+            let uri = format!("{TEST_LEDGER_PATH}/expense_types/{}", id);
+            let response = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        }
+
+        let purge = || {
+            services
+                .trash_service
+                .purge(Utc::now() + TimeDelta::seconds(1))
+                .unwrap_or_else(|err| panic!("Failed to purge the trash: {}", err.message))
+        };
+
+        assert_eq!(purge(), 1);
+        assert_eq!(act_trash_request(&app).await[0].id, groceries);
+        assert_eq!(purge(), 1);
+        assert!(act_trash_request(&app).await.is_empty());
+    }
+}
@@ -0,0 +1,416 @@
+use axum::extract::State;
+use axum::{Json, extract::Path, http::StatusCode};
+use uuid::Uuid;
+
+use crate::api::routes::Services;
+use crate::domain::webhook::{WebhookDelivery, WebhookSubscription};
+use crate::service::application_error::ApplicationError;
+use crate::service::webhook::WebhookSubscriptionNew;
+
+pub async fn webhook_post(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+    subscription: Json<WebhookSubscriptionNew>,
+) -> Result<Json<WebhookSubscription>, ApplicationError> {
+    let new_subscription_dto: WebhookSubscriptionNew = subscription.0;
+    let created_subscription = services
+        .webhook_service
+        .create(ledger_id, new_subscription_dto)?;
+    Ok(Json(created_subscription))
+}
+
+pub async fn webhook_list(
+    State(services): State<Services>,
+    Path(ledger_id): Path<Uuid>,
+) -> Result<Json<Vec<WebhookSubscription>>, ApplicationError> {
+    let subscriptions = services.webhook_service.list(ledger_id)?;
+    Ok(Json(subscriptions))
+}
+
+pub async fn webhook_get(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<WebhookSubscription>, ApplicationError> {
+    let found_subscription = services.webhook_service.get(ledger_id, id)?;
+    Ok(Json(found_subscription))
+}
+
+pub async fn webhook_delete(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApplicationError> {
+    services.webhook_service.delete(ledger_id, id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn webhook_deliveries_get(
+    State(services): State<Services>,
+    Path((ledger_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<WebhookDelivery>>, ApplicationError> {
+    let deliveries = services.webhook_service.deliveries(ledger_id, id)?;
+    Ok(Json(deliveries))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use axum::Router;
+    use axum::extract::State;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::{
+        body::{Body, Bytes},
+        http::{Method, Request, StatusCode},
+        response::Response,
+    };
+    use chrono::{DateTime, Duration, Utc};
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use crate::api::routes::Services;
+    use crate::domain::budget::{BudgetAlert, BudgetThreshold};
+    use crate::domain::webhook::{WebhookDelivery, WebhookSubscription, signature};
+    use crate::repository::notifierrepository::http::WebhookSenderHttpRepository;
+    use crate::repository::notifierrepository::webhook::NotifierWebhookRepository;
+    use crate::service::budget::NotifierPort;
+    use crate::service::webhook::WebhookSenderPort;
+    use crate::test_util::test_utility::{
+        TEST_LEDGER_PATH, TEST_VALID_UUID, setup_test_services,
+        setup_test_services_with_public_webhook_targets,
+    };
+
+    // a local receiver that records every request and answers with a settable status
+    #[derive(Clone)]
+    struct StandIn {
+        status: Arc<AtomicU16>,
+        received: Arc<Mutex<Vec<(HeaderMap, Bytes)>>>,
+    }
+
+    async fn receive(
+        State(stand_in): State<StandIn>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        stand_in.received.lock().unwrap().push((headers, body));
+        StatusCode::from_u16(stand_in.status.load(Ordering::SeqCst)).unwrap()
+    }
+
+    async fn start_stand_in(status: u16) -> (String, StandIn) {
+        let stand_in = StandIn {
+            status: Arc::new(AtomicU16::new(status)),
+            received: Arc::new(Mutex::new(Vec::new())),
+        };
+        let router = Router::new()
+            .route("/hook", post(receive))
+            .with_state(stand_in.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind stand-in server.");
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (url, stand_in)
+    }
+
+    async fn setup_test_app() -> (Router, Services) {
+        let services = setup_test_services();
+        // routes the seeded changes before anyone subscribes
+        dispatch(&services, Utc::now()).await;
+        let app = crate::api::routes::setup_routing(services.clone()).await;
+        (app, services)
+    }
+
+    // the sender blocks on the runtime, so dispatching happens off the async workers
+    async fn dispatch(services: &Services, now: DateTime<Utc>) {
+        let outbox_service = services.outbox_service.clone();
+        tokio::task::spawn_blocking(move || outbox_service.dispatch(now))
+            .await
+            .expect("Dispatcher panicked.")
+            .unwrap_or_else(|err| panic!("Dispatching failed: {}", err.message));
+    }
+
+    async fn arrange_and_act_request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .expect("Failed to finalize request.");
+
+        app.clone()
+            .oneshot(request)
+            .await
+            .expect("Failed to receive response.")
+    }
+
+    async fn act_json_request<T: DeserializeOwned>(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> T {
+        let response = arrange_and_act_request(app, method, uri, body).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to receive body from response.");
+        serde_json::from_slice(&body).expect("Failed to parse response.")
+    }
+
+    async fn arrange_subscription(app: &Router, url: &str, events: Value) -> WebhookSubscription {
+        act_json_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/webhooks"),
+            Some(json!({"url": url, "events": events, "secret": "s3cret"})),
+        )
+        .await
+    }
+
+    async fn arrange_expense_entry(app: &Router) {
+        let response = arrange_and_act_request(
+            app,
+            Method::POST,
+            &format!("{TEST_LEDGER_PATH}/expense_entries"),
+            Some(json!({
+                "cost_shares": [{"cost_bearer_id": TEST_VALID_UUID, "amount": 20.0}],
+                "expense_type": TEST_VALID_UUID,
+                "description": "Groceries",
+                "expense_date": "2026-03-15T12:00:00Z"
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn webhook_signature_is_hmac_sha256() {
+        // test case 2 of RFC 4231
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn webhook_delivers_signed_filtered_events() {
+        let (app, services) = setup_test_app().await;
+        let (url, stand_in) = start_stand_in(200).await;
+        let subscription = arrange_subscription(&app, &url, json!(["expense_entry.created"])).await;
+        arrange_expense_entry(&app).await;
+        let uri = format!("{TEST_LEDGER_PATH}/cost_bearers/{}", TEST_VALID_UUID);
+        let response = arrange_and_act_request(
+            &app,
+            Method::PATCH,
+            &uri,
+            Some(json!({"name": "Barclays Debit Card", "exists_from": "2025-01-01T00:00:00Z"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        dispatch(&services, Utc::now()).await;
+
+        let received = stand_in.received.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        let (headers, body) = &received[0];
+        assert_eq!(headers["x-webhook-event"], "expense_entry.created");
+        assert_eq!(
+            headers["x-webhook-signature"].to_str().unwrap(),
+            format!("sha256={}", signature("s3cret", body))
+        );
+        let payload: Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(payload["event"], "expense_entry.created");
+        assert_eq!(payload["data"]["description"], "Groceries");
+        let listed: Vec<Value> = act_json_request(
+            &app,
+            Method::GET,
+            &format!("{TEST_LEDGER_PATH}/webhooks"),
+            None,
+        )
+        .await;
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].get("secret").is_none());
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/webhooks/{}/deliveries",
+            subscription.id()
+        );
+        let deliveries: Vec<WebhookDelivery> =
+            act_json_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(deliveries.len(), 1);
+        assert!(deliveries[0].succeeded);
+        assert_eq!(deliveries[0].status_code, Some(200));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn webhook_retries_only_failed_subscribers() {
+        let (app, services) = setup_test_app().await;
+        let (healthy_url, healthy) = start_stand_in(200).await;
+        let (flaky_url, flaky) = start_stand_in(500).await;
+        arrange_subscription(&app, &healthy_url, json!(["*"])).await;
+        let flaky_subscription =
+            arrange_subscription(&app, &flaky_url, json!(["expense_entry.*"])).await;
+        arrange_expense_entry(&app).await;
+        let now = Utc::now();
+
+        dispatch(&services, now).await;
+        flaky.status.store(200, Ordering::SeqCst);
+        dispatch(&services, now + Duration::seconds(2)).await;
+
+        assert_eq!(healthy.received.lock().unwrap().len(), 1);
+        assert_eq!(flaky.received.lock().unwrap().len(), 2);
+        let uri = format!(
+            "{TEST_LEDGER_PATH}/webhooks/{}/deliveries",
+            flaky_subscription.id()
+        );
+        let deliveries: Vec<WebhookDelivery> =
+            act_json_request(&app, Method::GET, &uri, None).await;
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(deliveries[0].attempt, 2);
+        assert!(deliveries[0].succeeded);
+        assert_eq!(deliveries[1].status_code, Some(500));
+        assert!(!deliveries[1].succeeded);
+    }
+
+    #[tokio::test]
+    async fn webhook_rejects_invalid_subscriptions() {
+        let (app, _) = setup_test_app().await;
+
+        for body in [
+            json!({"url": "ftp://example.com", "events": ["*"], "secret": "s3cret"}),
+            json!({"url": "http://", "events": ["*"], "secret": "s3cret"}),
+            json!({"url": "http://example.com", "events": ["budget.created"], "secret": "s3cret"}),
+            json!({"url": "http://example.com", "events": [], "secret": "s3cret"}),
+            json!({"url": "http://example.com", "events": ["*"], "secret": ""}),
+        ] {
+            let response = arrange_and_act_request(
+                &app,
+                Method::POST,
+                &format!("{TEST_LEDGER_PATH}/webhooks"),
+                Some(body),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
+    #[tokio::test]
+    async fn webhook_rejects_private_addresses() {
+        let services = setup_test_services_with_public_webhook_targets();
+        let app = crate::api::routes::setup_routing(services).await;
+
+        for url in [
+            "http://localhost:8080/hook",
+            "http://127.0.0.1/hook",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.20/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://0.0.0.0/hook",
+            "http://[::1]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+            "http://[fd00::1]/hook",
+        ] {
+            let response = arrange_and_act_request(
+                &app,
+                Method::POST,
+                &format!("{TEST_LEDGER_PATH}/webhooks"),
+                Some(json!({"url": url, "events": ["*"], "secret": "s3cret"})),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY, "{url}");
+        }
+        let accepted = arrange_subscription(&app, "https://example.com/hook", json!(["*"])).await;
+        assert_eq!(accepted.url(), "https://example.com/hook");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn webhook_sender_refuses_private_addresses_and_redirects() {
+        let (url, stand_in) = start_stand_in(200).await;
+        let location = url.clone();
+        let router = Router::new().route(
+            "/moved",
+            post(move || async move { (StatusCode::FOUND, [("location", location)]) }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind redirecting server.");
+        let moved = format!("http://{}/moved", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        let send = |private_targets: bool, url: String| {
+            tokio::task::spawn_blocking(move || {
+                WebhookSenderHttpRepository::new(private_targets).send(&url, &[], b"{}".to_vec())
+            })
+        };
+
+        let refused = send(false, url.clone()).await.unwrap();
+        let refused_by_name = send(false, url.replace("127.0.0.1", "localhost"))
+            .await
+            .unwrap();
+        let redirected = send(true, moved).await.unwrap();
+
+        assert!(refused.is_err());
+        assert!(refused_by_name.is_err());
+        // the redirect is handed back as the answer instead of being followed
+        assert_eq!(
+            redirected.unwrap_or_else(|err| panic!("{}", err.message)),
+            302
+        );
+        assert!(stand_in.received.lock().unwrap().is_empty());
+    }
+
+    // the outbox dispatcher calls the notifier off the async workers, also on a single thread
+    #[tokio::test]
+    async fn budget_alerts_are_posted_through_the_webhook_sender() {
+        let (url, stand_in) = start_stand_in(500).await;
+        let notifier = Arc::new(NotifierWebhookRepository::new(
+            url,
+            Arc::new(WebhookSenderHttpRepository::new(true)),
+        ));
+        let notify = |notifier: Arc<NotifierWebhookRepository>| {
+            tokio::task::spawn_blocking(move || {
+                notifier.notify(&BudgetAlert {
+                    budget_id: TEST_VALID_UUID,
+                    expense_type: TEST_VALID_UUID,
+                    cost_bearer_id: None,
+                    threshold: BudgetThreshold::Warning,
+                    percent: 80,
+                    period_start: "2026-03-01".parse().unwrap(),
+                    period_end: "2026-03-31".parse().unwrap(),
+                    limit: 100.0,
+                    spent: 85.0,
+                })
+            })
+        };
+
+        let rejected = notify(notifier.clone()).await.unwrap();
+        stand_in.status.store(200, Ordering::SeqCst);
+        let accepted = notify(notifier).await.unwrap();
+
+        assert!(rejected.is_err());
+        assert!(accepted.is_ok());
+        let received = stand_in.received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[1].0["content-type"], "application/json");
+        let alert: Value = serde_json::from_slice(&received[1].1).unwrap();
+        assert_eq!(alert["threshold"], "warning");
+    }
+
+    #[tokio::test]
+    async fn webhook_delete_removes_subscription() {
+        let (app, _) = setup_test_app().await;
+        let subscription = arrange_subscription(&app, "http://example.com", json!(["*"])).await;
+        let uri = format!("{TEST_LEDGER_PATH}/webhooks/{}", subscription.id());
+
+        let deleted = arrange_and_act_request(&app, Method::DELETE, &uri, None).await;
+        let found = arrange_and_act_request(&app, Method::GET, &uri, None).await;
+
+        assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+        assert_eq!(found.status(), StatusCode::NOT_FOUND);
+    }
+}
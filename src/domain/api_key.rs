@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+// every key starts with it, so leaked keys are easy to recognise
+const KEY_PREFIX: &str = "exk_";
+
+// characters of a key kept in clear to tell keys apart
+const VISIBLE_KEY_LENGTH: usize = 12;
+
+// validated and guaranteed to be correct data, only the hash of the key itself is kept; a key
+// bound to a user acts with the user's ledger roles, the others are the operator's
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct ApiKey {
+    id: Uuid,
+    name: String,
+    user: Option<String>,
+    prefix: String,
+    #[serde(skip_serializing, default)]
+    key_hash: String,
+    created_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+// the key is only handed out once, right after it was created
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ApiKeyValidationError {
+    #[error("Api Key Validation failed: Name is empty or whitespace.")]
+    MissingName,
+
+    #[error("Api Key Validation failed: User is empty or whitespace.")]
+    InvalidUser,
+}
+
+// hex encoded SHA-256, keys are random enough that a slow hash adds nothing
+pub fn hash_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+impl ApiKey {
+    // a new key with 244 random bits
+    pub fn generate(
+        name: String,
+        user: Option<String>,
+    ) -> Result<CreatedApiKey, ApiKeyValidationError> {
+        if name.trim().is_empty() {
+            return Err(ApiKeyValidationError::MissingName);
+        }
+        if user.as_ref().is_some_and(|user| user.trim().is_empty()) {
+            return Err(ApiKeyValidationError::InvalidUser);
+        }
+        let key = format!(
+            "{KEY_PREFIX}{}{}",
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        let api_key = ApiKey {
+            id: Uuid::new_v4(),
+            name: name.trim().to_string(),
+            user: user.map(|user| user.trim().to_string()),
+            prefix: key[..VISIBLE_KEY_LENGTH].to_string(),
+            key_hash: hash_key(&key),
+            created_at: Utc::now(),
+            revoked_at: None,
+        };
+        Ok(CreatedApiKey { api_key, key })
+    }
+
+    pub fn restore(
+        id: Uuid,
+        name: String,
+        user: Option<String>,
+        prefix: String,
+        key_hash: String,
+        created_at: DateTime<Utc>,
+        revoked_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        ApiKey {
+            id,
+            name,
+            user,
+            prefix,
+            key_hash,
+            created_at,
+            revoked_at,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn key_hash(&self) -> &str {
+        &self.key_hash
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+}
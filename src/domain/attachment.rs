@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+// receipts are scans or photos, anything else is rejected
+const ALLOWED_CONTENT_TYPES: [&str; 5] = [
+    "application/pdf",
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+];
+
+// validated and guaranteed to be correct data
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct Attachment {
+    id: Uuid,
+    expense_entry_id: Uuid,
+    file_name: String,
+    content_type: String,
+    size: u64,
+    content_hash: String,
+    uploaded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum AttachmentValidationError {
+    #[error("Attachment Validation failed: File name is empty or whitespace.")]
+    MissingFileName,
+
+    #[error("Attachment Validation failed: File is empty.")]
+    EmptyFile,
+
+    #[error("Attachment Validation failed: File exceeds the maximum size of {0} bytes.")]
+    FileTooLarge(u64),
+
+    #[error("Attachment Validation failed: Unsupported content type.")]
+    UnsupportedContentType,
+}
+
+impl Attachment {
+    pub fn new(
+        expense_entry_id: Uuid,
+        file_name: String,
+        content: &[u8],
+        max_size: u64,
+    ) -> Result<Self, AttachmentValidationError> {
+        // validate the file name, only keep the last path segment
+        let file_name = file_name
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if file_name.is_empty() {
+            return Err(AttachmentValidationError::MissingFileName);
+        }
+
+        // validate the size
+        if content.is_empty() {
+            return Err(AttachmentValidationError::EmptyFile);
+        }
+        let size = content.len() as u64;
+        if size > max_size {
+            return Err(AttachmentValidationError::FileTooLarge(max_size));
+        }
+
+        // the content type is derived from the content, never from the client
+        let content_type = match sniff_content_type(content) {
+            Some(content_type) if ALLOWED_CONTENT_TYPES.contains(&content_type) => content_type,
+            _ => return Err(AttachmentValidationError::UnsupportedContentType),
+        };
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            expense_entry_id,
+            file_name,
+            content_type: content_type.to_string(),
+            size,
+            content_hash: content_hash(content),
+            uploaded_at: Utc::now(),
+        })
+    }
+
+    // rebuilds an already validated attachment from storage
+    pub fn restore(
+        id: Uuid,
+        expense_entry_id: Uuid,
+        file_name: String,
+        content_type: String,
+        size: u64,
+        content_hash: String,
+        uploaded_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            expense_entry_id,
+            file_name,
+            content_type,
+            size,
+            content_hash,
+            uploaded_at,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn expense_entry_id(&self) -> Uuid {
+        self.expense_entry_id
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn content_hash(&self) -> &str {
+        &self.content_hash
+    }
+
+    pub fn uploaded_at(&self) -> DateTime<Utc> {
+        self.uploaded_at
+    }
+}
+
+// hex encoded SHA-256 of the content, used as the blob key for deduplication
+pub fn content_hash(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+// determine the content type from the magic bytes of the file
+pub fn sniff_content_type(content: &[u8]) -> Option<&'static str> {
+    if content.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if content.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if content.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if content.starts_with(b"GIF87a") || content.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if content.starts_with(b"RIFF") && content.get(8..12) == Some(b"WEBP".as_slice()) {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
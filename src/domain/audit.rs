@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditedEntity {
+    ExpenseEntry,
+    Attachment,
+    CostBearer,
+    ExpenseType,
+    Budget,
+    RecurringExpense,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Created,
+    Updated,
+    Deleted,
+    Restored,
+    Purged,
+}
+
+// one change of one entity, the snapshots are missing where the entity did not exist
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct AuditRecord {
+    pub id: Uuid,
+    pub ledger_id: Uuid,
+    pub entity: AuditedEntity,
+    pub entity_id: Uuid,
+    pub action: AuditAction,
+    pub actor: String,
+    pub request_id: String,
+    pub recorded_at: DateTime<Utc>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
@@ -0,0 +1,258 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::expense_entry::ExpenseEntry;
+
+// the calendar period a budget limit applies to, weeks start on monday
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+// validated and guaranteed to be correct data
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct Budget {
+    id: Uuid,
+    expense_type: Uuid,
+    cost_bearer_id: Option<Uuid>,
+    period: BudgetPeriod,
+    limit: f64,
+}
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetState {
+    OnTrack,
+    AtRisk,
+    Overspent,
+}
+
+// how a budget is doing within the period containing a given date, both period dates are inclusive
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct BudgetStatus {
+    pub budget_id: Uuid,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub limit: f64,
+    pub spent: f64,
+    pub remaining: f64,
+    pub projected: f64,
+    pub state: BudgetState,
+}
+
+// share of the limit at which an alert is sent
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetThreshold {
+    Warning,
+    Exceeded,
+}
+
+pub const BUDGET_THRESHOLDS: [BudgetThreshold; 2] =
+    [BudgetThreshold::Warning, BudgetThreshold::Exceeded];
+
+// queued in the outbox with the entry that reached the threshold, read back to notify about it
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct BudgetAlert {
+    pub budget_id: Uuid,
+    pub expense_type: Uuid,
+    pub cost_bearer_id: Option<Uuid>,
+    pub threshold: BudgetThreshold,
+    pub percent: u32,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub limit: f64,
+    pub spent: f64,
+}
+
+#[derive(Debug, Error)]
+pub enum BudgetValidationError {
+    #[error("Budget Validation failed: Missing expense type.")]
+    MissingExpenseType,
+
+    #[error("Budget Validation failed: Cost bearer Id is nil.")]
+    MalformedCostBearerId,
+
+    #[error("Budget Validation failed: Limit must be a positive amount.")]
+    InvalidLimit,
+}
+
+impl BudgetPeriod {
+    // first day of the period containing the date and the first day of the following period
+    pub fn bounds(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let start = match self {
+            BudgetPeriod::Weekly => {
+                date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+            }
+            BudgetPeriod::Monthly => date.with_day(1).unwrap_or(date),
+            BudgetPeriod::Yearly => date.with_ordinal(1).unwrap_or(date),
+        };
+        let end = match self {
+            BudgetPeriod::Weekly => start + Days::new(7),
+            BudgetPeriod::Monthly => start + Months::new(1),
+            BudgetPeriod::Yearly => start + Months::new(12),
+        };
+        (start, end)
+    }
+}
+
+impl BudgetThreshold {
+    pub fn percent(&self) -> u32 {
+        match self {
+            BudgetThreshold::Warning => 80,
+            BudgetThreshold::Exceeded => 100,
+        }
+    }
+}
+
+impl BudgetStatus {
+    pub fn reached_thresholds(&self) -> Vec<BudgetThreshold> {
+        BUDGET_THRESHOLDS
+            .into_iter()
+            .filter(|threshold| self.spent >= self.limit * f64::from(threshold.percent()) / 100.0)
+            .collect()
+    }
+}
+
+impl Budget {
+    pub fn new(
+        expense_type: Uuid,
+        cost_bearer_id: Option<Uuid>,
+        period: BudgetPeriod,
+        limit: f64,
+    ) -> Result<Self, BudgetValidationError> {
+        // validate expense type
+        if expense_type.is_nil() {
+            return Err(BudgetValidationError::MissingExpenseType);
+        }
+
+        // an optional cost bearer still has to be a real id
+        if cost_bearer_id.is_some_and(|id| id.is_nil()) {
+            return Err(BudgetValidationError::MalformedCostBearerId);
+        }
+
+        // validate limit
+        if !limit.is_finite() || limit <= 0.0 {
+            return Err(BudgetValidationError::InvalidLimit);
+        }
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            expense_type,
+            cost_bearer_id,
+            period,
+            limit,
+        })
+    }
+
+    // rebuilds an already validated budget from storage
+    pub fn restore(
+        id: Uuid,
+        expense_type: Uuid,
+        cost_bearer_id: Option<Uuid>,
+        period: BudgetPeriod,
+        limit: f64,
+    ) -> Self {
+        Self {
+            id,
+            expense_type,
+            cost_bearer_id,
+            period,
+            limit,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn expense_type(&self) -> Uuid {
+        self.expense_type
+    }
+
+    pub fn cost_bearer_id(&self) -> Option<Uuid> {
+        self.cost_bearer_id
+    }
+
+    pub fn period(&self) -> BudgetPeriod {
+        self.period
+    }
+
+    pub fn limit(&self) -> f64 {
+        self.limit
+    }
+
+    // amount of an entry counted against this budget, only the bearer's share if one is set
+    pub fn amount_of(&self, entry: &ExpenseEntry) -> f64 {
+        if entry.expense_type() != self.expense_type {
+            return 0.0;
+        }
+        entry
+            .cost_shares()
+            .iter()
+            .filter(|share| {
+                self.cost_bearer_id
+                    .is_none_or(|cost_bearer_id| share.cost_bearer_id == cost_bearer_id)
+            })
+            .map(|share| share.amount)
+            .sum()
+    }
+
+    // entries outside of the period containing the date are ignored
+    pub fn status(&self, entries: &[ExpenseEntry], date: NaiveDate) -> BudgetStatus {
+        let (period_start, next_period_start) = self.period.bounds(date);
+        let spent: f64 = entries
+            .iter()
+            .filter(|entry| {
+                let day = entry.expense_date().date_naive();
+                day >= period_start && day < next_period_start
+            })
+            .map(|entry| self.amount_of(entry))
+            .sum();
+
+        // linear projection of the spending so far onto the whole period
+        let period_days = (next_period_start - period_start).num_days() as f64;
+        let elapsed_days = ((date - period_start).num_days() + 1) as f64;
+        let projected = spent / elapsed_days * period_days;
+
+        let state = if spent > self.limit {
+            BudgetState::Overspent
+        } else if projected > self.limit {
+            BudgetState::AtRisk
+        } else {
+            BudgetState::OnTrack
+        };
+
+        BudgetStatus {
+            budget_id: self.id,
+            period_start,
+            period_end: next_period_start - Days::new(1),
+            limit: self.limit,
+            spent,
+            remaining: self.limit - spent,
+            projected,
+            state,
+        }
+    }
+
+    pub fn alert(&self, status: &BudgetStatus, threshold: BudgetThreshold) -> BudgetAlert {
+        BudgetAlert {
+            budget_id: self.id,
+            expense_type: self.expense_type,
+            cost_bearer_id: self.cost_bearer_id,
+            threshold,
+            percent: threshold.percent(),
+            period_start: status.period_start,
+            period_end: status.period_end,
+            limit: status.limit,
+            spent: status.spent,
+        }
+    }
+}
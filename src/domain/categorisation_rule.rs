@@ -0,0 +1,283 @@
+use regex::{Regex, RegexBuilder};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::expense_entry::{ExpenseEntry, ExpenseEntryValidationError};
+use crate::domain::tag::{Tag, TagValidationError};
+
+// compiled patterns may not grow larger, so a rule cannot make matching slow or eat the memory
+const PATTERN_SIZE_LIMIT: usize = 256 * 1024;
+
+// validated and guaranteed to be correct data, all set conditions have to match for the actions
+// to be applied
+#[derive(serde::Serialize, Clone)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct CategorisationRule {
+    id: Uuid,
+    name: String,
+    priority: i64,
+    description_pattern: Option<String>,
+    // the description pattern compiled once for all entries it is matched against
+    #[serde(skip)]
+    pattern: Option<Regex>,
+    min_amount: Option<f64>,
+    max_amount: Option<f64>,
+    cost_bearer_id: Option<Uuid>,
+    set_expense_type: Option<Uuid>,
+    add_tags: Vec<Tag>,
+    rewrite_description: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum CategorisationRuleValidationError {
+    #[error("Categorisation Rule Validation failed: Name is empty or whitespace.")]
+    MissingName,
+
+    #[error("Categorisation Rule Validation failed: No condition given.")]
+    MissingCondition,
+
+    #[error("Categorisation Rule Validation failed: No action given.")]
+    MissingAction,
+
+    #[error("Categorisation Rule Validation failed: Invalid description pattern.")]
+    InvalidPattern,
+
+    #[error("Categorisation Rule Validation failed: Minimum amount is above the maximum.")]
+    InvalidAmountRange,
+
+    #[error("Categorisation Rule Validation failed: Rewritten description is empty.")]
+    MissingDescription,
+
+    #[error("Categorisation Rule Validation failed: Invalid tag: {0}")]
+    InvalidTag(#[from] TagValidationError),
+}
+
+// the conditions and actions of a rule as given by the user
+pub struct CategorisationRuleParts {
+    pub name: String,
+    pub priority: i64,
+    pub description_pattern: Option<String>,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub cost_bearer_id: Option<Uuid>,
+    pub set_expense_type: Option<Uuid>,
+    pub add_tags: Vec<String>,
+    pub rewrite_description: Option<String>,
+}
+
+fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .size_limit(PATTERN_SIZE_LIMIT)
+        .build()
+}
+
+impl CategorisationRule {
+    pub fn new(parts: CategorisationRuleParts) -> Result<Self, CategorisationRuleValidationError> {
+        let name = parts.name.trim().to_string();
+        if name.is_empty() {
+            return Err(CategorisationRuleValidationError::MissingName);
+        }
+
+        // empty patterns would match every description
+        let description_pattern = parts
+            .description_pattern
+            .filter(|pattern| !pattern.is_empty());
+        let pattern = description_pattern
+            .as_deref()
+            .map(compile)
+            .transpose()
+            .map_err(|_| CategorisationRuleValidationError::InvalidPattern)?;
+        if description_pattern.is_none()
+            && parts.min_amount.is_none()
+            && parts.max_amount.is_none()
+            && parts.cost_bearer_id.is_none()
+        {
+            return Err(CategorisationRuleValidationError::MissingCondition);
+        }
+        if let (Some(min_amount), Some(max_amount)) = (parts.min_amount, parts.max_amount)
+            && min_amount > max_amount
+        {
+            return Err(CategorisationRuleValidationError::InvalidAmountRange);
+        }
+
+        if parts
+            .rewrite_description
+            .as_ref()
+            .is_some_and(|description| description.trim().is_empty())
+        {
+            return Err(CategorisationRuleValidationError::MissingDescription);
+        }
+        let mut add_tags = parts
+            .add_tags
+            .iter()
+            .map(|tag| Tag::new(tag))
+            .collect::<Result<Vec<_>, _>>()?;
+        add_tags.sort();
+        add_tags.dedup();
+        if parts.set_expense_type.is_none()
+            && add_tags.is_empty()
+            && parts.rewrite_description.is_none()
+        {
+            return Err(CategorisationRuleValidationError::MissingAction);
+        }
+
+        Ok(CategorisationRule {
+            id: Uuid::new_v4(),
+            name,
+            priority: parts.priority,
+            description_pattern,
+            pattern,
+            min_amount: parts.min_amount,
+            max_amount: parts.max_amount,
+            cost_bearer_id: parts.cost_bearer_id,
+            set_expense_type: parts.set_expense_type,
+            add_tags,
+            rewrite_description: parts.rewrite_description,
+        })
+    }
+
+    // rebuilds an already validated rule from storage
+    #[allow(clippy::too_many_arguments)]
+    pub fn restore(
+        id: Uuid,
+        name: String,
+        priority: i64,
+        description_pattern: Option<String>,
+        min_amount: Option<f64>,
+        max_amount: Option<f64>,
+        cost_bearer_id: Option<Uuid>,
+        set_expense_type: Option<Uuid>,
+        add_tags: Vec<Tag>,
+        rewrite_description: Option<String>,
+    ) -> Self {
+        // validated on creation
+        let pattern = description_pattern
+            .as_deref()
+            .and_then(|pattern| compile(pattern).ok());
+        CategorisationRule {
+            id,
+            name,
+            priority,
+            description_pattern,
+            pattern,
+            min_amount,
+            max_amount,
+            cost_bearer_id,
+            set_expense_type,
+            add_tags,
+            rewrite_description,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn priority(&self) -> i64 {
+        self.priority
+    }
+
+    pub fn description_pattern(&self) -> Option<&str> {
+        self.description_pattern.as_deref()
+    }
+
+    pub fn min_amount(&self) -> Option<f64> {
+        self.min_amount
+    }
+
+    pub fn max_amount(&self) -> Option<f64> {
+        self.max_amount
+    }
+
+    pub fn cost_bearer_id(&self) -> Option<Uuid> {
+        self.cost_bearer_id
+    }
+
+    pub fn set_expense_type(&self) -> Option<Uuid> {
+        self.set_expense_type
+    }
+
+    pub fn add_tags(&self) -> &[Tag] {
+        &self.add_tags
+    }
+
+    pub fn rewrite_description(&self) -> Option<&str> {
+        self.rewrite_description.as_deref()
+    }
+
+    // the amount range applies to the total of all cost shares
+    pub fn matches(&self, entry: &ExpenseEntry) -> bool {
+        let amount: f64 = entry.cost_shares().iter().map(|share| share.amount).sum();
+        self.pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(entry.description()))
+            && self
+                .min_amount
+                .is_none_or(|min_amount| amount >= min_amount)
+            && self
+                .max_amount
+                .is_none_or(|max_amount| amount <= max_amount)
+            && self.cost_bearer_id.is_none_or(|cost_bearer_id| {
+                entry
+                    .cost_shares()
+                    .iter()
+                    .any(|share| share.cost_bearer_id == cost_bearer_id)
+            })
+    }
+
+    // the rewritten description, `$1` or `${name}` stand for the groups of the pattern
+    fn rewritten_description(&self, description: &str) -> Option<String> {
+        let template = self.rewrite_description.as_deref()?;
+        let rewritten = match self
+            .pattern
+            .as_ref()
+            .and_then(|pattern| pattern.captures(description))
+        {
+            Some(captures) => {
+                let mut rewritten = String::new();
+                captures.expand(template, &mut rewritten);
+                rewritten
+            }
+            None => template.to_string(),
+        };
+        Some(rewritten).filter(|rewritten| !rewritten.trim().is_empty())
+    }
+}
+
+// runs the rules in priority order against the entry as it was given, the first matching rule
+// setting the expense type or the description decides it, tags of all matching rules are added,
+// the result is validated like any other change to the entry
+pub fn categorise(
+    rules: &[CategorisationRule],
+    entry: &ExpenseEntry,
+) -> Result<ExpenseEntry, ExpenseEntryValidationError> {
+    let mut rules: Vec<&CategorisationRule> = rules.iter().collect();
+    rules.sort_by_key(|rule| rule.priority);
+    let mut expense_type = None;
+    let mut description = None;
+    let mut tags = entry.tags().to_vec();
+    for rule in rules.into_iter().filter(|rule| rule.matches(entry)) {
+        expense_type = expense_type.or(rule.set_expense_type);
+        description = description.or_else(|| rule.rewritten_description(entry.description()));
+        tags.extend(rule.add_tags.iter().cloned());
+    }
+    let validated_entry = ExpenseEntry::new(
+        entry.cost_shares().to_vec(),
+        expense_type.unwrap_or(entry.expense_type()),
+        description.unwrap_or_else(|| entry.description().to_string()),
+        entry.expense_date(),
+        tags.iter().map(|tag| tag.as_str().to_string()).collect(),
+    )?;
+    Ok(ExpenseEntry::restore(
+        entry.id(),
+        validated_entry.expense_date(),
+        validated_entry.cost_shares().to_vec(),
+        validated_entry.expense_type(),
+        validated_entry.description().to_string(),
+        validated_entry.tags().to_vec(),
+    ))
+}
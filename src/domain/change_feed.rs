@@ -0,0 +1,81 @@
+use uuid::Uuid;
+
+use crate::domain::outbox::{ChangeEvent, ChangedEntity};
+
+// what a live feed client wants to see, every given criterion has to match
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+pub struct ChangeFilter {
+    pub ledger_id: Option<Uuid>,
+    pub entity: Option<ChangedEntity>,
+    pub entity_id: Option<Uuid>,
+    // the cost bearer itself and entries it bears a share of
+    pub cost_bearer_id: Option<Uuid>,
+    // the expense type itself and entries booked on it
+    pub expense_type: Option<Uuid>,
+}
+
+fn entry_field(event: &ChangeEvent, field: &str) -> Option<serde_json::Value> {
+    event
+        .data
+        .as_ref()
+        .and_then(|data| data.get(field).cloned())
+}
+
+fn has_cost_bearer(event: &ChangeEvent, id: Uuid) -> bool {
+    match event.entity {
+        ChangedEntity::CostBearer => event.entity_id == id,
+        ChangedEntity::ExpenseEntry => entry_field(event, "cost_shares")
+            .and_then(|shares| shares.as_array().cloned())
+            .is_some_and(|shares| {
+                shares
+                    .iter()
+                    .any(|share| share["cost_bearer_id"] == id.to_string())
+            }),
+        ChangedEntity::ExpenseType | ChangedEntity::BudgetAlert => false,
+    }
+}
+
+fn has_expense_type(event: &ChangeEvent, id: Uuid) -> bool {
+    match event.entity {
+        ChangedEntity::ExpenseType => event.entity_id == id,
+        ChangedEntity::ExpenseEntry => entry_field(event, "expense_type")
+            .is_some_and(|expense_type| expense_type == id.to_string()),
+        ChangedEntity::CostBearer | ChangedEntity::BudgetAlert => false,
+    }
+}
+
+impl ChangeFilter {
+    // purged entries carry no data, so only the entity and id criteria can match them
+    pub fn matches(&self, event: &ChangeEvent) -> bool {
+        self.ledger_id.is_none_or(|id| id == event.ledger_id)
+            && self.entity.is_none_or(|entity| entity == event.entity)
+            && self.entity_id.is_none_or(|id| id == event.entity_id)
+            && self
+                .cost_bearer_id
+                .is_none_or(|id| has_cost_bearer(event, id))
+            && self
+                .expense_type
+                .is_none_or(|id| has_expense_type(event, id))
+    }
+}
+
+// a change as numbered by the live feed, the epoch tells feeds of different server runs apart
+#[derive(Clone)]
+pub struct FeedEvent {
+    pub epoch: Uuid,
+    pub sequence: u64,
+    pub change: ChangeEvent,
+}
+
+impl FeedEvent {
+    // used as the SSE event id, e.g. 0f8f...:42
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.epoch.simple(), self.sequence)
+    }
+}
+
+// the epoch and sequence of an event id, nothing if it is no event id of the feed
+pub fn parse_event_id(id: &str) -> Option<(Uuid, u64)> {
+    let (epoch, sequence) = id.trim().split_once(':')?;
+    Some((Uuid::try_parse(epoch).ok()?, sequence.parse().ok()?))
+}
@@ -2,13 +2,16 @@ use chrono::{DateTime, Utc};
 use thiserror::Error;
 use uuid::Uuid;
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 #[cfg_attr(test, derive(serde::Deserialize))]
 pub struct CostBearer {
     id: Uuid,
     name: String,
     exists_from: DateTime<Utc>,
     exists_to: Option<DateTime<Utc>>,
+    // the users allowed to see the entries borne by a personal account, empty for shared ones
+    #[cfg_attr(test, serde(default))]
+    private_to: Vec<String>,
 }
 
 #[derive(Debug, Error)]
@@ -18,6 +21,9 @@ pub enum CostBearerValidationError {
 
     #[error("Cost Bearer Validation failed: Date validation failed.")]
     InvalidDate,
+
+    #[error("Cost Bearer Validation failed: A private user is empty or whitespace.")]
+    InvalidPrivateUser,
 }
 
 impl CostBearer {
@@ -25,6 +31,7 @@ impl CostBearer {
         name: String,
         exists_from: DateTime<Utc>,
         exists_to: impl Into<Option<DateTime<Utc>>>,
+        private_to: Vec<String>,
     ) -> Result<Self, CostBearerValidationError> {
         // validate that the name is no empty
         if name.trim().is_empty() {
@@ -39,14 +46,43 @@ impl CostBearer {
             return Err(CostBearerValidationError::InvalidDate);
         }
 
+        let mut users = Vec::with_capacity(private_to.len());
+        for user in private_to {
+            let user = user.trim();
+            if user.is_empty() {
+                return Err(CostBearerValidationError::InvalidPrivateUser);
+            }
+            if !users.iter().any(|known| known == user) {
+                users.push(user.to_string());
+            }
+        }
+
         Ok(Self {
             id: Uuid::new_v4(),
             name,
             exists_from,
             exists_to,
+            private_to: users,
         })
     }
 
+    // rebuilds an already validated cost bearer from storage
+    pub fn restore(
+        id: Uuid,
+        name: String,
+        exists_from: DateTime<Utc>,
+        exists_to: Option<DateTime<Utc>>,
+        private_to: Vec<String>,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            exists_from,
+            exists_to,
+            private_to,
+        }
+    }
+
     pub fn id(&self) -> Uuid {
         self.id
     }
@@ -62,4 +98,8 @@ impl CostBearer {
     pub fn exists_to(&self) -> Option<DateTime<Utc>> {
         self.exists_to
     }
+
+    pub fn private_to(&self) -> &[String] {
+        &self.private_to
+    }
 }
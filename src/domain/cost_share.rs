@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Copy)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq)]
 pub struct CostShare {
     pub cost_bearer_id: Uuid,
     pub amount: f64,
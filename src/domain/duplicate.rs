@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use chrono::Duration;
+
+use crate::domain::expense_entry::ExpenseEntry;
+
+// entries further apart than this are never taken for the same purchase
+pub const DUPLICATE_WINDOW_DAYS: i64 = 3;
+
+// relative difference of the total amounts up to which two entries may be duplicates
+const AMOUNT_TOLERANCE: f64 = 0.05;
+
+// pairs scoring lower are not reported
+pub const DUPLICATE_MIN_SCORE: f64 = 0.75;
+
+// two entries that likely record the same purchase, the later one is the suspected duplicate
+#[derive(serde::Serialize)]
+pub struct DuplicatePair {
+    pub entry: ExpenseEntry,
+    pub duplicate_of: ExpenseEntry,
+    pub score: f64,
+}
+
+fn total_amount(entry: &ExpenseEntry) -> f64 {
+    entry.cost_shares().iter().map(|share| share.amount).sum()
+}
+
+fn shares_cost_bearer(entry: &ExpenseEntry, other: &ExpenseEntry) -> bool {
+    let cost_bearer_ids: HashSet<_> = entry
+        .cost_shares()
+        .iter()
+        .map(|share| share.cost_bearer_id)
+        .collect();
+    other
+        .cost_shares()
+        .iter()
+        .any(|share| cost_bearer_ids.contains(&share.cost_bearer_id))
+}
+
+// the pairs of adjacent characters of the lowercased words
+fn bigrams(description: &str) -> Vec<(char, char)> {
+    let mut bigrams = Vec::new();
+    for word in description.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = word.to_lowercase().chars().collect();
+        bigrams.extend(chars.windows(2).map(|pair| (pair[0], pair[1])));
+    }
+    bigrams
+}
+
+// dice coefficient of the character bigrams, case and punctuation do not matter and a shortened
+// description like "Rewe" for "REWE Markt 123" still scores partly
+fn description_similarity(description: &str, other: &str) -> f64 {
+    let own_bigrams = bigrams(description);
+    let mut other_bigrams = bigrams(other);
+    if own_bigrams.is_empty() || other_bigrams.is_empty() {
+        return if description.trim().eq_ignore_ascii_case(other.trim()) {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    let total = (own_bigrams.len() + other_bigrams.len()) as f64;
+    let mut shared = 0;
+    for bigram in own_bigrams {
+        if let Some(position) = other_bigrams.iter().position(|other| *other == bigram) {
+            other_bigrams.swap_remove(position);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total
+}
+
+// how alike two entries are from 0 to 1, nothing if they cannot be the same purchase because
+// they share no cost bearer or the amounts or dates are too far apart
+pub fn similarity(entry: &ExpenseEntry, other: &ExpenseEntry) -> Option<f64> {
+    if entry.id() == other.id() || !shares_cost_bearer(entry, other) {
+        return None;
+    }
+    let (amount, other_amount) = (total_amount(entry), total_amount(other));
+    let largest_amount = amount.abs().max(other_amount.abs());
+    let amount_difference = if largest_amount == 0.0 {
+        0.0
+    } else {
+        (amount - other_amount).abs() / largest_amount
+    };
+    if amount_difference > AMOUNT_TOLERANCE {
+        return None;
+    }
+    let window = Duration::days(DUPLICATE_WINDOW_DAYS);
+    let distance = (entry.expense_date() - other.expense_date()).abs();
+    if distance > window {
+        return None;
+    }
+
+    let amount_score = 1.0 - amount_difference / AMOUNT_TOLERANCE;
+    let date_score = 1.0 - distance.num_seconds() as f64 / window.num_seconds() as f64;
+    let description_score = description_similarity(entry.description(), other.description());
+    let score = 0.4 * amount_score + 0.2 * date_score + 0.4 * description_score;
+    Some((score * 100.0).round() / 100.0)
+}
+
+// the candidates among the entries, best first
+pub fn duplicate_pairs(mut entries: Vec<ExpenseEntry>) -> Vec<DuplicatePair> {
+    entries.sort_by_key(|entry| entry.expense_date());
+    let window = Duration::days(DUPLICATE_WINDOW_DAYS);
+    let mut pairs = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        // sorted by date, so only the following entries within the window can match
+        for later in entries[index + 1..]
+            .iter()
+            .take_while(|later| later.expense_date() - entry.expense_date() <= window)
+        {
+            if let Some(score) =
+                similarity(later, entry).filter(|score| *score >= DUPLICATE_MIN_SCORE)
+            {
+                pairs.push(DuplicatePair {
+                    entry: later.clone(),
+                    duplicate_of: entry.clone(),
+                    score,
+                });
+            }
+        }
+    }
+    pairs.sort_by(|pair, other| other.score.total_cmp(&pair.score));
+    pairs
+}
@@ -1,11 +1,15 @@
+use std::collections::HashSet;
+
 use chrono::{DateTime, Utc};
+use serde_json::Value;
 use thiserror::Error;
 use uuid::Uuid;
 
 use crate::domain::cost_share::CostShare;
+use crate::domain::tag::{Tag, TagValidationError};
 
 // validated and guaranteed to be correct data
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 #[cfg_attr(test, derive(serde::Deserialize))]
 pub struct ExpenseEntry {
     id: Uuid,
@@ -13,6 +17,11 @@ pub struct ExpenseEntry {
     cost_shares: Vec<CostShare>,
     expense_type: Uuid,
     description: String,
+    tags: Vec<Tag>,
+    // set when the details were left out for a member not allowed to see them
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[cfg_attr(test, serde(default))]
+    redacted: bool,
 }
 
 #[derive(Debug, Error)]
@@ -37,6 +46,9 @@ pub enum ExpenseEntryValidationError {
 
     #[error("Expense Entry Validation failed: Description is empty or whitespace.")]
     MissingDescription,
+
+    #[error("Expense Entry Validation failed: Invalid tag: {0}")]
+    InvalidTag(#[from] TagValidationError),
 }
 
 impl ExpenseEntry {
@@ -45,6 +57,7 @@ impl ExpenseEntry {
         expense_type: Uuid,
         description: String,
         expense_date: impl Into<Option<DateTime<Utc>>>,
+        tags: Vec<String>,
     ) -> Result<Self, ExpenseEntryValidationError> {
         // validate cost shares
         if cost_shares.is_empty() {
@@ -86,15 +99,45 @@ impl ExpenseEntry {
             return Err(ExpenseEntryValidationError::MissingDescription);
         }
 
+        // normalise tags into a sorted set
+        let mut tags = tags
+            .iter()
+            .map(|tag| Tag::new(tag))
+            .collect::<Result<Vec<_>, _>>()?;
+        tags.sort();
+        tags.dedup();
+
         Ok(Self {
             id: Uuid::new_v4(),
             expense_date: expense_date.into().unwrap_or_else(chrono::Utc::now),
             cost_shares,
             expense_type,
             description,
+            tags,
+            redacted: false,
         })
     }
 
+    // rebuilds an already validated expense entry from storage
+    pub fn restore(
+        id: Uuid,
+        expense_date: DateTime<Utc>,
+        cost_shares: Vec<CostShare>,
+        expense_type: Uuid,
+        description: String,
+        tags: Vec<Tag>,
+    ) -> Self {
+        Self {
+            id,
+            expense_date,
+            cost_shares,
+            expense_type,
+            description,
+            tags,
+            redacted: false,
+        }
+    }
+
     pub fn id(&self) -> Uuid {
         self.id
     }
@@ -110,4 +153,45 @@ impl ExpenseEntry {
     pub fn description(&self) -> &str {
         &self.description
     }
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    pub fn is_redacted(&self) -> bool {
+        self.redacted
+    }
+
+    // keeps only what adds up in the totals: date, expense type and the shares
+    pub fn redact(self) -> Self {
+        Self {
+            description: String::new(),
+            tags: Vec::new(),
+            redacted: true,
+            ..self
+        }
+    }
+}
+
+// an entry serialized into a snapshot or a change is redacted like the entry itself once it has a
+// share on one of the hidden cost bearers
+pub fn redact_snapshot(snapshot: Option<&mut Value>, hidden: &HashSet<Uuid>) {
+    let Some(Value::Object(entry)) = snapshot else {
+        return;
+    };
+    let on_hidden = entry
+        .get("cost_shares")
+        .and_then(Value::as_array)
+        .is_some_and(|shares| {
+            shares.iter().any(|share| {
+                share["cost_bearer_id"]
+                    .as_str()
+                    .and_then(|id| Uuid::parse_str(id).ok())
+                    .is_some_and(|id| hidden.contains(&id))
+            })
+        });
+    if on_hidden {
+        entry.insert(String::from("description"), Value::String(String::new()));
+        entry.insert(String::from("tags"), Value::Array(Vec::new()));
+        entry.insert(String::from("redacted"), Value::Bool(true));
+    }
 }
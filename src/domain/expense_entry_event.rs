@@ -0,0 +1,214 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::cost_share::CostShare;
+use crate::domain::expense_entry::ExpenseEntry;
+use crate::domain::tag::Tag;
+
+// what happened to one expense entry, its stream of events is the source of truth of the event store
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum ExpenseEntryEvent {
+    ExpenseEntryCreated {
+        expense_date: DateTime<Utc>,
+        cost_shares: Vec<CostShare>,
+        expense_type: Uuid,
+        description: String,
+        tags: Vec<String>,
+    },
+    ExpenseDateChanged {
+        expense_date: DateTime<Utc>,
+    },
+    CostSharesChanged {
+        cost_shares: Vec<CostShare>,
+    },
+    ExpenseTypeChanged {
+        expense_type: Uuid,
+    },
+    DescriptionChanged {
+        description: String,
+    },
+    TagsChanged {
+        tags: Vec<String>,
+    },
+    ExpenseEntryDeleted {
+        deleted_at: DateTime<Utc>,
+    },
+    ExpenseEntryRestored,
+    ExpenseEntryPurged,
+}
+
+impl ExpenseEntryEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExpenseEntryEvent::ExpenseEntryCreated { .. } => "ExpenseEntryCreated",
+            ExpenseEntryEvent::ExpenseDateChanged { .. } => "ExpenseDateChanged",
+            ExpenseEntryEvent::CostSharesChanged { .. } => "CostSharesChanged",
+            ExpenseEntryEvent::ExpenseTypeChanged { .. } => "ExpenseTypeChanged",
+            ExpenseEntryEvent::DescriptionChanged { .. } => "DescriptionChanged",
+            ExpenseEntryEvent::TagsChanged { .. } => "TagsChanged",
+            ExpenseEntryEvent::ExpenseEntryDeleted { .. } => "ExpenseEntryDeleted",
+            ExpenseEntryEvent::ExpenseEntryRestored => "ExpenseEntryRestored",
+            ExpenseEntryEvent::ExpenseEntryPurged => "ExpenseEntryPurged",
+        }
+    }
+}
+
+fn tag_names(tags: &[Tag]) -> Vec<String> {
+    tags.iter().map(|tag| tag.as_str().to_string()).collect()
+}
+
+// the state of an expense entry as far as the events tell it
+struct ExpenseEntryState {
+    expense_date: DateTime<Utc>,
+    cost_shares: Vec<CostShare>,
+    expense_type: Uuid,
+    description: String,
+    tags: Vec<String>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+// an expense entry rebuilt by replaying its events in order, the version is the number of events
+pub struct ExpenseEntryAggregate {
+    id: Uuid,
+    version: u64,
+    state: Option<ExpenseEntryState>,
+}
+
+impl ExpenseEntryAggregate {
+    pub fn replay<'a>(id: Uuid, events: impl IntoIterator<Item = &'a ExpenseEntryEvent>) -> Self {
+        let mut aggregate = ExpenseEntryAggregate {
+            id,
+            version: 0,
+            state: None,
+        };
+        for event in events {
+            aggregate.apply(event);
+        }
+        aggregate
+    }
+
+    pub fn apply(&mut self, event: &ExpenseEntryEvent) {
+        self.version += 1;
+        if let ExpenseEntryEvent::ExpenseEntryCreated {
+            expense_date,
+            cost_shares,
+            expense_type,
+            description,
+            tags,
+        } = event
+        {
+            self.state = Some(ExpenseEntryState {
+                expense_date: *expense_date,
+                cost_shares: cost_shares.clone(),
+                expense_type: *expense_type,
+                description: description.clone(),
+                tags: tags.clone(),
+                deleted_at: None,
+            });
+            return;
+        }
+        if let ExpenseEntryEvent::ExpenseEntryPurged = event {
+            self.state = None;
+            return;
+        }
+        // changes of an entry that does not exist are meaningless
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+        match event {
+            ExpenseEntryEvent::ExpenseDateChanged { expense_date } => {
+                state.expense_date = *expense_date
+            }
+            ExpenseEntryEvent::CostSharesChanged { cost_shares } => {
+                state.cost_shares = cost_shares.clone()
+            }
+            ExpenseEntryEvent::ExpenseTypeChanged { expense_type } => {
+                state.expense_type = *expense_type
+            }
+            ExpenseEntryEvent::DescriptionChanged { description } => {
+                state.description = description.clone()
+            }
+            ExpenseEntryEvent::TagsChanged { tags } => state.tags = tags.clone(),
+            ExpenseEntryEvent::ExpenseEntryDeleted { deleted_at } => {
+                state.deleted_at = Some(*deleted_at)
+            }
+            ExpenseEntryEvent::ExpenseEntryRestored => state.deleted_at = None,
+            ExpenseEntryEvent::ExpenseEntryCreated { .. }
+            | ExpenseEntryEvent::ExpenseEntryPurged => {}
+        }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    // the entry, also while it is in the trash
+    pub fn entry(&self) -> Option<ExpenseEntry> {
+        self.state.as_ref().map(|state| {
+            ExpenseEntry::restore(
+                self.id,
+                state.expense_date,
+                state.cost_shares.clone(),
+                state.expense_type,
+                state.description.clone(),
+                state.tags.iter().cloned().map(Tag::restore).collect(),
+            )
+        })
+    }
+
+    pub fn exists(&self) -> bool {
+        self.state.is_some()
+    }
+
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.state.as_ref().and_then(|state| state.deleted_at)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.exists() && self.deleted_at().is_none()
+    }
+
+    pub fn created(entry: &ExpenseEntry) -> ExpenseEntryEvent {
+        ExpenseEntryEvent::ExpenseEntryCreated {
+            expense_date: entry.expense_date(),
+            cost_shares: entry.cost_shares().to_vec(),
+            expense_type: entry.expense_type(),
+            description: entry.description().to_string(),
+            tags: tag_names(entry.tags()),
+        }
+    }
+
+    // one event per changed field, nothing if the entry is unchanged
+    pub fn changes(&self, entry: &ExpenseEntry) -> Vec<ExpenseEntryEvent> {
+        let Some(state) = self.state.as_ref() else {
+            return vec![Self::created(entry)];
+        };
+        let mut events = Vec::new();
+        if state.expense_date != entry.expense_date() {
+            events.push(ExpenseEntryEvent::ExpenseDateChanged {
+                expense_date: entry.expense_date(),
+            });
+        }
+        if state.cost_shares != entry.cost_shares() {
+            events.push(ExpenseEntryEvent::CostSharesChanged {
+                cost_shares: entry.cost_shares().to_vec(),
+            });
+        }
+        if state.expense_type != entry.expense_type() {
+            events.push(ExpenseEntryEvent::ExpenseTypeChanged {
+                expense_type: entry.expense_type(),
+            });
+        }
+        if state.description != entry.description() {
+            events.push(ExpenseEntryEvent::DescriptionChanged {
+                description: entry.description().to_string(),
+            });
+        }
+        let tags = tag_names(entry.tags());
+        if state.tags != tags {
+            events.push(ExpenseEntryEvent::TagsChanged { tags });
+        }
+        events
+    }
+}
@@ -1,13 +1,32 @@
+use std::collections::{HashMap, HashSet};
+
 use thiserror::Error;
 use uuid::Uuid;
 
 // validated and guaranteed to be correct data
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 #[cfg_attr(test, derive(serde::Deserialize))]
 pub struct ExpenseType {
     id: Uuid,
     name: String,
     description: String,
+    parent_id: Option<Uuid>,
+}
+
+// all expense types, used for validations and calculations that span the tree
+pub struct ExpenseTypeHierarchy {
+    expense_types: HashMap<Uuid, ExpenseType>,
+}
+
+// amounts of one expense type, the total includes all of its sub types
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct ExpenseTypeTotal {
+    pub expense_type_id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    pub amount: f64,
+    pub total_amount: f64,
 }
 
 #[derive(Debug, Error)]
@@ -18,10 +37,18 @@ pub enum ExpenseTypeValidationError {
     MissingDescription,
     #[error("Expense Type Validation failed: Name already in use.")]
     DuplicateName,
+    #[error("Expense Type Validation failed: Parent does not exist.")]
+    UnknownParent,
+    #[error("Expense Type Validation failed: Expense type would be its own ancestor.")]
+    CyclicParent,
 }
 
 impl ExpenseType {
-    pub fn new(name: String, description: String) -> Result<Self, ExpenseTypeValidationError> {
+    pub fn new(
+        name: String,
+        description: String,
+        parent_id: Option<Uuid>,
+    ) -> Result<Self, ExpenseTypeValidationError> {
         // validate that the name is not empty
         if name.trim().is_empty() {
             return Err(ExpenseTypeValidationError::MissingName);
@@ -32,20 +59,26 @@ impl ExpenseType {
             return Err(ExpenseTypeValidationError::MissingDescription);
         }
 
-        // validate that the name is unique (database-dependent validation)
-        if false {
-            todo!(
-                "Implementation of expense type name uniqueness validation must still happen once we have a DB."
-            );
-        }
+        // the name uniqueness among siblings and the parent are validated by ExpenseTypeHierarchy
 
         Ok(Self {
             id: Uuid::new_v4(),
             name,
             description,
+            parent_id,
         })
     }
 
+    // rebuilds an already validated expense type from storage
+    pub fn restore(id: Uuid, name: String, description: String, parent_id: Option<Uuid>) -> Self {
+        Self {
+            id,
+            name,
+            description,
+            parent_id,
+        }
+    }
+
     pub fn id(&self) -> Uuid {
         self.id
     }
@@ -57,4 +90,87 @@ impl ExpenseType {
     pub fn description(&self) -> &str {
         &self.description
     }
+
+    pub fn parent_id(&self) -> Option<Uuid> {
+        self.parent_id
+    }
+}
+
+impl ExpenseTypeHierarchy {
+    pub fn new(expense_types: Vec<ExpenseType>) -> Self {
+        Self {
+            expense_types: expense_types
+                .into_iter()
+                .map(|expense_type| (expense_type.id(), expense_type))
+                .collect(),
+        }
+    }
+
+    // checks a new or changed expense type against all other expense types
+    pub fn validate(&self, candidate: &ExpenseType) -> Result<(), ExpenseTypeValidationError> {
+        // walk up from the new parent, reaching the candidate or any ancestor again means a cycle
+        let mut ancestor_id = candidate.parent_id();
+        let mut visited = HashSet::new();
+        while let Some(id) = ancestor_id {
+            if id == candidate.id() || !visited.insert(id) {
+                return Err(ExpenseTypeValidationError::CyclicParent);
+            }
+            let ancestor = self
+                .expense_types
+                .get(&id)
+                .ok_or(ExpenseTypeValidationError::UnknownParent)?;
+            ancestor_id = ancestor.parent_id();
+        }
+
+        // names only have to be unique among siblings
+        let name = candidate.name().trim();
+        let duplicate = self.expense_types.values().any(|other| {
+            other.id() != candidate.id()
+                && other.parent_id() == candidate.parent_id()
+                && other.name().trim().eq_ignore_ascii_case(name)
+        });
+        if duplicate {
+            return Err(ExpenseTypeValidationError::DuplicateName);
+        }
+
+        Ok(())
+    }
+
+    pub fn has_children(&self, id: Uuid) -> bool {
+        self.expense_types
+            .values()
+            .any(|expense_type| expense_type.parent_id() == Some(id))
+    }
+
+    // sums the amounts per expense type up the tree, amounts of unknown expense types are ignored
+    pub fn roll_up(&self, amounts: &HashMap<Uuid, f64>) -> Vec<ExpenseTypeTotal> {
+        let mut totals: HashMap<Uuid, f64> = HashMap::new();
+        for (expense_type_id, amount) in amounts {
+            let mut current = self.expense_types.get(expense_type_id);
+            // stored data is not trusted to be free of cycles, each ancestor counts once
+            let mut visited = HashSet::new();
+            while let Some(expense_type) =
+                current.filter(|expense_type| visited.insert(expense_type.id()))
+            {
+                *totals.entry(expense_type.id()).or_default() += amount;
+                current = expense_type
+                    .parent_id()
+                    .and_then(|parent_id| self.expense_types.get(&parent_id));
+            }
+        }
+
+        let mut expense_type_totals: Vec<ExpenseTypeTotal> = self
+            .expense_types
+            .values()
+            .map(|expense_type| ExpenseTypeTotal {
+                expense_type_id: expense_type.id(),
+                name: expense_type.name().to_string(),
+                parent_id: expense_type.parent_id(),
+                amount: amounts.get(&expense_type.id()).copied().unwrap_or_default(),
+                total_amount: totals.get(&expense_type.id()).copied().unwrap_or_default(),
+            })
+            .collect();
+        expense_type_totals.sort_by(|a, b| a.name.cmp(&b.name));
+        expense_type_totals
+    }
 }
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+// longer keys are no uuids or similar tokens but most likely a client bug
+const MAX_KEY_LENGTH: usize = 255;
+
+// the response handed out again when a request is retried with the same key
+#[derive(Clone)]
+pub struct IdempotentResponse {
+    pub status_code: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+// a key a client sent with a request, the response is missing while the request is running
+pub struct IdempotencyRecord {
+    scope: String,
+    key: String,
+    fingerprint: String,
+    response: Option<IdempotentResponse>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum IdempotencyValidationError {
+    #[error("Idempotency Validation failed: Key is empty or too long.")]
+    InvalidKey,
+}
+
+// hex encoded SHA-256 over what makes two requests the same one, including who sent them
+pub fn fingerprint(sender: &str, method: &str, path: &str, body: &[u8]) -> String {
+    Sha256::new()
+        .chain_update(sender.as_bytes())
+        .chain_update([0])
+        .chain_update(method.as_bytes())
+        .chain_update([0])
+        .chain_update(path.as_bytes())
+        .chain_update([0])
+        .chain_update(body)
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+impl IdempotencyRecord {
+    // keys are only unique per sender, the scope keeps senders from replaying each other
+    pub fn new(
+        scope: String,
+        key: String,
+        fingerprint: String,
+    ) -> Result<Self, IdempotencyValidationError> {
+        if key.trim().is_empty() || key.len() > MAX_KEY_LENGTH {
+            return Err(IdempotencyValidationError::InvalidKey);
+        }
+        Ok(IdempotencyRecord {
+            scope,
+            key,
+            fingerprint,
+            response: None,
+            created_at: Utc::now(),
+        })
+    }
+
+    // rebuilds an already validated record from storage
+    pub fn restore(
+        scope: String,
+        key: String,
+        fingerprint: String,
+        response: Option<IdempotentResponse>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        IdempotencyRecord {
+            scope,
+            key,
+            fingerprint,
+            response,
+            created_at,
+        }
+    }
+
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    pub fn response(&self) -> Option<&IdempotentResponse> {
+        self.response.as_ref()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
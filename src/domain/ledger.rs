@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+// the books of one household, every expense entry, cost bearer and expense type belongs to
+// exactly one ledger and is never visible from another
+#[derive(serde::Serialize, Clone)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct Ledger {
+    id: Uuid,
+    name: String,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum LedgerValidationError {
+    #[error("Ledger Validation failed: Name is empty or whitespace.")]
+    MissingName,
+}
+
+impl Ledger {
+    pub fn new(name: String) -> Result<Self, LedgerValidationError> {
+        if name.trim().is_empty() {
+            return Err(LedgerValidationError::MissingName);
+        }
+        Ok(Ledger {
+            id: Uuid::new_v4(),
+            name: name.trim().to_string(),
+            created_at: Utc::now(),
+        })
+    }
+
+    // rebuilds an already validated ledger from storage
+    pub fn restore(id: Uuid, name: String, created_at: DateTime<Utc>) -> Self {
+        Ledger {
+            id,
+            name,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+// what a member may do in a ledger, every role includes the permissions of the ones before it
+#[derive(
+    serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerRole {
+    // reads everything
+    Viewer,
+    // manages expense entries, recurring expenses and budgets
+    Editor,
+    // manages cost bearers, expense types and the members
+    Owner,
+}
+
+// a user signed in through the sso with access to one ledger, identified by the subject of the token
+#[derive(serde::Serialize, Clone)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct LedgerMember {
+    ledger_id: Uuid,
+    user: String,
+    role: LedgerRole,
+    added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum LedgerMemberValidationError {
+    #[error("Ledger Member Validation failed: User is empty or whitespace.")]
+    MissingUser,
+}
+
+impl LedgerMember {
+    pub fn new(
+        ledger_id: Uuid,
+        user: String,
+        role: LedgerRole,
+    ) -> Result<Self, LedgerMemberValidationError> {
+        if user.trim().is_empty() {
+            return Err(LedgerMemberValidationError::MissingUser);
+        }
+        Ok(LedgerMember {
+            ledger_id,
+            user: user.trim().to_string(),
+            role,
+            added_at: Utc::now(),
+        })
+    }
+
+    // rebuilds an already validated member from storage
+    pub fn restore(
+        ledger_id: Uuid,
+        user: String,
+        role: LedgerRole,
+        added_at: DateTime<Utc>,
+    ) -> Self {
+        LedgerMember {
+            ledger_id,
+            user,
+            role,
+            added_at,
+        }
+    }
+
+    pub fn ledger_id(&self) -> Uuid {
+        self.ledger_id
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    pub fn role(&self) -> LedgerRole {
+        self.role
+    }
+
+    pub fn added_at(&self) -> DateTime<Utc> {
+        self.added_at
+    }
+}
@@ -1,4 +1,22 @@
+pub mod api_key;
+pub mod attachment;
+pub mod audit;
+pub mod budget;
+pub mod categorisation_rule;
+pub mod change_feed;
 pub mod cost_bearer;
 pub mod cost_share;
+pub mod duplicate;
 pub mod expense_entry;
+pub mod expense_entry_event;
 pub mod expense_type;
+pub mod idempotency;
+pub mod ledger;
+pub mod ledger_member;
+pub mod outbox;
+pub mod principal;
+pub mod projection;
+pub mod recurring_expense;
+pub mod tag;
+pub mod trash;
+pub mod webhook;
@@ -0,0 +1,115 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangedEntity {
+    ExpenseEntry,
+    CostBearer,
+    ExpenseType,
+    // only handed to the budget notifier, never published to webhooks or live feeds
+    BudgetAlert,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    Created,
+    Updated,
+    Deleted,
+    Restored,
+    Purged,
+}
+
+// a committed change in one ledger as handed to the outbox handlers, data is the entity after the
+// change and missing once it is purged
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct ChangeEvent {
+    pub id: Uuid,
+    pub ledger_id: Uuid,
+    pub entity: ChangedEntity,
+    pub entity_id: Uuid,
+    pub action: ChangeAction,
+    pub data: Option<serde_json::Value>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl ChangedEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangedEntity::ExpenseEntry => "expense_entry",
+            ChangedEntity::CostBearer => "cost_bearer",
+            ChangedEntity::ExpenseType => "expense_type",
+            ChangedEntity::BudgetAlert => "budget_alert",
+        }
+    }
+
+    // whether subscribers of the ledger's changes get to see it
+    pub fn is_published(&self) -> bool {
+        !matches!(self, ChangedEntity::BudgetAlert)
+    }
+}
+
+impl ChangeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeAction::Created => "created",
+            ChangeAction::Updated => "updated",
+            ChangeAction::Deleted => "deleted",
+            ChangeAction::Restored => "restored",
+            ChangeAction::Purged => "purged",
+        }
+    }
+}
+
+impl ChangeEvent {
+    // entity and action joined by a dot, e.g. expense_entry.created
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.entity.as_str(), self.action.as_str())
+    }
+}
+
+// how a change is handed out to webhooks and live feeds, the change event with its name
+#[derive(serde::Serialize)]
+pub struct ChangeNotification<'a> {
+    pub event: String,
+    #[serde(flatten)]
+    pub change: &'a ChangeEvent,
+}
+
+impl<'a> From<&'a ChangeEvent> for ChangeNotification<'a> {
+    fn from(change: &'a ChangeEvent) -> Self {
+        ChangeNotification {
+            event: change.name(),
+            change,
+        }
+    }
+}
+
+// one event still to be delivered to one handler
+pub struct OutboxDelivery {
+    pub event: ChangeEvent,
+    pub handler: String,
+    pub attempts: u32,
+}
+
+// a delivery that was given up on after too many failed attempts
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct DeadLetter {
+    pub event: ChangeEvent,
+    pub handler: String,
+    pub attempts: u32,
+    pub last_error: String,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+const MAX_RETRY_DELAY_SECONDS: i64 = 60 * 60;
+
+// doubles with every failed attempt, starting at one second and capped at an hour
+pub fn retry_delay(attempts: u32) -> Duration {
+    let seconds = 1i64
+        .checked_shl(attempts.saturating_sub(1))
+        .unwrap_or(MAX_RETRY_DELAY_SECONDS);
+    Duration::seconds(seconds.min(MAX_RETRY_DELAY_SECONDS))
+}
@@ -0,0 +1,78 @@
+use uuid::Uuid;
+
+// how a request proved who sent it
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum PrincipalKind {
+    ApiKey,
+    User,
+}
+
+// the authenticated sender of a request, either an api key or a user signed in through the sso
+#[derive(Clone, Debug, serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct Principal {
+    kind: PrincipalKind,
+    subject: String,
+    // names of api keys are not unique, their id is
+    #[serde(skip)]
+    key_id: Option<Uuid>,
+    // the user an api key acts for
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    user: Option<String>,
+}
+
+impl Principal {
+    pub fn api_key(id: Uuid, name: &str, user: Option<&str>) -> Self {
+        Principal {
+            kind: PrincipalKind::ApiKey,
+            subject: name.to_string(),
+            key_id: Some(id),
+            user: user.map(str::to_string),
+        }
+    }
+
+    // users are identified by the subject of their token
+    pub fn user(subject: &str) -> Self {
+        Principal {
+            kind: PrincipalKind::User,
+            subject: subject.to_string(),
+            key_id: None,
+            user: None,
+        }
+    }
+
+    pub fn kind(&self) -> PrincipalKind {
+        self.kind
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    // whose ledger roles apply to the principal, none for the operator's api keys
+    pub fn acting_user(&self) -> Option<&str> {
+        match self.kind() {
+            PrincipalKind::ApiKey => self.user.as_deref(),
+            PrincipalKind::User => Some(self.subject()),
+        }
+    }
+
+    // how changes made by the principal show up in the audit log, e.g. `user:alice`
+    pub fn actor(&self) -> String {
+        let kind = match self.kind {
+            PrincipalKind::ApiKey => "api_key",
+            PrincipalKind::User => "user",
+        };
+        format!("{kind}:{}", self.subject)
+    }
+
+    // tells principals apart across requests, e.g. `api_key:<id>` or `user:alice`
+    pub fn identity(&self) -> String {
+        match self.key_id {
+            Some(id) => format!("api_key:{id}"),
+            None => self.actor(),
+        }
+    }
+}
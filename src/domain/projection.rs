@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::tag::Tag;
+
+// denormalised read models kept up to date from the expense entry events, names are resolved
+// when projecting and are missing for unknown expense types and cost bearers
+
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct CostShareRow {
+    pub cost_bearer_id: Uuid,
+    pub cost_bearer_name: Option<String>,
+    pub amount: f64,
+}
+
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct ExpenseEntryRow {
+    pub id: Uuid,
+    pub expense_date: DateTime<Utc>,
+    pub expense_type: Uuid,
+    pub expense_type_name: Option<String>,
+    pub description: String,
+    pub cost_shares: Vec<CostShareRow>,
+    pub tags: Vec<Tag>,
+    // set when the details were left out for a member not allowed to see them
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[cfg_attr(test, serde(default))]
+    pub redacted: bool,
+}
+
+impl ExpenseEntryRow {
+    // keeps only what adds up in the totals, like a redacted expense entry
+    pub fn redact(self) -> Self {
+        ExpenseEntryRow {
+            description: String::new(),
+            tags: Vec::new(),
+            redacted: true,
+            ..self
+        }
+    }
+}
+
+// the month is formatted as yyyy-mm
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct MonthlyTotal {
+    pub expense_type: Uuid,
+    pub expense_type_name: Option<String>,
+    pub month: String,
+    pub total: f64,
+}
+
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct CostBearerBalance {
+    pub cost_bearer_id: Uuid,
+    pub cost_bearer_name: Option<String>,
+    pub balance: f64,
+}
+
+// how many events a rebuild replayed into the emptied read models
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct ProjectionRebuild {
+    pub replayed_events: u64,
+}
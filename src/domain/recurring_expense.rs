@@ -0,0 +1,277 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::cost_share::CostShare;
+use crate::domain::expense_entry::{ExpenseEntry, ExpenseEntryValidationError};
+
+// how often a recurring expense repeats, the day is taken from the start date unless given
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(tag = "frequency", rename_all = "snake_case")]
+pub enum Recurrence {
+    Weekly,
+    Monthly { day_of_month: u32 },
+    Yearly,
+}
+
+#[derive(serde::Serialize, Clone, Copy)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct Schedule {
+    recurrence: Recurrence,
+    interval: u32,
+    starts_on: NaiveDate,
+    ends_on: Option<NaiveDate>,
+}
+
+// the expense entry data every occurrence is booked with
+#[derive(serde::Serialize, Clone, PartialEq)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct ExpenseEntryTemplate {
+    cost_shares: Vec<CostShare>,
+    expense_type: Uuid,
+    description: String,
+}
+
+// validated and guaranteed to be correct data
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct RecurringExpense {
+    id: Uuid,
+    template: ExpenseEntryTemplate,
+    schedule: Schedule,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum OccurrenceStatus {
+    Pending,
+    Skipped,
+    Overridden,
+    Booked,
+}
+
+#[derive(serde::Serialize, Clone)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct RecurringExpenseOccurrence {
+    pub occurrence_date: NaiveDate,
+    pub status: OccurrenceStatus,
+    pub override_template: Option<ExpenseEntryTemplate>,
+    pub expense_entry_id: Option<Uuid>,
+}
+
+#[derive(Debug, Error)]
+pub enum RecurringExpenseValidationError {
+    #[error("Recurring Expense Validation failed: Invalid template: {0}")]
+    MalformedTemplate(#[from] ExpenseEntryValidationError),
+
+    #[error("Recurring Expense Validation failed: Interval must be at least 1.")]
+    ZeroInterval,
+
+    #[error("Recurring Expense Validation failed: Day of month must be between 1 and 31.")]
+    InvalidDayOfMonth,
+
+    #[error("Recurring Expense Validation failed: End date is before the start date.")]
+    InvalidDate,
+}
+
+impl ExpenseEntryTemplate {
+    pub fn new(
+        cost_shares: Vec<CostShare>,
+        expense_type: Uuid,
+        description: String,
+    ) -> Result<Self, ExpenseEntryValidationError> {
+        // a template has to be able to produce a valid expense entry
+        ExpenseEntry::new(
+            cost_shares.clone(),
+            expense_type,
+            description.clone(),
+            None,
+            Vec::new(),
+        )?;
+
+        Ok(Self {
+            cost_shares,
+            expense_type,
+            description,
+        })
+    }
+
+    // rebuilds an already validated template from storage
+    pub fn restore(cost_shares: Vec<CostShare>, expense_type: Uuid, description: String) -> Self {
+        Self {
+            cost_shares,
+            expense_type,
+            description,
+        }
+    }
+
+    pub fn cost_shares(&self) -> &[CostShare] {
+        &self.cost_shares
+    }
+
+    pub fn expense_type(&self) -> Uuid {
+        self.expense_type
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl Schedule {
+    pub fn new(
+        recurrence: Recurrence,
+        interval: u32,
+        starts_on: NaiveDate,
+        ends_on: Option<NaiveDate>,
+    ) -> Result<Self, RecurringExpenseValidationError> {
+        if interval == 0 {
+            return Err(RecurringExpenseValidationError::ZeroInterval);
+        }
+
+        if let Recurrence::Monthly { day_of_month } = recurrence
+            && !(1..=31).contains(&day_of_month)
+        {
+            return Err(RecurringExpenseValidationError::InvalidDayOfMonth);
+        }
+
+        // verify that ends_on is not before starts_on
+        if let Some(end) = ends_on
+            && end < starts_on
+        {
+            return Err(RecurringExpenseValidationError::InvalidDate);
+        }
+
+        Ok(Self {
+            recurrence,
+            interval,
+            starts_on,
+            ends_on,
+        })
+    }
+
+    // rebuilds an already validated schedule from storage
+    pub fn restore(
+        recurrence: Recurrence,
+        interval: u32,
+        starts_on: NaiveDate,
+        ends_on: Option<NaiveDate>,
+    ) -> Self {
+        Self {
+            recurrence,
+            interval,
+            starts_on,
+            ends_on,
+        }
+    }
+
+    pub fn recurrence(&self) -> Recurrence {
+        self.recurrence
+    }
+
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    pub fn starts_on(&self) -> NaiveDate {
+        self.starts_on
+    }
+
+    pub fn ends_on(&self) -> Option<NaiveDate> {
+        self.ends_on
+    }
+
+    // all occurrences on or after the given date, ordered and ending with the end date
+    pub fn occurrences_from(&self, from: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        (self.first_period_from(from)..)
+            .map_while(|period| self.nth_occurrence(period))
+            .take_while(|date| self.ends_on.is_none_or(|end| *date <= end))
+            .filter(move |date| *date >= self.starts_on && *date >= from)
+    }
+
+    pub fn is_occurrence(&self, date: NaiveDate) -> bool {
+        self.occurrences_from(date).next() == Some(date)
+    }
+
+    // no occurrence on or after the date comes before this period, it is one period early as a
+    // monthly occurrence may fall before the start's day of the month
+    fn first_period_from(&self, from: NaiveDate) -> u32 {
+        let elapsed = match self.recurrence {
+            Recurrence::Weekly => (from - self.starts_on).num_days() / 7,
+            Recurrence::Monthly { .. } => {
+                i64::from(from.year() - self.starts_on.year()) * 12 + i64::from(from.month0())
+                    - i64::from(self.starts_on.month0())
+            }
+            Recurrence::Yearly => i64::from(from.year() - self.starts_on.year()),
+        };
+        u32::try_from(elapsed / i64::from(self.interval))
+            .unwrap_or(0)
+            .saturating_sub(1)
+    }
+
+    fn nth_occurrence(&self, period: u32) -> Option<NaiveDate> {
+        let step = period.checked_mul(self.interval)?;
+        match self.recurrence {
+            Recurrence::Weekly => self
+                .starts_on
+                .checked_add_days(Days::new(u64::from(step) * 7)),
+            Recurrence::Monthly { day_of_month } => {
+                let month = self
+                    .starts_on
+                    .with_day(1)?
+                    .checked_add_months(Months::new(step))?;
+                // short months book on their last day
+                let last_day = month.checked_add_months(Months::new(1))?.pred_opt()?;
+                month.with_day(day_of_month.min(last_day.day()))
+            }
+            // chrono clamps the 29th of February to the 28th in common years
+            Recurrence::Yearly => self
+                .starts_on
+                .checked_add_months(Months::new(step.checked_mul(12)?)),
+        }
+    }
+}
+
+impl RecurringExpense {
+    pub fn new(template: ExpenseEntryTemplate, schedule: Schedule) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            template,
+            schedule,
+            created_at: Utc::now(),
+        }
+    }
+
+    // rebuilds an already validated recurring expense from storage
+    pub fn restore(
+        id: Uuid,
+        template: ExpenseEntryTemplate,
+        schedule: Schedule,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            template,
+            schedule,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn template(&self) -> &ExpenseEntryTemplate {
+        &self.template
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
@@ -0,0 +1,67 @@
+use thiserror::Error;
+
+const MAX_TAG_LENGTH: usize = 50;
+
+// normalised free-form label, lowercase with dashes instead of whitespace
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(transparent)]
+pub struct Tag(String);
+
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct TagUsage {
+    pub tag: Tag,
+    pub expense_entries: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum TagValidationError {
+    #[error("Tag Validation failed: Tag is empty or whitespace.")]
+    MissingTag,
+
+    #[error("Tag Validation failed: Tag is longer than {MAX_TAG_LENGTH} characters.")]
+    TooLong,
+
+    #[error(
+        "Tag Validation failed: Tag contains characters other than letters, digits, '-' and '_'."
+    )]
+    InvalidCharacters,
+}
+
+impl Tag {
+    pub fn new(raw: &str) -> Result<Self, TagValidationError> {
+        // "Tax Deductible " and "tax-deductible" are the same tag
+        let normalised = raw
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-")
+            .to_lowercase();
+
+        if normalised.is_empty() {
+            return Err(TagValidationError::MissingTag);
+        }
+
+        if normalised.chars().count() > MAX_TAG_LENGTH {
+            return Err(TagValidationError::TooLong);
+        }
+
+        if !normalised
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(TagValidationError::InvalidCharacters);
+        }
+
+        Ok(Self(normalised))
+    }
+
+    // rebuilds an already normalised tag from storage
+    pub fn restore(tag: String) -> Self {
+        Self(tag)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashedEntity {
+    ExpenseEntry,
+    CostBearer,
+    ExpenseType,
+    Budget,
+    RecurringExpense,
+}
+
+// a soft deleted entity that can still be restored until it is purged
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct TrashItem {
+    pub entity: TrashedEntity,
+    pub id: Uuid,
+    pub label: String,
+    pub deleted_at: DateTime<Utc>,
+}
@@ -0,0 +1,225 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use url::{Host, Url};
+use uuid::Uuid;
+
+use crate::domain::outbox::{ChangeAction, ChangeEvent, ChangedEntity};
+
+const ENTITIES: [ChangedEntity; 3] = [
+    ChangedEntity::ExpenseEntry,
+    ChangedEntity::CostBearer,
+    ChangedEntity::ExpenseType,
+];
+
+const ACTIONS: [ChangeAction; 5] = [
+    ChangeAction::Created,
+    ChangeAction::Updated,
+    ChangeAction::Deleted,
+    ChangeAction::Restored,
+    ChangeAction::Purged,
+];
+
+// validated and guaranteed to be correct data, the secret is never handed out again
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct WebhookSubscription {
+    id: Uuid,
+    url: String,
+    events: Vec<String>,
+    #[serde(skip_serializing, default)]
+    secret: String,
+    // the user whose access the pushed changes are limited to, none for the operator's
+    created_by: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum WebhookValidationError {
+    #[error("Webhook Validation failed: Url is not http or https.")]
+    InvalidUrl,
+
+    #[error("Webhook Validation failed: Url points at a private address.")]
+    PrivateAddress,
+
+    #[error("Webhook Validation failed: Unknown event filter.")]
+    InvalidEvent,
+
+    #[error("Webhook Validation failed: Secret is empty.")]
+    MissingSecret,
+}
+
+// an event filter is *, an entity with * or an entity and action, e.g. expense_entry.created
+fn is_valid_event(event: &str) -> bool {
+    event == "*"
+        || ENTITIES.iter().any(|entity| {
+            event == format!("{}.*", entity.as_str())
+                || ACTIONS
+                    .iter()
+                    .any(|action| event == format!("{}.{}", entity.as_str(), action.as_str()))
+        })
+}
+
+// loopback, private, link-local, unspecified and broadcast addresses belong to this host or its
+// network, webhooks must not reach them
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_address(IpAddr::V4(ip)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+// addresses that are not public and names reserved for this host
+pub fn is_private_host(host: &Host<&str>) -> bool {
+    match host {
+        Host::Ipv4(ip) => !is_public_address(IpAddr::V4(*ip)),
+        Host::Ipv6(ip) => !is_public_address(IpAddr::V6(*ip)),
+        Host::Domain(name) => *name == "localhost" || name.ends_with(".localhost"),
+    }
+}
+
+// only names and public addresses are accepted unless private targets are allowed, names are
+// checked again once they are resolved for sending
+fn validate_url(url: &str, private_targets: bool) -> Result<String, WebhookValidationError> {
+    let parsed = Url::parse(url.trim()).map_err(|_| WebhookValidationError::InvalidUrl)?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(WebhookValidationError::InvalidUrl);
+    }
+    let host = parsed.host().ok_or(WebhookValidationError::InvalidUrl)?;
+    if is_private_host(&host) && !private_targets {
+        return Err(WebhookValidationError::PrivateAddress);
+    }
+    Ok(url.trim().to_string())
+}
+
+impl WebhookSubscription {
+    pub fn new(
+        url: String,
+        events: Vec<String>,
+        secret: String,
+        created_by: Option<String>,
+        private_targets: bool,
+    ) -> Result<Self, WebhookValidationError> {
+        let url = validate_url(&url, private_targets)?;
+        if events.is_empty() || !events.iter().all(|event| is_valid_event(event)) {
+            return Err(WebhookValidationError::InvalidEvent);
+        }
+        if secret.is_empty() {
+            return Err(WebhookValidationError::MissingSecret);
+        }
+        Ok(WebhookSubscription {
+            id: Uuid::new_v4(),
+            url,
+            events,
+            secret,
+            created_by,
+            created_at: Utc::now(),
+        })
+    }
+
+    pub fn restore(
+        id: Uuid,
+        url: String,
+        events: Vec<String>,
+        secret: String,
+        created_by: Option<String>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        WebhookSubscription {
+            id,
+            url,
+            events,
+            secret,
+            created_by,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn created_by(&self) -> Option<&str> {
+        self.created_by.as_deref()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn matches(&self, event: &ChangeEvent) -> bool {
+        let name = event.name();
+        let entity_wildcard = format!("{}.*", event.entity.as_str());
+        self.events
+            .iter()
+            .any(|filter| filter == "*" || *filter == entity_wildcard || *filter == name)
+    }
+}
+
+// one attempt to post an event to a subscriber
+#[derive(serde::Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_id: Uuid,
+    pub event: String,
+    pub attempt: u32,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub succeeded: bool,
+    pub attempted_at: DateTime<Utc>,
+}
+
+const HMAC_BLOCK_SIZE: usize = 64;
+
+// hex encoded HMAC-SHA256 of the body, sent as sha256=<hex> so receivers can verify the sender
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut key = [0u8; HMAC_BLOCK_SIZE];
+    if secret.len() > HMAC_BLOCK_SIZE {
+        key[..32].copy_from_slice(&Sha256::digest(secret.as_bytes()));
+    } else {
+        key[..secret.len()].copy_from_slice(secret.as_bytes());
+    }
+    let inner_key = key.map(|byte| byte ^ 0x36);
+    let outer_key = key.map(|byte| byte ^ 0x5c);
+    let inner = Sha256::new()
+        .chain_update(inner_key)
+        .chain_update(body)
+        .finalize();
+    Sha256::new()
+        .chain_update(outer_key)
+        .chain_update(inner)
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
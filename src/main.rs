@@ -6,25 +6,287 @@ mod service;
 //#[cfg(any(test, feature = "test-utils"))]
 pub mod test_util;
 
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::routes::setup_routing;
-use repository::sqliterepository::expense_entry::{
-    ExpenseEntryReadSqliteRepository, ExpenseEntryWriteSqliteRepository,
+use repository::filesystemrepository::blob_store::BlobStoreFilesystemRepository;
+use repository::filesystemrepository::token_key::TokenKeyFilesystemRepository;
+use repository::notifierrepository::http::WebhookSenderHttpRepository;
+use repository::notifierrepository::log::NotifierLogRepository;
+use repository::notifierrepository::webhook::NotifierWebhookRepository;
+use repository::sqliterepository::api_key::{
+    ApiKeyReadSqliteRepository, ApiKeyWriteSqliteRepository,
+};
+use repository::sqliterepository::attachment::{
+    AttachmentReadSqliteRepository, AttachmentWriteSqliteRepository,
+};
+use repository::sqliterepository::audit::AuditReadSqliteRepository;
+use repository::sqliterepository::budget::{
+    BudgetReadSqliteRepository, BudgetWriteSqliteRepository,
+};
+use repository::sqliterepository::categorisation_rule::{
+    CategorisationRuleReadSqliteRepository, CategorisationRuleWriteSqliteRepository,
+};
+use repository::sqliterepository::cost_bearer::{
+    CostBearerReadSqliteRepository, CostBearerWriteSqliteRepository,
+};
+use repository::sqliterepository::database::SqliteDatabase;
+use repository::sqliterepository::expense_entry::ExpenseEntryWriteSqliteRepository;
+use repository::sqliterepository::expense_entry_event_store::ExpenseEntryEventStoreRepository;
+use repository::sqliterepository::expense_type::{
+    ExpenseTypeReadSqliteRepository, ExpenseTypeWriteSqliteRepository,
+};
+use repository::sqliterepository::idempotency::{
+    IdempotencyReadSqliteRepository, IdempotencyWriteSqliteRepository,
+};
+use repository::sqliterepository::ledger::{
+    LedgerReadSqliteRepository, LedgerWriteSqliteRepository,
+};
+use repository::sqliterepository::outbox::{
+    OutboxReadSqliteRepository, OutboxWriteSqliteRepository,
+};
+use repository::sqliterepository::projection::{
+    ProjectionReadSqliteRepository, ProjectionWriteSqliteRepository,
+};
+use repository::sqliterepository::recurring_expense::{
+    RecurringExpenseReadSqliteRepository, RecurringExpenseWriteSqliteRepository,
+};
+use repository::sqliterepository::webhook::{
+    WebhookReadSqliteRepository, WebhookWriteSqliteRepository,
 };
 
+use crate::service::api_key::{ApiKeyNew, ApiKeyService};
+use crate::service::attachment::{AttachmentService, DEFAULT_MAX_ATTACHMENT_SIZE};
+use crate::service::audit::AuditService;
+use crate::service::budget::{BudgetService, NotifierPort};
+use crate::service::categorisation_rule::CategorisationRuleService;
+use crate::service::change_feed::ChangeFeedService;
+use crate::service::cost_bearer::CostBearerService;
+use crate::service::expense_entry::{DuplicatePolicy, ExpenseEntryWritePort};
+use crate::service::expense_type::ExpenseTypeService;
+use crate::service::idempotency::{DEFAULT_IDEMPOTENCY_KEY_TTL_HOURS, IdempotencyService};
+use crate::service::ledger::LedgerService;
+use crate::service::outbox::{OutboxService, spawn_outbox_dispatcher};
+use crate::service::projection::{ProjectionService, spawn_projector};
+use crate::service::recurring_expense::{RecurringExpenseService, spawn_recurring_expense_booking};
+use crate::service::token::TokenService;
+use crate::service::trash::{DEFAULT_TRASH_RETENTION_DAYS, TrashService, spawn_trash_purge};
+use crate::service::webhook::WebhookService;
 use crate::{api::routes::Services, service::expense_entry::ExpenseEntryService};
 
+const RECURRING_EXPENSE_BOOKING_PERIOD: Duration = Duration::from_secs(60 * 60);
+const TRASH_PURGE_PERIOD: Duration = Duration::from_secs(60 * 60);
+const PROJECTION_PERIOD: Duration = Duration::from_secs(1);
+const OUTBOX_DISPATCH_PERIOD: Duration = Duration::from_secs(1);
+
 #[tokio::main]
 async fn main() {
-    let read_repo = Arc::new(ExpenseEntryReadSqliteRepository::new());
-    let write_repo = Arc::new(ExpenseEntryWriteSqliteRepository::new());
-    let expense_entry_service = Arc::new(ExpenseEntryService::new(read_repo, write_repo));
+    let database = SqliteDatabase::open("expenses.db").unwrap();
+
+    // every household keeps its data in its own ledger
+    let ledger_service = Arc::new(LedgerService::new(
+        Arc::new(LedgerReadSqliteRepository::new(database.clone())),
+        Arc::new(LedgerWriteSqliteRepository::new(database.clone())),
+    ));
+    let read_repo = Arc::new(ProjectionReadSqliteRepository::new(database.clone()));
+    let projection_service = Arc::new(ProjectionService::new(
+        read_repo.clone(),
+        Arc::new(ProjectionWriteSqliteRepository::new(database.clone())),
+        ledger_service.clone(),
+    ));
+    // expense entries are overwritten in place unless the event store is chosen as write model
+    let write_repo: Arc<dyn ExpenseEntryWritePort + Send + Sync> =
+        match std::env::var("EXPENSE_ENTRY_WRITE_MODEL").as_deref() {
+            Ok("event_store") => Arc::new(ExpenseEntryEventStoreRepository::new(database.clone())),
+            _ => Arc::new(ExpenseEntryWriteSqliteRepository::new(database.clone())),
+        };
+    // changes are recorded in the outbox with every write, the dispatcher publishes them
+    let outbox_service = Arc::new(OutboxService::new(
+        Arc::new(OutboxReadSqliteRepository::new(database.clone())),
+        Arc::new(OutboxWriteSqliteRepository::new(database.clone())),
+    ));
+    // webhooks only reach public addresses unless receivers on this host or network are allowed
+    let webhook_private_targets =
+        std::env::var("WEBHOOK_PRIVATE_TARGETS").as_deref() == Ok("allowed");
+    // one http client for the ledger webhooks and the budget alerts
+    let webhook_sender = Arc::new(WebhookSenderHttpRepository::new(webhook_private_targets));
+    let webhook_service = Arc::new(WebhookService::new(
+        Arc::new(WebhookReadSqliteRepository::new(database.clone())),
+        Arc::new(WebhookWriteSqliteRepository::new(database.clone())),
+        webhook_sender.clone(),
+        ledger_service.clone(),
+        webhook_private_targets,
+    ));
+    outbox_service.register(webhook_service.clone());
+    let change_feed_service = Arc::new(ChangeFeedService::new(ledger_service.clone()));
+    outbox_service.register(change_feed_service.clone());
+    let audit_service = Arc::new(AuditService::new(
+        Arc::new(AuditReadSqliteRepository::new(database.clone())),
+        ledger_service.clone(),
+    ));
+    let attachment_service = Arc::new(AttachmentService::new(
+        Arc::new(AttachmentReadSqliteRepository::new(database.clone())),
+        Arc::new(AttachmentWriteSqliteRepository::new(database.clone())),
+        Arc::new(BlobStoreFilesystemRepository::new("attachments").unwrap()),
+        read_repo.clone(),
+        DEFAULT_MAX_ATTACHMENT_SIZE,
+        ledger_service.clone(),
+    ));
+    // budget alerts go to the configured webhook, otherwise they are only logged, either way they
+    // are handed out by the outbox
+    let notifier: Arc<dyn NotifierPort + Send + Sync> =
+        match std::env::var("BUDGET_ALERT_WEBHOOK_URL") {
+            Ok(url) => Arc::new(NotifierWebhookRepository::new(url, webhook_sender.clone())),
+            Err(_) => Arc::new(NotifierLogRepository::new("budget_alerts.log")),
+        };
+    let budget_service = Arc::new(BudgetService::new(
+        Arc::new(BudgetReadSqliteRepository::new(database.clone())),
+        Arc::new(BudgetWriteSqliteRepository::new(database.clone())),
+        read_repo.clone(),
+        notifier,
+        ledger_service.clone(),
+    ));
+    outbox_service.register(budget_service.clone());
+    let expense_type_service = Arc::new(ExpenseTypeService::new(
+        Arc::new(ExpenseTypeReadSqliteRepository::new(database.clone())),
+        Arc::new(ExpenseTypeWriteSqliteRepository::new(database.clone())),
+        read_repo.clone(),
+        ledger_service.clone(),
+    ));
+    let cost_bearer_service = Arc::new(CostBearerService::new(
+        Arc::new(CostBearerReadSqliteRepository::new(database.clone())),
+        Arc::new(CostBearerWriteSqliteRepository::new(database.clone())),
+        ledger_service.clone(),
+    ));
+    let categorisation_rule_service = Arc::new(CategorisationRuleService::new(
+        Arc::new(CategorisationRuleReadSqliteRepository::new(
+            database.clone(),
+        )),
+        Arc::new(CategorisationRuleWriteSqliteRepository::new(
+            database.clone(),
+        )),
+        ledger_service.clone(),
+    ));
+    // new expense entries resembling existing ones are reported unless told to block or ignore
+    let duplicate_policy = match std::env::var("DUPLICATE_EXPENSE_ENTRIES").as_deref() {
+        Ok("block") => DuplicatePolicy::Block,
+        Ok("off") => DuplicatePolicy::Off,
+        _ => DuplicatePolicy::Warn,
+    };
+    let expense_entry_service = Arc::new(ExpenseEntryService::new(
+        read_repo,
+        write_repo,
+        attachment_service.clone(),
+        budget_service.clone(),
+        ledger_service.clone(),
+        categorisation_rule_service.clone(),
+        duplicate_policy,
+    ));
+    let recurring_expense_service = Arc::new(RecurringExpenseService::new(
+        Arc::new(RecurringExpenseReadSqliteRepository::new(database.clone())),
+        Arc::new(RecurringExpenseWriteSqliteRepository::new(database.clone())),
+        expense_entry_service.clone(),
+        ledger_service.clone(),
+    ));
+    // requests need an api key unless authentication is explicitly disabled
+    let api_key_service = Arc::new(ApiKeyService::new(
+        Arc::new(ApiKeyReadSqliteRepository::new(database.clone())),
+        Arc::new(ApiKeyWriteSqliteRepository::new(database.clone())),
+        std::env::var("API_AUTHENTICATION").as_deref() != Ok("disabled"),
+    ));
+    // responses of requests with an idempotency key are replayed for the configured number of
+    // hours
+    let idempotency_key_ttl_hours = std::env::var("IDEMPOTENCY_KEY_TTL_HOURS")
+        .ok()
+        .and_then(|hours| hours.parse().ok())
+        .unwrap_or(DEFAULT_IDEMPOTENCY_KEY_TTL_HOURS);
+    let idempotency_service = Arc::new(IdempotencyService::new(
+        Arc::new(IdempotencyReadSqliteRepository::new(database.clone())),
+        Arc::new(IdempotencyWriteSqliteRepository::new(database.clone())),
+        chrono::Duration::hours(idempotency_key_ttl_hours),
+    ));
+    // bearer tokens of the sso are verified with a shared HS256 secret or the public keys of a
+    // local JWKS file
+    let token_key_repo = TokenKeyFilesystemRepository::new(
+        std::env::var("JWT_SECRET").ok(),
+        std::env::var("JWT_JWKS_FILE")
+            .ok()
+            .as_deref()
+            .map(Path::new),
+    )
+    .expect("Failed to read the JWKS file.");
+    let token_service = Arc::new(TokenService::new(
+        Arc::new(token_key_repo),
+        std::env::var("JWT_ISSUER").unwrap_or_default(),
+        std::env::var("JWT_AUDIENCE").unwrap_or_default(),
+    ));
+    // `expenses_api create-api-key <name> [<user>]` prints a new key and exits, the first key has
+    // to be created this way; keys without a user have the operator's access
+    if std::env::args().nth(1).as_deref() == Some("create-api-key") {
+        let name = std::env::args().nth(2).unwrap_or_default();
+        let user = std::env::args().nth(3);
+        match api_key_service.create(ApiKeyNew { name, user }) {
+            Ok(created) => println!("{}", created.key),
+            Err(err) => eprintln!("Creating the api key failed: {}", err.message),
+        }
+        return;
+    }
+    // `expenses_api rebuild-projections` replays all events into emptied read models and exits
+    if std::env::args().nth(1).as_deref() == Some("rebuild-projections") {
+        match projection_service.rebuild() {
+            Ok(rebuild) => println!("Replayed {} events.", rebuild.replayed_events),
+            Err(err) => eprintln!("Rebuilding the projections failed: {}", err.message),
+        }
+        return;
+    }
+    spawn_projector(projection_service.clone(), PROJECTION_PERIOD);
+    spawn_outbox_dispatcher(outbox_service.clone(), OUTBOX_DISPATCH_PERIOD);
+    spawn_recurring_expense_booking(
+        recurring_expense_service.clone(),
+        RECURRING_EXPENSE_BOOKING_PERIOD,
+    );
+    let trash_service = Arc::new(TrashService::new(
+        expense_entry_service.clone(),
+        cost_bearer_service.clone(),
+        expense_type_service.clone(),
+        budget_service.clone(),
+        recurring_expense_service.clone(),
+        ledger_service.clone(),
+    ));
+    // deleted entities stay restorable for the configured number of days
+    let trash_retention_days = std::env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+    spawn_trash_purge(
+        trash_service.clone(),
+        TRASH_PURGE_PERIOD,
+        chrono::Duration::days(trash_retention_days),
+    );
+
     let services = Services {
         expense_entry_service: expense_entry_service.clone(),
+        attachment_service: attachment_service.clone(),
+        recurring_expense_service: recurring_expense_service.clone(),
+        budget_service: budget_service.clone(),
+        categorisation_rule_service: categorisation_rule_service.clone(),
+        expense_type_service: expense_type_service.clone(),
+        audit_service: audit_service.clone(),
+        projection_service: projection_service.clone(),
+        outbox_service: outbox_service.clone(),
+        webhook_service: webhook_service.clone(),
+        change_feed_service: change_feed_service.clone(),
+        cost_bearer_service: cost_bearer_service.clone(),
+        trash_service: trash_service.clone(),
+        api_key_service: api_key_service.clone(),
+        token_service: token_service.clone(),
+        ledger_service: ledger_service.clone(),
+        idempotency_service: idempotency_service.clone(),
     };
 
-    let router = setup_routing().await.with_state(services);
+    let router = setup_routing(services).await;
     let addr = "0.0.0.0:6570";
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
 
@@ -0,0 +1,77 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+use crate::service::{
+    application_error::{ApplicationError, ApplicationErrorType},
+    attachment::BlobStorePort,
+};
+
+// stores every blob as a file named after its content hash below the root directory
+pub struct BlobStoreFilesystemRepository {
+    root: PathBuf,
+}
+
+impl BlobStoreFilesystemRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self, std::io::Error> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(BlobStoreFilesystemRepository { root })
+    }
+
+    fn path(&self, content_hash: &str) -> Result<PathBuf, ApplicationError> {
+        // the hash is used as a file name, never allow it to escape the root directory
+        if content_hash.is_empty() || !content_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ApplicationError {
+                error_type: ApplicationErrorType::Internal,
+                message: String::from("Invalid blob key."),
+            });
+        }
+        Ok(self.root.join(content_hash))
+    }
+}
+
+fn storage_error(err: std::io::Error) -> ApplicationError {
+    match err.kind() {
+        ErrorKind::NotFound => ApplicationError {
+            error_type: ApplicationErrorType::NotFound,
+            message: String::from("Attachment file not found."),
+        },
+        _ => ApplicationError {
+            error_type: ApplicationErrorType::Internal,
+            message: format!("File storage error: {err}"),
+        },
+    }
+}
+
+impl BlobStorePort for BlobStoreFilesystemRepository {
+    fn put(&self, content_hash: &str, content: &[u8]) -> Result<(), ApplicationError> {
+        let path = self.path(content_hash)?;
+        if path.exists() {
+            return Ok(());
+        }
+        // write to a temporary file first so a crash never leaves a truncated blob behind, each
+        // upload gets its own so concurrent uploads of the same content do not clobber each other
+        let temporary = self
+            .root
+            .join(format!("{content_hash}.{}.tmp", Uuid::new_v4()));
+        let written = fs::write(&temporary, content).and_then(|_| fs::rename(&temporary, &path));
+        if written.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        written.map_err(storage_error)
+    }
+
+    fn get(&self, content_hash: &str) -> Result<Vec<u8>, ApplicationError> {
+        fs::read(self.path(content_hash)?).map_err(storage_error)
+    }
+
+    fn delete(&self, content_hash: &str) -> Result<(), ApplicationError> {
+        match fs::remove_file(self.path(content_hash)?) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(storage_error(err)),
+            _ => Ok(()),
+        }
+    }
+}
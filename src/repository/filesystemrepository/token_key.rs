@@ -0,0 +1,64 @@
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey};
+
+use crate::service::token::TokenKeyPort;
+
+// verifies HS256 tokens with the shared secret and RS256 and EdDSA tokens with the public keys
+// of a local JWKS file, both are read once at startup
+pub struct TokenKeyFilesystemRepository {
+    secret: Option<String>,
+    key_set: JwkSet,
+}
+
+impl TokenKeyFilesystemRepository {
+    pub fn new(secret: Option<String>, jwks_path: Option<&Path>) -> Result<Self, Error> {
+        let key_set = match jwks_path {
+            Some(path) => serde_json::from_slice(&fs::read(path)?)
+                .map_err(|err| Error::new(ErrorKind::InvalidData, err))?,
+            None => JwkSet { keys: Vec::new() },
+        };
+        Ok(TokenKeyFilesystemRepository {
+            secret: secret.filter(|secret| !secret.is_empty()),
+            key_set,
+        })
+    }
+
+    // a key only verifies the algorithm it was published for
+    fn signs(jwk: &Jwk, algorithm: Algorithm) -> bool {
+        matches!(
+            (&jwk.algorithm, algorithm),
+            (AlgorithmParameters::RSA(_), Algorithm::RS256)
+                | (AlgorithmParameters::OctetKeyPair(_), Algorithm::EdDSA)
+        )
+    }
+}
+
+impl TokenKeyPort for TokenKeyFilesystemRepository {
+    fn decoding_key(&self, algorithm: Algorithm, key_id: Option<&str>) -> Option<DecodingKey> {
+        if algorithm == Algorithm::HS256 {
+            return self
+                .secret
+                .as_ref()
+                .map(|secret| DecodingKey::from_secret(secret.as_bytes()));
+        }
+        // without a key id only an unambiguous key is used
+        let candidates: Vec<&Jwk> = self
+            .key_set
+            .keys
+            .iter()
+            .filter(|jwk| Self::signs(jwk, algorithm))
+            .collect();
+        let jwk = match key_id {
+            Some(key_id) => candidates
+                .into_iter()
+                .find(|jwk| jwk.common.key_id.as_deref() == Some(key_id)),
+            None if candidates.len() == 1 => candidates.into_iter().next(),
+            None => None,
+        }?;
+        DecodingKey::from_jwk(jwk).ok()
+    }
+}
@@ -1 +1,3 @@
+pub mod filesystemrepository;
+pub mod notifierrepository;
 pub mod sqliterepository;
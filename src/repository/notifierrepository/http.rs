@@ -0,0 +1,92 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use tokio::runtime::Handle;
+use url::Url;
+
+use crate::domain::webhook::{is_private_host, is_public_address};
+use crate::service::{
+    application_error::{ApplicationError, ApplicationErrorType},
+    webhook::WebhookSenderPort,
+};
+
+// receivers that do not answer in time count as failed, the outbox retries them later
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+// posts webhook payloads over http and waits for the answer
+pub struct WebhookSenderHttpRepository {
+    client: reqwest::Client,
+    // whether receivers may be on loopback or private addresses, e.g. on a home network
+    private_targets: bool,
+}
+
+// resolves names like the system does, but refuses names pointing at a non-public address so a
+// subscription cannot reach this host or its network by changing its dns record
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            if addrs.iter().any(|addr| !is_public_address(addr.ip())) {
+                return Err(format!("{host} resolves to a private address").into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+impl WebhookSenderHttpRepository {
+    pub fn new(private_targets: bool) -> Self {
+        // a redirect could lead anywhere, receivers have to answer themselves
+        let builder = reqwest::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .redirect(Policy::none());
+        let builder = match private_targets {
+            true => builder,
+            false => builder.dns_resolver(Arc::new(PublicAddressResolver)),
+        };
+        WebhookSenderHttpRepository {
+            client: builder.build().unwrap_or_default(),
+            private_targets,
+        }
+    }
+}
+
+fn webhook_error(err: impl std::fmt::Display) -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::Internal,
+        message: format!("Webhook error: {err}"),
+    }
+}
+
+impl WebhookSenderPort for WebhookSenderHttpRepository {
+    fn send(
+        &self,
+        url: &str,
+        headers: &[(&str, String)],
+        body: Vec<u8>,
+    ) -> Result<u16, ApplicationError> {
+        // addresses in the url are not resolved, so the resolver never sees them
+        let target = Url::parse(url).map_err(webhook_error)?;
+        if let Some(host) = target.host()
+            && is_private_host(&host)
+            && !self.private_targets
+        {
+            return Err(webhook_error(format!("{host} is a private address")));
+        }
+        // webhooks are sent from the blocking dispatcher, the runtime drives the request
+        let runtime = Handle::try_current().map_err(webhook_error)?;
+        let mut request = self.client.post(target).body(body);
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        let response = runtime.block_on(request.send()).map_err(webhook_error)?;
+        Ok(response.status().as_u16())
+    }
+}
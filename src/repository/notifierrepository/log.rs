@@ -0,0 +1,54 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::domain::budget::BudgetAlert;
+use crate::service::{
+    application_error::{ApplicationError, ApplicationErrorType},
+    budget::NotifierPort,
+};
+
+// appends every alert as one json line to a file
+pub struct NotifierLogRepository {
+    path: PathBuf,
+    // keeps lines of concurrent alerts from interleaving
+    lock: Mutex<()>,
+}
+
+impl NotifierLogRepository {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        NotifierLogRepository {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+fn log_error(err: std::io::Error) -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::Internal,
+        message: format!("Notification log error: {err}"),
+    }
+}
+
+impl NotifierPort for NotifierLogRepository {
+    fn notify(&self, alert: &BudgetAlert) -> Result<(), ApplicationError> {
+        let mut line = serde_json::to_string(alert).map_err(|err| ApplicationError {
+            error_type: ApplicationErrorType::Internal,
+            message: format!("Serialization error: {err}"),
+        })?;
+        line.push('\n');
+
+        let _guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(log_error)
+    }
+}
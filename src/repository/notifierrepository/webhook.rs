@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use crate::domain::budget::BudgetAlert;
+use crate::repository::notifierrepository::http::WebhookSenderHttpRepository;
+use crate::service::{
+    application_error::{ApplicationError, ApplicationErrorType},
+    budget::NotifierPort,
+    webhook::WebhookSenderPort,
+};
+
+// posts every alert as json to a fixed url, through the same sender as the ledger webhooks
+pub struct NotifierWebhookRepository {
+    url: String,
+    sender: Arc<WebhookSenderHttpRepository>,
+}
+
+impl NotifierWebhookRepository {
+    pub fn new(url: impl Into<String>, sender: Arc<WebhookSenderHttpRepository>) -> Self {
+        NotifierWebhookRepository {
+            url: url.into(),
+            sender,
+        }
+    }
+}
+
+impl NotifierPort for NotifierWebhookRepository {
+    // alerts are sent from the outbox dispatcher, answers other than 2xx are retried by it
+    fn notify(&self, alert: &BudgetAlert) -> Result<(), ApplicationError> {
+        let body = serde_json::to_vec(alert).map_err(|err| ApplicationError {
+            error_type: ApplicationErrorType::Internal,
+            message: format!("Serialization error: {err}"),
+        })?;
+        let headers = [("content-type", String::from("application/json"))];
+        match self.sender.send(&self.url, &headers, body)? {
+            200..=299 => Ok(()),
+            status => Err(ApplicationError {
+                error_type: ApplicationErrorType::Internal,
+                message: format!("Webhook error: Receiver answered with status {status}."),
+            }),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Row, params};
+use uuid::Uuid;
+
+use crate::{
+    domain::api_key::ApiKey,
+    repository::sqliterepository::database::{SqliteDatabase, uuid_column},
+    service::{
+        api_key::{ApiKeyReadPort, ApiKeyWritePort},
+        application_error::{ApplicationError, ApplicationErrorType},
+    },
+};
+
+pub struct ApiKeyReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct ApiKeyWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl ApiKeyReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        ApiKeyReadSqliteRepository { database }
+    }
+}
+
+impl ApiKeyWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        ApiKeyWriteSqliteRepository { database }
+    }
+}
+
+fn api_key_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Api key not found."),
+    }
+}
+
+const API_KEY_COLUMNS: &str = "id, name, user, prefix, key_hash, created_at, revoked_at";
+
+fn api_key_from_row(row: &Row) -> rusqlite::Result<ApiKey> {
+    Ok(ApiKey::restore(
+        uuid_column(row, 0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+        row.get(6)?,
+    ))
+}
+
+impl ApiKeyReadPort for ApiKeyReadSqliteRepository {
+    fn list(&self) -> Result<Vec<ApiKey>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {API_KEY_COLUMNS} FROM api_key ORDER BY created_at"
+        ))?;
+        let api_keys = statement
+            .query_map([], api_key_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(api_keys)
+    }
+
+    fn find_active(&self, key_hash: &str) -> Result<Option<ApiKey>, ApplicationError> {
+        let api_key = self
+            .database
+            .connection()
+            .query_row(
+                &format!(
+                    "SELECT {API_KEY_COLUMNS} FROM api_key WHERE key_hash = ?1 AND revoked_at IS NULL"
+                ),
+                params![key_hash],
+                api_key_from_row,
+            )
+            .optional()?;
+        Ok(api_key)
+    }
+}
+
+impl ApiKeyWritePort for ApiKeyWriteSqliteRepository {
+    fn insert(&self, api_key: ApiKey) -> Result<ApiKey, ApplicationError> {
+        self.database.connection().execute(
+            &format!("INSERT INTO api_key ({API_KEY_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"),
+            params![
+                api_key.id().to_string(),
+                api_key.name(),
+                api_key.user(),
+                api_key.prefix(),
+                api_key.key_hash(),
+                api_key.created_at(),
+                api_key.revoked_at(),
+            ],
+        )?;
+        Ok(api_key)
+    }
+
+    fn revoke(&self, id: Uuid) -> Result<(), ApplicationError> {
+        let revoked = self.database.connection().execute(
+            "UPDATE api_key SET revoked_at = ?2 WHERE id = ?1 AND revoked_at IS NULL",
+            params![id.to_string(), Utc::now()],
+        )?;
+        match revoked {
+            0 => Err(api_key_not_found()),
+            _ => Ok(()),
+        }
+    }
+}
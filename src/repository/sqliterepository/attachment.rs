@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Row, params};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        attachment::Attachment,
+        audit::{AuditAction, AuditedEntity},
+    },
+    repository::sqliterepository::{
+        audit,
+        database::{SqliteDatabase, uuid_column},
+    },
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        attachment::{AttachmentReadPort, AttachmentWritePort},
+    },
+};
+
+pub struct AttachmentReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct AttachmentWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl AttachmentReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        AttachmentReadSqliteRepository { database }
+    }
+}
+
+impl AttachmentWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        AttachmentWriteSqliteRepository { database }
+    }
+}
+
+fn attachment_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Attachment not found."),
+    }
+}
+
+fn attachment_from_row(row: &Row) -> rusqlite::Result<Attachment> {
+    Ok(Attachment::restore(
+        uuid_column(row, 0)?,
+        uuid_column(row, 1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get::<_, i64>(4)? as u64,
+        row.get(5)?,
+        row.get::<_, DateTime<Utc>>(6)?,
+    ))
+}
+
+impl AttachmentReadPort for AttachmentReadSqliteRepository {
+    fn get(&self, expense_entry_id: Uuid, id: Uuid) -> Result<Attachment, ApplicationError> {
+        self.database
+            .connection()
+            .query_row(
+                "SELECT id, expense_entry_id, file_name, content_type, size, content_hash, uploaded_at
+                 FROM attachment WHERE id = ?1 AND expense_entry_id = ?2
+                 AND expense_entry_id NOT IN (SELECT id FROM expense_entry WHERE deleted_at IS NOT NULL)",
+                params![id.to_string(), expense_entry_id.to_string()],
+                attachment_from_row,
+            )
+            .optional()?
+            .ok_or_else(attachment_not_found)
+    }
+
+    fn list(&self, expense_entry_id: Uuid) -> Result<Vec<Attachment>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(
+            "SELECT id, expense_entry_id, file_name, content_type, size, content_hash, uploaded_at
+             FROM attachment WHERE expense_entry_id = ?1 ORDER BY uploaded_at",
+        )?;
+        let attachments = statement
+            .query_map(params![expense_entry_id.to_string()], attachment_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(attachments)
+    }
+
+    fn count_by_hash(&self, content_hash: &str) -> Result<u64, ApplicationError> {
+        let count: i64 = self.database.connection().query_row(
+            "SELECT COUNT(*) FROM attachment WHERE content_hash = ?1",
+            params![content_hash],
+            |row| row.get(0),
+        )?;
+        Ok(count as u64)
+    }
+}
+
+impl AttachmentWritePort for AttachmentWriteSqliteRepository {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        attachment: Attachment,
+    ) -> Result<Attachment, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO attachment (id, expense_entry_id, file_name, content_type, size, content_hash, uploaded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                attachment.id().to_string(),
+                attachment.expense_entry_id().to_string(),
+                attachment.file_name(),
+                attachment.content_type(),
+                attachment.size() as i64,
+                attachment.content_hash(),
+                attachment.uploaded_at(),
+            ],
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::Attachment,
+            attachment.id(),
+            AuditAction::Created,
+            None::<&Attachment>,
+            Some(&attachment),
+        )?;
+        transaction.commit()?;
+        Ok(attachment)
+    }
+
+    fn delete(
+        &self,
+        ledger_id: Uuid,
+        expense_entry_id: Uuid,
+        id: Uuid,
+    ) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let attachment = transaction
+            .query_row(
+                "DELETE FROM attachment WHERE id = ?1 AND expense_entry_id = ?2
+                 RETURNING id, expense_entry_id, file_name, content_type, size, content_hash, uploaded_at",
+                params![id.to_string(), expense_entry_id.to_string()],
+                attachment_from_row,
+            )
+            .optional()?
+            .ok_or_else(attachment_not_found)?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::Attachment,
+            id,
+            AuditAction::Deleted,
+            Some(&attachment),
+            None::<&Attachment>,
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
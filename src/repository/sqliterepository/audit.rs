@@ -0,0 +1,138 @@
+use chrono::Utc;
+use rusqlite::{Connection, Row, params};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::audit::{AuditAction, AuditRecord, AuditedEntity},
+    repository::sqliterepository::database::{SqliteDatabase, to_json, uuid_column},
+    service::{
+        application_error::ApplicationError, audit::AuditReadPort, request_context::RequestContext,
+    },
+};
+
+pub struct AuditReadSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl AuditReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        AuditReadSqliteRepository { database }
+    }
+}
+
+fn entity_to_sql(entity: AuditedEntity) -> &'static str {
+    match entity {
+        AuditedEntity::ExpenseEntry => "expense_entry",
+        AuditedEntity::Attachment => "attachment",
+        AuditedEntity::CostBearer => "cost_bearer",
+        AuditedEntity::ExpenseType => "expense_type",
+        AuditedEntity::Budget => "budget",
+        AuditedEntity::RecurringExpense => "recurring_expense",
+    }
+}
+
+fn action_to_sql(action: AuditAction) -> &'static str {
+    match action {
+        AuditAction::Created => "created",
+        AuditAction::Updated => "updated",
+        AuditAction::Deleted => "deleted",
+        AuditAction::Restored => "restored",
+        AuditAction::Purged => "purged",
+    }
+}
+
+fn action_from_sql(action: &str) -> AuditAction {
+    match action {
+        "created" => AuditAction::Created,
+        "deleted" => AuditAction::Deleted,
+        "restored" => AuditAction::Restored,
+        "purged" => AuditAction::Purged,
+        _ => AuditAction::Updated,
+    }
+}
+
+fn snapshot_column(row: &Row, index: usize) -> rusqlite::Result<Option<serde_json::Value>> {
+    match row.get::<_, Option<String>>(index)? {
+        Some(text) => serde_json::from_str(&text).map(Some).map_err(|err| {
+            rusqlite::Error::FromSqlConversionFailure(
+                index,
+                rusqlite::types::Type::Text,
+                Box::new(err),
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+impl AuditReadPort for AuditReadSqliteRepository {
+    fn history(
+        &self,
+        ledger_id: Uuid,
+        entity: AuditedEntity,
+        entity_id: Uuid,
+    ) -> Result<Vec<AuditRecord>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(
+            "SELECT id, entity_id, action, actor, request_id, recorded_at, before, after
+             FROM audit_log WHERE ledger_id = ?1 AND entity = ?2 AND entity_id = ?3
+             ORDER BY recorded_at, rowid",
+        )?;
+        let records = statement
+            .query_map(
+                params![
+                    ledger_id.to_string(),
+                    entity_to_sql(entity),
+                    entity_id.to_string()
+                ],
+                |row| {
+                    Ok(AuditRecord {
+                        id: uuid_column(row, 0)?,
+                        ledger_id,
+                        entity,
+                        entity_id: uuid_column(row, 1)?,
+                        action: action_from_sql(&row.get::<_, String>(2)?),
+                        actor: row.get(3)?,
+                        request_id: row.get(4)?,
+                        recorded_at: row.get(5)?,
+                        before: snapshot_column(row, 6)?,
+                        after: snapshot_column(row, 7)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+}
+
+// records a change in the transaction that makes it, so the history holds exactly what committed
+pub(in crate::repository) fn record(
+    connection: &Connection,
+    ledger_id: Uuid,
+    entity: AuditedEntity,
+    entity_id: Uuid,
+    action: AuditAction,
+    before: Option<&impl Serialize>,
+    after: Option<&impl Serialize>,
+) -> rusqlite::Result<()> {
+    let context = RequestContext::current();
+    connection
+        .prepare_cached(
+            "INSERT INTO audit_log
+             (id, ledger_id, entity, entity_id, action, actor, request_id, recorded_at, before, after)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )?
+        .execute(params![
+            Uuid::new_v4().to_string(),
+            ledger_id.to_string(),
+            entity_to_sql(entity),
+            entity_id.to_string(),
+            action_to_sql(action),
+            context.actor,
+            context.request_id,
+            Utc::now(),
+            before.map(to_json).transpose()?,
+            after.map(to_json).transpose()?,
+        ])?;
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        audit::{AuditAction, AuditedEntity},
+        budget::{Budget, BudgetAlert, BudgetPeriod, BudgetThreshold},
+        outbox::{ChangeAction, ChangedEntity},
+        trash::{TrashItem, TrashedEntity},
+    },
+    repository::sqliterepository::{
+        audit,
+        database::{SqliteDatabase, optional_uuid_column, uuid_column},
+        ledger, outbox, trash,
+    },
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        budget::{BudgetReadPort, BudgetWritePort},
+    },
+};
+
+pub struct BudgetReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct BudgetWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl BudgetReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        BudgetReadSqliteRepository { database }
+    }
+}
+
+impl BudgetWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        BudgetWriteSqliteRepository { database }
+    }
+}
+
+fn budget_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Budget not found."),
+    }
+}
+
+fn period_to_sql(period: BudgetPeriod) -> &'static str {
+    match period {
+        BudgetPeriod::Weekly => "weekly",
+        BudgetPeriod::Monthly => "monthly",
+        BudgetPeriod::Yearly => "yearly",
+    }
+}
+
+fn period_from_sql(period: &str) -> BudgetPeriod {
+    match period {
+        "weekly" => BudgetPeriod::Weekly,
+        "yearly" => BudgetPeriod::Yearly,
+        _ => BudgetPeriod::Monthly,
+    }
+}
+
+fn threshold_to_sql(threshold: BudgetThreshold) -> &'static str {
+    match threshold {
+        BudgetThreshold::Warning => "warning",
+        BudgetThreshold::Exceeded => "exceeded",
+    }
+}
+
+const BUDGET_COLUMNS: &str = "id, expense_type, cost_bearer_id, period, limit_amount";
+
+fn budget_from_row(row: &Row) -> rusqlite::Result<Budget> {
+    Ok(Budget::restore(
+        uuid_column(row, 0)?,
+        uuid_column(row, 1)?,
+        optional_uuid_column(row, 2)?,
+        period_from_sql(&row.get::<_, String>(3)?),
+        row.get(4)?,
+    ))
+}
+
+// the stored budget of the ledger, also while it is in the trash
+fn load_stored(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<Option<Budget>> {
+    connection
+        .query_row(
+            &format!("SELECT {BUDGET_COLUMNS} FROM budget WHERE id = ?1 AND ledger_id = ?2"),
+            params![id.to_string(), ledger_id.to_string()],
+            budget_from_row,
+        )
+        .optional()
+}
+
+impl BudgetReadPort for BudgetReadSqliteRepository {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<Budget, ApplicationError> {
+        self.database
+            .connection()
+            .query_row(
+                &format!(
+                    "SELECT {BUDGET_COLUMNS} FROM budget
+                     WHERE id = ?1 AND ledger_id = ?2 AND deleted_at IS NULL"
+                ),
+                params![id.to_string(), ledger_id.to_string()],
+                budget_from_row,
+            )
+            .optional()?
+            .ok_or_else(budget_not_found)
+    }
+
+    fn find_by_expense_type(
+        &self,
+        ledger_id: Uuid,
+        expense_type: Uuid,
+    ) -> Result<Vec<Budget>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {BUDGET_COLUMNS} FROM budget
+             WHERE ledger_id = ?1 AND expense_type = ?2 AND deleted_at IS NULL ORDER BY id"
+        ))?;
+        let budgets = statement
+            .query_map(
+                params![ledger_id.to_string(), expense_type.to_string()],
+                budget_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(budgets)
+    }
+
+    fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {BUDGET_COLUMNS}, deleted_at FROM budget
+             WHERE ledger_id = ?1 AND deleted_at IS NOT NULL ORDER BY deleted_at"
+        ))?;
+        let items = statement
+            .query_map(params![ledger_id.to_string()], |row| {
+                let budget = budget_from_row(row)?;
+                Ok(TrashItem {
+                    entity: TrashedEntity::Budget,
+                    id: budget.id(),
+                    label: format!(
+                        "{} budget of {}",
+                        period_to_sql(budget.period()),
+                        budget.limit()
+                    ),
+                    deleted_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+}
+
+impl BudgetWritePort for BudgetWriteSqliteRepository {
+    fn insert(&self, ledger_id: Uuid, budget: Budget) -> Result<Budget, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        ledger::verify_references(
+            &transaction,
+            ledger_id,
+            &budget.cost_bearer_id().into_iter().collect::<Vec<_>>(),
+            &[budget.expense_type()],
+        )?;
+        transaction.execute(
+            &format!(
+                "INSERT INTO budget ({BUDGET_COLUMNS}, ledger_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+            ),
+            params![
+                budget.id().to_string(),
+                budget.expense_type().to_string(),
+                budget.cost_bearer_id().map(|id| id.to_string()),
+                period_to_sql(budget.period()),
+                budget.limit(),
+                ledger_id.to_string(),
+            ],
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::Budget,
+            budget.id(),
+            AuditAction::Created,
+            None::<&Budget>,
+            Some(&budget),
+        )?;
+        transaction.commit()?;
+        Ok(budget)
+    }
+
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if !trash::soft_delete(&transaction, "budget", ledger_id, id)? {
+            return Err(budget_not_found());
+        }
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::Budget,
+            id,
+            AuditAction::Deleted,
+            load_stored(&transaction, ledger_id, id)?.as_ref(),
+            None::<&Budget>,
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if !trash::restore(&transaction, "budget", ledger_id, id)? {
+            return Err(budget_not_found());
+        }
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::Budget,
+            id,
+            AuditAction::Restored,
+            None::<&Budget>,
+            load_stored(&transaction, ledger_id, id)?.as_ref(),
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        // sent alerts are removed by the foreign key cascade
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let purged_ids = trash::purge(&transaction, "budget", ledger_id, deleted_before)?;
+        for &id in &purged_ids {
+            audit::record(
+                &transaction,
+                ledger_id,
+                AuditedEntity::Budget,
+                id,
+                AuditAction::Purged,
+                None::<&Budget>,
+                None::<&Budget>,
+            )?;
+        }
+        transaction.commit()?;
+        Ok(purged_ids)
+    }
+
+    fn claim_alert(&self, ledger_id: Uuid, alert: &BudgetAlert) -> Result<bool, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let inserted = transaction.execute(
+            "INSERT OR IGNORE INTO budget_alert (budget_id, threshold, period_start) VALUES (?1, ?2, ?3)",
+            params![
+                alert.budget_id.to_string(),
+                threshold_to_sql(alert.threshold),
+                alert.period_start
+            ],
+        )?;
+        if inserted > 0 {
+            outbox::enqueue(
+                &transaction,
+                ledger_id,
+                ChangedEntity::BudgetAlert,
+                alert.budget_id,
+                ChangeAction::Created,
+                Some(alert),
+            )?;
+        }
+        transaction.commit()?;
+        Ok(inserted > 0)
+    }
+}
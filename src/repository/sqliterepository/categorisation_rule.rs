@@ -0,0 +1,180 @@
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use uuid::Uuid;
+
+use crate::{
+    domain::{categorisation_rule::CategorisationRule, tag::Tag},
+    repository::sqliterepository::{
+        database::{SqliteDatabase, json_column, optional_uuid_column, to_json, uuid_column},
+        ledger,
+    },
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        categorisation_rule::{CategorisationRuleReadPort, CategorisationRuleWritePort},
+    },
+};
+
+pub struct CategorisationRuleReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct CategorisationRuleWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl CategorisationRuleReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        CategorisationRuleReadSqliteRepository { database }
+    }
+}
+
+impl CategorisationRuleWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        CategorisationRuleWriteSqliteRepository { database }
+    }
+}
+
+fn categorisation_rule_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Categorisation rule not found."),
+    }
+}
+
+const CATEGORISATION_RULE_COLUMNS: &str = "id, name, priority, description_pattern, min_amount,
+    max_amount, cost_bearer_id, set_expense_type, add_tags, rewrite_description";
+
+fn categorisation_rule_from_row(row: &Row) -> rusqlite::Result<CategorisationRule> {
+    let add_tags: Vec<String> = json_column(row, 8)?;
+    Ok(CategorisationRule::restore(
+        uuid_column(row, 0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+        optional_uuid_column(row, 6)?,
+        optional_uuid_column(row, 7)?,
+        add_tags.into_iter().map(Tag::restore).collect(),
+        row.get(9)?,
+    ))
+}
+
+impl CategorisationRuleReadPort for CategorisationRuleReadSqliteRepository {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<CategorisationRule, ApplicationError> {
+        self.database
+            .connection()
+            .query_row(
+                &format!(
+                    "SELECT {CATEGORISATION_RULE_COLUMNS} FROM categorisation_rule
+                     WHERE id = ?1 AND ledger_id = ?2"
+                ),
+                params![id.to_string(), ledger_id.to_string()],
+                categorisation_rule_from_row,
+            )
+            .optional()?
+            .ok_or_else(categorisation_rule_not_found)
+    }
+
+    fn list(&self, ledger_id: Uuid) -> Result<Vec<CategorisationRule>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {CATEGORISATION_RULE_COLUMNS} FROM categorisation_rule WHERE ledger_id = ?1
+             ORDER BY priority, name"
+        ))?;
+        let rules = statement
+            .query_map(params![ledger_id.to_string()], categorisation_rule_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rules)
+    }
+}
+
+// the cost bearer and the expense type have to be the ledger's own and not in the trash
+fn verify_references(
+    connection: &Connection,
+    ledger_id: Uuid,
+    rule: &CategorisationRule,
+) -> Result<(), ApplicationError> {
+    ledger::verify_references(
+        connection,
+        ledger_id,
+        &rule.cost_bearer_id().into_iter().collect::<Vec<_>>(),
+        &rule.set_expense_type().into_iter().collect::<Vec<_>>(),
+    )
+}
+
+impl CategorisationRuleWritePort for CategorisationRuleWriteSqliteRepository {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        rule: CategorisationRule,
+    ) -> Result<CategorisationRule, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        verify_references(&transaction, ledger_id, &rule)?;
+        transaction.execute(
+            "INSERT INTO categorisation_rule (id, ledger_id, name, priority, description_pattern,
+                 min_amount, max_amount, cost_bearer_id, set_expense_type, add_tags,
+                 rewrite_description)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                rule.id().to_string(),
+                ledger_id.to_string(),
+                rule.name(),
+                rule.priority(),
+                rule.description_pattern(),
+                rule.min_amount(),
+                rule.max_amount(),
+                rule.cost_bearer_id().map(|id| id.to_string()),
+                rule.set_expense_type().map(|id| id.to_string()),
+                to_json(&rule.add_tags())?,
+                rule.rewrite_description(),
+            ],
+        )?;
+        transaction.commit()?;
+        Ok(rule)
+    }
+
+    fn update(
+        &self,
+        ledger_id: Uuid,
+        rule: CategorisationRule,
+    ) -> Result<CategorisationRule, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        verify_references(&transaction, ledger_id, &rule)?;
+        let updated = transaction.execute(
+            "UPDATE categorisation_rule SET name = ?3, priority = ?4, description_pattern = ?5,
+                 min_amount = ?6, max_amount = ?7, cost_bearer_id = ?8, set_expense_type = ?9,
+                 add_tags = ?10, rewrite_description = ?11
+             WHERE id = ?1 AND ledger_id = ?2",
+            params![
+                rule.id().to_string(),
+                ledger_id.to_string(),
+                rule.name(),
+                rule.priority(),
+                rule.description_pattern(),
+                rule.min_amount(),
+                rule.max_amount(),
+                rule.cost_bearer_id().map(|id| id.to_string()),
+                rule.set_expense_type().map(|id| id.to_string()),
+                to_json(&rule.add_tags())?,
+                rule.rewrite_description(),
+            ],
+        )?;
+        if updated == 0 {
+            return Err(categorisation_rule_not_found());
+        }
+        transaction.commit()?;
+        Ok(rule)
+    }
+
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let deleted = self.database.connection().execute(
+            "DELETE FROM categorisation_rule WHERE id = ?1 AND ledger_id = ?2",
+            params![id.to_string(), ledger_id.to_string()],
+        )?;
+        if deleted == 0 {
+            return Err(categorisation_rule_not_found());
+        }
+        Ok(())
+    }
+}
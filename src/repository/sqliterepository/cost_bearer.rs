@@ -0,0 +1,487 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        audit::{AuditAction, AuditedEntity},
+        cost_bearer::CostBearer,
+        cost_share::CostShare,
+        outbox::{ChangeAction, ChangedEntity},
+        trash::{TrashItem, TrashedEntity},
+    },
+    repository::sqliterepository::{
+        audit,
+        database::{SqliteDatabase, json_column, to_json, uuid_column},
+        expense_entry_event_store, outbox, projection, trash,
+    },
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        cost_bearer::{CostBearerReadPort, CostBearerWritePort},
+    },
+};
+
+pub struct CostBearerReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct CostBearerWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl CostBearerReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        CostBearerReadSqliteRepository { database }
+    }
+}
+
+impl CostBearerWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        CostBearerWriteSqliteRepository { database }
+    }
+}
+
+fn cost_bearer_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Cost Bearer not found."),
+    }
+}
+
+const COST_BEARER_COLUMNS: &str = "id, name, exists_from, exists_to, private_to";
+
+fn cost_bearer_from_row(row: &Row) -> rusqlite::Result<CostBearer> {
+    Ok(CostBearer::restore(
+        uuid_column(row, 0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        json_column(row, 4)?,
+    ))
+}
+
+// the stored cost bearer of the ledger, also while it is in the trash
+fn load_stored(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<Option<CostBearer>> {
+    connection
+        .query_row(
+            &format!(
+                "SELECT {COST_BEARER_COLUMNS} FROM cost_bearer WHERE id = ?1 AND ledger_id = ?2"
+            ),
+            params![id.to_string(), ledger_id.to_string()],
+            cost_bearer_from_row,
+        )
+        .optional()
+}
+
+impl CostBearerReadPort for CostBearerReadSqliteRepository {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<CostBearer, ApplicationError> {
+        self.database
+            .connection()
+            .query_row(
+                &format!(
+                    "SELECT {COST_BEARER_COLUMNS} FROM cost_bearer
+                     WHERE id = ?1 AND ledger_id = ?2 AND deleted_at IS NULL"
+                ),
+                params![id.to_string(), ledger_id.to_string()],
+                cost_bearer_from_row,
+            )
+            .optional()?
+            .ok_or_else(cost_bearer_not_found)
+    }
+
+    fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(
+            "SELECT id, name, deleted_at FROM cost_bearer
+             WHERE ledger_id = ?1 AND deleted_at IS NOT NULL ORDER BY deleted_at",
+        )?;
+        let items = statement
+            .query_map(params![ledger_id.to_string()], |row| {
+                Ok(TrashItem {
+                    entity: TrashedEntity::CostBearer,
+                    id: uuid_column(row, 0)?,
+                    label: row.get(1)?,
+                    deleted_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+}
+
+// a cost bearer that is still used can only be deleted by reassigning what uses it, counted in
+// the deleting transaction so nothing can start using it in between
+fn verify_unused(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> Result<(), ApplicationError> {
+    let expense_entries = connection.query_row(
+        "SELECT COUNT(*) FROM expense_entry
+         WHERE ledger_id = ?1 AND deleted_at IS NULL AND id IN (
+             SELECT expense_entry_id FROM cost_share WHERE cost_bearer_id = ?2)",
+        params![ledger_id.to_string(), id.to_string()],
+        |row| row.get::<_, i64>(0),
+    )?;
+    if expense_entries > 0 {
+        return Err(ApplicationError {
+            error_type: ApplicationErrorType::Conflict,
+            message: format!(
+                "Cost bearer is still used by {expense_entries} expense {}.",
+                if expense_entries == 1 {
+                    "entry"
+                } else {
+                    "entries"
+                }
+            ),
+        });
+    }
+    let references = connection.query_row(
+        "SELECT
+             (SELECT COUNT(*) FROM budget
+              WHERE ledger_id = ?1 AND cost_bearer_id = ?2 AND deleted_at IS NULL)
+           + (SELECT COUNT(*) FROM recurring_expense
+              WHERE ledger_id = ?1 AND deleted_at IS NULL AND (EXISTS (
+                  SELECT 1 FROM json_each(cost_shares)
+                  WHERE json_extract(value, '$.cost_bearer_id') = ?2) OR id IN (
+                  SELECT recurring_expense_id FROM recurring_expense_occurrence, json_each(
+                      recurring_expense_occurrence.override_cost_shares)
+                  WHERE json_extract(value, '$.cost_bearer_id') = ?2 AND status != 'booked')))
+           + (SELECT COUNT(*) FROM categorisation_rule
+              WHERE ledger_id = ?1 AND cost_bearer_id = ?2)",
+        params![ledger_id.to_string(), id.to_string()],
+        |row| row.get::<_, i64>(0),
+    )?;
+    if references > 0 {
+        return Err(ApplicationError {
+            error_type: ApplicationErrorType::Conflict,
+            message: format!(
+                "Cost bearer is still used by {references} budgets, recurring expenses or \
+                 categorisation rules."
+            ),
+        });
+    }
+    Ok(())
+}
+
+// moves the source's share of cost shares stored as json to the target, adding it to a share the
+// target already has
+fn reassign_json_cost_shares(
+    connection: &Connection,
+    table: &str,
+    column: &str,
+    ledger_id: Uuid,
+    id: Uuid,
+    target_id: Uuid,
+) -> rusqlite::Result<()> {
+    let rows = connection
+        .prepare(&format!(
+            "SELECT rowid, {column} FROM {table}
+             WHERE {} AND EXISTS (
+                 SELECT 1 FROM json_each({table}.{column})
+                 WHERE json_extract(value, '$.cost_bearer_id') = ?2)",
+            match table {
+                "recurring_expense" => "ledger_id = ?1",
+                _ => "recurring_expense_id IN (SELECT id FROM recurring_expense WHERE ledger_id = ?1)",
+            }
+        ))?
+        .query_map(params![ledger_id.to_string(), id.to_string()], |row| {
+            Ok((row.get::<_, i64>(0)?, json_column::<Vec<CostShare>>(row, 1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (rowid, cost_shares) in rows {
+        let mut reassigned: Vec<CostShare> = Vec::with_capacity(cost_shares.len());
+        for mut share in cost_shares {
+            if share.cost_bearer_id == id {
+                share.cost_bearer_id = target_id;
+            }
+            match reassigned
+                .iter_mut()
+                .find(|existing| existing.cost_bearer_id == share.cost_bearer_id)
+            {
+                Some(existing) => existing.amount += share.amount,
+                None => reassigned.push(share),
+            }
+        }
+        connection.execute(
+            &format!("UPDATE {table} SET {column} = ?2 WHERE rowid = ?1"),
+            params![rowid, to_json(&reassigned)?],
+        )?;
+    }
+    Ok(())
+}
+
+// points everything else that uses the cost bearer at the target, the entries' cost shares are
+// moved by the caller
+fn reassign_references(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+    target_id: Uuid,
+) -> rusqlite::Result<()> {
+    let params = params![ledger_id.to_string(), id.to_string(), target_id.to_string()];
+    connection.execute(
+        "UPDATE budget SET cost_bearer_id = ?3 WHERE ledger_id = ?1 AND cost_bearer_id = ?2",
+        params,
+    )?;
+    connection.execute(
+        "UPDATE categorisation_rule SET cost_bearer_id = ?3
+         WHERE ledger_id = ?1 AND cost_bearer_id = ?2",
+        params,
+    )?;
+    reassign_json_cost_shares(
+        connection,
+        "recurring_expense",
+        "cost_shares",
+        ledger_id,
+        id,
+        target_id,
+    )?;
+    reassign_json_cost_shares(
+        connection,
+        "recurring_expense_occurrence",
+        "override_cost_shares",
+        ledger_id,
+        id,
+        target_id,
+    )
+}
+
+impl CostBearerWritePort for CostBearerWriteSqliteRepository {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        cost_bearer: CostBearer,
+    ) -> Result<CostBearer, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            &format!(
+                "INSERT INTO cost_bearer ({COST_BEARER_COLUMNS}, ledger_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+            ),
+            params![
+                cost_bearer.id().to_string(),
+                cost_bearer.name(),
+                cost_bearer.exists_from(),
+                cost_bearer.exists_to(),
+                to_json(&cost_bearer.private_to())?,
+                ledger_id.to_string(),
+            ],
+        )?;
+        outbox::enqueue(
+            &transaction,
+            ledger_id,
+            ChangedEntity::CostBearer,
+            cost_bearer.id(),
+            ChangeAction::Created,
+            Some(&cost_bearer),
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::CostBearer,
+            cost_bearer.id(),
+            AuditAction::Created,
+            None::<&CostBearer>,
+            Some(&cost_bearer),
+        )?;
+        transaction.commit()?;
+        Ok(cost_bearer)
+    }
+
+    fn update(
+        &self,
+        ledger_id: Uuid,
+        cost_bearer: CostBearer,
+    ) -> Result<CostBearer, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let existing = load_stored(&transaction, ledger_id, cost_bearer.id())?;
+        let updated = transaction.execute(
+            "UPDATE cost_bearer SET name = ?2, exists_from = ?3, exists_to = ?4, private_to = ?6
+             WHERE id = ?1 AND ledger_id = ?5 AND deleted_at IS NULL",
+            params![
+                cost_bearer.id().to_string(),
+                cost_bearer.name(),
+                cost_bearer.exists_from(),
+                cost_bearer.exists_to(),
+                ledger_id.to_string(),
+                to_json(&cost_bearer.private_to())?,
+            ],
+        )?;
+        if updated == 0 {
+            return Err(cost_bearer_not_found());
+        }
+        projection::rename_cost_bearer(&transaction, cost_bearer.id(), cost_bearer.name())?;
+        outbox::enqueue(
+            &transaction,
+            ledger_id,
+            ChangedEntity::CostBearer,
+            cost_bearer.id(),
+            ChangeAction::Updated,
+            Some(&cost_bearer),
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::CostBearer,
+            cost_bearer.id(),
+            AuditAction::Updated,
+            existing.as_ref(),
+            Some(&cost_bearer),
+        )?;
+        transaction.commit()?;
+        Ok(cost_bearer)
+    }
+
+    fn delete(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        reassign_to: Option<Uuid>,
+    ) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if let Some(target_id) = reassign_to {
+            let reassigned_ids = transaction
+                .prepare("SELECT expense_entry_id FROM cost_share WHERE cost_bearer_id = ?1")?
+                .query_map(params![id.to_string()], |row| uuid_column(row, 0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            // an entry may only have one share per cost bearer, so shares meeting on the target
+            // are combined into the target's share
+            transaction.execute(
+                "UPDATE cost_share SET amount = amount + (
+                     SELECT source.amount FROM cost_share AS source
+                     WHERE source.expense_entry_id = cost_share.expense_entry_id
+                     AND source.cost_bearer_id = ?1)
+                 WHERE cost_bearer_id = ?2 AND expense_entry_id IN (
+                     SELECT expense_entry_id FROM cost_share WHERE cost_bearer_id = ?1)",
+                params![id.to_string(), target_id.to_string()],
+            )?;
+            transaction.execute(
+                "DELETE FROM cost_share WHERE cost_bearer_id = ?1 AND expense_entry_id IN (
+                     SELECT expense_entry_id FROM cost_share WHERE cost_bearer_id = ?2)",
+                params![id.to_string(), target_id.to_string()],
+            )?;
+            transaction.execute(
+                "UPDATE cost_share SET cost_bearer_id = ?2 WHERE cost_bearer_id = ?1",
+                params![id.to_string(), target_id.to_string()],
+            )?;
+            expense_entry_event_store::record_stored_changes(
+                &transaction,
+                ledger_id,
+                &reassigned_ids,
+            )?;
+            reassign_references(&transaction, ledger_id, id, target_id)?;
+        } else {
+            verify_unused(&transaction, ledger_id, id)?;
+        }
+        if !trash::soft_delete(&transaction, "cost_bearer", ledger_id, id)? {
+            return Err(cost_bearer_not_found());
+        }
+        let deleted = load_stored(&transaction, ledger_id, id)?;
+        outbox::enqueue(
+            &transaction,
+            ledger_id,
+            ChangedEntity::CostBearer,
+            id,
+            ChangeAction::Deleted,
+            deleted.as_ref(),
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::CostBearer,
+            id,
+            AuditAction::Deleted,
+            deleted.as_ref(),
+            None::<&CostBearer>,
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if !trash::restore(&transaction, "cost_bearer", ledger_id, id)? {
+            return Err(cost_bearer_not_found());
+        }
+        let restored = load_stored(&transaction, ledger_id, id)?;
+        outbox::enqueue(
+            &transaction,
+            ledger_id,
+            ChangedEntity::CostBearer,
+            id,
+            ChangeAction::Restored,
+            restored.as_ref(),
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::CostBearer,
+            id,
+            AuditAction::Restored,
+            None::<&CostBearer>,
+            restored.as_ref(),
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        // cost bearers anything still points at, even from the trash, are kept, a later purge
+        // removes them
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let mut statement = transaction.prepare(
+            "DELETE FROM cost_bearer
+             WHERE ledger_id = ?1 AND deleted_at IS NOT NULL AND deleted_at < ?2
+             AND id NOT IN (SELECT cost_bearer_id FROM cost_share)
+             AND id NOT IN (SELECT cost_bearer_id FROM budget WHERE cost_bearer_id IS NOT NULL)
+             AND id NOT IN (SELECT cost_bearer_id FROM categorisation_rule
+                            WHERE cost_bearer_id IS NOT NULL)
+             AND NOT EXISTS (SELECT 1 FROM recurring_expense, json_each(recurring_expense.cost_shares)
+                             WHERE json_extract(value, '$.cost_bearer_id') = cost_bearer.id)
+             AND NOT EXISTS (SELECT 1 FROM recurring_expense_occurrence, json_each(
+                                 recurring_expense_occurrence.override_cost_shares)
+                             WHERE json_extract(value, '$.cost_bearer_id') = cost_bearer.id)
+             RETURNING id",
+        )?;
+        let purged_ids = statement
+            .query_map(params![ledger_id.to_string(), deleted_before], |row| {
+                uuid_column(row, 0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(statement);
+        for &id in &purged_ids {
+            outbox::enqueue(
+                &transaction,
+                ledger_id,
+                ChangedEntity::CostBearer,
+                id,
+                ChangeAction::Purged,
+                None::<&CostBearer>,
+            )?;
+            audit::record(
+                &transaction,
+                ledger_id,
+                AuditedEntity::CostBearer,
+                id,
+                AuditAction::Purged,
+                None::<&CostBearer>,
+                None::<&CostBearer>,
+            )?;
+        }
+        transaction.commit()?;
+        Ok(purged_ids)
+    }
+}
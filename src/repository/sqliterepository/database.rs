@@ -0,0 +1,487 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::Utc;
+use rusqlite::types::Type;
+use rusqlite::{Connection, Row, params};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::repository::sqliterepository::projection::VIEW_TABLES;
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+
+// tables are created on startup if they do not exist yet
+const SCHEMA: &str = "
+    PRAGMA foreign_keys = ON;
+
+    CREATE TABLE IF NOT EXISTS ledger (
+        id TEXT PRIMARY KEY NOT NULL,
+        name TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS ledger_member (
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        user TEXT NOT NULL,
+        role TEXT NOT NULL,
+        added_at TEXT NOT NULL,
+        PRIMARY KEY (ledger_id, user)
+    );
+    CREATE INDEX IF NOT EXISTS ledger_member_user ON ledger_member (user);
+
+    CREATE TABLE IF NOT EXISTS expense_entry (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        expense_date TEXT NOT NULL,
+        expense_type TEXT NOT NULL,
+        description TEXT NOT NULL,
+        deleted_at TEXT
+    );
+    CREATE INDEX IF NOT EXISTS expense_entry_expense_type ON expense_entry (expense_type, expense_date);
+    CREATE TABLE IF NOT EXISTS cost_share (
+        expense_entry_id TEXT NOT NULL REFERENCES expense_entry (id) ON DELETE CASCADE,
+        cost_bearer_id TEXT NOT NULL,
+        amount REAL NOT NULL,
+        position INTEGER NOT NULL,
+        PRIMARY KEY (expense_entry_id, cost_bearer_id)
+    );
+    CREATE INDEX IF NOT EXISTS cost_share_cost_bearer_id ON cost_share (cost_bearer_id);
+    CREATE TABLE IF NOT EXISTS expense_entry_tag (
+        expense_entry_id TEXT NOT NULL REFERENCES expense_entry (id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (expense_entry_id, tag)
+    );
+    CREATE INDEX IF NOT EXISTS expense_entry_tag_tag ON expense_entry_tag (tag);
+    CREATE TABLE IF NOT EXISTS attachment (
+        id TEXT PRIMARY KEY NOT NULL,
+        expense_entry_id TEXT NOT NULL,
+        file_name TEXT NOT NULL,
+        content_type TEXT NOT NULL,
+        size INTEGER NOT NULL,
+        content_hash TEXT NOT NULL,
+        uploaded_at TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS attachment_expense_entry_id ON attachment (expense_entry_id);
+    CREATE INDEX IF NOT EXISTS attachment_content_hash ON attachment (content_hash);
+
+    CREATE TABLE IF NOT EXISTS cost_bearer (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        name TEXT NOT NULL,
+        exists_from TEXT NOT NULL,
+        exists_to TEXT,
+        private_to TEXT NOT NULL DEFAULT '[]',
+        deleted_at TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS expense_type (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        parent_id TEXT REFERENCES expense_type (id),
+        deleted_at TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS budget (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        expense_type TEXT NOT NULL,
+        cost_bearer_id TEXT,
+        period TEXT NOT NULL,
+        limit_amount REAL NOT NULL,
+        deleted_at TEXT
+    );
+    CREATE INDEX IF NOT EXISTS budget_expense_type ON budget (expense_type);
+
+    CREATE TABLE IF NOT EXISTS budget_alert (
+        budget_id TEXT NOT NULL REFERENCES budget (id) ON DELETE CASCADE,
+        threshold TEXT NOT NULL,
+        period_start TEXT NOT NULL,
+        PRIMARY KEY (budget_id, threshold, period_start)
+    );
+
+    CREATE TABLE IF NOT EXISTS recurring_expense (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        cost_shares TEXT NOT NULL,
+        expense_type TEXT NOT NULL,
+        description TEXT NOT NULL,
+        recurrence TEXT NOT NULL,
+        repeat_interval INTEGER NOT NULL,
+        starts_on TEXT NOT NULL,
+        ends_on TEXT,
+        created_at TEXT NOT NULL,
+        deleted_at TEXT
+    );
+    CREATE TABLE IF NOT EXISTS recurring_expense_occurrence (
+        recurring_expense_id TEXT NOT NULL REFERENCES recurring_expense (id) ON DELETE CASCADE,
+        occurrence_date TEXT NOT NULL,
+        status TEXT NOT NULL,
+        override_cost_shares TEXT,
+        override_expense_type TEXT,
+        override_description TEXT,
+        expense_entry_id TEXT,
+        PRIMARY KEY (recurring_expense_id, occurrence_date)
+    );
+
+    CREATE TABLE IF NOT EXISTS audit_log (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        entity TEXT NOT NULL,
+        entity_id TEXT NOT NULL,
+        action TEXT NOT NULL,
+        actor TEXT NOT NULL,
+        request_id TEXT NOT NULL,
+        recorded_at TEXT NOT NULL,
+        before TEXT,
+        after TEXT
+    );
+    CREATE INDEX IF NOT EXISTS audit_log_entity ON audit_log (entity, entity_id);
+    CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+    BEGIN
+        SELECT RAISE(ABORT, 'audit log is append-only');
+    END;
+    CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+    BEGIN
+        SELECT RAISE(ABORT, 'audit log is append-only');
+    END;
+
+    CREATE TABLE IF NOT EXISTS expense_entry_event (
+        stream_id TEXT NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        version INTEGER NOT NULL,
+        event_type TEXT NOT NULL,
+        payload TEXT NOT NULL,
+        recorded_at TEXT NOT NULL,
+        PRIMARY KEY (stream_id, version)
+    );
+    CREATE INDEX IF NOT EXISTS expense_entry_event_type ON expense_entry_event (event_type);
+
+    CREATE TABLE IF NOT EXISTS expense_entry_view (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        expense_date TEXT NOT NULL,
+        expense_type TEXT NOT NULL,
+        expense_type_name TEXT,
+        description TEXT NOT NULL,
+        deleted_at TEXT
+    );
+    CREATE INDEX IF NOT EXISTS expense_entry_view_expense_type
+        ON expense_entry_view (expense_type, expense_date);
+    CREATE TABLE IF NOT EXISTS expense_entry_view_share (
+        expense_entry_id TEXT NOT NULL REFERENCES expense_entry_view (id) ON DELETE CASCADE,
+        cost_bearer_id TEXT NOT NULL,
+        cost_bearer_name TEXT,
+        amount REAL NOT NULL,
+        position INTEGER NOT NULL,
+        PRIMARY KEY (expense_entry_id, cost_bearer_id)
+    );
+    CREATE INDEX IF NOT EXISTS expense_entry_view_share_cost_bearer_id
+        ON expense_entry_view_share (cost_bearer_id);
+    CREATE TABLE IF NOT EXISTS expense_entry_view_tag (
+        expense_entry_id TEXT NOT NULL REFERENCES expense_entry_view (id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (expense_entry_id, tag)
+    );
+    CREATE INDEX IF NOT EXISTS expense_entry_view_tag_tag ON expense_entry_view_tag (tag);
+    CREATE TABLE IF NOT EXISTS monthly_total_view (
+        ledger_id TEXT NOT NULL,
+        expense_type TEXT NOT NULL,
+        expense_type_name TEXT,
+        month TEXT NOT NULL,
+        total REAL NOT NULL,
+        PRIMARY KEY (ledger_id, expense_type, month)
+    );
+    CREATE TABLE IF NOT EXISTS cost_bearer_balance_view (
+        ledger_id TEXT NOT NULL,
+        cost_bearer_id TEXT NOT NULL,
+        cost_bearer_name TEXT,
+        balance REAL NOT NULL,
+        PRIMARY KEY (ledger_id, cost_bearer_id)
+    );
+    CREATE TABLE IF NOT EXISTS projection_checkpoint (
+        projection TEXT PRIMARY KEY NOT NULL,
+        position INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS outbox (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        entity TEXT NOT NULL,
+        entity_id TEXT NOT NULL,
+        action TEXT NOT NULL,
+        data TEXT,
+        occurred_at TEXT NOT NULL,
+        routed_at TEXT
+    );
+    CREATE INDEX IF NOT EXISTS outbox_routed_at ON outbox (routed_at);
+    CREATE TABLE IF NOT EXISTS outbox_delivery (
+        outbox_id TEXT NOT NULL REFERENCES outbox (id) ON DELETE CASCADE,
+        handler TEXT NOT NULL,
+        attempts INTEGER NOT NULL,
+        next_attempt_at TEXT NOT NULL,
+        last_error TEXT,
+        delivered_at TEXT,
+        dead_lettered_at TEXT,
+        PRIMARY KEY (outbox_id, handler)
+    );
+    CREATE INDEX IF NOT EXISTS outbox_delivery_next_attempt_at
+        ON outbox_delivery (next_attempt_at) WHERE delivered_at IS NULL AND dead_lettered_at IS NULL;
+
+    CREATE TABLE IF NOT EXISTS webhook_subscription (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        url TEXT NOT NULL,
+        events TEXT NOT NULL,
+        secret TEXT NOT NULL,
+        created_by TEXT,
+        created_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS webhook_delivery (
+        id TEXT PRIMARY KEY NOT NULL,
+        subscription_id TEXT NOT NULL REFERENCES webhook_subscription (id) ON DELETE CASCADE,
+        event_id TEXT NOT NULL,
+        event TEXT NOT NULL,
+        attempt INTEGER NOT NULL,
+        status_code INTEGER,
+        error TEXT,
+        succeeded INTEGER NOT NULL,
+        attempted_at TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS webhook_delivery_subscription_id
+        ON webhook_delivery (subscription_id, event_id);
+
+    CREATE TABLE IF NOT EXISTS api_key (
+        id TEXT PRIMARY KEY NOT NULL,
+        name TEXT NOT NULL,
+        user TEXT,
+        prefix TEXT NOT NULL,
+        key_hash TEXT NOT NULL UNIQUE,
+        created_at TEXT NOT NULL,
+        revoked_at TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS idempotency_key (
+        scope TEXT NOT NULL,
+        idempotency_key TEXT NOT NULL,
+        fingerprint TEXT NOT NULL,
+        status_code INTEGER,
+        content_type TEXT,
+        body BLOB,
+        created_at TEXT NOT NULL,
+        PRIMARY KEY (scope, idempotency_key)
+    );
+    CREATE INDEX IF NOT EXISTS idempotency_key_created_at ON idempotency_key (created_at);
+
+    CREATE TABLE IF NOT EXISTS categorisation_rule (
+        id TEXT PRIMARY KEY NOT NULL,
+        ledger_id TEXT NOT NULL REFERENCES ledger (id),
+        name TEXT NOT NULL,
+        priority INTEGER NOT NULL,
+        description_pattern TEXT,
+        min_amount REAL,
+        max_amount REAL,
+        cost_bearer_id TEXT,
+        set_expense_type TEXT,
+        add_tags TEXT NOT NULL DEFAULT '[]',
+        rewrite_description TEXT
+    );
+    CREATE INDEX IF NOT EXISTS categorisation_rule_ledger ON categorisation_rule (ledger_id, priority);
+";
+
+// soft deleted rows keep their data but are hidden from all reads
+const SOFT_DELETE_TABLES: [&str; 4] = [
+    "expense_entry",
+    "expense_type",
+    "budget",
+    "recurring_expense",
+];
+
+// tables whose rows belong to a ledger, databases from before ledgers get the column added
+const LEDGER_TABLES: [&str; 9] = [
+    "expense_entry",
+    "cost_bearer",
+    "expense_type",
+    "budget",
+    "recurring_expense",
+    "audit_log",
+    "expense_entry_event",
+    "outbox",
+    "webhook_subscription",
+];
+
+// needs the deleted_at and ledger_id columns, so it runs after older databases got them
+const LATE_INDEXES: &str = "
+    DROP INDEX IF EXISTS expense_type_sibling_name;
+    DROP INDEX IF EXISTS expense_type_active_sibling_name;
+    CREATE UNIQUE INDEX IF NOT EXISTS expense_type_ledger_sibling_name
+        ON expense_type (ledger_id, COALESCE(parent_id, ''), lower(trim(name)))
+        WHERE deleted_at IS NULL;
+    CREATE INDEX IF NOT EXISTS expense_entry_ledger_id ON expense_entry (ledger_id);
+    CREATE INDEX IF NOT EXISTS cost_bearer_ledger_id ON cost_bearer (ledger_id);
+    CREATE INDEX IF NOT EXISTS expense_type_ledger_id ON expense_type (ledger_id);
+    CREATE INDEX IF NOT EXISTS budget_ledger_id ON budget (ledger_id);
+    CREATE INDEX IF NOT EXISTS recurring_expense_ledger_id ON recurring_expense (ledger_id);
+    CREATE INDEX IF NOT EXISTS webhook_subscription_ledger_id ON webhook_subscription (ledger_id);
+    CREATE INDEX IF NOT EXISTS audit_log_ledger_entity ON audit_log (ledger_id, entity, entity_id);
+    CREATE INDEX IF NOT EXISTS expense_entry_view_ledger_id
+        ON expense_entry_view (ledger_id, expense_date);
+";
+
+// shared handle to the sqlite database, cloned into every repository
+#[derive(Clone)]
+pub struct SqliteDatabase {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteDatabase {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, rusqlite::Error> {
+        Self::initialize(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, rusqlite::Error> {
+        Self::initialize(Connection::open_in_memory()?)
+    }
+
+    fn initialize(connection: Connection) -> Result<Self, rusqlite::Error> {
+        connection.execute_batch(SCHEMA)?;
+        for table in SOFT_DELETE_TABLES {
+            add_missing_column(&connection, table, "deleted_at", "TEXT")?;
+        }
+        scope_to_ledgers(&connection)?;
+        add_missing_column(
+            &connection,
+            "cost_bearer",
+            "private_to",
+            "TEXT NOT NULL DEFAULT '[]'",
+        )?;
+        // keys from before are the operator's
+        add_missing_column(&connection, "api_key", "user", "TEXT")?;
+        // as are the webhooks
+        add_missing_column(&connection, "webhook_subscription", "created_by", "TEXT")?;
+        connection.execute_batch(LATE_INDEXES)?;
+        Ok(SqliteDatabase {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    pub(in crate::repository) fn connection(&self) -> MutexGuard<'_, Connection> {
+        // a poisoned lock only means another request panicked, the connection itself is fine
+        self.connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn has_column(connection: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut statement =
+        connection.prepare(&format!("SELECT name FROM pragma_table_info('{table}')"))?;
+    let exists = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+    Ok(exists)
+}
+
+fn add_missing_column(
+    connection: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    if !has_column(connection, table, column)? {
+        connection.execute_batch(&format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+        ))?;
+    }
+    Ok(())
+}
+
+// data from before ledgers moves into a ledger named Default
+fn scope_to_ledgers(connection: &Connection) -> rusqlite::Result<()> {
+    // the read models are keyed by ledger now, they are replayed from the events
+    if !has_column(connection, "monthly_total_view", "ledger_id")? {
+        for table in VIEW_TABLES {
+            connection.execute_batch(&format!("DROP TABLE IF EXISTS {table}"))?;
+        }
+        connection.execute_batch("DELETE FROM projection_checkpoint")?;
+        connection.execute_batch(SCHEMA)?;
+    }
+    for table in LEDGER_TABLES {
+        add_missing_column(
+            connection,
+            table,
+            "ledger_id",
+            "TEXT REFERENCES ledger (id)",
+        )?;
+    }
+    let mut unscoped = false;
+    for table in LEDGER_TABLES {
+        unscoped |= connection.query_row(
+            &format!("SELECT EXISTS (SELECT 1 FROM {table} WHERE ledger_id IS NULL)"),
+            [],
+            |row| row.get::<_, bool>(0),
+        )?;
+    }
+    if unscoped {
+        let ledger_id = Uuid::new_v4().to_string();
+        connection.execute(
+            "INSERT INTO ledger (id, name, created_at) VALUES (?1, 'Default', ?2)",
+            params![ledger_id, Utc::now()],
+        )?;
+        // the audit log is append-only, its trigger is recreated by the schema right after
+        connection.execute_batch("DROP TRIGGER IF EXISTS audit_log_no_update")?;
+        for table in LEDGER_TABLES {
+            connection.execute(
+                &format!("UPDATE {table} SET ledger_id = ?1 WHERE ledger_id IS NULL"),
+                params![ledger_id],
+            )?;
+        }
+        connection.execute_batch(SCHEMA)?;
+    }
+    Ok(())
+}
+
+// uuids are stored as their hyphenated text representation
+pub(in crate::repository) fn uuid_column(row: &Row, index: usize) -> rusqlite::Result<Uuid> {
+    let text: String = row.get(index)?;
+    Uuid::parse_str(&text)
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(err)))
+}
+
+pub(in crate::repository) fn optional_uuid_column(
+    row: &Row,
+    index: usize,
+) -> rusqlite::Result<Option<Uuid>> {
+    match row.get::<_, Option<String>>(index)? {
+        Some(_) => uuid_column(row, index).map(Some),
+        None => Ok(None),
+    }
+}
+
+// structured values without a table of their own are stored as json text
+pub(in crate::repository) fn json_column<T: DeserializeOwned>(
+    row: &Row,
+    index: usize,
+) -> rusqlite::Result<T> {
+    let text: String = row.get(index)?;
+    serde_json::from_str(&text)
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(err)))
+}
+
+pub(in crate::repository) fn to_json(value: &impl Serialize) -> rusqlite::Result<String> {
+    serde_json::to_string(value)
+        .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))
+}
+
+impl From<rusqlite::Error> for ApplicationError {
+    fn from(err: rusqlite::Error) -> Self {
+        ApplicationError {
+            error_type: ApplicationErrorType::Internal,
+            message: format!("Database error: {err}"),
+        }
+    }
+}
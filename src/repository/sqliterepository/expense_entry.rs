@@ -1,66 +1,363 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, Row, params};
 use uuid::Uuid;
 
 use crate::{
-    domain::{cost_share::CostShare, expense_entry::ExpenseEntry},
+    domain::{cost_share::CostShare, expense_entry::ExpenseEntry, tag::Tag},
+    repository::sqliterepository::{
+        database::{SqliteDatabase, uuid_column},
+        expense_entry_event_store, ledger, trash,
+    },
     service::{
         application_error::{ApplicationError, ApplicationErrorType},
-        expense_entry::{ExpenseEntryReadPort, ExpenseEntryWritePort},
+        expense_entry::{ExpenseEntryWrite, ExpenseEntryWritePort},
     },
-    test_util::test_utility::TEST_VALID_UUID,
 };
 
-pub struct ExpenseEntryReadSqliteRepository {}
-pub struct ExpenseEntryWriteSqliteRepository {}
+pub struct ExpenseEntryWriteSqliteRepository {
+    database: SqliteDatabase,
+}
 
-impl ExpenseEntryReadSqliteRepository {
-    pub fn new() -> Self {
-        ExpenseEntryReadSqliteRepository {}
+impl ExpenseEntryWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        ExpenseEntryWriteSqliteRepository { database }
     }
 }
 
-impl ExpenseEntryWriteSqliteRepository {
-    pub fn new() -> Self {
-        ExpenseEntryWriteSqliteRepository {}
+fn expense_entry_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Expense entry not found."),
     }
 }
 
-impl ExpenseEntryReadPort for ExpenseEntryReadSqliteRepository {
-    fn get(&self, id: Uuid) -> Result<ExpenseEntry, ApplicationError> {
-        match id {
-            id if id == TEST_VALID_UUID => Ok(ExpenseEntry::new(
-                vec![CostShare {
-                    cost_bearer_id: Uuid::new_v4(),
-                    amount: 12.5,
-                }],
-                Uuid::new_v4(),
-                String::from("Some Description"),
-                None,
-            )
-            .unwrap()),
-            _ => Err(ApplicationError {
-                error_type: ApplicationErrorType::NotFound,
-                message: String::from("Expense entry not found."),
-            }),
-        }
+// the entry row without its cost shares
+struct ExpenseEntryRow {
+    id: Uuid,
+    expense_date: chrono::DateTime<chrono::Utc>,
+    expense_type: Uuid,
+    description: String,
+}
+
+fn expense_entry_row(row: &Row) -> rusqlite::Result<ExpenseEntryRow> {
+    Ok(ExpenseEntryRow {
+        id: uuid_column(row, 0)?,
+        expense_date: row.get(1)?,
+        expense_type: uuid_column(row, 2)?,
+        description: row.get(3)?,
+    })
+}
+
+fn load_cost_shares(connection: &Connection, id: Uuid) -> rusqlite::Result<Vec<CostShare>> {
+    let mut statement = connection.prepare_cached(
+        "SELECT cost_bearer_id, amount FROM cost_share WHERE expense_entry_id = ?1 ORDER BY position",
+    )?;
+    statement
+        .query_map(params![id.to_string()], |row| {
+            Ok(CostShare {
+                cost_bearer_id: uuid_column(row, 0)?,
+                amount: row.get(1)?,
+            })
+        })?
+        .collect()
+}
+
+fn load_tags(connection: &Connection, id: Uuid) -> rusqlite::Result<Vec<Tag>> {
+    let mut statement = connection.prepare_cached(
+        "SELECT tag FROM expense_entry_tag WHERE expense_entry_id = ?1 ORDER BY tag",
+    )?;
+    statement
+        .query_map(params![id.to_string()], |row| Ok(Tag::restore(row.get(0)?)))?
+        .collect()
+}
+
+fn complete(connection: &Connection, row: ExpenseEntryRow) -> rusqlite::Result<ExpenseEntry> {
+    Ok(ExpenseEntry::restore(
+        row.id,
+        row.expense_date,
+        load_cost_shares(connection, row.id)?,
+        row.expense_type,
+        row.description,
+        load_tags(connection, row.id)?,
+    ))
+}
+
+fn insert_cost_shares(connection: &Connection, entry: &ExpenseEntry) -> rusqlite::Result<()> {
+    let mut statement = connection.prepare_cached(
+        "INSERT INTO cost_share (expense_entry_id, cost_bearer_id, amount, position) VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for (position, share) in entry.cost_shares().iter().enumerate() {
+        statement.execute(params![
+            entry.id().to_string(),
+            share.cost_bearer_id.to_string(),
+            share.amount,
+            position as i64,
+        ])?;
     }
+    Ok(())
+}
+
+fn insert_tags(connection: &Connection, entry: &ExpenseEntry) -> rusqlite::Result<()> {
+    let mut statement = connection
+        .prepare_cached("INSERT INTO expense_entry_tag (expense_entry_id, tag) VALUES (?1, ?2)")?;
+    for tag in entry.tags() {
+        statement.execute(params![entry.id().to_string(), tag.as_str()])?;
+    }
+    Ok(())
+}
+
+// the stored entry of the ledger, also while it is in the trash, together with its deletion time
+pub(super) fn load_stored(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<Option<(ExpenseEntry, Option<DateTime<Utc>>)>> {
+    let stored = connection
+        .query_row(
+            "SELECT id, expense_date, expense_type, description, deleted_at FROM expense_entry
+             WHERE id = ?1 AND ledger_id = ?2",
+            params![id.to_string(), ledger_id.to_string()],
+            |row| Ok((expense_entry_row(row)?, row.get(4)?)),
+        )
+        .optional()?;
+    match stored {
+        Some((row, deleted_at)) => Ok(Some((complete(connection, row)?, deleted_at))),
+        None => Ok(None),
+    }
+}
+
+// overwrites the stored entry with the given state, attachments of the entry are kept
+pub(super) fn store(
+    connection: &Connection,
+    ledger_id: Uuid,
+    entry: &ExpenseEntry,
+    deleted_at: Option<DateTime<Utc>>,
+) -> rusqlite::Result<()> {
+    connection.execute(
+        "INSERT INTO expense_entry
+             (id, ledger_id, expense_date, expense_type, description, deleted_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT (id) DO UPDATE SET expense_date = excluded.expense_date,
+             expense_type = excluded.expense_type, description = excluded.description,
+             deleted_at = excluded.deleted_at",
+        params![
+            entry.id().to_string(),
+            ledger_id.to_string(),
+            entry.expense_date(),
+            entry.expense_type().to_string(),
+            entry.description(),
+            deleted_at,
+        ],
+    )?;
+    connection.execute(
+        "DELETE FROM cost_share WHERE expense_entry_id = ?1",
+        params![entry.id().to_string()],
+    )?;
+    connection.execute(
+        "DELETE FROM expense_entry_tag WHERE expense_entry_id = ?1",
+        params![entry.id().to_string()],
+    )?;
+    insert_cost_shares(connection, entry)?;
+    insert_tags(connection, entry)
+}
+
+// the cost bearers and the expense type have to be the ledger's own and not in the trash
+pub(super) fn verify_references(
+    connection: &Connection,
+    ledger_id: Uuid,
+    entry: &ExpenseEntry,
+) -> Result<(), ApplicationError> {
+    let cost_bearer_ids: Vec<Uuid> = entry
+        .cost_shares()
+        .iter()
+        .map(|share| share.cost_bearer_id)
+        .collect();
+    ledger::verify_references(
+        connection,
+        ledger_id,
+        &cost_bearer_ids,
+        &[entry.expense_type()],
+    )
+}
+
+pub(super) fn insert_entry(
+    connection: &Connection,
+    ledger_id: Uuid,
+    entry: &ExpenseEntry,
+) -> Result<(), ApplicationError> {
+    verify_references(connection, ledger_id, entry)?;
+    connection.execute(
+        "INSERT INTO expense_entry (id, ledger_id, expense_date, expense_type, description)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            entry.id().to_string(),
+            ledger_id.to_string(),
+            entry.expense_date(),
+            entry.expense_type().to_string(),
+            entry.description(),
+        ],
+    )?;
+    insert_cost_shares(connection, entry)?;
+    insert_tags(connection, entry)?;
+    expense_entry_event_store::record_stored_changes(connection, ledger_id, &[entry.id()])
+}
+
+fn update_entry(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+    entry: &ExpenseEntry,
+) -> Result<(), ApplicationError> {
+    verify_references(connection, ledger_id, entry)?;
+    let updated = connection.execute(
+        "UPDATE expense_entry SET expense_date = ?3, expense_type = ?4, description = ?5
+         WHERE id = ?1 AND ledger_id = ?2 AND deleted_at IS NULL",
+        params![
+            id.to_string(),
+            ledger_id.to_string(),
+            entry.expense_date(),
+            entry.expense_type().to_string(),
+            entry.description(),
+        ],
+    )?;
+    if updated == 0 {
+        return Err(expense_entry_not_found());
+    }
+    connection.execute(
+        "DELETE FROM cost_share WHERE expense_entry_id = ?1",
+        params![id.to_string()],
+    )?;
+    connection.execute(
+        "DELETE FROM expense_entry_tag WHERE expense_entry_id = ?1",
+        params![id.to_string()],
+    )?;
+    insert_cost_shares(connection, entry)?;
+    insert_tags(connection, entry)?;
+    expense_entry_event_store::record_stored_changes(connection, ledger_id, &[entry.id()])
+}
+
+fn delete_entry(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> Result<(), ApplicationError> {
+    if !trash::soft_delete(connection, "expense_entry", ledger_id, id)? {
+        return Err(expense_entry_not_found());
+    }
+    expense_entry_event_store::record_stored_changes(connection, ledger_id, &[id])
 }
 
 impl ExpenseEntryWritePort for ExpenseEntryWriteSqliteRepository {
-    fn insert(&self, entry: ExpenseEntry) -> Result<ExpenseEntry, ApplicationError> {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        entry: ExpenseEntry,
+    ) -> Result<ExpenseEntry, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        insert_entry(&transaction, ledger_id, &entry)?;
+        transaction.commit()?;
         Ok(entry)
     }
 
-    fn update(&self, id: Uuid, entry: ExpenseEntry) -> Result<ExpenseEntry, ApplicationError> {
-        todo!()
+    fn update(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        entry: ExpenseEntry,
+    ) -> Result<ExpenseEntry, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        update_entry(&transaction, ledger_id, id, &entry)?;
+        transaction.commit()?;
+        Ok(entry)
+    }
+
+    fn apply(&self, ledger_id: Uuid, writes: &[ExpenseEntryWrite]) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        for write in writes {
+            match write {
+                ExpenseEntryWrite::Insert(entry) => insert_entry(&transaction, ledger_id, entry)?,
+                ExpenseEntryWrite::Update(entry) => {
+                    update_entry(&transaction, ledger_id, entry.id(), entry)?
+                }
+                ExpenseEntryWrite::Delete(id) => delete_entry(&transaction, ledger_id, *id)?,
+            }
+        }
+        transaction.commit()?;
+        Ok(())
     }
 
-    fn delete(&self, id: Uuid) -> Result<(), ApplicationError> {
-        match id {
-            id if id == TEST_VALID_UUID => Ok(()),
-            _ => Err(ApplicationError {
+    fn rename_tag(
+        &self,
+        ledger_id: Uuid,
+        tag: &Tag,
+        new_tag: &Tag,
+    ) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let tagged_ids = transaction
+            .prepare(
+                "SELECT expense_entry_id FROM expense_entry_tag WHERE tag = ?1
+                 AND expense_entry_id IN (SELECT id FROM expense_entry WHERE ledger_id = ?2)",
+            )?
+            .query_map(params![tag.as_str(), ledger_id.to_string()], |row| {
+                uuid_column(row, 0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let renamed = transaction.execute(
+            "INSERT OR IGNORE INTO expense_entry_tag (expense_entry_id, tag)
+             SELECT expense_entry_id, ?2 FROM expense_entry_tag WHERE tag = ?1
+             AND expense_entry_id IN (SELECT id FROM expense_entry WHERE ledger_id = ?3)",
+            params![tag.as_str(), new_tag.as_str(), ledger_id.to_string()],
+        )?;
+        let removed = transaction.execute(
+            "DELETE FROM expense_entry_tag WHERE tag = ?1
+             AND expense_entry_id IN (SELECT id FROM expense_entry WHERE ledger_id = ?2)",
+            params![tag.as_str(), ledger_id.to_string()],
+        )?;
+        if renamed == 0 && removed == 0 {
+            return Err(ApplicationError {
                 error_type: ApplicationErrorType::NotFound,
-                message: String::from("Expense entry not found."),
-            }),
+                message: String::from("Tag not found."),
+            });
         }
+        expense_entry_event_store::record_stored_changes(&transaction, ledger_id, &tagged_ids)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        delete_entry(&transaction, ledger_id, id)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if !trash::restore(&transaction, "expense_entry", ledger_id, id)? {
+            return Err(expense_entry_not_found());
+        }
+        expense_entry_event_store::record_stored_changes(&transaction, ledger_id, &[id])?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        // cost shares and tags are removed by the foreign key cascade
+        let purged_ids = trash::purge(&transaction, "expense_entry", ledger_id, deleted_before)?;
+        expense_entry_event_store::record_stored_changes(&transaction, ledger_id, &purged_ids)?;
+        transaction.commit()?;
+        Ok(purged_ids)
     }
 }
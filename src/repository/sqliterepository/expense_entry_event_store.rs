@@ -0,0 +1,442 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, ErrorCode, OptionalExtension, params};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        audit::{AuditAction, AuditedEntity},
+        expense_entry::ExpenseEntry,
+        expense_entry_event::{ExpenseEntryAggregate, ExpenseEntryEvent},
+        outbox::{ChangeAction, ChangedEntity},
+        tag::Tag,
+    },
+    repository::sqliterepository::{
+        audit,
+        database::{SqliteDatabase, json_column, to_json, uuid_column},
+        expense_entry, outbox,
+    },
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        expense_entry::{ExpenseEntryWrite, ExpenseEntryWritePort},
+    },
+};
+
+// write side that appends events per entry instead of overwriting it, the expense entry tables
+// are kept in step within the same transaction
+pub struct ExpenseEntryEventStoreRepository {
+    database: SqliteDatabase,
+}
+
+impl ExpenseEntryEventStoreRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        ExpenseEntryEventStoreRepository { database }
+    }
+}
+
+fn expense_entry_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Expense entry not found."),
+    }
+}
+
+pub(super) fn load_events(
+    connection: &Connection,
+    id: Uuid,
+) -> rusqlite::Result<Vec<ExpenseEntryEvent>> {
+    let mut statement = connection.prepare_cached(
+        "SELECT payload FROM expense_entry_event WHERE stream_id = ?1 ORDER BY version",
+    )?;
+    statement
+        .query_map(params![id.to_string()], |row| json_column(row, 0))?
+        .collect()
+}
+
+// the ledger the entry of the stream is kept in, nothing if the stream has no events yet
+pub(super) fn stream_ledger(connection: &Connection, id: Uuid) -> rusqlite::Result<Option<Uuid>> {
+    connection
+        .query_row(
+            "SELECT ledger_id FROM expense_entry_event WHERE stream_id = ?1 LIMIT 1",
+            params![id.to_string()],
+            |row| uuid_column(row, 0),
+        )
+        .optional()
+}
+
+// whether the stream exists in another ledger, such entries are treated as missing
+fn is_foreign(connection: &Connection, ledger_id: Uuid, id: Uuid) -> rusqlite::Result<bool> {
+    Ok(stream_ledger(connection, id)?.is_some_and(|stream_ledger| stream_ledger != ledger_id))
+}
+
+// appends the events after the version the aggregate was loaded at, another writer having
+// appended in between makes the versions collide
+fn append_events(
+    connection: &Connection,
+    ledger_id: Uuid,
+    aggregate: &mut ExpenseEntryAggregate,
+    id: Uuid,
+    events: Vec<ExpenseEntryEvent>,
+) -> Result<(), ApplicationError> {
+    let mut statement = connection.prepare_cached(
+        "INSERT INTO expense_entry_event
+             (stream_id, ledger_id, version, event_type, payload, recorded_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for event in events {
+        let appended = statement.execute(params![
+            id.to_string(),
+            ledger_id.to_string(),
+            (aggregate.version() + 1) as i64,
+            event.name(),
+            to_json(&event)?,
+            Utc::now(),
+        ]);
+        match appended {
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if err.code == ErrorCode::ConstraintViolation =>
+            {
+                return Err(ApplicationError {
+                    error_type: ApplicationErrorType::Conflict,
+                    message: String::from("Expense entry was changed concurrently."),
+                });
+            }
+            appended => appended?,
+        };
+        aggregate.apply(&event);
+    }
+    Ok(())
+}
+
+// what the events amount to for the outbox, field changes are all just an update
+fn change_action(events: &[ExpenseEntryEvent]) -> ChangeAction {
+    let mut action = ChangeAction::Updated;
+    for event in events {
+        action = match event {
+            ExpenseEntryEvent::ExpenseEntryCreated { .. } => ChangeAction::Created,
+            ExpenseEntryEvent::ExpenseEntryDeleted { .. } => ChangeAction::Deleted,
+            ExpenseEntryEvent::ExpenseEntryRestored => ChangeAction::Restored,
+            ExpenseEntryEvent::ExpenseEntryPurged => ChangeAction::Purged,
+            _ => action,
+        };
+    }
+    action
+}
+
+// appends the events and publishes the resulting change of the entry through the outbox and the
+// audit trail
+fn append(
+    connection: &Connection,
+    ledger_id: Uuid,
+    aggregate: &mut ExpenseEntryAggregate,
+    id: Uuid,
+    events: Vec<ExpenseEntryEvent>,
+) -> Result<(), ApplicationError> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    let action = change_action(&events);
+    let before = aggregate.entry();
+    append_events(connection, ledger_id, aggregate, id, events)?;
+    let after = aggregate.entry();
+    outbox::enqueue(
+        connection,
+        ledger_id,
+        ChangedEntity::ExpenseEntry,
+        id,
+        action,
+        after.as_ref(),
+    )?;
+    // the audit trail keeps the entry on the sides of the change where it was in use
+    let (audit_action, before, after) = match action {
+        ChangeAction::Created => (AuditAction::Created, None, after),
+        ChangeAction::Updated => (AuditAction::Updated, before, after),
+        ChangeAction::Deleted => (AuditAction::Deleted, before, None),
+        ChangeAction::Restored => (AuditAction::Restored, None, after),
+        ChangeAction::Purged => (AuditAction::Purged, None, None),
+    };
+    audit::record(
+        connection,
+        ledger_id,
+        AuditedEntity::ExpenseEntry,
+        id,
+        audit_action,
+        before.as_ref(),
+        after.as_ref(),
+    )?;
+    Ok(())
+}
+
+// rebuilds the entry from its events, entries stored before the event store took over get a
+// stream that starts from their stored state
+fn load(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> Result<ExpenseEntryAggregate, ApplicationError> {
+    if is_foreign(connection, ledger_id, id)? {
+        return Err(expense_entry_not_found());
+    }
+    let events = load_events(connection, id)?;
+    let mut aggregate = ExpenseEntryAggregate::replay(id, &events);
+    if events.is_empty()
+        && let Some((entry, deleted_at)) = expense_entry::load_stored(connection, ledger_id, id)?
+    {
+        let mut adopted = vec![ExpenseEntryAggregate::created(&entry)];
+        if let Some(deleted_at) = deleted_at {
+            adopted.push(ExpenseEntryEvent::ExpenseEntryDeleted { deleted_at });
+        }
+        // adopting changes nothing, there is nothing to publish
+        append_events(connection, ledger_id, &mut aggregate, id, adopted)?;
+    }
+    Ok(aggregate)
+}
+
+// brings the expense entry tables in step with the aggregate
+fn project(
+    connection: &Connection,
+    ledger_id: Uuid,
+    aggregate: &ExpenseEntryAggregate,
+) -> rusqlite::Result<()> {
+    match aggregate.entry() {
+        Some(entry) => expense_entry::store(connection, ledger_id, &entry, aggregate.deleted_at()),
+        None => Ok(()),
+    }
+}
+
+// appends whatever changed between the streams and the stored entries, used wherever entries
+// are written to their tables directly so the streams stay complete for the projections
+pub(in crate::repository) fn record_stored_changes(
+    connection: &Connection,
+    ledger_id: Uuid,
+    ids: &[Uuid],
+) -> Result<(), ApplicationError> {
+    for &id in ids {
+        if is_foreign(connection, ledger_id, id)? {
+            continue;
+        }
+        let mut aggregate = ExpenseEntryAggregate::replay(id, &load_events(connection, id)?);
+        let changes = match expense_entry::load_stored(connection, ledger_id, id)? {
+            Some((entry, deleted_at)) => {
+                let mut changes = aggregate.changes(&entry);
+                match (aggregate.deleted_at(), deleted_at) {
+                    (None, Some(deleted_at)) => {
+                        changes.push(ExpenseEntryEvent::ExpenseEntryDeleted { deleted_at })
+                    }
+                    (Some(_), None) => changes.push(ExpenseEntryEvent::ExpenseEntryRestored),
+                    _ => {}
+                }
+                changes
+            }
+            None if aggregate.exists() => vec![ExpenseEntryEvent::ExpenseEntryPurged],
+            None => Vec::new(),
+        };
+        append(connection, ledger_id, &mut aggregate, id, changes)?;
+    }
+    Ok(())
+}
+
+fn insert_entry(
+    connection: &Connection,
+    ledger_id: Uuid,
+    entry: &ExpenseEntry,
+) -> Result<(), ApplicationError> {
+    expense_entry::verify_references(connection, ledger_id, entry)?;
+    let mut aggregate = load(connection, ledger_id, entry.id())?;
+    if aggregate.exists() {
+        return Err(ApplicationError {
+            error_type: ApplicationErrorType::Conflict,
+            message: String::from("Expense entry already exists."),
+        });
+    }
+    append(
+        connection,
+        ledger_id,
+        &mut aggregate,
+        entry.id(),
+        vec![ExpenseEntryAggregate::created(entry)],
+    )?;
+    project(connection, ledger_id, &aggregate)?;
+    Ok(())
+}
+
+fn update_entry(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+    entry: &ExpenseEntry,
+) -> Result<(), ApplicationError> {
+    expense_entry::verify_references(connection, ledger_id, entry)?;
+    let mut aggregate = load(connection, ledger_id, id)?;
+    if !aggregate.is_active() {
+        return Err(expense_entry_not_found());
+    }
+    let changes = aggregate.changes(entry);
+    append(connection, ledger_id, &mut aggregate, id, changes)?;
+    project(connection, ledger_id, &aggregate)?;
+    Ok(())
+}
+
+fn delete_entry(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> Result<(), ApplicationError> {
+    let mut aggregate = load(connection, ledger_id, id)?;
+    if !aggregate.is_active() {
+        return Err(expense_entry_not_found());
+    }
+    let deleted = ExpenseEntryEvent::ExpenseEntryDeleted {
+        deleted_at: Utc::now(),
+    };
+    append(connection, ledger_id, &mut aggregate, id, vec![deleted])?;
+    project(connection, ledger_id, &aggregate)?;
+    Ok(())
+}
+
+impl ExpenseEntryWritePort for ExpenseEntryEventStoreRepository {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        entry: ExpenseEntry,
+    ) -> Result<ExpenseEntry, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        insert_entry(&transaction, ledger_id, &entry)?;
+        transaction.commit()?;
+        Ok(entry)
+    }
+
+    fn update(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        entry: ExpenseEntry,
+    ) -> Result<ExpenseEntry, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        update_entry(&transaction, ledger_id, id, &entry)?;
+        transaction.commit()?;
+        Ok(entry)
+    }
+
+    fn apply(&self, ledger_id: Uuid, writes: &[ExpenseEntryWrite]) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        for write in writes {
+            match write {
+                ExpenseEntryWrite::Insert(entry) => insert_entry(&transaction, ledger_id, entry)?,
+                ExpenseEntryWrite::Update(entry) => {
+                    update_entry(&transaction, ledger_id, entry.id(), entry)?
+                }
+                ExpenseEntryWrite::Delete(id) => delete_entry(&transaction, ledger_id, *id)?,
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        delete_entry(&transaction, ledger_id, id)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let mut aggregate = load(&transaction, ledger_id, id)?;
+        if aggregate.deleted_at().is_none() {
+            return Err(expense_entry_not_found());
+        }
+        let restored = ExpenseEntryEvent::ExpenseEntryRestored;
+        append(&transaction, ledger_id, &mut aggregate, id, vec![restored])?;
+        project(&transaction, ledger_id, &aggregate)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        // the stored entries only serve as index of the trash, the events decide
+        let candidate_ids = transaction
+            .prepare(
+                "SELECT id FROM expense_entry WHERE ledger_id = ?1 AND deleted_at IS NOT NULL",
+            )?
+            .query_map(params![ledger_id.to_string()], |row| uuid_column(row, 0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut purged_ids = Vec::new();
+        for id in candidate_ids {
+            let mut aggregate = load(&transaction, ledger_id, id)?;
+            if aggregate
+                .deleted_at()
+                .is_none_or(|deleted_at| deleted_at >= deleted_before)
+            {
+                continue;
+            }
+            let purged = ExpenseEntryEvent::ExpenseEntryPurged;
+            append(&transaction, ledger_id, &mut aggregate, id, vec![purged])?;
+            // cost shares and tags are removed by the foreign key cascade
+            transaction.execute(
+                "DELETE FROM expense_entry WHERE id = ?1",
+                params![id.to_string()],
+            )?;
+            purged_ids.push(id);
+        }
+        transaction.commit()?;
+        Ok(purged_ids)
+    }
+
+    fn rename_tag(
+        &self,
+        ledger_id: Uuid,
+        tag: &Tag,
+        new_tag: &Tag,
+    ) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let tagged_ids = transaction
+            .prepare(
+                "SELECT expense_entry_id FROM expense_entry_tag WHERE tag = ?1
+                 AND expense_entry_id IN (SELECT id FROM expense_entry WHERE ledger_id = ?2)",
+            )?
+            .query_map(params![tag.as_str(), ledger_id.to_string()], |row| {
+                uuid_column(row, 0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        if tagged_ids.is_empty() {
+            return Err(ApplicationError {
+                error_type: ApplicationErrorType::NotFound,
+                message: String::from("Tag not found."),
+            });
+        }
+        for id in tagged_ids {
+            let mut aggregate = load(&transaction, ledger_id, id)?;
+            let Some(entry) = aggregate.entry() else {
+                continue;
+            };
+            let mut tags = entry
+                .tags()
+                .iter()
+                .filter(|&entry_tag| entry_tag != tag)
+                .cloned()
+                .chain([new_tag.clone()])
+                .collect::<Vec<_>>();
+            tags.sort();
+            tags.dedup();
+            let renamed = ExpenseEntryEvent::TagsChanged {
+                tags: tags.iter().map(|tag| tag.as_str().to_string()).collect(),
+            };
+            append(&transaction, ledger_id, &mut aggregate, id, vec![renamed])?;
+            project(&transaction, ledger_id, &aggregate)?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,452 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        audit::{AuditAction, AuditedEntity},
+        expense_type::{ExpenseType, ExpenseTypeHierarchy},
+        outbox::{ChangeAction, ChangedEntity},
+        trash::{TrashItem, TrashedEntity},
+    },
+    repository::sqliterepository::{
+        audit,
+        database::{SqliteDatabase, optional_uuid_column, uuid_column},
+        expense_entry_event_store, outbox, projection, trash,
+    },
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        expense_type::{ExpenseTypeReadPort, ExpenseTypeWritePort},
+    },
+};
+
+pub struct ExpenseTypeReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct ExpenseTypeWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl ExpenseTypeReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        ExpenseTypeReadSqliteRepository { database }
+    }
+}
+
+impl ExpenseTypeWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        ExpenseTypeWriteSqliteRepository { database }
+    }
+}
+
+fn expense_type_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Expense type not found."),
+    }
+}
+
+const EXPENSE_TYPE_COLUMNS: &str = "id, name, description, parent_id";
+
+fn expense_type_from_row(row: &Row) -> rusqlite::Result<ExpenseType> {
+    Ok(ExpenseType::restore(
+        uuid_column(row, 0)?,
+        row.get(1)?,
+        row.get(2)?,
+        optional_uuid_column(row, 3)?,
+    ))
+}
+
+// the stored expense type of the ledger, also while it is in the trash
+fn load_stored(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<Option<ExpenseType>> {
+    connection
+        .query_row(
+            &format!(
+                "SELECT {EXPENSE_TYPE_COLUMNS} FROM expense_type WHERE id = ?1 AND ledger_id = ?2"
+            ),
+            params![id.to_string(), ledger_id.to_string()],
+            expense_type_from_row,
+        )
+        .optional()
+}
+
+fn load_active(connection: &Connection, ledger_id: Uuid) -> rusqlite::Result<Vec<ExpenseType>> {
+    let mut statement = connection.prepare(&format!(
+        "SELECT {EXPENSE_TYPE_COLUMNS} FROM expense_type
+         WHERE ledger_id = ?1 AND deleted_at IS NULL ORDER BY name"
+    ))?;
+    statement
+        .query_map(params![ledger_id.to_string()], expense_type_from_row)?
+        .collect()
+}
+
+// checked against the hierarchy as it is inside the writing transaction, so concurrent
+// reparents cannot build a cycle between them
+fn validate_in_hierarchy(
+    connection: &Connection,
+    ledger_id: Uuid,
+    expense_type: &ExpenseType,
+) -> Result<(), ApplicationError> {
+    ExpenseTypeHierarchy::new(load_active(connection, ledger_id)?).validate(expense_type)?;
+    Ok(())
+}
+
+impl ExpenseTypeReadPort for ExpenseTypeReadSqliteRepository {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseType, ApplicationError> {
+        self.database
+            .connection()
+            .query_row(
+                &format!(
+                    "SELECT {EXPENSE_TYPE_COLUMNS} FROM expense_type
+                     WHERE id = ?1 AND ledger_id = ?2 AND deleted_at IS NULL"
+                ),
+                params![id.to_string(), ledger_id.to_string()],
+                expense_type_from_row,
+            )
+            .optional()?
+            .ok_or_else(expense_type_not_found)
+    }
+
+    fn get_deleted(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseType, ApplicationError> {
+        self.database
+            .connection()
+            .query_row(
+                &format!(
+                    "SELECT {EXPENSE_TYPE_COLUMNS} FROM expense_type
+                     WHERE id = ?1 AND ledger_id = ?2 AND deleted_at IS NOT NULL"
+                ),
+                params![id.to_string(), ledger_id.to_string()],
+                expense_type_from_row,
+            )
+            .optional()?
+            .ok_or_else(expense_type_not_found)
+    }
+
+    fn list(&self, ledger_id: Uuid) -> Result<Vec<ExpenseType>, ApplicationError> {
+        Ok(load_active(&self.database.connection(), ledger_id)?)
+    }
+
+    fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(
+            "SELECT id, name, deleted_at FROM expense_type
+             WHERE ledger_id = ?1 AND deleted_at IS NOT NULL ORDER BY deleted_at",
+        )?;
+        let items = statement
+            .query_map(params![ledger_id.to_string()], |row| {
+                Ok(TrashItem {
+                    entity: TrashedEntity::ExpenseType,
+                    id: uuid_column(row, 0)?,
+                    label: row.get(1)?,
+                    deleted_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+}
+
+// an expense type that is still used can only be deleted by reassigning what uses it, counted in
+// the deleting transaction so nothing can start using it in between
+fn verify_unused(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> Result<(), ApplicationError> {
+    let expense_entries = connection.query_row(
+        "SELECT COUNT(*) FROM expense_entry
+         WHERE ledger_id = ?1 AND expense_type = ?2 AND deleted_at IS NULL",
+        params![ledger_id.to_string(), id.to_string()],
+        |row| row.get::<_, i64>(0),
+    )?;
+    if expense_entries > 0 {
+        return Err(ApplicationError {
+            error_type: ApplicationErrorType::Conflict,
+            message: format!(
+                "Expense type is still used by {expense_entries} expense {}.",
+                if expense_entries == 1 {
+                    "entry"
+                } else {
+                    "entries"
+                }
+            ),
+        });
+    }
+    let references = connection.query_row(
+        "SELECT
+             (SELECT COUNT(*) FROM budget
+              WHERE ledger_id = ?1 AND expense_type = ?2 AND deleted_at IS NULL)
+           + (SELECT COUNT(*) FROM recurring_expense
+              WHERE ledger_id = ?1 AND deleted_at IS NULL AND (expense_type = ?2 OR id IN (
+                  SELECT recurring_expense_id FROM recurring_expense_occurrence
+                  WHERE override_expense_type = ?2 AND status != 'booked')))
+           + (SELECT COUNT(*) FROM categorisation_rule
+              WHERE ledger_id = ?1 AND set_expense_type = ?2)",
+        params![ledger_id.to_string(), id.to_string()],
+        |row| row.get::<_, i64>(0),
+    )?;
+    if references > 0 {
+        return Err(ApplicationError {
+            error_type: ApplicationErrorType::Conflict,
+            message: format!(
+                "Expense type is still used by {references} budgets, recurring expenses or \
+                 categorisation rules."
+            ),
+        });
+    }
+    Ok(())
+}
+
+// points everything else that uses the expense type at the target, the entries are moved by
+// the caller
+fn reassign_references(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+    target_id: Uuid,
+) -> rusqlite::Result<()> {
+    let params = params![ledger_id.to_string(), id.to_string(), target_id.to_string()];
+    connection.execute(
+        "UPDATE budget SET expense_type = ?3 WHERE ledger_id = ?1 AND expense_type = ?2",
+        params,
+    )?;
+    connection.execute(
+        "UPDATE recurring_expense SET expense_type = ?3 WHERE ledger_id = ?1 AND expense_type = ?2",
+        params,
+    )?;
+    connection.execute(
+        "UPDATE recurring_expense_occurrence SET override_expense_type = ?3
+         WHERE override_expense_type = ?2 AND recurring_expense_id IN (
+             SELECT id FROM recurring_expense WHERE ledger_id = ?1)",
+        params,
+    )?;
+    connection.execute(
+        "UPDATE categorisation_rule SET set_expense_type = ?3
+         WHERE ledger_id = ?1 AND set_expense_type = ?2",
+        params,
+    )?;
+    Ok(())
+}
+
+impl ExpenseTypeWritePort for ExpenseTypeWriteSqliteRepository {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        expense_type: ExpenseType,
+    ) -> Result<ExpenseType, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        validate_in_hierarchy(&transaction, ledger_id, &expense_type)?;
+        transaction.execute(
+            &format!(
+                "INSERT INTO expense_type ({EXPENSE_TYPE_COLUMNS}, ledger_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5)"
+            ),
+            params![
+                expense_type.id().to_string(),
+                expense_type.name(),
+                expense_type.description(),
+                expense_type.parent_id().map(|id| id.to_string()),
+                ledger_id.to_string(),
+            ],
+        )?;
+        outbox::enqueue(
+            &transaction,
+            ledger_id,
+            ChangedEntity::ExpenseType,
+            expense_type.id(),
+            ChangeAction::Created,
+            Some(&expense_type),
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::ExpenseType,
+            expense_type.id(),
+            AuditAction::Created,
+            None::<&ExpenseType>,
+            Some(&expense_type),
+        )?;
+        transaction.commit()?;
+        Ok(expense_type)
+    }
+
+    fn update(
+        &self,
+        ledger_id: Uuid,
+        expense_type: ExpenseType,
+    ) -> Result<ExpenseType, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        validate_in_hierarchy(&transaction, ledger_id, &expense_type)?;
+        let existing = load_stored(&transaction, ledger_id, expense_type.id())?;
+        let updated = transaction.execute(
+            "UPDATE expense_type SET name = ?2, description = ?3, parent_id = ?4
+             WHERE id = ?1 AND ledger_id = ?5 AND deleted_at IS NULL",
+            params![
+                expense_type.id().to_string(),
+                expense_type.name(),
+                expense_type.description(),
+                expense_type.parent_id().map(|id| id.to_string()),
+                ledger_id.to_string(),
+            ],
+        )?;
+        if updated == 0 {
+            return Err(expense_type_not_found());
+        }
+        projection::rename_expense_type(&transaction, expense_type.id(), expense_type.name())?;
+        outbox::enqueue(
+            &transaction,
+            ledger_id,
+            ChangedEntity::ExpenseType,
+            expense_type.id(),
+            ChangeAction::Updated,
+            Some(&expense_type),
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::ExpenseType,
+            expense_type.id(),
+            AuditAction::Updated,
+            existing.as_ref(),
+            Some(&expense_type),
+        )?;
+        transaction.commit()?;
+        Ok(expense_type)
+    }
+
+    fn delete(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        reassign_to: Option<Uuid>,
+    ) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if let Some(target_id) = reassign_to {
+            let reassigned_ids = transaction
+                .prepare("SELECT id FROM expense_entry WHERE expense_type = ?1")?
+                .query_map(params![id.to_string()], |row| uuid_column(row, 0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            transaction.execute(
+                "UPDATE expense_entry SET expense_type = ?2 WHERE expense_type = ?1",
+                params![id.to_string(), target_id.to_string()],
+            )?;
+            expense_entry_event_store::record_stored_changes(
+                &transaction,
+                ledger_id,
+                &reassigned_ids,
+            )?;
+            reassign_references(&transaction, ledger_id, id, target_id)?;
+        } else {
+            verify_unused(&transaction, ledger_id, id)?;
+        }
+        if !trash::soft_delete(&transaction, "expense_type", ledger_id, id)? {
+            return Err(expense_type_not_found());
+        }
+        let deleted = load_stored(&transaction, ledger_id, id)?;
+        outbox::enqueue(
+            &transaction,
+            ledger_id,
+            ChangedEntity::ExpenseType,
+            id,
+            ChangeAction::Deleted,
+            deleted.as_ref(),
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::ExpenseType,
+            id,
+            AuditAction::Deleted,
+            deleted.as_ref(),
+            None::<&ExpenseType>,
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if !trash::restore(&transaction, "expense_type", ledger_id, id)? {
+            return Err(expense_type_not_found());
+        }
+        let restored = load_stored(&transaction, ledger_id, id)?;
+        outbox::enqueue(
+            &transaction,
+            ledger_id,
+            ChangedEntity::ExpenseType,
+            id,
+            ChangeAction::Restored,
+            restored.as_ref(),
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::ExpenseType,
+            id,
+            AuditAction::Restored,
+            None::<&ExpenseType>,
+            restored.as_ref(),
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        // parents of sub types and expense types anything still points at, even from the
+        // trash, are kept, a later purge removes them
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let mut statement = transaction.prepare(
+            "DELETE FROM expense_type
+             WHERE ledger_id = ?1 AND deleted_at IS NOT NULL AND deleted_at < ?2
+             AND id NOT IN (SELECT parent_id FROM expense_type WHERE parent_id IS NOT NULL)
+             AND id NOT IN (SELECT expense_type FROM expense_entry)
+             AND id NOT IN (SELECT expense_type FROM budget)
+             AND id NOT IN (SELECT expense_type FROM recurring_expense)
+             AND id NOT IN (SELECT override_expense_type FROM recurring_expense_occurrence
+                            WHERE override_expense_type IS NOT NULL)
+             AND id NOT IN (SELECT set_expense_type FROM categorisation_rule
+                            WHERE set_expense_type IS NOT NULL)
+             RETURNING id",
+        )?;
+        let purged_ids = statement
+            .query_map(params![ledger_id.to_string(), deleted_before], |row| {
+                uuid_column(row, 0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(statement);
+        for &id in &purged_ids {
+            outbox::enqueue(
+                &transaction,
+                ledger_id,
+                ChangedEntity::ExpenseType,
+                id,
+                ChangeAction::Purged,
+                None::<&ExpenseType>,
+            )?;
+            audit::record(
+                &transaction,
+                ledger_id,
+                AuditedEntity::ExpenseType,
+                id,
+                AuditAction::Purged,
+                None::<&ExpenseType>,
+                None::<&ExpenseType>,
+            )?;
+        }
+        transaction.commit()?;
+        Ok(purged_ids)
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Row, params};
+
+use crate::{
+    domain::idempotency::{IdempotencyRecord, IdempotentResponse},
+    repository::sqliterepository::database::SqliteDatabase,
+    service::{
+        application_error::ApplicationError,
+        idempotency::{IdempotencyReadPort, IdempotencyWritePort},
+    },
+};
+
+pub struct IdempotencyReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct IdempotencyWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl IdempotencyReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        IdempotencyReadSqliteRepository { database }
+    }
+}
+
+impl IdempotencyWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        IdempotencyWriteSqliteRepository { database }
+    }
+}
+
+const IDEMPOTENCY_COLUMNS: &str =
+    "scope, idempotency_key, fingerprint, status_code, content_type, body, created_at";
+
+fn record_from_row(row: &Row) -> rusqlite::Result<IdempotencyRecord> {
+    // the status is only set once the response is stored
+    let response = match row.get::<_, Option<u16>>(3)? {
+        Some(status_code) => Some(IdempotentResponse {
+            status_code,
+            content_type: row.get(4)?,
+            body: row.get(5)?,
+        }),
+        None => None,
+    };
+    Ok(IdempotencyRecord::restore(
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        response,
+        row.get(6)?,
+    ))
+}
+
+impl IdempotencyReadPort for IdempotencyReadSqliteRepository {
+    fn get(
+        &self,
+        scope: &str,
+        key: &str,
+        expired_before: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, ApplicationError> {
+        let record = self
+            .database
+            .connection()
+            .query_row(
+                &format!(
+                    "SELECT {IDEMPOTENCY_COLUMNS} FROM idempotency_key
+                     WHERE scope = ?1 AND idempotency_key = ?2 AND created_at >= ?3"
+                ),
+                params![scope, key, expired_before],
+                record_from_row,
+            )
+            .optional()?;
+        Ok(record)
+    }
+}
+
+impl IdempotencyWritePort for IdempotencyWriteSqliteRepository {
+    fn reserve(
+        &self,
+        record: &IdempotencyRecord,
+        expired_before: DateTime<Utc>,
+    ) -> Result<bool, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "DELETE FROM idempotency_key WHERE created_at < ?1",
+            params![expired_before],
+        )?;
+        let inserted = transaction.execute(
+            "INSERT OR IGNORE INTO idempotency_key (scope, idempotency_key, fingerprint, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                record.scope(),
+                record.key(),
+                record.fingerprint(),
+                record.created_at()
+            ],
+        )?;
+        transaction.commit()?;
+        Ok(inserted == 1)
+    }
+
+    fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        response: &IdempotentResponse,
+    ) -> Result<(), ApplicationError> {
+        self.database.connection().execute(
+            "UPDATE idempotency_key SET status_code = ?3, content_type = ?4, body = ?5
+             WHERE scope = ?1 AND idempotency_key = ?2",
+            params![
+                scope,
+                key,
+                response.status_code,
+                response.content_type,
+                response.body
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn release(&self, scope: &str, key: &str) -> Result<(), ApplicationError> {
+        self.database.connection().execute(
+            "DELETE FROM idempotency_key WHERE scope = ?1 AND idempotency_key = ?2",
+            params![scope, key],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::HashSet;
+
+use rusqlite::{Connection, OptionalExtension, Row, params, params_from_iter};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        expense_entry::ExpenseEntryValidationError,
+        ledger::Ledger,
+        ledger_member::{LedgerMember, LedgerRole},
+    },
+    repository::sqliterepository::database::{SqliteDatabase, uuid_column},
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        ledger::{LedgerReadPort, LedgerWritePort},
+    },
+};
+
+pub struct LedgerReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct LedgerWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl LedgerReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        LedgerReadSqliteRepository { database }
+    }
+}
+
+impl LedgerWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        LedgerWriteSqliteRepository { database }
+    }
+}
+
+fn ledger_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Ledger not found."),
+    }
+}
+
+const LEDGER_COLUMNS: &str = "id, name, created_at";
+
+fn ledger_from_row(row: &Row) -> rusqlite::Result<Ledger> {
+    Ok(Ledger::restore(
+        uuid_column(row, 0)?,
+        row.get(1)?,
+        row.get(2)?,
+    ))
+}
+
+fn role_to_sql(role: LedgerRole) -> &'static str {
+    match role {
+        LedgerRole::Viewer => "viewer",
+        LedgerRole::Editor => "editor",
+        LedgerRole::Owner => "owner",
+    }
+}
+
+fn role_from_sql(role: &str) -> LedgerRole {
+    match role {
+        "owner" => LedgerRole::Owner,
+        "editor" => LedgerRole::Editor,
+        _ => LedgerRole::Viewer,
+    }
+}
+
+const MEMBER_COLUMNS: &str = "ledger_id, user, role, added_at";
+
+fn member_from_row(row: &Row) -> rusqlite::Result<LedgerMember> {
+    Ok(LedgerMember::restore(
+        uuid_column(row, 0)?,
+        row.get(1)?,
+        role_from_sql(&row.get::<_, String>(2)?),
+        row.get(3)?,
+    ))
+}
+
+// the first of the given ids that is no entity of the table in the ledger outside the trash
+fn first_unknown(
+    connection: &Connection,
+    table: &str,
+    ledger_id: Uuid,
+    ids: &[Uuid],
+) -> rusqlite::Result<Option<Uuid>> {
+    if ids.is_empty() {
+        return Ok(None);
+    }
+    let placeholders = (2..ids.len() + 2)
+        .map(|index| format!("?{index}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = std::iter::once(ledger_id)
+        .chain(ids.iter().copied())
+        .map(|id| id.to_string());
+    let known = connection
+        .prepare(&format!(
+            "SELECT id FROM {table}
+             WHERE ledger_id = ?1 AND deleted_at IS NULL AND id IN ({placeholders})"
+        ))?
+        .query_map(params_from_iter(values), |row| uuid_column(row, 0))?
+        .collect::<Result<HashSet<_>, _>>()?;
+    Ok(ids.iter().copied().find(|id| !known.contains(id)))
+}
+
+fn kept_in_other_ledger(
+    connection: &Connection,
+    table: &str,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<bool> {
+    connection.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM {table} WHERE id = ?1 AND ledger_id != ?2)"),
+        params![id.to_string(), ledger_id.to_string()],
+        |row| row.get(0),
+    )
+}
+
+fn foreign_reference() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::ValidationFailed,
+        message: String::from("Json references data of another ledger."),
+    }
+}
+
+// data of one ledger may only point at the ledger's own cost bearers and expense types that are
+// not in the trash, checked in the transaction that writes it
+pub(in crate::repository) fn verify_references(
+    connection: &Connection,
+    ledger_id: Uuid,
+    cost_bearer_ids: &[Uuid],
+    expense_type_ids: &[Uuid],
+) -> Result<(), ApplicationError> {
+    if let Some(id) = first_unknown(connection, "cost_bearer", ledger_id, cost_bearer_ids)? {
+        if kept_in_other_ledger(connection, "cost_bearer", ledger_id, id)? {
+            return Err(foreign_reference());
+        }
+        return Err(ExpenseEntryValidationError::InvalidCostBearerId(id).into());
+    }
+    if let Some(id) = first_unknown(connection, "expense_type", ledger_id, expense_type_ids)? {
+        if kept_in_other_ledger(connection, "expense_type", ledger_id, id)? {
+            return Err(foreign_reference());
+        }
+        return Err(ExpenseEntryValidationError::InvalidExpenseTypeId(id).into());
+    }
+    Ok(())
+}
+
+impl LedgerReadPort for LedgerReadSqliteRepository {
+    fn get(&self, id: Uuid) -> Result<Ledger, ApplicationError> {
+        self.database
+            .connection()
+            .query_row(
+                &format!("SELECT {LEDGER_COLUMNS} FROM ledger WHERE id = ?1"),
+                params![id.to_string()],
+                ledger_from_row,
+            )
+            .optional()?
+            .ok_or_else(ledger_not_found)
+    }
+
+    fn list(&self) -> Result<Vec<Ledger>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {LEDGER_COLUMNS} FROM ledger ORDER BY created_at, id"
+        ))?;
+        let ledgers = statement
+            .query_map([], ledger_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ledgers)
+    }
+
+    fn list_of_member(&self, user: &str) -> Result<Vec<Ledger>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(
+            "SELECT ledger.id, ledger.name, ledger.created_at FROM ledger \
+             JOIN ledger_member ON ledger_member.ledger_id = ledger.id \
+             WHERE ledger_member.user = ?1 ORDER BY ledger.created_at, ledger.id",
+        )?;
+        let ledgers = statement
+            .query_map(params![user], ledger_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ledgers)
+    }
+
+    fn members(&self, ledger_id: Uuid) -> Result<Vec<LedgerMember>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {MEMBER_COLUMNS} FROM ledger_member WHERE ledger_id = ?1 ORDER BY added_at, user"
+        ))?;
+        let members = statement
+            .query_map(params![ledger_id.to_string()], member_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(members)
+    }
+
+    fn role(&self, ledger_id: Uuid, user: &str) -> Result<Option<LedgerRole>, ApplicationError> {
+        let role = self
+            .database
+            .connection()
+            .query_row(
+                "SELECT role FROM ledger_member WHERE ledger_id = ?1 AND user = ?2",
+                params![ledger_id.to_string(), user],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        Ok(role.as_deref().map(role_from_sql))
+    }
+
+    fn cost_bearers_hidden_from(
+        &self,
+        ledger_id: Uuid,
+        user: &str,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        let connection = self.database.connection();
+        // deleted cost bearers keep hiding the entries they bore
+        let mut statement = connection.prepare(
+            "SELECT id FROM cost_bearer WHERE ledger_id = ?1 AND private_to != '[]'
+             AND NOT EXISTS (SELECT 1 FROM json_each(cost_bearer.private_to) WHERE value = ?2)",
+        )?;
+        let ids = statement
+            .query_map(params![ledger_id.to_string(), user], |row| {
+                uuid_column(row, 0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
+    fn verify_references(
+        &self,
+        ledger_id: Uuid,
+        cost_bearer_ids: &[Uuid],
+        expense_type_ids: &[Uuid],
+    ) -> Result<(), ApplicationError> {
+        verify_references(
+            &self.database.connection(),
+            ledger_id,
+            cost_bearer_ids,
+            expense_type_ids,
+        )
+    }
+}
+
+impl LedgerWritePort for LedgerWriteSqliteRepository {
+    fn insert(&self, ledger: Ledger) -> Result<Ledger, ApplicationError> {
+        self.database.connection().execute(
+            &format!("INSERT INTO ledger ({LEDGER_COLUMNS}) VALUES (?1, ?2, ?3)"),
+            params![ledger.id().to_string(), ledger.name(), ledger.created_at()],
+        )?;
+        Ok(ledger)
+    }
+
+    fn set_member(&self, member: LedgerMember) -> Result<LedgerMember, ApplicationError> {
+        let connection = self.database.connection();
+        // a member keeps the time it was first added when its role changes
+        connection.execute(
+            &format!(
+                "INSERT INTO ledger_member ({MEMBER_COLUMNS}) VALUES (?1, ?2, ?3, ?4) \
+                 ON CONFLICT (ledger_id, user) DO UPDATE SET role = excluded.role"
+            ),
+            params![
+                member.ledger_id().to_string(),
+                member.user(),
+                role_to_sql(member.role()),
+                member.added_at()
+            ],
+        )?;
+        let stored = connection.query_row(
+            &format!(
+                "SELECT {MEMBER_COLUMNS} FROM ledger_member WHERE ledger_id = ?1 AND user = ?2"
+            ),
+            params![member.ledger_id().to_string(), member.user()],
+            member_from_row,
+        )?;
+        Ok(stored)
+    }
+
+    fn remove_member(&self, ledger_id: Uuid, user: &str) -> Result<(), ApplicationError> {
+        let removed = self.database.connection().execute(
+            "DELETE FROM ledger_member WHERE ledger_id = ?1 AND user = ?2",
+            params![ledger_id.to_string(), user],
+        )?;
+        match removed {
+            0 => Err(ApplicationError {
+                error_type: ApplicationErrorType::NotFound,
+                message: String::from("Ledger member not found."),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
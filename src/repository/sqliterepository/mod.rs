@@ -1 +1,17 @@
+pub mod api_key;
+pub mod attachment;
+pub mod audit;
+pub mod budget;
+pub mod categorisation_rule;
+pub mod cost_bearer;
+pub mod database;
 pub mod expense_entry;
+pub mod expense_entry_event_store;
+pub mod expense_type;
+pub mod idempotency;
+pub mod ledger;
+pub mod outbox;
+pub mod projection;
+pub mod recurring_expense;
+pub mod trash;
+pub mod webhook;
@@ -0,0 +1,208 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, Row, ToSql, params};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::outbox::{ChangeAction, ChangeEvent, ChangedEntity, DeadLetter, OutboxDelivery},
+    repository::sqliterepository::database::{SqliteDatabase, json_column, to_json, uuid_column},
+    service::{
+        application_error::ApplicationError,
+        outbox::{OutboxReadPort, OutboxWritePort},
+    },
+};
+
+pub struct OutboxReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct OutboxWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl OutboxReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        OutboxReadSqliteRepository { database }
+    }
+}
+
+impl OutboxWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        OutboxWriteSqliteRepository { database }
+    }
+}
+
+// the enums are stored by their serde names, json_column reads them back from the quoted form
+fn enum_text(value: &impl Serialize) -> rusqlite::Result<String> {
+    Ok(to_json(value)?.trim_matches('"').to_string())
+}
+
+// records a change in the transaction that makes it, so it is published exactly when it commits
+pub(in crate::repository) fn enqueue(
+    connection: &Connection,
+    ledger_id: Uuid,
+    entity: ChangedEntity,
+    entity_id: Uuid,
+    action: ChangeAction,
+    data: Option<&impl Serialize>,
+) -> rusqlite::Result<()> {
+    connection
+        .prepare_cached(
+            "INSERT INTO outbox (id, ledger_id, entity, entity_id, action, data, occurred_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?
+        .execute(params![
+            Uuid::new_v4().to_string(),
+            ledger_id.to_string(),
+            enum_text(&entity)?,
+            entity_id.to_string(),
+            enum_text(&action)?,
+            data.map(to_json).transpose()?,
+            Utc::now(),
+        ])?;
+    Ok(())
+}
+
+const CHANGE_EVENT_COLUMNS: &str = "outbox.id, outbox.entity, outbox.entity_id, outbox.action,
+    outbox.data, outbox.occurred_at, outbox.ledger_id";
+
+fn enum_column<T: serde::de::DeserializeOwned>(row: &Row, index: usize) -> rusqlite::Result<T> {
+    let text: String = row.get(index)?;
+    serde_json::from_value(serde_json::Value::String(text)).map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(err))
+    })
+}
+
+fn change_event_from_row(row: &Row) -> rusqlite::Result<ChangeEvent> {
+    let data = match row.get::<_, Option<String>>(4)? {
+        Some(_) => Some(json_column(row, 4)?),
+        None => None,
+    };
+    Ok(ChangeEvent {
+        id: uuid_column(row, 0)?,
+        entity: enum_column(row, 1)?,
+        entity_id: uuid_column(row, 2)?,
+        action: enum_column(row, 3)?,
+        data,
+        occurred_at: row.get(5)?,
+        ledger_id: uuid_column(row, 6)?,
+    })
+}
+
+impl OutboxReadPort for OutboxReadSqliteRepository {
+    fn due(
+        &self,
+        now: DateTime<Utc>,
+        handlers: &[String],
+        limit: usize,
+    ) -> Result<Vec<OutboxDelivery>, ApplicationError> {
+        if handlers.is_empty() {
+            return Ok(Vec::new());
+        }
+        // deliveries of handlers not registered must not fill the batch of those that are
+        let placeholders = (3..handlers.len() + 3)
+            .map(|index| format!("?{index}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let limit = limit as i64;
+        let mut values: Vec<&dyn ToSql> = vec![&now, &limit];
+        values.extend(handlers.iter().map(|handler| handler as &dyn ToSql));
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {CHANGE_EVENT_COLUMNS}, outbox_delivery.handler, outbox_delivery.attempts
+             FROM outbox_delivery JOIN outbox ON outbox.id = outbox_delivery.outbox_id
+             WHERE outbox_delivery.delivered_at IS NULL
+             AND outbox_delivery.dead_lettered_at IS NULL
+             AND outbox_delivery.next_attempt_at <= ?1
+             AND outbox_delivery.handler IN ({placeholders})
+             ORDER BY outbox.rowid, outbox_delivery.handler LIMIT ?2"
+        ))?;
+        let deliveries = statement
+            .query_map(values.as_slice(), |row| {
+                Ok(OutboxDelivery {
+                    event: change_event_from_row(row)?,
+                    handler: row.get(7)?,
+                    attempts: row.get::<_, i64>(8)? as u32,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(deliveries)
+    }
+
+    fn dead_letters(&self) -> Result<Vec<DeadLetter>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {CHANGE_EVENT_COLUMNS}, outbox_delivery.handler, outbox_delivery.attempts,
+                 outbox_delivery.last_error, outbox_delivery.dead_lettered_at
+             FROM outbox_delivery JOIN outbox ON outbox.id = outbox_delivery.outbox_id
+             WHERE outbox_delivery.dead_lettered_at IS NOT NULL
+             ORDER BY outbox_delivery.dead_lettered_at, outbox_delivery.handler"
+        ))?;
+        let dead_letters = statement
+            .query_map([], |row| {
+                Ok(DeadLetter {
+                    event: change_event_from_row(row)?,
+                    handler: row.get(7)?,
+                    attempts: row.get::<_, i64>(8)? as u32,
+                    last_error: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+                    dead_lettered_at: row.get(10)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(dead_letters)
+    }
+}
+
+impl OutboxWritePort for OutboxWriteSqliteRepository {
+    fn route(&self, handlers: &[String]) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        // changes routed before a handler was registered are not delivered to it
+        for handler in handlers {
+            transaction.execute(
+                "INSERT OR IGNORE INTO outbox_delivery (outbox_id, handler, attempts, next_attempt_at)
+                 SELECT id, ?1, 0, occurred_at FROM outbox WHERE routed_at IS NULL",
+                params![handler],
+            )?;
+        }
+        transaction.execute(
+            "UPDATE outbox SET routed_at = ?1 WHERE routed_at IS NULL",
+            params![Utc::now()],
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn delivered(&self, event_id: Uuid, handler: &str) -> Result<(), ApplicationError> {
+        self.database.connection().execute(
+            "UPDATE outbox_delivery SET attempts = attempts + 1, delivered_at = ?3, last_error = NULL
+             WHERE outbox_id = ?1 AND handler = ?2",
+            params![event_id.to_string(), handler, Utc::now()],
+        )?;
+        Ok(())
+    }
+
+    fn failed(
+        &self,
+        event_id: Uuid,
+        handler: &str,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), ApplicationError> {
+        // without a retry time the delivery is given up and kept as dead letter
+        match retry_at {
+            Some(retry_at) => self.database.connection().execute(
+                "UPDATE outbox_delivery SET attempts = attempts + 1, last_error = ?3,
+                     next_attempt_at = ?4
+                 WHERE outbox_id = ?1 AND handler = ?2",
+                params![event_id.to_string(), handler, error, retry_at],
+            )?,
+            None => self.database.connection().execute(
+                "UPDATE outbox_delivery SET attempts = attempts + 1, last_error = ?3,
+                     dead_lettered_at = ?4
+                 WHERE outbox_id = ?1 AND handler = ?2",
+                params![event_id.to_string(), handler, error, Utc::now()],
+            )?,
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,614 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use rusqlite::types::ToSql;
+use rusqlite::{Connection, OptionalExtension, Row, params, params_from_iter};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        cost_share::CostShare,
+        expense_entry::ExpenseEntry,
+        expense_entry_event::ExpenseEntryAggregate,
+        projection::{CostBearerBalance, CostShareRow, ExpenseEntryRow, MonthlyTotal},
+        tag::{Tag, TagUsage},
+        trash::{TrashItem, TrashedEntity},
+    },
+    repository::sqliterepository::{
+        database::{SqliteDatabase, to_json, uuid_column},
+        expense_entry_event_store,
+    },
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        expense_entry::{ExpenseEntryFilter, ExpenseEntryReadPort, TagFilter},
+        projection::{ProjectionReadPort, ProjectionWritePort},
+    },
+};
+
+// all read models are fed by the same projector, so they share one checkpoint
+const CHECKPOINT: &str = "expense_entry";
+
+// the read models in the order they are emptied on a rebuild
+pub(in crate::repository) const VIEW_TABLES: [&str; 5] = [
+    "expense_entry_view_share",
+    "expense_entry_view_tag",
+    "expense_entry_view",
+    "monthly_total_view",
+    "cost_bearer_balance_view",
+];
+
+// serves the expense entry queries from the read models, every query first projects the events
+// the background projector has not gotten to yet, so a change is visible right after it was made
+pub struct ProjectionReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct ProjectionWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl ProjectionReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        ProjectionReadSqliteRepository { database }
+    }
+}
+
+impl ProjectionWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        ProjectionWriteSqliteRepository { database }
+    }
+}
+
+fn expense_entry_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Expense entry not found."),
+    }
+}
+
+fn month_of(date: DateTime<Utc>) -> String {
+    date.format("%Y-%m").to_string()
+}
+
+fn expense_type_name(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<Option<String>> {
+    connection
+        .query_row(
+            "SELECT name FROM expense_type WHERE id = ?1 AND ledger_id = ?2",
+            params![id.to_string(), ledger_id.to_string()],
+            |row| row.get(0),
+        )
+        .optional()
+}
+
+fn cost_bearer_name(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<Option<String>> {
+    connection
+        .query_row(
+            "SELECT name FROM cost_bearer WHERE id = ?1 AND ledger_id = ?2",
+            params![id.to_string(), ledger_id.to_string()],
+            |row| row.get(0),
+        )
+        .optional()
+}
+
+fn load_share_rows(connection: &Connection, id: Uuid) -> rusqlite::Result<Vec<CostShareRow>> {
+    let mut statement = connection.prepare_cached(
+        "SELECT cost_bearer_id, cost_bearer_name, amount FROM expense_entry_view_share
+         WHERE expense_entry_id = ?1 ORDER BY position",
+    )?;
+    statement
+        .query_map(params![id.to_string()], |row| {
+            Ok(CostShareRow {
+                cost_bearer_id: uuid_column(row, 0)?,
+                cost_bearer_name: row.get(1)?,
+                amount: row.get(2)?,
+            })
+        })?
+        .collect()
+}
+
+fn load_tags(connection: &Connection, id: Uuid) -> rusqlite::Result<Vec<Tag>> {
+    let mut statement = connection.prepare_cached(
+        "SELECT tag FROM expense_entry_view_tag WHERE expense_entry_id = ?1 ORDER BY tag",
+    )?;
+    statement
+        .query_map(params![id.to_string()], |row| Ok(Tag::restore(row.get(0)?)))?
+        .collect()
+}
+
+// the entry row without its cost shares and tags
+fn expense_entry_row(row: &Row) -> rusqlite::Result<ExpenseEntryRow> {
+    Ok(ExpenseEntryRow {
+        id: uuid_column(row, 0)?,
+        expense_date: row.get(1)?,
+        expense_type: uuid_column(row, 2)?,
+        expense_type_name: row.get(3)?,
+        description: row.get(4)?,
+        cost_shares: Vec::new(),
+        tags: Vec::new(),
+        redacted: false,
+    })
+}
+
+fn complete(connection: &Connection, row: ExpenseEntryRow) -> rusqlite::Result<ExpenseEntryRow> {
+    Ok(ExpenseEntryRow {
+        cost_shares: load_share_rows(connection, row.id)?,
+        tags: load_tags(connection, row.id)?,
+        ..row
+    })
+}
+
+fn to_expense_entry(row: ExpenseEntryRow) -> ExpenseEntry {
+    ExpenseEntry::restore(
+        row.id,
+        row.expense_date,
+        row.cost_shares
+            .iter()
+            .map(|share| CostShare {
+                cost_bearer_id: share.cost_bearer_id,
+                amount: share.amount,
+            })
+            .collect(),
+        row.expense_type,
+        row.description,
+        row.tags,
+    )
+}
+
+// the projected entry of the ledger, also while it is in the trash, together with its deletion
+// time
+fn load_projected(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<Option<(ExpenseEntryRow, Option<DateTime<Utc>>)>> {
+    let projected = connection
+        .query_row(
+            "SELECT id, expense_date, expense_type, expense_type_name, description, deleted_at
+             FROM expense_entry_view WHERE id = ?1 AND ledger_id = ?2",
+            params![id.to_string(), ledger_id.to_string()],
+            |row| Ok((expense_entry_row(row)?, row.get(5)?)),
+        )
+        .optional()?;
+    match projected {
+        Some((row, deleted_at)) => Ok(Some((complete(connection, row)?, deleted_at))),
+        None => Ok(None),
+    }
+}
+
+// adds the amounts of an entry to the totals and balances, a negative sign takes them out again
+fn add_to_totals(
+    connection: &Connection,
+    ledger_id: Uuid,
+    row: &ExpenseEntryRow,
+    sign: f64,
+) -> rusqlite::Result<()> {
+    let total: f64 = row.cost_shares.iter().map(|share| share.amount).sum();
+    connection.execute(
+        "INSERT INTO monthly_total_view (ledger_id, expense_type, expense_type_name, month, total)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (ledger_id, expense_type, month) DO UPDATE SET total = total + excluded.total",
+        params![
+            ledger_id.to_string(),
+            row.expense_type.to_string(),
+            row.expense_type_name,
+            month_of(row.expense_date),
+            sign * total,
+        ],
+    )?;
+    let mut statement = connection.prepare_cached(
+        "INSERT INTO cost_bearer_balance_view (ledger_id, cost_bearer_id, cost_bearer_name, balance)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (ledger_id, cost_bearer_id) DO UPDATE SET balance = balance + excluded.balance",
+    )?;
+    for share in &row.cost_shares {
+        statement.execute(params![
+            ledger_id.to_string(),
+            share.cost_bearer_id.to_string(),
+            share.cost_bearer_name,
+            sign * share.amount,
+        ])?;
+    }
+    Ok(())
+}
+
+fn remove_projected(connection: &Connection, ledger_id: Uuid, id: Uuid) -> rusqlite::Result<()> {
+    if let Some((row, deleted_at)) = load_projected(connection, ledger_id, id)? {
+        // entries in the trash do not count
+        if deleted_at.is_none() {
+            add_to_totals(connection, ledger_id, &row, -1.0)?;
+        }
+        // cost shares and tags are removed by the foreign key cascade
+        connection.execute(
+            "DELETE FROM expense_entry_view WHERE id = ?1",
+            params![id.to_string()],
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_projected(
+    connection: &Connection,
+    ledger_id: Uuid,
+    entry: &ExpenseEntry,
+    deleted_at: Option<DateTime<Utc>>,
+) -> rusqlite::Result<()> {
+    let row = ExpenseEntryRow {
+        id: entry.id(),
+        expense_date: entry.expense_date(),
+        expense_type: entry.expense_type(),
+        expense_type_name: expense_type_name(connection, ledger_id, entry.expense_type())?,
+        description: entry.description().to_string(),
+        cost_shares: entry
+            .cost_shares()
+            .iter()
+            .map(|share| {
+                Ok(CostShareRow {
+                    cost_bearer_id: share.cost_bearer_id,
+                    cost_bearer_name: cost_bearer_name(
+                        connection,
+                        ledger_id,
+                        share.cost_bearer_id,
+                    )?,
+                    amount: share.amount,
+                })
+            })
+            .collect::<rusqlite::Result<Vec<_>>>()?,
+        tags: entry.tags().to_vec(),
+        redacted: false,
+    };
+    connection.execute(
+        "INSERT INTO expense_entry_view
+         (id, ledger_id, expense_date, expense_type, expense_type_name, description, deleted_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            row.id.to_string(),
+            ledger_id.to_string(),
+            row.expense_date,
+            row.expense_type.to_string(),
+            row.expense_type_name,
+            row.description,
+            deleted_at,
+        ],
+    )?;
+    let mut statement = connection.prepare_cached(
+        "INSERT INTO expense_entry_view_share
+         (expense_entry_id, cost_bearer_id, cost_bearer_name, amount, position)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (position, share) in row.cost_shares.iter().enumerate() {
+        statement.execute(params![
+            row.id.to_string(),
+            share.cost_bearer_id.to_string(),
+            share.cost_bearer_name,
+            share.amount,
+            position as i64,
+        ])?;
+    }
+    let mut statement = connection.prepare_cached(
+        "INSERT INTO expense_entry_view_tag (expense_entry_id, tag) VALUES (?1, ?2)",
+    )?;
+    for tag in &row.tags {
+        statement.execute(params![row.id.to_string(), tag.as_str()])?;
+    }
+    if deleted_at.is_none() {
+        add_to_totals(connection, ledger_id, &row, 1.0)?;
+    }
+    Ok(())
+}
+
+// applies the events after the checkpoint, each touched entry is replayed from its stream once
+fn project_pending(connection: &Connection) -> rusqlite::Result<u64> {
+    let checkpoint: i64 = connection
+        .query_row(
+            "SELECT position FROM projection_checkpoint WHERE projection = ?1",
+            params![CHECKPOINT],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(0);
+    let pending = connection
+        .prepare_cached(
+            "SELECT rowid, stream_id FROM expense_entry_event WHERE rowid > ?1 ORDER BY rowid",
+        )?
+        .query_map(params![checkpoint], |row| {
+            Ok((row.get::<_, i64>(0)?, uuid_column(row, 1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let Some(&(last_position, _)) = pending.last() else {
+        return Ok(0);
+    };
+
+    let mut stream_ids = pending.iter().map(|&(_, id)| id).collect::<Vec<_>>();
+    stream_ids.sort();
+    stream_ids.dedup();
+    for id in stream_ids {
+        // a stream stays in the ledger its first event was recorded in
+        let Some(ledger_id) = expense_entry_event_store::stream_ledger(connection, id)? else {
+            continue;
+        };
+        let events = expense_entry_event_store::load_events(connection, id)?;
+        let aggregate = ExpenseEntryAggregate::replay(id, &events);
+        remove_projected(connection, ledger_id, id)?;
+        if let Some(entry) = aggregate.entry() {
+            insert_projected(connection, ledger_id, &entry, aggregate.deleted_at())?;
+        }
+    }
+    // totals that were taken out completely are not worth keeping
+    connection.execute_batch(
+        "DELETE FROM monthly_total_view WHERE abs(total) < 0.000001;
+         DELETE FROM cost_bearer_balance_view WHERE abs(balance) < 0.000001;",
+    )?;
+    connection.execute(
+        "INSERT INTO projection_checkpoint (projection, position) VALUES (?1, ?2)
+         ON CONFLICT (projection) DO UPDATE SET position = excluded.position",
+        params![CHECKPOINT, last_position],
+    )?;
+    Ok(pending.len() as u64)
+}
+
+impl ProjectionReadSqliteRepository {
+    fn caught_up_connection(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, Connection>, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        project_pending(&transaction)?;
+        transaction.commit()?;
+        Ok(connection)
+    }
+}
+
+// keeps the names in the read models current when an expense type or cost bearer is renamed
+pub(in crate::repository) fn rename_expense_type(
+    connection: &Connection,
+    id: Uuid,
+    name: &str,
+) -> rusqlite::Result<()> {
+    connection.execute(
+        "UPDATE expense_entry_view SET expense_type_name = ?2 WHERE expense_type = ?1",
+        params![id.to_string(), name],
+    )?;
+    connection.execute(
+        "UPDATE monthly_total_view SET expense_type_name = ?2 WHERE expense_type = ?1",
+        params![id.to_string(), name],
+    )?;
+    Ok(())
+}
+
+pub(in crate::repository) fn rename_cost_bearer(
+    connection: &Connection,
+    id: Uuid,
+    name: &str,
+) -> rusqlite::Result<()> {
+    connection.execute(
+        "UPDATE expense_entry_view_share SET cost_bearer_name = ?2 WHERE cost_bearer_id = ?1",
+        params![id.to_string(), name],
+    )?;
+    connection.execute(
+        "UPDATE cost_bearer_balance_view SET cost_bearer_name = ?2 WHERE cost_bearer_id = ?1",
+        params![id.to_string(), name],
+    )?;
+    Ok(())
+}
+
+// the where clause and its values for all active entries of the ledger matching the filter
+fn filter_condition(ledger_id: Uuid, filter: &ExpenseEntryFilter) -> (String, Vec<Box<dyn ToSql>>) {
+    let mut conditions = vec![
+        String::from("ledger_id = ?1"),
+        String::from("deleted_at IS NULL"),
+    ];
+    let mut values: Vec<Box<dyn ToSql>> = vec![Box::new(ledger_id.to_string())];
+    if let Some(expense_type) = filter.expense_type {
+        values.push(Box::new(expense_type.to_string()));
+        conditions.push(format!("expense_type = ?{}", values.len()));
+    }
+    if let Some(cost_bearer_id) = filter.cost_bearer_id {
+        values.push(Box::new(cost_bearer_id.to_string()));
+        conditions.push(format!(
+            "id IN (SELECT expense_entry_id FROM expense_entry_view_share
+                    WHERE cost_bearer_id = ?{})",
+            values.len()
+        ));
+    }
+    if let Some(from) = filter.from {
+        values.push(Box::new(from));
+        conditions.push(format!("expense_date >= ?{}", values.len()));
+    }
+    if let Some(to) = filter.to {
+        values.push(Box::new(to));
+        conditions.push(format!("expense_date < ?{}", values.len()));
+    }
+    if let Some(TagFilter::AnyOf(tags) | TagFilter::AllOf(tags)) = &filter.tags {
+        let mut placeholders = Vec::new();
+        for tag in tags {
+            values.push(Box::new(tag.as_str().to_string()));
+            placeholders.push(format!("?{}", values.len()));
+        }
+        let having = match &filter.tags {
+            Some(TagFilter::AllOf(_)) => format!("HAVING COUNT(DISTINCT tag) = {}", tags.len()),
+            _ => String::new(),
+        };
+        conditions.push(format!(
+            "id IN (SELECT expense_entry_id FROM expense_entry_view_tag
+                    WHERE tag IN ({}) GROUP BY expense_entry_id {having})",
+            placeholders.join(", ")
+        ));
+    }
+    (conditions.join(" AND "), values)
+}
+
+fn find_rows(
+    connection: &Connection,
+    ledger_id: Uuid,
+    filter: &ExpenseEntryFilter,
+) -> Result<Vec<ExpenseEntryRow>, ApplicationError> {
+    let (condition, values) = filter_condition(ledger_id, filter);
+    let mut statement = connection.prepare(&format!(
+        "SELECT id, expense_date, expense_type, expense_type_name, description
+         FROM expense_entry_view WHERE {condition} ORDER BY expense_date, id"
+    ))?;
+    let rows = statement
+        .query_map(params_from_iter(values.iter()), expense_entry_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    let rows = rows
+        .into_iter()
+        .map(|row| complete(connection, row))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+impl ExpenseEntryReadPort for ProjectionReadSqliteRepository {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseEntry, ApplicationError> {
+        let connection = self.caught_up_connection()?;
+        match load_projected(&connection, ledger_id, id)? {
+            Some((row, None)) => Ok(to_expense_entry(row)),
+            _ => Err(expense_entry_not_found()),
+        }
+    }
+
+    fn find(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<ExpenseEntry>, ApplicationError> {
+        let connection = self.caught_up_connection()?;
+        let rows = find_rows(&connection, ledger_id, filter)?;
+        Ok(rows.into_iter().map(to_expense_entry).collect())
+    }
+
+    fn tags(
+        &self,
+        ledger_id: Uuid,
+        hidden: &HashSet<Uuid>,
+    ) -> Result<Vec<TagUsage>, ApplicationError> {
+        let connection = self.caught_up_connection()?;
+        let mut statement = connection.prepare(
+            "SELECT tag, COUNT(*) FROM expense_entry_view_tag
+             WHERE expense_entry_id IN (
+                 SELECT id FROM expense_entry_view WHERE ledger_id = ?1 AND deleted_at IS NULL)
+             AND expense_entry_id NOT IN (
+                 SELECT expense_entry_id FROM expense_entry_view_share
+                 WHERE cost_bearer_id IN (SELECT value FROM json_each(?2)))
+             GROUP BY tag ORDER BY tag",
+        )?;
+        let tags = statement
+            .query_map(params![ledger_id.to_string(), to_json(hidden)?], |row| {
+                Ok(TagUsage {
+                    tag: Tag::restore(row.get(0)?),
+                    expense_entries: row.get::<_, i64>(1)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tags)
+    }
+
+    fn trash(
+        &self,
+        ledger_id: Uuid,
+        hidden: &HashSet<Uuid>,
+    ) -> Result<Vec<TrashItem>, ApplicationError> {
+        let connection = self.caught_up_connection()?;
+        let mut statement = connection.prepare(
+            "SELECT id, CASE WHEN id IN (
+                     SELECT expense_entry_id FROM expense_entry_view_share
+                     WHERE cost_bearer_id IN (SELECT value FROM json_each(?2)))
+                 THEN '' ELSE description END, deleted_at
+             FROM expense_entry_view
+             WHERE ledger_id = ?1 AND deleted_at IS NOT NULL ORDER BY deleted_at",
+        )?;
+        let items = statement
+            .query_map(params![ledger_id.to_string(), to_json(hidden)?], |row| {
+                Ok(TrashItem {
+                    entity: TrashedEntity::ExpenseEntry,
+                    id: uuid_column(row, 0)?,
+                    label: row.get(1)?,
+                    deleted_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+}
+
+impl ProjectionReadPort for ProjectionReadSqliteRepository {
+    fn expense_entry_rows(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<ExpenseEntryRow>, ApplicationError> {
+        let connection = self.caught_up_connection()?;
+        find_rows(&connection, ledger_id, filter)
+    }
+
+    fn monthly_totals(&self, ledger_id: Uuid) -> Result<Vec<MonthlyTotal>, ApplicationError> {
+        let connection = self.caught_up_connection()?;
+        let mut statement = connection.prepare(
+            "SELECT expense_type, expense_type_name, month, total FROM monthly_total_view
+             WHERE ledger_id = ?1 ORDER BY month, expense_type_name, expense_type",
+        )?;
+        let totals = statement
+            .query_map(params![ledger_id.to_string()], |row| {
+                Ok(MonthlyTotal {
+                    expense_type: uuid_column(row, 0)?,
+                    expense_type_name: row.get(1)?,
+                    month: row.get(2)?,
+                    total: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(totals)
+    }
+
+    fn cost_bearer_balances(
+        &self,
+        ledger_id: Uuid,
+    ) -> Result<Vec<CostBearerBalance>, ApplicationError> {
+        let connection = self.caught_up_connection()?;
+        let mut statement = connection.prepare(
+            "SELECT cost_bearer_id, cost_bearer_name, balance FROM cost_bearer_balance_view
+             WHERE ledger_id = ?1 ORDER BY cost_bearer_name, cost_bearer_id",
+        )?;
+        let balances = statement
+            .query_map(params![ledger_id.to_string()], |row| {
+                Ok(CostBearerBalance {
+                    cost_bearer_id: uuid_column(row, 0)?,
+                    cost_bearer_name: row.get(1)?,
+                    balance: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(balances)
+    }
+}
+
+impl ProjectionWritePort for ProjectionWriteSqliteRepository {
+    fn project_pending(&self) -> Result<u64, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let projected = project_pending(&transaction)?;
+        transaction.commit()?;
+        Ok(projected)
+    }
+
+    fn rebuild(&self) -> Result<u64, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        for table in VIEW_TABLES {
+            transaction.execute(&format!("DELETE FROM {table}"), [])?;
+        }
+        transaction.execute(
+            "DELETE FROM projection_checkpoint WHERE projection = ?1",
+            params![CHECKPOINT],
+        )?;
+        let replayed = project_pending(&transaction)?;
+        transaction.commit()?;
+        Ok(replayed)
+    }
+}
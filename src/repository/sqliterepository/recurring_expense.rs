@@ -0,0 +1,441 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        audit::{AuditAction, AuditedEntity},
+        expense_entry::ExpenseEntry,
+        recurring_expense::{
+            ExpenseEntryTemplate, OccurrenceStatus, RecurringExpense, RecurringExpenseOccurrence,
+            Schedule,
+        },
+        trash::{TrashItem, TrashedEntity},
+    },
+    repository::sqliterepository::{
+        audit,
+        database::{SqliteDatabase, json_column, optional_uuid_column, to_json, uuid_column},
+        expense_entry, ledger, trash,
+    },
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        recurring_expense::{RecurringExpenseReadPort, RecurringExpenseWritePort},
+    },
+};
+
+pub struct RecurringExpenseReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct RecurringExpenseWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl RecurringExpenseReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        RecurringExpenseReadSqliteRepository { database }
+    }
+}
+
+impl RecurringExpenseWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        RecurringExpenseWriteSqliteRepository { database }
+    }
+}
+
+fn recurring_expense_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Recurring expense not found."),
+    }
+}
+
+fn status_to_sql(status: OccurrenceStatus) -> &'static str {
+    match status {
+        OccurrenceStatus::Pending => "pending",
+        OccurrenceStatus::Skipped => "skipped",
+        OccurrenceStatus::Overridden => "overridden",
+        OccurrenceStatus::Booked => "booked",
+    }
+}
+
+fn status_from_sql(status: &str) -> OccurrenceStatus {
+    match status {
+        "skipped" => OccurrenceStatus::Skipped,
+        "overridden" => OccurrenceStatus::Overridden,
+        "booked" => OccurrenceStatus::Booked,
+        _ => OccurrenceStatus::Pending,
+    }
+}
+
+const RECURRING_EXPENSE_COLUMNS: &str = "id, cost_shares, expense_type, description, recurrence, repeat_interval, starts_on, ends_on, created_at";
+
+fn recurring_expense_from_row(row: &Row) -> rusqlite::Result<RecurringExpense> {
+    Ok(RecurringExpense::restore(
+        uuid_column(row, 0)?,
+        ExpenseEntryTemplate::restore(json_column(row, 1)?, uuid_column(row, 2)?, row.get(3)?),
+        Schedule::restore(json_column(row, 4)?, row.get(5)?, row.get(6)?, row.get(7)?),
+        row.get(8)?,
+    ))
+}
+
+const OCCURRENCE_COLUMNS: &str = "occurrence_date, status, override_cost_shares, override_expense_type, override_description, expense_entry_id";
+
+fn occurrence_from_row(row: &Row) -> rusqlite::Result<RecurringExpenseOccurrence> {
+    let override_template = match row.get::<_, Option<String>>(4)? {
+        Some(description) => Some(ExpenseEntryTemplate::restore(
+            json_column(row, 2)?,
+            uuid_column(row, 3)?,
+            description,
+        )),
+        None => None,
+    };
+
+    Ok(RecurringExpenseOccurrence {
+        occurrence_date: row.get(0)?,
+        status: status_from_sql(&row.get::<_, String>(1)?),
+        override_template,
+        expense_entry_id: optional_uuid_column(row, 5)?,
+    })
+}
+
+// the cost bearers and the expense type have to be the ledger's own and not in the trash
+fn verify_references(
+    connection: &Connection,
+    ledger_id: Uuid,
+    template: &ExpenseEntryTemplate,
+) -> Result<(), ApplicationError> {
+    let cost_bearer_ids: Vec<Uuid> = template
+        .cost_shares()
+        .iter()
+        .map(|share| share.cost_bearer_id)
+        .collect();
+    ledger::verify_references(
+        connection,
+        ledger_id,
+        &cost_bearer_ids,
+        &[template.expense_type()],
+    )
+}
+
+// the stored recurring expense of the ledger, also while it is in the trash
+fn load_stored(
+    connection: &Connection,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<Option<RecurringExpense>> {
+    connection
+        .query_row(
+            &format!(
+                "SELECT {RECURRING_EXPENSE_COLUMNS} FROM recurring_expense
+                 WHERE id = ?1 AND ledger_id = ?2"
+            ),
+            params![id.to_string(), ledger_id.to_string()],
+            recurring_expense_from_row,
+        )
+        .optional()
+}
+
+fn load_occurrence(
+    connection: &Connection,
+    id: Uuid,
+    occurrence_date: NaiveDate,
+) -> rusqlite::Result<Option<RecurringExpenseOccurrence>> {
+    connection
+        .query_row(
+            &format!(
+                "SELECT {OCCURRENCE_COLUMNS} FROM recurring_expense_occurrence
+                 WHERE recurring_expense_id = ?1 AND occurrence_date = ?2"
+            ),
+            params![id.to_string(), occurrence_date],
+            occurrence_from_row,
+        )
+        .optional()
+}
+
+impl RecurringExpenseReadPort for RecurringExpenseReadSqliteRepository {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<RecurringExpense, ApplicationError> {
+        self.database
+            .connection()
+            .query_row(
+                &format!(
+                    "SELECT {RECURRING_EXPENSE_COLUMNS} FROM recurring_expense
+                     WHERE id = ?1 AND ledger_id = ?2 AND deleted_at IS NULL"
+                ),
+                params![id.to_string(), ledger_id.to_string()],
+                recurring_expense_from_row,
+            )
+            .optional()?
+            .ok_or_else(recurring_expense_not_found)
+    }
+
+    fn list(&self, ledger_id: Uuid) -> Result<Vec<RecurringExpense>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {RECURRING_EXPENSE_COLUMNS} FROM recurring_expense
+             WHERE ledger_id = ?1 AND deleted_at IS NULL ORDER BY created_at"
+        ))?;
+        let recurring_expenses = statement
+            .query_map(params![ledger_id.to_string()], recurring_expense_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(recurring_expenses)
+    }
+
+    fn occurrences(&self, id: Uuid) -> Result<Vec<RecurringExpenseOccurrence>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {OCCURRENCE_COLUMNS} FROM recurring_expense_occurrence
+             WHERE recurring_expense_id = ?1 ORDER BY occurrence_date"
+        ))?;
+        let occurrences = statement
+            .query_map(params![id.to_string()], occurrence_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(occurrences)
+    }
+
+    fn occurrences_from(
+        &self,
+        id: Uuid,
+        from: NaiveDate,
+    ) -> Result<Vec<RecurringExpenseOccurrence>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {OCCURRENCE_COLUMNS} FROM recurring_expense_occurrence
+             WHERE recurring_expense_id = ?1 AND occurrence_date >= ?2 ORDER BY occurrence_date"
+        ))?;
+        let occurrences = statement
+            .query_map(params![id.to_string(), from], occurrence_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(occurrences)
+    }
+
+    fn last_booked_occurrence(&self, id: Uuid) -> Result<Option<NaiveDate>, ApplicationError> {
+        Ok(self.database.connection().query_row(
+            "SELECT MAX(occurrence_date) FROM recurring_expense_occurrence
+             WHERE recurring_expense_id = ?1 AND status = 'booked'",
+            params![id.to_string()],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(
+            "SELECT id, description, deleted_at FROM recurring_expense
+             WHERE ledger_id = ?1 AND deleted_at IS NOT NULL ORDER BY deleted_at",
+        )?;
+        let items = statement
+            .query_map(params![ledger_id.to_string()], |row| {
+                Ok(TrashItem {
+                    entity: TrashedEntity::RecurringExpense,
+                    id: uuid_column(row, 0)?,
+                    label: row.get(1)?,
+                    deleted_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+}
+
+impl RecurringExpenseWritePort for RecurringExpenseWriteSqliteRepository {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        recurring_expense: RecurringExpense,
+    ) -> Result<RecurringExpense, ApplicationError> {
+        let template = recurring_expense.template();
+        let schedule = recurring_expense.schedule();
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        verify_references(&transaction, ledger_id, template)?;
+        transaction.execute(
+            &format!(
+                "INSERT INTO recurring_expense ({RECURRING_EXPENSE_COLUMNS}, ledger_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
+            ),
+            params![
+                recurring_expense.id().to_string(),
+                to_json(&template.cost_shares())?,
+                template.expense_type().to_string(),
+                template.description(),
+                to_json(&schedule.recurrence())?,
+                schedule.interval(),
+                schedule.starts_on(),
+                schedule.ends_on(),
+                recurring_expense.created_at(),
+                ledger_id.to_string(),
+            ],
+        )?;
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::RecurringExpense,
+            recurring_expense.id(),
+            AuditAction::Created,
+            None::<&RecurringExpense>,
+            Some(&recurring_expense),
+        )?;
+        transaction.commit()?;
+        Ok(recurring_expense)
+    }
+
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if !trash::soft_delete(&transaction, "recurring_expense", ledger_id, id)? {
+            return Err(recurring_expense_not_found());
+        }
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::RecurringExpense,
+            id,
+            AuditAction::Deleted,
+            load_stored(&transaction, ledger_id, id)?.as_ref(),
+            None::<&RecurringExpense>,
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if !trash::restore(&transaction, "recurring_expense", ledger_id, id)? {
+            return Err(recurring_expense_not_found());
+        }
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::RecurringExpense,
+            id,
+            AuditAction::Restored,
+            None::<&RecurringExpense>,
+            load_stored(&transaction, ledger_id, id)?.as_ref(),
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        // already booked expense entries stay, only the schedule and its occurrences go
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        let purged_ids =
+            trash::purge(&transaction, "recurring_expense", ledger_id, deleted_before)?;
+        for &id in &purged_ids {
+            audit::record(
+                &transaction,
+                ledger_id,
+                AuditedEntity::RecurringExpense,
+                id,
+                AuditAction::Purged,
+                None::<&RecurringExpense>,
+                None::<&RecurringExpense>,
+            )?;
+        }
+        transaction.commit()?;
+        Ok(purged_ids)
+    }
+
+    // occurrences are part of their recurring expense, changing one is audited as its update
+    fn set_occurrence(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        occurrence: RecurringExpenseOccurrence,
+    ) -> Result<(), ApplicationError> {
+        let template = occurrence.override_template.as_ref();
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        if let Some(template) = template {
+            verify_references(&transaction, ledger_id, template)?;
+        }
+        let previous_occurrence = load_occurrence(&transaction, id, occurrence.occurrence_date)?
+            .unwrap_or(RecurringExpenseOccurrence {
+                occurrence_date: occurrence.occurrence_date,
+                status: OccurrenceStatus::Pending,
+                override_template: None,
+                expense_entry_id: None,
+            });
+        let changed = transaction.execute(
+            "INSERT INTO recurring_expense_occurrence
+                 (recurring_expense_id, occurrence_date, status, override_cost_shares, override_expense_type, override_description)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (recurring_expense_id, occurrence_date) DO UPDATE SET
+                 status = excluded.status,
+                 override_cost_shares = excluded.override_cost_shares,
+                 override_expense_type = excluded.override_expense_type,
+                 override_description = excluded.override_description
+             WHERE status != 'booked'",
+            params![
+                id.to_string(),
+                occurrence.occurrence_date,
+                status_to_sql(occurrence.status),
+                template.map(|t| to_json(&t.cost_shares())).transpose()?,
+                template.map(|t| t.expense_type().to_string()),
+                template.map(|t| t.description()),
+            ],
+        )?;
+        if changed == 0 {
+            return Err(ApplicationError {
+                error_type: ApplicationErrorType::Conflict,
+                message: String::from("Occurrence has already been booked."),
+            });
+        }
+        audit::record(
+            &transaction,
+            ledger_id,
+            AuditedEntity::RecurringExpense,
+            id,
+            AuditAction::Updated,
+            Some(&previous_occurrence),
+            Some(&occurrence),
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn book_occurrence(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        occurrence: &RecurringExpenseOccurrence,
+        entry: &ExpenseEntry,
+    ) -> Result<bool, ApplicationError> {
+        let mut connection = self.database.connection();
+        let transaction = connection.transaction()?;
+        // pending occurrences are not stored, overridden ones have to keep the override read
+        let unchanged = match load_occurrence(&transaction, id, occurrence.occurrence_date)? {
+            None => occurrence.status == OccurrenceStatus::Pending,
+            Some(stored) => {
+                stored.status == OccurrenceStatus::Overridden
+                    && occurrence.status == OccurrenceStatus::Overridden
+                    && stored.override_template == occurrence.override_template
+            }
+        };
+        if !unchanged {
+            return Ok(false);
+        }
+        expense_entry::insert_entry(&transaction, ledger_id, entry)?;
+        transaction.execute(
+            "INSERT INTO recurring_expense_occurrence
+                 (recurring_expense_id, occurrence_date, status, expense_entry_id)
+             VALUES (?1, ?2, 'booked', ?3)
+             ON CONFLICT (recurring_expense_id, occurrence_date) DO UPDATE SET
+                 status = 'booked',
+                 expense_entry_id = excluded.expense_entry_id",
+            params![
+                id.to_string(),
+                occurrence.occurrence_date,
+                entry.id().to_string()
+            ],
+        )?;
+        transaction.commit()?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, params};
+use uuid::Uuid;
+
+use crate::repository::sqliterepository::database::uuid_column;
+
+// helpers shared by all repositories of soft deletable entities, table names are never user input
+
+// returns false if there is no such row in the ledger that is not deleted yet
+pub(in crate::repository) fn soft_delete(
+    connection: &Connection,
+    table: &str,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<bool> {
+    let deleted = connection.execute(
+        &format!(
+            "UPDATE {table} SET deleted_at = ?3
+             WHERE id = ?1 AND ledger_id = ?2 AND deleted_at IS NULL"
+        ),
+        params![id.to_string(), ledger_id.to_string(), Utc::now()],
+    )?;
+    Ok(deleted > 0)
+}
+
+// returns false if there is no such row in the trash of the ledger
+pub(in crate::repository) fn restore(
+    connection: &Connection,
+    table: &str,
+    ledger_id: Uuid,
+    id: Uuid,
+) -> rusqlite::Result<bool> {
+    let restored = connection.execute(
+        &format!(
+            "UPDATE {table} SET deleted_at = NULL
+             WHERE id = ?1 AND ledger_id = ?2 AND deleted_at IS NOT NULL"
+        ),
+        params![id.to_string(), ledger_id.to_string()],
+    )?;
+    Ok(restored > 0)
+}
+
+// removes every row of the ledger deleted before the given time for good, dependent rows go by
+// cascade
+pub(in crate::repository) fn purge(
+    connection: &Connection,
+    table: &str,
+    ledger_id: Uuid,
+    deleted_before: DateTime<Utc>,
+) -> rusqlite::Result<Vec<Uuid>> {
+    let mut statement = connection.prepare(&format!(
+        "DELETE FROM {table}
+         WHERE ledger_id = ?1 AND deleted_at IS NOT NULL AND deleted_at < ?2 RETURNING id"
+    ))?;
+    statement
+        .query_map(params![ledger_id.to_string(), deleted_before], |row| {
+            uuid_column(row, 0)
+        })?
+        .collect()
+}
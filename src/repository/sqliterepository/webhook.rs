@@ -0,0 +1,178 @@
+use rusqlite::{OptionalExtension, Row, params};
+use uuid::Uuid;
+
+use crate::{
+    domain::webhook::{WebhookDelivery, WebhookSubscription},
+    repository::sqliterepository::database::{SqliteDatabase, json_column, to_json, uuid_column},
+    service::{
+        application_error::{ApplicationError, ApplicationErrorType},
+        webhook::{WebhookReadPort, WebhookWritePort},
+    },
+};
+
+pub struct WebhookReadSqliteRepository {
+    database: SqliteDatabase,
+}
+pub struct WebhookWriteSqliteRepository {
+    database: SqliteDatabase,
+}
+
+impl WebhookReadSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        WebhookReadSqliteRepository { database }
+    }
+}
+
+impl WebhookWriteSqliteRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        WebhookWriteSqliteRepository { database }
+    }
+}
+
+fn webhook_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Webhook not found."),
+    }
+}
+
+const WEBHOOK_COLUMNS: &str = "id, url, events, secret, created_by, created_at";
+
+fn webhook_from_row(row: &Row) -> rusqlite::Result<WebhookSubscription> {
+    Ok(WebhookSubscription::restore(
+        uuid_column(row, 0)?,
+        row.get(1)?,
+        json_column(row, 2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+    ))
+}
+
+fn delivery_from_row(row: &Row) -> rusqlite::Result<WebhookDelivery> {
+    Ok(WebhookDelivery {
+        id: uuid_column(row, 0)?,
+        subscription_id: uuid_column(row, 1)?,
+        event_id: uuid_column(row, 2)?,
+        event: row.get(3)?,
+        attempt: row.get::<_, i64>(4)? as u32,
+        status_code: row.get::<_, Option<i64>>(5)?.map(|code| code as u16),
+        error: row.get(6)?,
+        succeeded: row.get(7)?,
+        attempted_at: row.get(8)?,
+    })
+}
+
+impl WebhookReadPort for WebhookReadSqliteRepository {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<WebhookSubscription, ApplicationError> {
+        self.database
+            .connection()
+            .query_row(
+                &format!(
+                    "SELECT {WEBHOOK_COLUMNS} FROM webhook_subscription
+                     WHERE id = ?1 AND ledger_id = ?2"
+                ),
+                params![id.to_string(), ledger_id.to_string()],
+                webhook_from_row,
+            )
+            .optional()?
+            .ok_or_else(webhook_not_found)
+    }
+
+    fn list(&self, ledger_id: Uuid) -> Result<Vec<WebhookSubscription>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhook_subscription WHERE ledger_id = ?1
+             ORDER BY created_at"
+        ))?;
+        let subscriptions = statement
+            .query_map(params![ledger_id.to_string()], webhook_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(subscriptions)
+    }
+
+    fn deliveries(&self, subscription_id: Uuid) -> Result<Vec<WebhookDelivery>, ApplicationError> {
+        let connection = self.database.connection();
+        let mut statement = connection.prepare(
+            "SELECT id, subscription_id, event_id, event, attempt, status_code, error, succeeded,
+                 attempted_at
+             FROM webhook_delivery WHERE subscription_id = ?1
+             ORDER BY attempted_at DESC, attempt DESC",
+        )?;
+        let deliveries = statement
+            .query_map(params![subscription_id.to_string()], delivery_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(deliveries)
+    }
+
+    fn attempts(
+        &self,
+        subscription_id: Uuid,
+        event_id: Uuid,
+    ) -> Result<(u32, bool), ApplicationError> {
+        let (attempts, succeeded) = self.database.connection().query_row(
+            "SELECT COUNT(*), COALESCE(MAX(succeeded), 0) FROM webhook_delivery
+             WHERE subscription_id = ?1 AND event_id = ?2",
+            params![subscription_id.to_string(), event_id.to_string()],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, bool>(1)?)),
+        )?;
+        Ok((attempts as u32, succeeded))
+    }
+}
+
+impl WebhookWritePort for WebhookWriteSqliteRepository {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        subscription: WebhookSubscription,
+    ) -> Result<WebhookSubscription, ApplicationError> {
+        self.database.connection().execute(
+            &format!(
+                "INSERT INTO webhook_subscription ({WEBHOOK_COLUMNS}, ledger_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            ),
+            params![
+                subscription.id().to_string(),
+                subscription.url(),
+                to_json(&subscription.events())?,
+                subscription.secret(),
+                subscription.created_by(),
+                subscription.created_at(),
+                ledger_id.to_string(),
+            ],
+        )?;
+        Ok(subscription)
+    }
+
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        // the delivery log is removed by the foreign key cascade
+        let deleted = self.database.connection().execute(
+            "DELETE FROM webhook_subscription WHERE id = ?1 AND ledger_id = ?2",
+            params![id.to_string(), ledger_id.to_string()],
+        )?;
+        match deleted {
+            0 => Err(webhook_not_found()),
+            _ => Ok(()),
+        }
+    }
+
+    fn log_delivery(&self, delivery: &WebhookDelivery) -> Result<(), ApplicationError> {
+        self.database.connection().execute(
+            "INSERT INTO webhook_delivery (id, subscription_id, event_id, event, attempt,
+                 status_code, error, succeeded, attempted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                delivery.id.to_string(),
+                delivery.subscription_id.to_string(),
+                delivery.event_id.to_string(),
+                delivery.event,
+                delivery.attempt as i64,
+                delivery.status_code.map(i64::from),
+                delivery.error,
+                delivery.succeeded,
+                delivery.attempted_at,
+            ],
+        )?;
+        Ok(())
+    }
+}
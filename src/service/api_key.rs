@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::domain::api_key::{ApiKey, ApiKeyValidationError};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+
+// raw unvalidated user input DTO
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ApiKeyNew {
+    pub name: String,
+    // the user the key acts for, keys without one have the operator's access
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+impl From<ApiKeyValidationError> for ApplicationError {
+    fn from(err: ApiKeyValidationError) -> Self {
+        match err {
+            ApiKeyValidationError::MissingName => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid name.".to_string(),
+            },
+            ApiKeyValidationError::InvalidUser => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid user.".to_string(),
+            },
+        }
+    }
+}
+
+// issues and checks the keys requests authenticate with, without enforcement every request is
+// let through as before
+#[derive(Clone)]
+pub struct ApiKeyService {
+    pub(in crate::service) read_repo: Arc<dyn ApiKeyReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn ApiKeyWritePort + Send + Sync>,
+    pub(in crate::service) enforced: bool,
+}
+
+impl ApiKeyService {
+    pub fn new(
+        read_repo: Arc<dyn ApiKeyReadPort + Send + Sync>,
+        write_repo: Arc<dyn ApiKeyWritePort + Send + Sync>,
+        enforced: bool,
+    ) -> Self {
+        ApiKeyService {
+            read_repo,
+            write_repo,
+            enforced,
+        }
+    }
+}
+
+pub trait ApiKeyReadPort {
+    fn list(&self) -> Result<Vec<ApiKey>, ApplicationError>;
+    // the key with the given hash unless it was revoked
+    fn find_active(&self, key_hash: &str) -> Result<Option<ApiKey>, ApplicationError>;
+}
+
+pub trait ApiKeyWritePort {
+    fn insert(&self, api_key: ApiKey) -> Result<ApiKey, ApplicationError>;
+    // revoked keys stay listed but no longer authenticate
+    fn revoke(&self, id: Uuid) -> Result<(), ApplicationError>;
+}
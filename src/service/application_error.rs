@@ -8,6 +8,11 @@
 pub enum ApplicationErrorType {
     NotFound,
     ValidationFailed,
+    Conflict,
+    PayloadTooLarge,
+    Unauthorized,
+    Forbidden,
+    Internal,
     // this can be extended
 }
 
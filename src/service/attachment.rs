@@ -0,0 +1,123 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use uuid::Uuid;
+
+use crate::domain::attachment::{Attachment, AttachmentValidationError};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::expense_entry::ExpenseEntryReadPort;
+use crate::service::ledger::LedgerService;
+
+// receipts are scans, 10 MiB is plenty
+pub const DEFAULT_MAX_ATTACHMENT_SIZE: u64 = 10 * 1024 * 1024;
+
+// uploads and removals of the same content take the same lock, so a blob is never deleted while
+// another attachment starts to reference it
+const BLOB_LOCK_STRIPES: usize = 64;
+
+// raw unvalidated user input DTO
+pub struct AttachmentNew {
+    pub file_name: String,
+    pub content: Vec<u8>,
+}
+
+// attachment metadata together with the stored file
+pub struct AttachmentContent {
+    pub attachment: Attachment,
+    pub content: Vec<u8>,
+}
+
+impl From<AttachmentValidationError> for ApplicationError {
+    fn from(err: AttachmentValidationError) -> Self {
+        match err {
+            AttachmentValidationError::MissingFileName => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Upload without valid file name.".to_string(),
+            },
+            AttachmentValidationError::EmptyFile => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Upload without file content.".to_string(),
+            },
+            AttachmentValidationError::FileTooLarge(max_size) => ApplicationError {
+                error_type: ApplicationErrorType::PayloadTooLarge,
+                message: format!("Upload exceeds the maximum size of {max_size} bytes."),
+            },
+            AttachmentValidationError::UnsupportedContentType => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Upload with unsupported content type.".to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AttachmentService {
+    pub(in crate::service) read_repo: Arc<dyn AttachmentReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn AttachmentWritePort + Send + Sync>,
+    pub(in crate::service) blob_store: Arc<dyn BlobStorePort + Send + Sync>,
+    pub(in crate::service) expense_entry_read_repo: Arc<dyn ExpenseEntryReadPort + Send + Sync>,
+    pub(in crate::service) max_size: u64,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+    pub(in crate::service) blob_locks: Arc<Vec<Mutex<()>>>,
+}
+
+impl AttachmentService {
+    pub fn new(
+        read_repo: Arc<dyn AttachmentReadPort + Send + Sync>,
+        write_repo: Arc<dyn AttachmentWritePort + Send + Sync>,
+        blob_store: Arc<dyn BlobStorePort + Send + Sync>,
+        expense_entry_read_repo: Arc<dyn ExpenseEntryReadPort + Send + Sync>,
+        max_size: u64,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        AttachmentService {
+            read_repo,
+            write_repo,
+            blob_store,
+            expense_entry_read_repo,
+            max_size,
+            ledger_service,
+            blob_locks: Arc::new((0..BLOB_LOCK_STRIPES).map(|_| Mutex::new(())).collect()),
+        }
+    }
+
+    // held while the references to a blob are counted and changed
+    pub(in crate::service) fn lock_blob(&self, content_hash: &str) -> MutexGuard<'_, ()> {
+        let stripe = content_hash.bytes().fold(0usize, |acc, byte| {
+            acc.wrapping_mul(31).wrapping_add(byte as usize)
+        });
+        self.blob_locks[stripe % BLOB_LOCK_STRIPES]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+}
+
+pub trait AttachmentReadPort {
+    fn get(&self, expense_entry_id: Uuid, id: Uuid) -> Result<Attachment, ApplicationError>;
+    fn list(&self, expense_entry_id: Uuid) -> Result<Vec<Attachment>, ApplicationError>;
+    fn count_by_hash(&self, content_hash: &str) -> Result<u64, ApplicationError>;
+}
+
+pub trait AttachmentWritePort {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        attachment: Attachment,
+    ) -> Result<Attachment, ApplicationError>;
+    fn delete(
+        &self,
+        ledger_id: Uuid,
+        expense_entry_id: Uuid,
+        id: Uuid,
+    ) -> Result<(), ApplicationError>;
+}
+
+// content addressed file storage, blobs are keyed by their content hash
+pub trait BlobStorePort {
+    fn put(&self, content_hash: &str, content: &[u8]) -> Result<(), ApplicationError>;
+    fn get(&self, content_hash: &str) -> Result<Vec<u8>, ApplicationError>;
+    fn delete(&self, content_hash: &str) -> Result<(), ApplicationError>;
+}
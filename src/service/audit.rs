@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditRecord, AuditedEntity};
+use crate::service::application_error::ApplicationError;
+use crate::service::ledger::LedgerService;
+
+#[derive(Clone)]
+pub struct AuditService {
+    pub(in crate::service) read_repo: Arc<dyn AuditReadPort + Send + Sync>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+}
+
+impl AuditService {
+    pub fn new(
+        read_repo: Arc<dyn AuditReadPort + Send + Sync>,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        AuditService {
+            read_repo,
+            ledger_service,
+        }
+    }
+}
+
+pub trait AuditReadPort {
+    // oldest change first
+    fn history(
+        &self,
+        ledger_id: Uuid,
+        entity: AuditedEntity,
+        entity_id: Uuid,
+    ) -> Result<Vec<AuditRecord>, ApplicationError>;
+}
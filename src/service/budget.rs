@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::budget::{Budget, BudgetAlert, BudgetPeriod, BudgetValidationError};
+use crate::domain::trash::TrashItem;
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::expense_entry::ExpenseEntryReadPort;
+use crate::service::ledger::LedgerService;
+
+// raw unvalidated user input DTO
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct BudgetNew {
+    pub expense_type: Uuid,
+    pub cost_bearer_id: Option<Uuid>,
+    pub period: BudgetPeriod,
+    pub limit: f64,
+}
+
+impl TryFrom<BudgetNew> for Budget {
+    type Error = BudgetValidationError;
+    fn try_from(value: BudgetNew) -> Result<Self, BudgetValidationError> {
+        Budget::new(
+            value.expense_type,
+            value.cost_bearer_id,
+            value.period,
+            value.limit,
+        )
+    }
+}
+
+impl From<BudgetValidationError> for ApplicationError {
+    fn from(err: BudgetValidationError) -> Self {
+        match err {
+            BudgetValidationError::MissingExpenseType => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid expense id.".to_string(),
+            },
+            BudgetValidationError::MalformedCostBearerId => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid cost bearer id.".to_string(),
+            },
+            BudgetValidationError::InvalidLimit => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid limit.".to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BudgetService {
+    pub(in crate::service) read_repo: Arc<dyn BudgetReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn BudgetWritePort + Send + Sync>,
+    pub(in crate::service) expense_entry_read_repo: Arc<dyn ExpenseEntryReadPort + Send + Sync>,
+    pub(in crate::service) notifier: Arc<dyn NotifierPort + Send + Sync>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+}
+
+impl BudgetService {
+    pub fn new(
+        read_repo: Arc<dyn BudgetReadPort + Send + Sync>,
+        write_repo: Arc<dyn BudgetWritePort + Send + Sync>,
+        expense_entry_read_repo: Arc<dyn ExpenseEntryReadPort + Send + Sync>,
+        notifier: Arc<dyn NotifierPort + Send + Sync>,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        BudgetService {
+            read_repo,
+            write_repo,
+            expense_entry_read_repo,
+            notifier,
+            ledger_service,
+        }
+    }
+}
+
+pub trait BudgetReadPort {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<Budget, ApplicationError>;
+    fn find_by_expense_type(
+        &self,
+        ledger_id: Uuid,
+        expense_type: Uuid,
+    ) -> Result<Vec<Budget>, ApplicationError>;
+    fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError>;
+}
+
+pub trait BudgetWritePort {
+    fn insert(&self, ledger_id: Uuid, budget: Budget) -> Result<Budget, ApplicationError>;
+    // moves the budget to the trash
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+    // removes budgets of the ledger deleted before the given time for good, returns their ids
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError>;
+    // records the alert and queues it in the outbox in one transaction, returns false if it was
+    // already sent for the period
+    fn claim_alert(&self, ledger_id: Uuid, alert: &BudgetAlert) -> Result<bool, ApplicationError>;
+}
+
+// an alert only counts as sent once the notifier returns, errors are retried by the outbox
+pub trait NotifierPort {
+    fn notify(&self, alert: &BudgetAlert) -> Result<(), ApplicationError>;
+}
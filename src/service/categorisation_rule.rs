@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::domain::categorisation_rule::{
+    CategorisationRule, CategorisationRuleParts, CategorisationRuleValidationError,
+};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::ledger::LedgerService;
+
+// raw unvalidated user input DTO
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CategorisationRuleNew {
+    pub name: String,
+    #[serde(default)]
+    pub priority: i64,
+    pub description_pattern: Option<String>,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub cost_bearer_id: Option<Uuid>,
+    pub set_expense_type: Option<Uuid>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    pub rewrite_description: Option<String>,
+}
+
+impl TryFrom<CategorisationRuleNew> for CategorisationRule {
+    type Error = CategorisationRuleValidationError;
+    fn try_from(new: CategorisationRuleNew) -> Result<Self, CategorisationRuleValidationError> {
+        CategorisationRule::new(CategorisationRuleParts {
+            name: new.name,
+            priority: new.priority,
+            description_pattern: new.description_pattern,
+            min_amount: new.min_amount,
+            max_amount: new.max_amount,
+            cost_bearer_id: new.cost_bearer_id,
+            set_expense_type: new.set_expense_type,
+            add_tags: new.add_tags,
+            rewrite_description: new.rewrite_description,
+        })
+    }
+}
+
+impl From<CategorisationRuleValidationError> for ApplicationError {
+    fn from(err: CategorisationRuleValidationError) -> Self {
+        let message = match err {
+            CategorisationRuleValidationError::MissingName => "Json without valid name.",
+            CategorisationRuleValidationError::MissingCondition => "Json without valid conditions.",
+            CategorisationRuleValidationError::MissingAction => "Json without valid actions.",
+            CategorisationRuleValidationError::InvalidPattern => {
+                "Json without valid description pattern."
+            }
+            CategorisationRuleValidationError::InvalidAmountRange => {
+                "Json without valid amount range."
+            }
+            CategorisationRuleValidationError::MissingDescription => {
+                "Json without valid rewritten description."
+            }
+            CategorisationRuleValidationError::InvalidTag(_) => "Json without valid tags.",
+        };
+        ApplicationError {
+            error_type: ApplicationErrorType::ValidationFailed,
+            message: message.to_string(),
+        }
+    }
+}
+
+// user defined rules that fill in the expense type, tags and description of expense entries
+#[derive(Clone)]
+pub struct CategorisationRuleService {
+    pub(in crate::service) read_repo: Arc<dyn CategorisationRuleReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn CategorisationRuleWritePort + Send + Sync>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+}
+
+impl CategorisationRuleService {
+    pub fn new(
+        read_repo: Arc<dyn CategorisationRuleReadPort + Send + Sync>,
+        write_repo: Arc<dyn CategorisationRuleWritePort + Send + Sync>,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        CategorisationRuleService {
+            read_repo,
+            write_repo,
+            ledger_service,
+        }
+    }
+}
+
+pub trait CategorisationRuleReadPort {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<CategorisationRule, ApplicationError>;
+    // the rules of the ledger in the order they are run
+    fn list(&self, ledger_id: Uuid) -> Result<Vec<CategorisationRule>, ApplicationError>;
+}
+
+pub trait CategorisationRuleWritePort {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        rule: CategorisationRule,
+    ) -> Result<CategorisationRule, ApplicationError>;
+    fn update(
+        &self,
+        ledger_id: Uuid,
+        rule: CategorisationRule,
+    ) -> Result<CategorisationRule, ApplicationError>;
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+}
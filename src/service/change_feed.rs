@@ -0,0 +1,53 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::domain::change_feed::FeedEvent;
+use crate::service::ledger::LedgerService;
+
+// changes a live client may fall behind by before it is disconnected
+pub const CHANGE_FEED_CAPACITY: usize = 256;
+
+// changes kept for clients resuming after a reconnect
+pub const CHANGE_FEED_BUFFER_SIZE: usize = 1000;
+
+// the most recent changes in publishing order
+pub(in crate::service) struct FeedBuffer {
+    pub(in crate::service) next_sequence: u64,
+    pub(in crate::service) events: VecDeque<FeedEvent>,
+}
+
+// fans the published changes out to the connected live feed clients, only the latest changes
+// are kept in memory for clients that reconnect
+#[derive(Clone)]
+pub struct ChangeFeedService {
+    pub(in crate::service) epoch: Uuid,
+    pub(in crate::service) sender: broadcast::Sender<FeedEvent>,
+    // also held while publishing, so a resuming client neither misses nor repeats a change
+    pub(in crate::service) buffer: Arc<Mutex<FeedBuffer>>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+}
+
+impl ChangeFeedService {
+    pub fn new(ledger_service: Arc<LedgerService>) -> Self {
+        let (sender, _) = broadcast::channel(CHANGE_FEED_CAPACITY);
+        ChangeFeedService {
+            epoch: Uuid::new_v4(),
+            sender,
+            buffer: Arc::new(Mutex::new(FeedBuffer {
+                next_sequence: 1,
+                events: VecDeque::with_capacity(CHANGE_FEED_BUFFER_SIZE),
+            })),
+            ledger_service,
+        }
+    }
+}
+
+// what a resuming client gets, complete is false if changes since its last event were dropped
+pub struct FeedResume {
+    pub missed: Vec<FeedEvent>,
+    pub complete: bool,
+    pub receiver: broadcast::Receiver<FeedEvent>,
+}
@@ -0,0 +1,24 @@
+use uuid::Uuid;
+
+use crate::domain::api_key::{ApiKey, CreatedApiKey};
+use crate::service::api_key::{ApiKeyNew, ApiKeyService};
+use crate::service::application_error::ApplicationError;
+use crate::service::ledger::authorize_operator;
+
+// keys are handed out by the operator only, a user could otherwise mint keys beyond their roles
+impl ApiKeyService {
+    pub fn create(&self, dto: ApiKeyNew) -> Result<CreatedApiKey, ApplicationError> {
+        authorize_operator()?;
+        let created = ApiKey::generate(dto.name, dto.user)?;
+        let api_key = self.write_repo.insert(created.api_key)?;
+        Ok(CreatedApiKey {
+            api_key,
+            key: created.key,
+        })
+    }
+
+    pub fn revoke(&self, id: Uuid) -> Result<(), ApplicationError> {
+        authorize_operator()?;
+        self.write_repo.revoke(id)
+    }
+}
@@ -0,0 +1,73 @@
+use uuid::Uuid;
+
+use crate::domain::attachment::Attachment;
+use crate::domain::ledger_member::LedgerRole;
+use crate::service::application_error::ApplicationError;
+use crate::service::attachment::{AttachmentNew, AttachmentService};
+
+impl AttachmentService {
+    pub fn create(
+        &self,
+        ledger_id: Uuid,
+        expense_entry_id: Uuid,
+        dto: AttachmentNew,
+    ) -> Result<Attachment, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        // the expense entry has to exist in the ledger
+        self.expense_entry_read_repo
+            .get(ledger_id, expense_entry_id)?;
+
+        let attachment =
+            Attachment::new(expense_entry_id, dto.file_name, &dto.content, self.max_size)?;
+
+        // identical files are only stored once
+        let _blob_lock = self.lock_blob(attachment.content_hash());
+        if self.read_repo.count_by_hash(attachment.content_hash())? == 0 {
+            self.blob_store
+                .put(attachment.content_hash(), &dto.content)?;
+        }
+        self.write_repo.insert(ledger_id, attachment)
+    }
+
+    pub fn delete(
+        &self,
+        ledger_id: Uuid,
+        expense_entry_id: Uuid,
+        id: Uuid,
+    ) -> Result<(), ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        self.expense_entry_read_repo
+            .get(ledger_id, expense_entry_id)?;
+        let attachment = self.read_repo.get(expense_entry_id, id)?;
+        self.delete_with_blob(ledger_id, &attachment)
+    }
+
+    // cleanup once the owning expense entry is gone
+    pub fn delete_all_for_expense_entry(
+        &self,
+        ledger_id: Uuid,
+        expense_entry_id: Uuid,
+    ) -> Result<(), ApplicationError> {
+        for attachment in self.read_repo.list(expense_entry_id)? {
+            self.delete_with_blob(ledger_id, &attachment)?;
+        }
+        Ok(())
+    }
+
+    // the blob goes once the last attachment referencing it is gone
+    fn delete_with_blob(
+        &self,
+        ledger_id: Uuid,
+        attachment: &Attachment,
+    ) -> Result<(), ApplicationError> {
+        let _blob_lock = self.lock_blob(attachment.content_hash());
+        self.write_repo
+            .delete(ledger_id, attachment.expense_entry_id(), attachment.id())?;
+        if self.read_repo.count_by_hash(attachment.content_hash())? == 0 {
+            self.blob_store.delete(attachment.content_hash())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::budget::{Budget, BudgetAlert};
+use crate::domain::expense_entry::ExpenseEntry;
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::outbox::{ChangeEvent, ChangedEntity};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::budget::{BudgetNew, BudgetService};
+use crate::service::outbox::OutboxHandlerPort;
+
+impl BudgetService {
+    pub fn create(&self, ledger_id: Uuid, dto: BudgetNew) -> Result<Budget, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        let budget = Budget::try_from(dto)?;
+        self.write_repo.insert(ledger_id, budget)
+    }
+
+    pub fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        self.write_repo.delete(ledger_id, id)
+    }
+
+    pub fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<Budget, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        self.write_repo.restore(ledger_id, id)?;
+        self.read_repo.get(ledger_id, id)
+    }
+
+    pub fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<usize, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        Ok(self.write_repo.purge(ledger_id, deleted_before)?.len())
+    }
+
+    // queues an alert for every threshold the entry's budgets reached, each one only once per
+    // period, the outbox hands them to the notifier
+    pub fn evaluate_alerts(
+        &self,
+        ledger_id: Uuid,
+        entry: &ExpenseEntry,
+    ) -> Result<Vec<BudgetAlert>, ApplicationError> {
+        let date = entry.expense_date().date_naive();
+        let mut alerts = Vec::new();
+
+        for budget in self
+            .read_repo
+            .find_by_expense_type(ledger_id, entry.expense_type())?
+        {
+            if budget.amount_of(entry) == 0.0 {
+                continue;
+            }
+            let status = self.status_of(ledger_id, &budget, date)?;
+            for threshold in status.reached_thresholds() {
+                let alert = budget.alert(&status, threshold);
+                if self.write_repo.claim_alert(ledger_id, &alert)? {
+                    alerts.push(alert);
+                }
+            }
+        }
+
+        Ok(alerts)
+    }
+}
+
+impl OutboxHandlerPort for BudgetService {
+    fn name(&self) -> &str {
+        "budget_alerts"
+    }
+
+    fn handle(&self, event: &ChangeEvent) -> Result<(), ApplicationError> {
+        if event.entity != ChangedEntity::BudgetAlert {
+            return Ok(());
+        }
+        let alert: BudgetAlert = event
+            .data
+            .clone()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|err| ApplicationError {
+                error_type: ApplicationErrorType::Internal,
+                message: format!("Serialization error: {err}"),
+            })?
+            .ok_or_else(|| ApplicationError {
+                error_type: ApplicationErrorType::Internal,
+                message: String::from("Budget alert without data."),
+            })?;
+        self.notifier.notify(&alert)
+    }
+}
@@ -0,0 +1,50 @@
+use uuid::Uuid;
+
+use crate::domain::categorisation_rule::CategorisationRule;
+use crate::domain::ledger_member::LedgerRole;
+use crate::service::application_error::ApplicationError;
+use crate::service::categorisation_rule::{CategorisationRuleNew, CategorisationRuleService};
+
+impl CategorisationRuleService {
+    pub fn create(
+        &self,
+        ledger_id: Uuid,
+        dto: CategorisationRuleNew,
+    ) -> Result<CategorisationRule, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        let rule = CategorisationRule::try_from(dto)?;
+        self.write_repo.insert(ledger_id, rule)
+    }
+
+    pub fn update(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        dto: CategorisationRuleNew,
+    ) -> Result<CategorisationRule, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.read_repo.get(ledger_id, id)?;
+        let validated_rule = CategorisationRule::try_from(dto)?;
+        let rule = CategorisationRule::restore(
+            id,
+            validated_rule.name().to_string(),
+            validated_rule.priority(),
+            validated_rule.description_pattern().map(String::from),
+            validated_rule.min_amount(),
+            validated_rule.max_amount(),
+            validated_rule.cost_bearer_id(),
+            validated_rule.set_expense_type(),
+            validated_rule.add_tags().to_vec(),
+            validated_rule.rewrite_description().map(String::from),
+        );
+        self.write_repo.update(ledger_id, rule)
+    }
+
+    pub fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.write_repo.delete(ledger_id, id)
+    }
+}
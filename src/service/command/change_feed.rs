@@ -0,0 +1,34 @@
+use crate::domain::change_feed::FeedEvent;
+use crate::domain::outbox::ChangeEvent;
+use crate::service::application_error::ApplicationError;
+use crate::service::change_feed::{CHANGE_FEED_BUFFER_SIZE, ChangeFeedService};
+use crate::service::outbox::OutboxHandlerPort;
+
+impl OutboxHandlerPort for ChangeFeedService {
+    fn name(&self) -> &str {
+        "change_feed"
+    }
+
+    fn handle(&self, event: &ChangeEvent) -> Result<(), ApplicationError> {
+        if !event.entity.is_published() {
+            return Ok(());
+        }
+        let mut buffer = self
+            .buffer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let feed_event = FeedEvent {
+            epoch: self.epoch,
+            sequence: buffer.next_sequence,
+            change: event.clone(),
+        };
+        buffer.next_sequence += 1;
+        if buffer.events.len() == CHANGE_FEED_BUFFER_SIZE {
+            buffer.events.pop_front();
+        }
+        buffer.events.push_back(feed_event.clone());
+        // without connected clients there is nobody to tell
+        let _ = self.sender.send(feed_event);
+        Ok(())
+    }
+}
@@ -1,29 +1,106 @@
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::domain::cost_bearer::CostBearer;
+use crate::domain::ledger_member::LedgerRole;
 use crate::service::application_error::{ApplicationError, ApplicationErrorType};
-use crate::service::cost_bearer::CostBearerNew;
+use crate::service::cost_bearer::{CostBearerNew, CostBearerService};
 
-use crate::test_util::test_utility::TEST_VALID_UUID;
+impl CostBearerService {
+    pub fn create(
+        &self,
+        ledger_id: Uuid,
+        dto: CostBearerNew,
+    ) -> Result<CostBearer, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        let cost_bearer = CostBearer::try_from(dto)?;
+        self.write_repo.insert(ledger_id, cost_bearer)
+    }
 
-pub fn create(dto: CostBearerNew) -> Result<CostBearer, ApplicationError> {
-    let cost_bearer = CostBearer::try_from(dto)?;
+    pub fn update(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        dto: CostBearerNew,
+    ) -> Result<CostBearer, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.read_repo.get(ledger_id, id)?;
+        let validated_cost_bearer = CostBearer::try_from(dto)?;
+        let cost_bearer = CostBearer::restore(
+            id,
+            validated_cost_bearer.name().to_string(),
+            validated_cost_bearer.exists_from(),
+            validated_cost_bearer.exists_to(),
+            validated_cost_bearer.private_to().to_vec(),
+        );
+        self.write_repo.update(ledger_id, cost_bearer)
+    }
 
-    // todo - save to DB
+    // referenced cost bearers can only be deleted when their cost shares move to another one
+    pub fn delete(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        reassign_to: Option<Uuid>,
+    ) -> Result<(), ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.read_repo.get(ledger_id, id)?;
+        if let Some(target_id) = reassign_to {
+            self.merge_target(ledger_id, id, target_id)
+                .ok_or_else(|| ApplicationError {
+                    error_type: ApplicationErrorType::ValidationFailed,
+                    message: String::from("Query without valid reassign target."),
+                })?;
+        }
+        self.write_repo.delete(ledger_id, id, reassign_to)
+    }
 
-    Ok(cost_bearer)
-}
+    // moves every cost share, budget, recurring expense and rule to the target and deletes the
+    // source, shares of an entry or recurring expense that end up on the target twice are combined
+    pub fn merge(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        target_id: Uuid,
+    ) -> Result<CostBearer, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.read_repo.get(ledger_id, id)?;
+        let target =
+            self.merge_target(ledger_id, id, target_id)
+                .ok_or_else(|| ApplicationError {
+                    error_type: ApplicationErrorType::ValidationFailed,
+                    message: String::from("Json without valid target id."),
+                })?;
+        self.write_repo.delete(ledger_id, id, Some(target_id))?;
+        Ok(target)
+    }
 
-pub fn update(id: Uuid, dto: CostBearerNew) -> Result<CostBearer, ApplicationError> {
-    todo!()
-}
+    // the target has to be another cost bearer of the same ledger
+    fn merge_target(&self, ledger_id: Uuid, id: Uuid, target_id: Uuid) -> Option<CostBearer> {
+        match target_id == id {
+            true => None,
+            false => self.read_repo.get(ledger_id, target_id).ok(),
+        }
+    }
+
+    pub fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<CostBearer, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.write_repo.restore(ledger_id, id)?;
+        self.read_repo.get(ledger_id, id)
+    }
 
-pub fn delete(id: Uuid) -> Result<(), ApplicationError> {
-    match id {
-        id if id == TEST_VALID_UUID => Ok(()),
-        _ => Err(ApplicationError {
-            error_type: ApplicationErrorType::NotFound,
-            message: String::from("Cost Bearer not found."),
-        }),
+    pub fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<usize, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        Ok(self.write_repo.purge(ledger_id, deleted_before)?.len())
     }
 }
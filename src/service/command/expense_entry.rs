@@ -1,22 +1,410 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+use crate::domain::categorisation_rule::{CategorisationRule, categorise};
 use crate::domain::expense_entry::ExpenseEntry;
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::tag::{Tag, TagUsage};
 use crate::service::application_error::{ApplicationError, ApplicationErrorType};
-use crate::service::expense_entry::{ExpenseEntryNew, ExpenseEntryService};
+use crate::service::expense_entry::{
+    BatchMode, Categorisation, DuplicatePolicy, ExpenseEntryBatch, ExpenseEntryFilter,
+    ExpenseEntryNew, ExpenseEntryOperation, ExpenseEntryOperationOutcome, ExpenseEntryService,
+    ExpenseEntryWrite, MAX_BATCH_OPERATIONS,
+};
+use crate::service::query::expense_entry::redact_hidden;
+
+fn expense_entry_not_found() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::NotFound,
+        message: String::from("Expense entry not found."),
+    }
+}
 
-use crate::test_util::test_utility::TEST_VALID_UUID;
+// the existing entry with the validated changes, an update without a date keeps the original one
+fn updated_entry(
+    existing_entry: &ExpenseEntry,
+    dto: ExpenseEntryNew,
+) -> Result<ExpenseEntry, ApplicationError> {
+    let validated_entry = ExpenseEntry::new(
+        dto.cost_shares,
+        dto.expense_type,
+        dto.description,
+        dto.expense_date.unwrap_or(existing_entry.expense_date()),
+        dto.tags,
+    )?;
+    Ok(ExpenseEntry::restore(
+        existing_entry.id(),
+        validated_entry.expense_date(),
+        validated_entry.cost_shares().to_vec(),
+        validated_entry.expense_type(),
+        validated_entry.description().to_string(),
+        validated_entry.tags().to_vec(),
+    ))
+}
 
-impl ExpenseEntryService {
-    pub fn create(&self, dto: ExpenseEntryNew) -> Result<ExpenseEntry, ApplicationError> {
+// a validated operation of a transactional batch
+enum PreparedWrite {
+    Insert(ExpenseEntry),
+    Update(ExpenseEntry),
+    Delete(Uuid),
+}
+
+impl PreparedWrite {
+    fn write(&self) -> ExpenseEntryWrite {
+        match self {
+            PreparedWrite::Insert(entry) => ExpenseEntryWrite::Insert(entry.clone()),
+            PreparedWrite::Update(entry) => ExpenseEntryWrite::Update(entry.clone()),
+            PreparedWrite::Delete(id) => ExpenseEntryWrite::Delete(*id),
+        }
+    }
+}
+
+// how an entry is created, batches may run it through the rules of the ledger like an import
+#[derive(Clone, Copy)]
+struct CreateOptions<'a> {
+    block_duplicates: bool,
+    rules: &'a [CategorisationRule],
+}
+
+impl CreateOptions<'_> {
+    fn prepared_entry(&self, dto: ExpenseEntryNew) -> Result<ExpenseEntry, ApplicationError> {
         let expense_entry = ExpenseEntry::try_from(dto)?;
-        self.write_repo.insert(expense_entry)
+        Ok(match self.rules.is_empty() {
+            true => expense_entry,
+            false => categorise(self.rules, &expense_entry)?,
+        })
+    }
+}
+
+impl ExpenseEntryService {
+    pub fn create(
+        &self,
+        ledger_id: Uuid,
+        dto: ExpenseEntryNew,
+    ) -> Result<ExpenseEntry, ApplicationError> {
+        self.create_entry(
+            ledger_id,
+            dto,
+            CreateOptions {
+                block_duplicates: true,
+                rules: &[],
+            },
+        )
+    }
+
+    // creates the entry even if the duplicate policy would block it, for entries known to be
+    // meant twice
+    pub fn create_allowing_duplicates(
+        &self,
+        ledger_id: Uuid,
+        dto: ExpenseEntryNew,
+    ) -> Result<ExpenseEntry, ApplicationError> {
+        self.create_entry(
+            ledger_id,
+            dto,
+            CreateOptions {
+                block_duplicates: false,
+                rules: &[],
+            },
+        )
+    }
+
+    fn create_entry(
+        &self,
+        ledger_id: Uuid,
+        dto: ExpenseEntryNew,
+        options: CreateOptions,
+    ) -> Result<ExpenseEntry, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        let expense_entry = options.prepared_entry(dto)?;
+        if options.block_duplicates {
+            self.reject_duplicate(ledger_id, &expense_entry)?;
+        }
+        let created_entry = self.write_repo.insert(ledger_id, expense_entry)?;
+        self.evaluate_budget_alerts(ledger_id, &created_entry);
+        Ok(created_entry)
+    }
+
+    pub fn update(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        dto: ExpenseEntryNew,
+    ) -> Result<ExpenseEntry, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        let existing_entry = self.read_repo.get(ledger_id, id)?;
+        let expense_entry = updated_entry(&existing_entry, dto)?;
+        let updated_entry = self.write_repo.update(ledger_id, id, expense_entry)?;
+        self.evaluate_budget_alerts(ledger_id, &updated_entry);
+        Ok(updated_entry)
+    }
+
+    pub fn apply_batch(
+        &self,
+        ledger_id: Uuid,
+        batch: ExpenseEntryBatch,
+    ) -> Result<Vec<ExpenseEntryOperationOutcome>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        if batch.operations.is_empty() || batch.operations.len() > MAX_BATCH_OPERATIONS {
+            return Err(ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: format!("Json without 1 to {MAX_BATCH_OPERATIONS} operations."),
+            });
+        }
+        let rules = match batch.apply_rules {
+            true => self.categorisation_rule_service.list(ledger_id)?,
+            false => Vec::new(),
+        };
+        let options = CreateOptions {
+            block_duplicates: !batch.allow_duplicate,
+            rules: &rules,
+        };
+        match batch.mode {
+            BatchMode::Transactional => {
+                self.apply_transactional(ledger_id, batch.operations, options)
+            }
+            BatchMode::BestEffort => Ok(batch
+                .operations
+                .into_iter()
+                .map(|operation| self.apply_operation(ledger_id, operation, options))
+                .collect()),
+        }
+    }
+
+    fn apply_operation(
+        &self,
+        ledger_id: Uuid,
+        operation: ExpenseEntryOperation,
+        options: CreateOptions,
+    ) -> ExpenseEntryOperationOutcome {
+        let outcome = match operation {
+            ExpenseEntryOperation::Create { entry } => self
+                .create_entry(ledger_id, entry, options)
+                .map(ExpenseEntryOperationOutcome::Created),
+            ExpenseEntryOperation::Update { id, entry } => self
+                .update(ledger_id, id, entry)
+                .map(ExpenseEntryOperationOutcome::Updated),
+            ExpenseEntryOperation::Delete { id } => self
+                .delete(ledger_id, id)
+                .map(|()| ExpenseEntryOperationOutcome::Deleted(id)),
+        };
+        outcome.unwrap_or_else(ExpenseEntryOperationOutcome::Failed)
+    }
+
+    // every operation is validated against the state the earlier ones leave behind, only when
+    // all of them are valid they are written together
+    fn apply_transactional(
+        &self,
+        ledger_id: Uuid,
+        operations: Vec<ExpenseEntryOperation>,
+        options: CreateOptions,
+    ) -> Result<Vec<ExpenseEntryOperationOutcome>, ApplicationError> {
+        let mut changed = HashMap::new();
+        let prepared: Vec<_> = operations
+            .into_iter()
+            .map(|operation| self.prepare_write(ledger_id, operation, options, &mut changed))
+            .collect();
+        if prepared.iter().any(Result::is_err) {
+            return Ok(prepared
+                .into_iter()
+                .map(|prepared| match prepared {
+                    Ok(_) => ExpenseEntryOperationOutcome::RolledBack,
+                    Err(err) => ExpenseEntryOperationOutcome::Failed(err),
+                })
+                .collect());
+        }
+        let prepared: Vec<PreparedWrite> = prepared.into_iter().flatten().collect();
+        let writes: Vec<ExpenseEntryWrite> = prepared.iter().map(PreparedWrite::write).collect();
+        self.write_repo.apply(ledger_id, &writes)?;
+
+        let mut outcomes = Vec::with_capacity(prepared.len());
+        for prepared_write in prepared {
+            outcomes.push(match prepared_write {
+                PreparedWrite::Insert(entry) => {
+                    self.evaluate_budget_alerts(ledger_id, &entry);
+                    ExpenseEntryOperationOutcome::Created(entry)
+                }
+                PreparedWrite::Update(entry) => {
+                    self.evaluate_budget_alerts(ledger_id, &entry);
+                    ExpenseEntryOperationOutcome::Updated(entry)
+                }
+                PreparedWrite::Delete(id) => ExpenseEntryOperationOutcome::Deleted(id),
+            });
+        }
+        Ok(outcomes)
+    }
+
+    // `changed` holds the entries of earlier operations of the batch, deleted ones as None
+    fn prepare_write(
+        &self,
+        ledger_id: Uuid,
+        operation: ExpenseEntryOperation,
+        options: CreateOptions,
+        changed: &mut HashMap<Uuid, Option<ExpenseEntry>>,
+    ) -> Result<PreparedWrite, ApplicationError> {
+        let current = |id: Uuid| match changed.get(&id) {
+            Some(Some(entry)) => Ok(entry.clone()),
+            Some(None) => Err(expense_entry_not_found()),
+            None => self.read_repo.get(ledger_id, id),
+        };
+        match operation {
+            ExpenseEntryOperation::Create { entry } => {
+                let expense_entry = options.prepared_entry(entry)?;
+                self.verify_references(ledger_id, &expense_entry)?;
+                if options.block_duplicates {
+                    self.reject_duplicate(ledger_id, &expense_entry)?;
+                }
+                changed.insert(expense_entry.id(), Some(expense_entry.clone()));
+                Ok(PreparedWrite::Insert(expense_entry))
+            }
+            ExpenseEntryOperation::Update { id, entry } => {
+                let existing_entry = current(id)?;
+                let expense_entry = updated_entry(&existing_entry, entry)?;
+                self.verify_references(ledger_id, &expense_entry)?;
+                changed.insert(id, Some(expense_entry.clone()));
+                Ok(PreparedWrite::Update(expense_entry))
+            }
+            ExpenseEntryOperation::Delete { id } => {
+                current(id)?;
+                changed.insert(id, None);
+                Ok(PreparedWrite::Delete(id))
+            }
+        }
+    }
+
+    // writes what the categorisation rules change on the entries matching the filter in one go
+    pub fn categorise(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<Categorisation>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        let categorisations = self.categorisations(ledger_id, filter)?;
+        if categorisations.is_empty() {
+            return Ok(categorisations);
+        }
+        let writes: Vec<ExpenseEntryWrite> = categorisations
+            .iter()
+            .map(|categorisation| ExpenseEntryWrite::Update(categorisation.after.clone()))
+            .collect();
+        self.write_repo.apply(ledger_id, &writes)?;
+        for categorisation in &categorisations {
+            self.evaluate_budget_alerts(ledger_id, &categorisation.after);
+        }
+        Ok(categorisations)
+    }
+
+    // attachments stay until the entry is purged, so a restored entry gets them back
+    pub fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        self.write_repo.delete(ledger_id, id)
+    }
+
+    pub fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseEntry, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        self.write_repo.restore(ledger_id, id)?;
+        let restored_entry = self.read_repo.get(ledger_id, id)?;
+        let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+        Ok(redact_hidden(restored_entry, &hidden))
+    }
+
+    pub fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<usize, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        let purged_ids = self.write_repo.purge(ledger_id, deleted_before)?;
+        for id in &purged_ids {
+            self.attachment_service
+                .delete_all_for_expense_entry(ledger_id, *id)?;
+        }
+        Ok(purged_ids.len())
+    }
+
+    pub fn rename_tag(
+        &self,
+        ledger_id: Uuid,
+        tag: &str,
+        new_tag: &str,
+    ) -> Result<TagUsage, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        let tag_not_found = || ApplicationError {
+            error_type: ApplicationErrorType::NotFound,
+            message: String::from("Tag not found."),
+        };
+        let tag = Tag::new(tag).map_err(|_| tag_not_found())?;
+        let new_tag = Tag::new(new_tag).map_err(|_| ApplicationError {
+            error_type: ApplicationErrorType::ValidationFailed,
+            message: String::from("Json without valid name."),
+        })?;
+        // renaming a tag onto itself would delete it
+        if tag != new_tag {
+            self.write_repo.rename_tag(ledger_id, &tag, &new_tag)?;
+        }
+        let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+        self.read_repo
+            .tags(ledger_id, &hidden)?
+            .into_iter()
+            .find(|usage| usage.tag == new_tag)
+            .ok_or_else(tag_not_found)
+    }
+
+    // only blocks while the duplicate policy says so
+    fn reject_duplicate(
+        &self,
+        ledger_id: Uuid,
+        entry: &ExpenseEntry,
+    ) -> Result<(), ApplicationError> {
+        if self.duplicate_policy != DuplicatePolicy::Block {
+            return Ok(());
+        }
+        // entries hidden from the member still block, without giving away their id
+        let message = match self.duplicate_candidates(ledger_id, entry)?.first() {
+            Some(pair) if pair.duplicate_of.is_redacted() => {
+                String::from("Expense entry looks like a duplicate of an existing entry.")
+            }
+            Some(pair) => format!(
+                "Expense entry looks like a duplicate of {}.",
+                pair.duplicate_of.id()
+            ),
+            None => return Ok(()),
+        };
+        Err(ApplicationError {
+            error_type: ApplicationErrorType::Conflict,
+            message,
+        })
     }
 
-    pub fn update(&self, id: Uuid, dto: ExpenseEntryNew) -> Result<ExpenseEntry, ApplicationError> {
-        todo!()
+    // cost bearers and the expense type have to belong to the entry's ledger
+    fn verify_references(
+        &self,
+        ledger_id: Uuid,
+        entry: &ExpenseEntry,
+    ) -> Result<(), ApplicationError> {
+        let cost_bearer_ids: Vec<Uuid> = entry
+            .cost_shares()
+            .iter()
+            .map(|share| share.cost_bearer_id)
+            .collect();
+        self.ledger_service
+            .verify_references(ledger_id, &cost_bearer_ids, &[entry.expense_type()])
     }
 
-    pub fn delete(&self, id: Uuid) -> Result<(), ApplicationError> {
-        self.write_repo.delete(id)
+    // the entry is stored either way, a failing alert must not fail the request
+    pub(in crate::service) fn evaluate_budget_alerts(&self, ledger_id: Uuid, entry: &ExpenseEntry) {
+        if let Err(err) = self.budget_service.evaluate_alerts(ledger_id, entry) {
+            eprintln!("Evaluating budget alerts failed: {}", err.message);
+        }
     }
 }
@@ -1,29 +1,139 @@
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::domain::expense_type::ExpenseType;
+use crate::domain::expense_type::{ExpenseType, ExpenseTypeHierarchy, ExpenseTypeValidationError};
+use crate::domain::ledger_member::LedgerRole;
 use crate::service::application_error::{ApplicationError, ApplicationErrorType};
-use crate::service::expense_type::ExpenseTypeNew;
+use crate::service::expense_type::{ExpenseTypeNew, ExpenseTypeService};
 
-use crate::test_util::test_utility::TEST_VALID_UUID;
+impl ExpenseTypeService {
+    pub fn create(
+        &self,
+        ledger_id: Uuid,
+        dto: ExpenseTypeNew,
+    ) -> Result<ExpenseType, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        // the parent has to be one of the ledger's own expense types, checked while writing
+        let expense_type = ExpenseType::try_from(dto)?;
+        self.write_repo.insert(ledger_id, expense_type)
+    }
 
-pub fn create(dto: ExpenseTypeNew) -> Result<ExpenseType, ApplicationError> {
-    let expense_type = ExpenseType::try_from(dto)?;
+    pub fn update(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        dto: ExpenseTypeNew,
+    ) -> Result<ExpenseType, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.read_repo.get(ledger_id, id)?;
+        let validated_expense_type = ExpenseType::try_from(dto)?;
+        let expense_type = ExpenseType::restore(
+            id,
+            validated_expense_type.name().to_string(),
+            validated_expense_type.description().to_string(),
+            validated_expense_type.parent_id(),
+        );
+        self.write_repo.update(ledger_id, expense_type)
+    }
 
-    // todo - save to DB
+    // referenced expense types can only be deleted when their entries and everything else using
+    // them move to another one
+    pub fn delete(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        reassign_to: Option<Uuid>,
+    ) -> Result<(), ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.read_repo.get(ledger_id, id)?;
+        // sub types would be left without their parent
+        if ExpenseTypeHierarchy::new(self.read_repo.list(ledger_id)?).has_children(id) {
+            return Err(ApplicationError {
+                error_type: ApplicationErrorType::Conflict,
+                message: String::from("Expense type still has sub types."),
+            });
+        }
+        if let Some(target_id) = reassign_to {
+            self.merge_target(ledger_id, id, target_id)
+                .ok_or_else(|| ApplicationError {
+                    error_type: ApplicationErrorType::ValidationFailed,
+                    message: String::from("Query without valid reassign target."),
+                })?;
+        }
+        self.write_repo.delete(ledger_id, id, reassign_to)
+    }
 
-    Ok(expense_type)
-}
+    // moves every expense entry, budget, recurring expense and rule to the target and deletes the
+    // source
+    pub fn merge(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        target_id: Uuid,
+    ) -> Result<ExpenseType, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.read_repo.get(ledger_id, id)?;
+        let target =
+            self.merge_target(ledger_id, id, target_id)
+                .ok_or_else(|| ApplicationError {
+                    error_type: ApplicationErrorType::ValidationFailed,
+                    message: String::from("Json without valid target id."),
+                })?;
+        // sub types have no obvious place under the target, they are merged or moved first
+        if ExpenseTypeHierarchy::new(self.read_repo.list(ledger_id)?).has_children(id) {
+            return Err(ApplicationError {
+                error_type: ApplicationErrorType::Conflict,
+                message: String::from("Expense type still has sub types."),
+            });
+        }
+        self.write_repo.delete(ledger_id, id, Some(target_id))?;
+        Ok(target)
+    }
 
-pub fn update(id: Uuid, dto: ExpenseTypeNew) -> Result<ExpenseType, ApplicationError> {
-    todo!()
-}
+    // the target has to be another expense type of the same ledger
+    fn merge_target(&self, ledger_id: Uuid, id: Uuid, target_id: Uuid) -> Option<ExpenseType> {
+        match target_id == id {
+            true => None,
+            false => self.read_repo.get(ledger_id, target_id).ok(),
+        }
+    }
+
+    pub fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseType, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        let expense_type = self.read_repo.get_deleted(ledger_id, id)?;
+        // the name may have been taken or the parent deleted in the meantime
+        match ExpenseTypeHierarchy::new(self.read_repo.list(ledger_id)?).validate(&expense_type) {
+            Ok(()) => {}
+            Err(ExpenseTypeValidationError::UnknownParent) => {
+                return Err(ApplicationError {
+                    error_type: ApplicationErrorType::Conflict,
+                    message: String::from("Parent expense type is deleted."),
+                });
+            }
+            Err(ExpenseTypeValidationError::DuplicateName) => {
+                return Err(ApplicationError {
+                    error_type: ApplicationErrorType::Conflict,
+                    message: String::from("Expense type with this name already exists."),
+                });
+            }
+            Err(err) => return Err(err.into()),
+        }
+        self.write_repo.restore(ledger_id, id)?;
+        Ok(expense_type)
+    }
 
-pub fn delete(id: Uuid) -> Result<(), ApplicationError> {
-    match id {
-        id if id == TEST_VALID_UUID => Ok(()),
-        _ => Err(ApplicationError {
-            error_type: ApplicationErrorType::NotFound,
-            message: String::from("Expense type not found."),
-        }),
+    pub fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<usize, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        Ok(self.write_repo.purge(ledger_id, deleted_before)?.len())
     }
 }
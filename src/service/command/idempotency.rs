@@ -0,0 +1,71 @@
+use chrono::Utc;
+
+use crate::domain::idempotency::{IdempotencyRecord, IdempotentResponse, fingerprint};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::idempotency::{IdempotencyClaim, IdempotencyService};
+use crate::service::request_context::RequestContext;
+
+// keys of different senders never meet, authenticated senders are told apart by their key id or
+// token subject, the unverified actor only separates senders while authentication is disabled
+fn scope() -> String {
+    let context = RequestContext::current();
+    match context.principal {
+        Some(principal) => principal.identity(),
+        None => context.actor,
+    }
+}
+
+fn still_running() -> ApplicationError {
+    ApplicationError {
+        error_type: ApplicationErrorType::Conflict,
+        message: String::from("A request with this Idempotency-Key is still running."),
+    }
+}
+
+impl IdempotencyService {
+    pub fn claim(
+        &self,
+        key: &str,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Result<IdempotencyClaim, ApplicationError> {
+        let scope = scope();
+        let fingerprint = fingerprint(&scope, method, path, body);
+        let record = IdempotencyRecord::new(scope, key.to_string(), fingerprint)?;
+        let expired_before = Utc::now() - self.ttl;
+        if self.write_repo.reserve(&record, expired_before)? {
+            return Ok(IdempotencyClaim::New);
+        }
+        // the first request may also have failed and released the key in the meantime
+        let Some(stored) = self
+            .read_repo
+            .get(record.scope(), record.key(), expired_before)?
+        else {
+            return Err(still_running());
+        };
+        if stored.fingerprint() != record.fingerprint() {
+            return Err(ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: String::from("Idempotency-Key was already used for a different request."),
+            });
+        }
+        match stored.response() {
+            Some(response) => Ok(IdempotencyClaim::Replay(response.clone())),
+            None => Err(still_running()),
+        }
+    }
+
+    pub fn complete(
+        &self,
+        key: &str,
+        response: &IdempotentResponse,
+    ) -> Result<(), ApplicationError> {
+        self.write_repo.complete(&scope(), key, response)
+    }
+
+    // forgets the key of a request that failed, so it can be retried with the same key
+    pub fn release(&self, key: &str) -> Result<(), ApplicationError> {
+        self.write_repo.release(&scope(), key)
+    }
+}
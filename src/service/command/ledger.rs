@@ -0,0 +1,58 @@
+use uuid::Uuid;
+
+use crate::domain::ledger::Ledger;
+use crate::domain::ledger_member::{LedgerMember, LedgerRole};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::ledger::{LedgerMemberNew, LedgerNew, LedgerService, requesting_user};
+
+impl LedgerService {
+    // the user creating a ledger becomes its first owner
+    pub fn create(&self, dto: LedgerNew) -> Result<Ledger, ApplicationError> {
+        let ledger = Ledger::try_from(dto)?;
+        let created_ledger = self.write_repo.insert(ledger)?;
+        if let Some(user) = requesting_user() {
+            self.write_repo.set_member(LedgerMember::new(
+                created_ledger.id(),
+                user,
+                LedgerRole::Owner,
+            )?)?;
+        }
+        Ok(created_ledger)
+    }
+
+    pub fn set_member(
+        &self,
+        ledger_id: Uuid,
+        user: String,
+        dto: LedgerMemberNew,
+    ) -> Result<LedgerMember, ApplicationError> {
+        self.authorize(ledger_id, LedgerRole::Owner)?;
+        let member = LedgerMember::new(ledger_id, user, dto.role)?;
+        if member.role() != LedgerRole::Owner {
+            self.keep_an_owner(ledger_id, member.user())?;
+        }
+        self.write_repo.set_member(member)
+    }
+
+    pub fn remove_member(&self, ledger_id: Uuid, user: &str) -> Result<(), ApplicationError> {
+        self.authorize(ledger_id, LedgerRole::Owner)?;
+        self.keep_an_owner(ledger_id, user)?;
+        self.write_repo.remove_member(ledger_id, user)
+    }
+
+    // the last owner can neither leave nor give up the role, nobody could manage the ledger after
+    fn keep_an_owner(&self, ledger_id: Uuid, leaving: &str) -> Result<(), ApplicationError> {
+        let members = self.read_repo.members(ledger_id)?;
+        let is_owner = |member: &&LedgerMember| member.role() == LedgerRole::Owner;
+        let leaving_owner = members
+            .iter()
+            .any(|member| is_owner(&member) && member.user() == leaving);
+        if leaving_owner && members.iter().filter(is_owner).count() == 1 {
+            return Err(ApplicationError {
+                error_type: ApplicationErrorType::Conflict,
+                message: String::from("A ledger needs at least one owner."),
+            });
+        }
+        Ok(())
+    }
+}
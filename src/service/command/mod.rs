@@ -1,3 +1,15 @@
+pub mod api_key;
+pub mod attachment;
+pub mod budget;
+pub mod categorisation_rule;
+pub mod change_feed;
 pub mod cost_bearer;
 pub mod expense_entry;
 pub mod expense_type;
+pub mod idempotency;
+pub mod ledger;
+pub mod outbox;
+pub mod projection;
+pub mod recurring_expense;
+pub mod trash;
+pub mod webhook;
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::outbox::retry_delay;
+use crate::service::application_error::ApplicationError;
+use crate::service::outbox::{
+    DISPATCH_BATCH_SIZE, MAX_DELIVERY_ATTEMPTS, OutboxHandlerPort, OutboxService,
+};
+
+impl OutboxService {
+    // handlers only receive changes not yet routed when they were registered
+    pub fn register(&self, handler: Arc<dyn OutboxHandlerPort + Send + Sync>) {
+        self.handlers
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(handler);
+    }
+
+    // the same outbox as after a restart, before the handlers are registered again
+    #[cfg(test)]
+    pub fn restarted(&self) -> Self {
+        OutboxService::new(self.read_repo.clone(), self.write_repo.clone())
+    }
+
+    // delivers the changes that are due, returns how many deliveries succeeded
+    pub fn dispatch(&self, now: DateTime<Utc>) -> Result<usize, ApplicationError> {
+        let handlers = self
+            .handlers
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        let names = handlers
+            .iter()
+            .map(|handler| handler.name().to_string())
+            .collect::<Vec<_>>();
+        self.write_repo.route(&names)?;
+        let mut delivered = 0;
+        // deliveries of handlers no longer registered wait until they are back
+        for delivery in self.read_repo.due(now, &names, DISPATCH_BATCH_SIZE)? {
+            let Some(handler) = handlers
+                .iter()
+                .find(|handler| handler.name() == delivery.handler)
+            else {
+                continue;
+            };
+            match handler.handle(&delivery.event) {
+                Ok(()) => {
+                    self.write_repo
+                        .delivered(delivery.event.id, &delivery.handler)?;
+                    delivered += 1;
+                }
+                Err(err) => {
+                    let attempts = delivery.attempts + 1;
+                    let retry_at =
+                        (attempts < MAX_DELIVERY_ATTEMPTS).then(|| now + retry_delay(attempts));
+                    self.write_repo.failed(
+                        delivery.event.id,
+                        &delivery.handler,
+                        &err.message,
+                        retry_at,
+                    )?;
+                }
+            }
+        }
+        Ok(delivered)
+    }
+}
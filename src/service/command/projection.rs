@@ -0,0 +1,17 @@
+use crate::domain::projection::ProjectionRebuild;
+use crate::service::application_error::ApplicationError;
+use crate::service::ledger::authorize_operator;
+use crate::service::projection::ProjectionService;
+
+impl ProjectionService {
+    pub fn project_pending(&self) -> Result<u64, ApplicationError> {
+        self.write_repo.project_pending()
+    }
+
+    // replays the events of every ledger
+    pub fn rebuild(&self) -> Result<ProjectionRebuild, ApplicationError> {
+        authorize_operator()?;
+        let replayed_events = self.write_repo.rebuild()?;
+        Ok(ProjectionRebuild { replayed_events })
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::domain::expense_entry::ExpenseEntry;
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::recurring_expense::{
+    ExpenseEntryTemplate, OccurrenceStatus, RecurringExpense, RecurringExpenseOccurrence,
+};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::expense_entry::ExpenseEntryNew;
+use crate::service::recurring_expense::{RecurringExpenseNew, RecurringExpenseService};
+
+impl RecurringExpenseService {
+    pub fn create(
+        &self,
+        ledger_id: Uuid,
+        dto: RecurringExpenseNew,
+    ) -> Result<RecurringExpense, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        let recurring_expense = RecurringExpense::try_from(dto)?;
+        self.write_repo.insert(ledger_id, recurring_expense)
+    }
+
+    pub fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        self.write_repo.delete(ledger_id, id)
+    }
+
+    // occurrences that fell due while in the trash are booked by the next run
+    pub fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<RecurringExpense, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        self.write_repo.restore(ledger_id, id)?;
+        self.read_repo.get(ledger_id, id)
+    }
+
+    pub fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<usize, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        Ok(self.write_repo.purge(ledger_id, deleted_before)?.len())
+    }
+
+    pub fn skip_occurrence(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        occurrence_date: NaiveDate,
+    ) -> Result<(), ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        self.verify_occurrence(ledger_id, id, occurrence_date)?;
+        self.write_repo.set_occurrence(
+            ledger_id,
+            id,
+            RecurringExpenseOccurrence {
+                occurrence_date,
+                status: OccurrenceStatus::Skipped,
+                override_template: None,
+                expense_entry_id: None,
+            },
+        )
+    }
+
+    pub fn override_occurrence(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        occurrence_date: NaiveDate,
+        dto: ExpenseEntryNew,
+    ) -> Result<RecurringExpenseOccurrence, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        self.verify_occurrence(ledger_id, id, occurrence_date)?;
+        let template = ExpenseEntryTemplate::try_from(dto)?;
+        let occurrence = RecurringExpenseOccurrence {
+            occurrence_date,
+            status: OccurrenceStatus::Overridden,
+            override_template: Some(template),
+            expense_entry_id: None,
+        };
+        self.write_repo
+            .set_occurrence(ledger_id, id, occurrence.clone())?;
+        Ok(occurrence)
+    }
+
+    // idempotent, an occurrence and its expense entry are stored together; a failing ledger or
+    // occurrence is logged and left for the next run instead of stopping the others
+    pub fn book_due_occurrences(
+        &self,
+        today: NaiveDate,
+    ) -> Result<Vec<ExpenseEntry>, ApplicationError> {
+        let mut booked = Vec::new();
+        for ledger in self.ledger_service.list()? {
+            match self.book_due_occurrences_of(ledger.id(), today) {
+                Ok(entries) => booked.extend(entries),
+                Err(err) => eprintln!(
+                    "Booking recurring expenses of ledger {} failed: {}",
+                    ledger.id(),
+                    err.message
+                ),
+            }
+        }
+        Ok(booked)
+    }
+
+    // earlier runs have handled everything up to the last booked occurrence
+    fn book_due_occurrences_of(
+        &self,
+        ledger_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<Vec<ExpenseEntry>, ApplicationError> {
+        let mut booked = Vec::new();
+
+        for recurring_expense in self.read_repo.list(ledger_id)? {
+            let schedule = recurring_expense.schedule();
+            let from = self
+                .read_repo
+                .last_booked_occurrence(recurring_expense.id())?
+                .unwrap_or(schedule.starts_on());
+            let stored: HashMap<NaiveDate, RecurringExpenseOccurrence> = self
+                .read_repo
+                .occurrences_from(recurring_expense.id(), from)?
+                .into_iter()
+                .map(|occurrence| (occurrence.occurrence_date, occurrence))
+                .collect();
+            for occurrence_date in schedule
+                .occurrences_from(from)
+                .take_while(|date| *date <= today)
+            {
+                let occurrence =
+                    stored
+                        .get(&occurrence_date)
+                        .cloned()
+                        .unwrap_or(RecurringExpenseOccurrence {
+                            occurrence_date,
+                            status: OccurrenceStatus::Pending,
+                            override_template: None,
+                            expense_entry_id: None,
+                        });
+                if !matches!(
+                    occurrence.status,
+                    OccurrenceStatus::Pending | OccurrenceStatus::Overridden
+                ) {
+                    continue;
+                }
+                match self.book_occurrence(ledger_id, &recurring_expense, &occurrence) {
+                    Ok(Some(entry)) => booked.push(entry),
+                    Ok(None) => {}
+                    Err(err) => eprintln!(
+                        "Booking occurrence {occurrence_date} of recurring expense {} failed: {}",
+                        recurring_expense.id(),
+                        err.message
+                    ),
+                }
+            }
+        }
+
+        Ok(booked)
+    }
+
+    // None if the occurrence was booked, skipped or overridden in the meantime
+    fn book_occurrence(
+        &self,
+        ledger_id: Uuid,
+        recurring_expense: &RecurringExpense,
+        occurrence: &RecurringExpenseOccurrence,
+    ) -> Result<Option<ExpenseEntry>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Editor)?;
+        let template = occurrence
+            .override_template
+            .as_ref()
+            .unwrap_or(recurring_expense.template());
+        // a booked occurrence is meant even if it resembles a manual entry
+        let entry = ExpenseEntry::try_from(ExpenseEntryNew {
+            cost_shares: template.cost_shares().to_vec(),
+            expense_type: template.expense_type(),
+            description: template.description().to_string(),
+            expense_date: occurrence
+                .occurrence_date
+                .and_hms_opt(0, 0, 0)
+                .map(|date| date.and_utc()),
+            tags: Vec::new(),
+        })?;
+        if !self.write_repo.book_occurrence(
+            ledger_id,
+            recurring_expense.id(),
+            occurrence,
+            &entry,
+        )? {
+            return Ok(None);
+        }
+        self.expense_entry_service
+            .evaluate_budget_alerts(ledger_id, &entry);
+        Ok(Some(entry))
+    }
+
+    fn verify_occurrence(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        occurrence_date: NaiveDate,
+    ) -> Result<(), ApplicationError> {
+        let recurring_expense = self.read_repo.get(ledger_id, id)?;
+        match recurring_expense.schedule().is_occurrence(occurrence_date) {
+            true => Ok(()),
+            false => Err(ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: String::from("Date is not an occurrence of this recurring expense."),
+            }),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+
+use crate::service::application_error::ApplicationError;
+use crate::service::trash::TrashService;
+
+impl TrashService {
+    // removes everything deleted before the given time for good, returns how many entities went
+    pub fn purge(&self, deleted_before: DateTime<Utc>) -> Result<usize, ApplicationError> {
+        let mut purged = 0;
+        for ledger in self.ledger_service.list()? {
+            let ledger_id = ledger.id();
+            // dependents first, cost bearers and expense types are only purged once nothing
+            // refers to them anymore
+            purged += self
+                .expense_entry_service
+                .purge(ledger_id, deleted_before)?
+                + self.budget_service.purge(ledger_id, deleted_before)?
+                + self
+                    .recurring_expense_service
+                    .purge(ledger_id, deleted_before)?
+                + self.expense_type_service.purge(ledger_id, deleted_before)?
+                + self.cost_bearer_service.purge(ledger_id, deleted_before)?;
+        }
+        Ok(purged)
+    }
+}
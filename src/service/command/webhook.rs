@@ -0,0 +1,126 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::outbox::{ChangeEvent, ChangeNotification};
+use crate::domain::webhook::{WebhookDelivery, WebhookSubscription, signature};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::ledger::requesting_user;
+use crate::service::outbox::OutboxHandlerPort;
+use crate::service::webhook::{WebhookService, WebhookSubscriptionNew};
+
+impl WebhookService {
+    pub fn create(
+        &self,
+        ledger_id: Uuid,
+        dto: WebhookSubscriptionNew,
+    ) -> Result<WebhookSubscription, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        let subscription = WebhookSubscription::new(
+            dto.url,
+            dto.events,
+            dto.secret,
+            requesting_user(),
+            self.private_targets,
+        )?;
+        self.write_repo.insert(ledger_id, subscription)
+    }
+
+    pub fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.write_repo.delete(ledger_id, id)
+    }
+
+    // posts the event to one subscriber and logs the attempt, returns whether it was accepted
+    fn deliver(
+        &self,
+        subscription: &WebhookSubscription,
+        event: &ChangeEvent,
+        attempt: u32,
+    ) -> Result<bool, ApplicationError> {
+        let body = serde_json::to_vec(&ChangeNotification::from(event)).map_err(|err| {
+            ApplicationError {
+                error_type: ApplicationErrorType::Internal,
+                message: format!("Serialization error: {err}"),
+            }
+        })?;
+        let headers = [
+            ("content-type", String::from("application/json")),
+            ("x-webhook-event", event.name()),
+            ("x-webhook-id", event.id.to_string()),
+            (
+                "x-webhook-signature",
+                format!("sha256={}", signature(subscription.secret(), &body)),
+            ),
+        ];
+        let (status_code, error) = match self.sender.send(subscription.url(), &headers, body) {
+            Ok(status_code) if (200..300).contains(&status_code) => (Some(status_code), None),
+            Ok(status_code) => (
+                Some(status_code),
+                Some(format!("Receiver answered with status {status_code}.")),
+            ),
+            Err(err) => (None, Some(err.message)),
+        };
+        let succeeded = error.is_none();
+        self.write_repo.log_delivery(&WebhookDelivery {
+            id: Uuid::new_v4(),
+            subscription_id: subscription.id(),
+            event_id: event.id,
+            event: event.name(),
+            attempt,
+            status_code,
+            error,
+            succeeded,
+            attempted_at: Utc::now(),
+        })?;
+        Ok(succeeded)
+    }
+}
+
+// the outbox retries the whole event while a subscriber fails, subscribers that already
+// accepted it are skipped
+impl OutboxHandlerPort for WebhookService {
+    fn name(&self) -> &str {
+        "webhooks"
+    }
+
+    fn handle(&self, event: &ChangeEvent) -> Result<(), ApplicationError> {
+        if !event.entity.is_published() {
+            return Ok(());
+        }
+        let mut failed = 0;
+        for subscription in self.read_repo.list(event.ledger_id)? {
+            if !subscription.matches(event) {
+                continue;
+            }
+            // the change as its creator may see it, left out once they lost access to the ledger
+            let event = match self
+                .ledger_service
+                .visible_change(subscription.created_by(), event)
+            {
+                Ok(event) => event,
+                Err(ApplicationError {
+                    error_type: ApplicationErrorType::Forbidden,
+                    ..
+                }) => continue,
+                Err(err) => return Err(err),
+            };
+            let (attempts, succeeded) = self.read_repo.attempts(subscription.id(), event.id)?;
+            if succeeded {
+                continue;
+            }
+            if !self.deliver(&subscription, &event, attempts + 1)? {
+                failed += 1;
+            }
+        }
+        match failed {
+            0 => Ok(()),
+            failed => Err(ApplicationError {
+                error_type: ApplicationErrorType::Internal,
+                message: format!("Webhook delivery failed for {failed} subscriptions."),
+            }),
+        }
+    }
+}
@@ -1,7 +1,12 @@
+use std::sync::Arc;
+
 use chrono::{DateTime, Utc};
+use uuid::Uuid;
 
 use crate::domain::cost_bearer::{CostBearer, CostBearerValidationError};
+use crate::domain::trash::TrashItem;
 use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::ledger::LedgerService;
 
 // raw unvalidated user input DTO
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -9,12 +14,19 @@ pub struct CostBearerNew {
     pub name: String,
     pub exists_from: DateTime<Utc>,
     pub exists_to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub private_to: Vec<String>,
 }
 
 impl TryFrom<CostBearerNew> for CostBearer {
     type Error = CostBearerValidationError;
     fn try_from(value: CostBearerNew) -> Result<Self, CostBearerValidationError> {
-        CostBearer::new(value.name, value.exists_from, value.exists_to)
+        CostBearer::new(
+            value.name,
+            value.exists_from,
+            value.exists_to,
+            value.private_to,
+        )
     }
 }
 
@@ -29,6 +41,66 @@ impl From<CostBearerValidationError> for ApplicationError {
                 error_type: ApplicationErrorType::ValidationFailed,
                 message: "Json without valid date.".to_string(),
             },
+            CostBearerValidationError::InvalidPrivateUser => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid private users.".to_string(),
+            },
         }
     }
 }
+
+#[derive(Clone)]
+pub struct CostBearerService {
+    pub(in crate::service) read_repo: Arc<dyn CostBearerReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn CostBearerWritePort + Send + Sync>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+}
+
+impl CostBearerService {
+    pub fn new(
+        read_repo: Arc<dyn CostBearerReadPort + Send + Sync>,
+        write_repo: Arc<dyn CostBearerWritePort + Send + Sync>,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        CostBearerService {
+            read_repo,
+            write_repo,
+            ledger_service,
+        }
+    }
+}
+
+pub trait CostBearerReadPort {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<CostBearer, ApplicationError>;
+    fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError>;
+}
+
+pub trait CostBearerWritePort {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        cost_bearer: CostBearer,
+    ) -> Result<CostBearer, ApplicationError>;
+    fn update(
+        &self,
+        ledger_id: Uuid,
+        cost_bearer: CostBearer,
+    ) -> Result<CostBearer, ApplicationError>;
+    // moves the cost bearer to the trash, its cost shares, budgets, recurring expenses and
+    // categorisation rules move to the target first if one is given, a share of an entry or
+    // recurring expense that already has one of the target is added to it; without a target
+    // it fails with a conflict while anything outside the trash still uses the cost bearer
+    fn delete(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        reassign_to: Option<Uuid>,
+    ) -> Result<(), ApplicationError>;
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+    // removes cost bearers of the ledger deleted before the given time for good, returns their ids
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError>;
+}
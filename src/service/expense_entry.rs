@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
@@ -5,7 +6,13 @@ use uuid::Uuid;
 
 use crate::domain::cost_share::CostShare;
 use crate::domain::expense_entry::{ExpenseEntry, ExpenseEntryValidationError};
+use crate::domain::tag::{Tag, TagUsage};
+use crate::domain::trash::TrashItem;
 use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::attachment::AttachmentService;
+use crate::service::budget::BudgetService;
+use crate::service::categorisation_rule::CategorisationRuleService;
+use crate::service::ledger::LedgerService;
 
 // raw unvalidated user input DTO
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -14,6 +21,8 @@ pub struct ExpenseEntryNew {
     pub expense_type: Uuid,
     pub description: String,
     pub expense_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl TryFrom<ExpenseEntryNew> for ExpenseEntry {
@@ -24,22 +33,75 @@ impl TryFrom<ExpenseEntryNew> for ExpenseEntry {
             new.expense_type,
             new.description,
             new.expense_date,
+            new.tags,
         )
     }
 }
 
+// one operation of a batch, updates and deletes name the entry they change
+#[derive(serde::Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ExpenseEntryOperation {
+    Create { entry: ExpenseEntryNew },
+    Update { id: Uuid, entry: ExpenseEntryNew },
+    Delete { id: Uuid },
+}
+
+// a transactional batch is applied completely or not at all, a best effort batch applies every
+// operation that succeeds on its own
+#[derive(serde::Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    #[default]
+    Transactional,
+    BestEffort,
+}
+
+// the most operations a single batch may hold
+pub const MAX_BATCH_OPERATIONS: usize = 500;
+
+#[derive(serde::Deserialize)]
+pub struct ExpenseEntryBatch {
+    pub operations: Vec<ExpenseEntryOperation>,
+    #[serde(default)]
+    pub mode: BatchMode,
+    // created entries are not blocked as duplicates
+    #[serde(default)]
+    pub allow_duplicate: bool,
+    // created entries are run through the categorisation rules of the ledger, as for an import
+    #[serde(default)]
+    pub apply_rules: bool,
+}
+
+// an entry the categorisation rules change, with its state before and after
+#[derive(serde::Serialize)]
+pub struct Categorisation {
+    pub before: ExpenseEntry,
+    pub after: ExpenseEntry,
+}
+
+// the result of each operation in the order of the batch
+pub enum ExpenseEntryOperationOutcome {
+    Created(ExpenseEntry),
+    Updated(ExpenseEntry),
+    Deleted(Uuid),
+    Failed(ApplicationError),
+    // valid, but not applied since another operation of the transactional batch failed
+    RolledBack,
+}
+
 impl From<ExpenseEntryValidationError> for ApplicationError {
     fn from(err: ExpenseEntryValidationError) -> Self {
         match err {
-            ExpenseEntryValidationError::DuplicateCostBearerIds(uuid) => ApplicationError {
+            ExpenseEntryValidationError::DuplicateCostBearerIds(_) => ApplicationError {
                 error_type: ApplicationErrorType::ValidationFailed,
                 message: "Json without valid cost shares.".to_string(),
             },
-            ExpenseEntryValidationError::InvalidCostBearerId(uuid) => ApplicationError {
+            ExpenseEntryValidationError::InvalidCostBearerId(_) => ApplicationError {
                 error_type: ApplicationErrorType::ValidationFailed,
                 message: "Json without valid cost shares.".to_string(),
             },
-            ExpenseEntryValidationError::InvalidExpenseTypeId(uuid) => ApplicationError {
+            ExpenseEntryValidationError::InvalidExpenseTypeId(_) => ApplicationError {
                 error_type: ApplicationErrorType::ValidationFailed,
                 message: "Json without valid expense id.".to_string(),
             },
@@ -59,34 +121,123 @@ impl From<ExpenseEntryValidationError> for ApplicationError {
                 error_type: ApplicationErrorType::ValidationFailed,
                 message: "Json without valid description.".to_string(),
             },
+            ExpenseEntryValidationError::InvalidTag(_) => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid tags.".to_string(),
+            },
         }
     }
 }
 
+// what happens to a new entry that looks like a duplicate of an existing one
+#[derive(Clone, Copy, PartialEq)]
+pub enum DuplicatePolicy {
+    Off,
+    Warn,
+    Block,
+}
+
 #[derive(Clone)]
 pub struct ExpenseEntryService {
     pub(in crate::service) read_repo: Arc<dyn ExpenseEntryReadPort + Send + Sync>,
     pub(in crate::service) write_repo: Arc<dyn ExpenseEntryWritePort + Send + Sync>,
+    pub(in crate::service) attachment_service: Arc<AttachmentService>,
+    pub(in crate::service) budget_service: Arc<BudgetService>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+    pub(in crate::service) categorisation_rule_service: Arc<CategorisationRuleService>,
+    pub(in crate::service) duplicate_policy: DuplicatePolicy,
 }
 
 impl ExpenseEntryService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         read_repo: Arc<dyn ExpenseEntryReadPort + Send + Sync>,
         write_repo: Arc<dyn ExpenseEntryWritePort + Send + Sync>,
+        attachment_service: Arc<AttachmentService>,
+        budget_service: Arc<BudgetService>,
+        ledger_service: Arc<LedgerService>,
+        categorisation_rule_service: Arc<CategorisationRuleService>,
+        duplicate_policy: DuplicatePolicy,
     ) -> Self {
         ExpenseEntryService {
             read_repo,
             write_repo,
+            attachment_service,
+            budget_service,
+            ledger_service,
+            categorisation_rule_service,
+            duplicate_policy,
         }
     }
 }
 
+// all set criteria have to match, the date range includes from and excludes to
+#[derive(Default)]
+pub struct ExpenseEntryFilter {
+    pub expense_type: Option<Uuid>,
+    pub cost_bearer_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub tags: Option<TagFilter>,
+}
+
+pub enum TagFilter {
+    AnyOf(Vec<Tag>),
+    AllOf(Vec<Tag>),
+}
+
 pub trait ExpenseEntryReadPort {
-    fn get(&self, id: Uuid) -> Result<ExpenseEntry, ApplicationError>;
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseEntry, ApplicationError>;
+    fn find(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<ExpenseEntry>, ApplicationError>;
+    // entries with a share on one of the hidden cost bearers are not counted
+    fn tags(
+        &self,
+        ledger_id: Uuid,
+        hidden: &HashSet<Uuid>,
+    ) -> Result<Vec<TagUsage>, ApplicationError>;
+    // entries with a share on one of the hidden cost bearers are listed without a label
+    fn trash(
+        &self,
+        ledger_id: Uuid,
+        hidden: &HashSet<Uuid>,
+    ) -> Result<Vec<TrashItem>, ApplicationError>;
 }
 
 pub trait ExpenseEntryWritePort {
-    fn insert(&self, entry: ExpenseEntry) -> Result<ExpenseEntry, ApplicationError>;
-    fn update(&self, id: Uuid, entry: ExpenseEntry) -> Result<ExpenseEntry, ApplicationError>;
-    fn delete(&self, id: Uuid) -> Result<(), ApplicationError>;
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        entry: ExpenseEntry,
+    ) -> Result<ExpenseEntry, ApplicationError>;
+    fn update(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        entry: ExpenseEntry,
+    ) -> Result<ExpenseEntry, ApplicationError>;
+    // moves the entry to the trash
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+    // applies all writes in one transaction, none of them if one fails
+    fn apply(&self, ledger_id: Uuid, writes: &[ExpenseEntryWrite]) -> Result<(), ApplicationError>;
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+    // removes entries of the ledger deleted before the given time for good, returns their ids
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError>;
+    // moves all entries of the ledger to the new tag, entries that already have it keep it once
+    fn rename_tag(&self, ledger_id: Uuid, tag: &Tag, new_tag: &Tag)
+    -> Result<(), ApplicationError>;
+}
+
+// a change of a batch, updated entries carry the id they are stored under
+pub enum ExpenseEntryWrite {
+    Insert(ExpenseEntry),
+    Update(ExpenseEntry),
+    Delete(Uuid),
 }
@@ -1,17 +1,26 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
 use crate::domain::expense_type::{ExpenseType, ExpenseTypeValidationError};
+use crate::domain::trash::TrashItem;
 use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::expense_entry::ExpenseEntryReadPort;
+use crate::service::ledger::LedgerService;
 
 // raw unvalidated user input DTO
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct ExpenseTypeNew {
     pub name: String,
     pub description: String,
+    pub parent_id: Option<Uuid>,
 }
 
 impl TryFrom<ExpenseTypeNew> for ExpenseType {
     type Error = ExpenseTypeValidationError;
     fn try_from(value: ExpenseTypeNew) -> Result<Self, ExpenseTypeValidationError> {
-        ExpenseType::new(value.name, value.description)
+        ExpenseType::new(value.name, value.description, value.parent_id)
     }
 }
 
@@ -30,6 +39,78 @@ impl From<ExpenseTypeValidationError> for ApplicationError {
                 error_type: ApplicationErrorType::ValidationFailed,
                 message: "Expense type with this name already exists.".to_string(),
             },
+            ExpenseTypeValidationError::UnknownParent => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid parent id.".to_string(),
+            },
+            ExpenseTypeValidationError::CyclicParent => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Expense type cannot be its own ancestor.".to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ExpenseTypeService {
+    pub(in crate::service) read_repo: Arc<dyn ExpenseTypeReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn ExpenseTypeWritePort + Send + Sync>,
+    pub(in crate::service) expense_entry_read_repo: Arc<dyn ExpenseEntryReadPort + Send + Sync>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+}
+
+impl ExpenseTypeService {
+    pub fn new(
+        read_repo: Arc<dyn ExpenseTypeReadPort + Send + Sync>,
+        write_repo: Arc<dyn ExpenseTypeWritePort + Send + Sync>,
+        expense_entry_read_repo: Arc<dyn ExpenseEntryReadPort + Send + Sync>,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        ExpenseTypeService {
+            read_repo,
+            write_repo,
+            expense_entry_read_repo,
+            ledger_service,
         }
     }
 }
+
+pub trait ExpenseTypeReadPort {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseType, ApplicationError>;
+    // only finds expense types in the trash
+    fn get_deleted(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseType, ApplicationError>;
+    fn list(&self, ledger_id: Uuid) -> Result<Vec<ExpenseType>, ApplicationError>;
+    fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError>;
+}
+
+pub trait ExpenseTypeWritePort {
+    // insert and update fail if the expense type does not fit into the ledger's hierarchy,
+    // checked in the same transaction as the write
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        expense_type: ExpenseType,
+    ) -> Result<ExpenseType, ApplicationError>;
+    fn update(
+        &self,
+        ledger_id: Uuid,
+        expense_type: ExpenseType,
+    ) -> Result<ExpenseType, ApplicationError>;
+    // moves the expense type to the trash, its expense entries, budgets, recurring expenses and
+    // categorisation rules move to the target first if one is given; without a target it fails
+    // with a conflict while anything outside the trash still uses the expense type
+    fn delete(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        reassign_to: Option<Uuid>,
+    ) -> Result<(), ApplicationError>;
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+    // removes expense types of the ledger deleted before the given time for good unless anything
+    // still points at them, returns their ids
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError>;
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::idempotency::{
+    IdempotencyRecord, IdempotencyValidationError, IdempotentResponse,
+};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+
+// default time a response is kept for retries with the same key
+pub const DEFAULT_IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+impl From<IdempotencyValidationError> for ApplicationError {
+    fn from(err: IdempotencyValidationError) -> Self {
+        match err {
+            IdempotencyValidationError::InvalidKey => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Request without valid Idempotency-Key.".to_string(),
+            },
+        }
+    }
+}
+
+// what to do with a request carrying an idempotency key
+pub enum IdempotencyClaim {
+    // first time the key is seen, the request has to be handled and its response completed
+    New,
+    // the key was used for the same request before, its response is to be sent again
+    Replay(IdempotentResponse),
+}
+
+// remembers the responses of requests sent with an idempotency key, so retried requests are
+// only handled once
+#[derive(Clone)]
+pub struct IdempotencyService {
+    pub(in crate::service) read_repo: Arc<dyn IdempotencyReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn IdempotencyWritePort + Send + Sync>,
+    pub(in crate::service) ttl: chrono::Duration,
+}
+
+impl IdempotencyService {
+    pub fn new(
+        read_repo: Arc<dyn IdempotencyReadPort + Send + Sync>,
+        write_repo: Arc<dyn IdempotencyWritePort + Send + Sync>,
+        ttl: chrono::Duration,
+    ) -> Self {
+        IdempotencyService {
+            read_repo,
+            write_repo,
+            ttl,
+        }
+    }
+}
+
+pub trait IdempotencyReadPort {
+    // the record of the key unless it was created before the given time
+    fn get(
+        &self,
+        scope: &str,
+        key: &str,
+        expired_before: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, ApplicationError>;
+}
+
+pub trait IdempotencyWritePort {
+    // stores the record unless its key is still taken, expired records are dropped first
+    fn reserve(
+        &self,
+        record: &IdempotencyRecord,
+        expired_before: DateTime<Utc>,
+    ) -> Result<bool, ApplicationError>;
+    fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        response: &IdempotentResponse,
+    ) -> Result<(), ApplicationError>;
+    fn release(&self, scope: &str, key: &str) -> Result<(), ApplicationError>;
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::domain::ledger::{Ledger, LedgerValidationError};
+use crate::domain::ledger_member::{LedgerMember, LedgerMemberValidationError, LedgerRole};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::request_context::RequestContext;
+
+// raw unvalidated user input DTO
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LedgerNew {
+    pub name: String,
+}
+
+impl TryFrom<LedgerNew> for Ledger {
+    type Error = LedgerValidationError;
+    fn try_from(value: LedgerNew) -> Result<Self, LedgerValidationError> {
+        Ledger::new(value.name)
+    }
+}
+
+impl From<LedgerValidationError> for ApplicationError {
+    fn from(err: LedgerValidationError) -> Self {
+        match err {
+            LedgerValidationError::MissingName => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid name.".to_string(),
+            },
+        }
+    }
+}
+
+// the member's user is taken from the path
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LedgerMemberNew {
+    pub role: LedgerRole,
+}
+
+impl From<LedgerMemberValidationError> for ApplicationError {
+    fn from(err: LedgerMemberValidationError) -> Self {
+        match err {
+            LedgerMemberValidationError::MissingUser => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid user.".to_string(),
+            },
+        }
+    }
+}
+
+// the user behind the current request, signed in through the sso or using an api key bound to
+// them, whose access is limited to the ledgers they are a member of; the operator's api keys,
+// background jobs and requests without authentication may access every ledger
+pub(in crate::service) fn requesting_user() -> Option<String> {
+    RequestContext::current()
+        .principal
+        .and_then(|principal| principal.acting_user().map(str::to_string))
+}
+
+// instance wide data such as the outbox or the read models is left to the operator, users only
+// ever get to see their ledgers
+pub(in crate::service) fn authorize_operator() -> Result<(), ApplicationError> {
+    match requesting_user() {
+        Some(_) => Err(ApplicationError {
+            error_type: ApplicationErrorType::Forbidden,
+            message: String::from("Missing permission for this instance."),
+        }),
+        None => Ok(()),
+    }
+}
+
+// the households sharing the instance, every other service is scoped to one of their ledgers
+#[derive(Clone)]
+pub struct LedgerService {
+    pub(in crate::service) read_repo: Arc<dyn LedgerReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn LedgerWritePort + Send + Sync>,
+}
+
+impl LedgerService {
+    pub fn new(
+        read_repo: Arc<dyn LedgerReadPort + Send + Sync>,
+        write_repo: Arc<dyn LedgerWritePort + Send + Sync>,
+    ) -> Self {
+        LedgerService {
+            read_repo,
+            write_repo,
+        }
+    }
+}
+
+pub trait LedgerReadPort {
+    fn get(&self, id: Uuid) -> Result<Ledger, ApplicationError>;
+    fn list(&self) -> Result<Vec<Ledger>, ApplicationError>;
+    fn list_of_member(&self, user: &str) -> Result<Vec<Ledger>, ApplicationError>;
+    fn members(&self, ledger_id: Uuid) -> Result<Vec<LedgerMember>, ApplicationError>;
+    fn role(&self, ledger_id: Uuid, user: &str) -> Result<Option<LedgerRole>, ApplicationError>;
+    // the private cost bearers of the ledger the user is not one of the users of
+    fn cost_bearers_hidden_from(
+        &self,
+        ledger_id: Uuid,
+        user: &str,
+    ) -> Result<Vec<Uuid>, ApplicationError>;
+    // fails unless all cost bearers and expense types are the ledger's own and not in the trash
+    fn verify_references(
+        &self,
+        ledger_id: Uuid,
+        cost_bearer_ids: &[Uuid],
+        expense_type_ids: &[Uuid],
+    ) -> Result<(), ApplicationError>;
+}
+
+pub trait LedgerWritePort {
+    fn insert(&self, ledger: Ledger) -> Result<Ledger, ApplicationError>;
+    // adds the member or changes its role
+    fn set_member(&self, member: LedgerMember) -> Result<LedgerMember, ApplicationError>;
+    fn remove_member(&self, ledger_id: Uuid, user: &str) -> Result<(), ApplicationError>;
+}
@@ -1,7 +1,22 @@
+pub mod api_key;
 pub mod application_error;
+pub mod attachment;
+pub mod audit;
+pub mod budget;
+pub mod categorisation_rule;
+pub mod change_feed;
 pub mod command;
 pub mod cost_bearer;
 pub mod expense_entry;
 pub mod expense_type;
+pub mod idempotency;
+pub mod ledger;
+pub mod outbox;
+pub mod projection;
 pub mod query;
+pub mod recurring_expense;
 pub mod repository_error;
+pub mod request_context;
+pub mod token;
+pub mod trash;
+pub mod webhook;
@@ -0,0 +1,85 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::domain::outbox::{ChangeEvent, DeadLetter, OutboxDelivery};
+use crate::service::application_error::ApplicationError;
+
+// deliveries failing this often are given up and listed as dead letters
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 8;
+
+// deliveries handed to the handlers per dispatch run
+pub const DISPATCH_BATCH_SIZE: usize = 100;
+
+// publishes the changes recorded in the outbox to the registered handlers, at least once each
+#[derive(Clone)]
+pub struct OutboxService {
+    pub(in crate::service) read_repo: Arc<dyn OutboxReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn OutboxWritePort + Send + Sync>,
+    pub(in crate::service) handlers: Arc<RwLock<Vec<Arc<dyn OutboxHandlerPort + Send + Sync>>>>,
+}
+
+impl OutboxService {
+    pub fn new(
+        read_repo: Arc<dyn OutboxReadPort + Send + Sync>,
+        write_repo: Arc<dyn OutboxWritePort + Send + Sync>,
+    ) -> Self {
+        OutboxService {
+            read_repo,
+            write_repo,
+            handlers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+}
+
+// a consumer of the published changes, it may see a change more than once and has to tolerate it
+pub trait OutboxHandlerPort {
+    // identifies the handler's deliveries, so it has to stay the same across restarts
+    fn name(&self) -> &str;
+    fn handle(&self, event: &ChangeEvent) -> Result<(), ApplicationError>;
+}
+
+pub trait OutboxReadPort {
+    // deliveries that are due for one of the handlers, oldest change first
+    fn due(
+        &self,
+        now: DateTime<Utc>,
+        handlers: &[String],
+        limit: usize,
+    ) -> Result<Vec<OutboxDelivery>, ApplicationError>;
+    fn dead_letters(&self) -> Result<Vec<DeadLetter>, ApplicationError>;
+}
+
+pub trait OutboxWritePort {
+    // creates a delivery per handler for every change not routed yet
+    fn route(&self, handlers: &[String]) -> Result<(), ApplicationError>;
+    fn delivered(&self, event_id: Uuid, handler: &str) -> Result<(), ApplicationError>;
+    // without a retry time the delivery becomes a dead letter
+    fn failed(
+        &self,
+        event_id: Uuid,
+        handler: &str,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), ApplicationError>;
+}
+
+// hands the recorded changes to the handlers in the background
+pub fn spawn_outbox_dispatcher(service: Arc<OutboxService>, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let service = service.clone();
+            // the repositories and handlers block, keep them off the async workers
+            let dispatched =
+                tokio::task::spawn_blocking(move || service.dispatch(Utc::now())).await;
+            if let Ok(Err(err)) = dispatched {
+                eprintln!("Dispatching outbox messages failed: {}", err.message);
+            }
+        }
+    })
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::domain::projection::{CostBearerBalance, ExpenseEntryRow, MonthlyTotal};
+use crate::service::application_error::ApplicationError;
+use crate::service::expense_entry::ExpenseEntryFilter;
+use crate::service::ledger::LedgerService;
+
+// the read models behind the expense entry queries, fed by the projector from the entry events
+#[derive(Clone)]
+pub struct ProjectionService {
+    pub(in crate::service) read_repo: Arc<dyn ProjectionReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn ProjectionWritePort + Send + Sync>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+}
+
+impl ProjectionService {
+    pub fn new(
+        read_repo: Arc<dyn ProjectionReadPort + Send + Sync>,
+        write_repo: Arc<dyn ProjectionWritePort + Send + Sync>,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        ProjectionService {
+            read_repo,
+            write_repo,
+            ledger_service,
+        }
+    }
+}
+
+pub trait ProjectionReadPort {
+    fn expense_entry_rows(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<ExpenseEntryRow>, ApplicationError>;
+    fn monthly_totals(&self, ledger_id: Uuid) -> Result<Vec<MonthlyTotal>, ApplicationError>;
+    fn cost_bearer_balances(
+        &self,
+        ledger_id: Uuid,
+    ) -> Result<Vec<CostBearerBalance>, ApplicationError>;
+}
+
+pub trait ProjectionWritePort {
+    // applies the events appended since the last run, returns how many there were
+    fn project_pending(&self) -> Result<u64, ApplicationError>;
+    // empties all read models and replays every event from the start
+    fn rebuild(&self) -> Result<u64, ApplicationError>;
+}
+
+// keeps the read models up to date in the background, queries catch up on their own as well
+pub fn spawn_projector(service: Arc<ProjectionService>, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let service = service.clone();
+            // the repositories block, keep them off the async workers
+            let projected = tokio::task::spawn_blocking(move || service.project_pending()).await;
+            if let Ok(Err(err)) = projected {
+                eprintln!("Projecting expense entry events failed: {}", err.message);
+            }
+        }
+    })
+}
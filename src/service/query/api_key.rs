@@ -0,0 +1,27 @@
+use crate::domain::api_key::{ApiKey, hash_key};
+use crate::service::api_key::ApiKeyService;
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::ledger::authorize_operator;
+
+impl ApiKeyService {
+    pub fn list(&self) -> Result<Vec<ApiKey>, ApplicationError> {
+        authorize_operator()?;
+        self.read_repo.list()
+    }
+
+    pub fn is_enforced(&self) -> bool {
+        self.enforced
+    }
+
+    // the active key matching the given one, missing and unknown keys are treated alike
+    pub fn authenticate(&self, key: Option<&str>) -> Result<ApiKey, ApplicationError> {
+        let unauthorized = || ApplicationError {
+            error_type: ApplicationErrorType::Unauthorized,
+            message: String::from("Missing or invalid api key."),
+        };
+        let key = key.ok_or_else(unauthorized)?;
+        self.read_repo
+            .find_active(&hash_key(key))?
+            .ok_or_else(unauthorized)
+    }
+}
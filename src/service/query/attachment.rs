@@ -0,0 +1,61 @@
+use uuid::Uuid;
+
+use crate::domain::attachment::Attachment;
+use crate::domain::ledger_member::LedgerRole;
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::attachment::{AttachmentContent, AttachmentService};
+
+impl AttachmentService {
+    pub fn list(
+        &self,
+        ledger_id: Uuid,
+        expense_entry_id: Uuid,
+    ) -> Result<Vec<Attachment>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.verify_visible(ledger_id, expense_entry_id)?;
+        self.read_repo.list(expense_entry_id)
+    }
+
+    pub fn get(
+        &self,
+        ledger_id: Uuid,
+        expense_entry_id: Uuid,
+        id: Uuid,
+    ) -> Result<AttachmentContent, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let attachment = self.read_repo.get(expense_entry_id, id)?;
+        // the attachment is only served within the ledger of its expense entry and to members
+        // allowed to see the entry
+        self.verify_visible(ledger_id, expense_entry_id)?;
+        let content = self.blob_store.get(attachment.content_hash())?;
+        Ok(AttachmentContent {
+            attachment,
+            content,
+        })
+    }
+
+    // receipts show the details a redacted expense entry leaves out
+    fn verify_visible(
+        &self,
+        ledger_id: Uuid,
+        expense_entry_id: Uuid,
+    ) -> Result<(), ApplicationError> {
+        let entry = self
+            .expense_entry_read_repo
+            .get(ledger_id, expense_entry_id)?;
+        let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+        match entry
+            .cost_shares()
+            .iter()
+            .any(|share| hidden.contains(&share.cost_bearer_id))
+        {
+            true => Err(ApplicationError {
+                error_type: ApplicationErrorType::Forbidden,
+                message: String::from("Missing permission for this expense entry."),
+            }),
+            false => Ok(()),
+        }
+    }
+}
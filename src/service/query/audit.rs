@@ -0,0 +1,28 @@
+use uuid::Uuid;
+
+use crate::domain::audit::{AuditRecord, AuditedEntity};
+use crate::domain::expense_entry::redact_snapshot;
+use crate::domain::ledger_member::LedgerRole;
+use crate::service::application_error::ApplicationError;
+use crate::service::audit::AuditService;
+
+impl AuditService {
+    pub fn history(
+        &self,
+        ledger_id: Uuid,
+        entity: AuditedEntity,
+        entity_id: Uuid,
+    ) -> Result<Vec<AuditRecord>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let mut history = self.read_repo.history(ledger_id, entity, entity_id)?;
+        if entity == AuditedEntity::ExpenseEntry {
+            let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+            for record in history.iter_mut() {
+                redact_snapshot(record.before.as_mut(), &hidden);
+                redact_snapshot(record.after.as_mut(), &hidden);
+            }
+        }
+        Ok(history)
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::domain::budget::{Budget, BudgetStatus};
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::trash::TrashItem;
+use crate::service::application_error::ApplicationError;
+use crate::service::budget::BudgetService;
+use crate::service::expense_entry::ExpenseEntryFilter;
+
+impl BudgetService {
+    pub fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<Budget, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.get(ledger_id, id)
+    }
+
+    pub fn status(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        date: NaiveDate,
+    ) -> Result<BudgetStatus, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let budget = self.read_repo.get(ledger_id, id)?;
+        self.status_of(ledger_id, &budget, date)
+    }
+
+    pub fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.trash(ledger_id)
+    }
+
+    pub(in crate::service) fn status_of(
+        &self,
+        ledger_id: Uuid,
+        budget: &Budget,
+        date: NaiveDate,
+    ) -> Result<BudgetStatus, ApplicationError> {
+        let (period_start, next_period_start) = budget.period().bounds(date);
+
+        let entries = self.expense_entry_read_repo.find(
+            ledger_id,
+            &ExpenseEntryFilter {
+                expense_type: Some(budget.expense_type()),
+                cost_bearer_id: budget.cost_bearer_id(),
+                from: period_start
+                    .and_hms_opt(0, 0, 0)
+                    .map(|start| start.and_utc()),
+                to: next_period_start
+                    .and_hms_opt(0, 0, 0)
+                    .map(|end| end.and_utc()),
+                tags: None,
+            },
+        )?;
+
+        Ok(budget.status(&entries, date))
+    }
+}
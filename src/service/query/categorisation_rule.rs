@@ -0,0 +1,20 @@
+use uuid::Uuid;
+
+use crate::domain::categorisation_rule::CategorisationRule;
+use crate::domain::ledger_member::LedgerRole;
+use crate::service::application_error::ApplicationError;
+use crate::service::categorisation_rule::CategorisationRuleService;
+
+impl CategorisationRuleService {
+    pub fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<CategorisationRule, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.get(ledger_id, id)
+    }
+
+    pub fn list(&self, ledger_id: Uuid) -> Result<Vec<CategorisationRule>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.list(ledger_id)
+    }
+}
@@ -0,0 +1,74 @@
+use tokio::sync::broadcast;
+
+use crate::domain::change_feed::{FeedEvent, parse_event_id};
+use crate::service::application_error::ApplicationError;
+use crate::service::change_feed::{ChangeFeedService, FeedResume};
+use crate::service::ledger::requesting_user;
+
+impl ChangeFeedService {
+    // the user the changes are pushed to, taken while handling the request that connects, the
+    // feed itself runs outside of it
+    pub fn subscriber(&self) -> Option<String> {
+        requesting_user()
+    }
+
+    // the change as the subscriber may see it, fails once they are no longer a member
+    pub fn visible(
+        &self,
+        subscriber: Option<&str>,
+        event: &FeedEvent,
+    ) -> Result<FeedEvent, ApplicationError> {
+        Ok(FeedEvent {
+            change: self
+                .ledger_service
+                .visible_change(subscriber, &event.change)?,
+            ..event.clone()
+        })
+    }
+
+    // receives every change published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<FeedEvent> {
+        self.sender.subscribe()
+    }
+
+    // the buffered changes after the given event id and every change published from now on
+    pub fn resume(&self, last_event_id: Option<&str>) -> FeedResume {
+        let buffer = self
+            .buffer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let receiver = self.sender.subscribe();
+        let Some(last_event_id) = last_event_id else {
+            return FeedResume {
+                missed: Vec::new(),
+                complete: true,
+                receiver,
+            };
+        };
+        let last_sequence = match parse_event_id(last_event_id) {
+            Some((epoch, sequence)) if epoch == self.epoch => sequence,
+            // ids of an earlier server run or none at all, the client has to start over
+            _ => 0,
+        };
+        let oldest_sequence = buffer
+            .events
+            .front()
+            .map_or(buffer.next_sequence, |event| event.sequence);
+        // after a gap the client starts over, the changes before it are of no use then
+        let complete = last_sequence > 0 && last_sequence + 1 >= oldest_sequence;
+        let missed = match complete {
+            true => buffer
+                .events
+                .iter()
+                .filter(|event| event.sequence > last_sequence)
+                .cloned()
+                .collect(),
+            false => Vec::new(),
+        };
+        FeedResume {
+            missed,
+            complete,
+            receiver,
+        }
+    }
+}
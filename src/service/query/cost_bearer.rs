@@ -1,22 +1,21 @@
-use chrono::{TimeZone, Utc};
 use uuid::Uuid;
 
 use crate::domain::cost_bearer::CostBearer;
-use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::trash::TrashItem;
+use crate::service::application_error::ApplicationError;
+use crate::service::cost_bearer::CostBearerService;
 
-use crate::test_util::test_utility::TEST_VALID_UUID;
+impl CostBearerService {
+    pub fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<CostBearer, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.get(ledger_id, id)
+    }
 
-pub fn get(id: Uuid) -> Result<CostBearer, ApplicationError> {
-    match id {
-        id if id == TEST_VALID_UUID => Ok(CostBearer::new(
-            String::from("Barclays Credit Card"),
-            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
-            Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
-        )
-        .unwrap()),
-        _ => Err(ApplicationError {
-            error_type: ApplicationErrorType::NotFound,
-            message: String::from("Cost Bearer not found."),
-        }),
+    pub fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.trash(ledger_id)
     }
 }
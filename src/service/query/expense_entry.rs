@@ -1,12 +1,164 @@
+use std::collections::HashSet;
+
+use chrono::Duration;
 use uuid::Uuid;
 
+use crate::domain::categorisation_rule::categorise;
+use crate::domain::duplicate::{
+    DUPLICATE_MIN_SCORE, DUPLICATE_WINDOW_DAYS, DuplicatePair, duplicate_pairs, similarity,
+};
 use crate::domain::expense_entry::ExpenseEntry;
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::tag::TagUsage;
+use crate::domain::trash::TrashItem;
 use crate::service::application_error::ApplicationError;
 
-use crate::service::expense_entry::ExpenseEntryService;
+use crate::service::expense_entry::{
+    Categorisation, DuplicatePolicy, ExpenseEntryFilter, ExpenseEntryService,
+};
+
+// entries with a share on a private cost bearer keep only their amounts for other members
+pub(in crate::service) fn redact_hidden(
+    entry: ExpenseEntry,
+    hidden: &HashSet<Uuid>,
+) -> ExpenseEntry {
+    match entry
+        .cost_shares()
+        .iter()
+        .any(|share| hidden.contains(&share.cost_bearer_id))
+    {
+        true => entry.redact(),
+        false => entry,
+    }
+}
 
 impl ExpenseEntryService {
-    pub fn get(&self, id: Uuid) -> Result<ExpenseEntry, ApplicationError> {
-        self.read_repo.get(id)
+    pub fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseEntry, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let entry = self.read_repo.get(ledger_id, id)?;
+        let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+        Ok(redact_hidden(entry, &hidden))
+    }
+
+    pub fn list(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<ExpenseEntry>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let entries = self.read_repo.find(ledger_id, filter)?;
+        let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| redact_hidden(entry, &hidden))
+            .collect())
+    }
+
+    pub fn tags(&self, ledger_id: Uuid) -> Result<Vec<TagUsage>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+        self.read_repo.tags(ledger_id, &hidden)
+    }
+
+    pub fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+        self.read_repo.trash(ledger_id, &hidden)
+    }
+
+    // stored entries the given one may duplicate, best first, compared in full but redacted
+    // where the member may not see them
+    pub(in crate::service) fn duplicate_candidates(
+        &self,
+        ledger_id: Uuid,
+        entry: &ExpenseEntry,
+    ) -> Result<Vec<DuplicatePair>, ApplicationError> {
+        if self.duplicate_policy == DuplicatePolicy::Off {
+            return Ok(Vec::new());
+        }
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let window = Duration::days(DUPLICATE_WINDOW_DAYS);
+        let nearby_entries = self.read_repo.find(
+            ledger_id,
+            &ExpenseEntryFilter {
+                from: Some(entry.expense_date() - window),
+                to: Some(entry.expense_date() + window + Duration::seconds(1)),
+                ..ExpenseEntryFilter::default()
+            },
+        )?;
+        let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+        let mut pairs: Vec<DuplicatePair> = nearby_entries
+            .into_iter()
+            .filter_map(|nearby_entry| {
+                similarity(entry, &nearby_entry)
+                    .filter(|score| *score >= DUPLICATE_MIN_SCORE)
+                    .map(|score| DuplicatePair {
+                        entry: entry.clone(),
+                        duplicate_of: redact_hidden(nearby_entry, &hidden),
+                        score,
+                    })
+            })
+            .collect();
+        pairs.sort_by(|pair, other| other.score.total_cmp(&pair.score));
+        Ok(pairs)
+    }
+
+    // stored entries the given one may duplicate, best first, entries the member may not see
+    // in full are left out
+    pub fn possible_duplicates(
+        &self,
+        ledger_id: Uuid,
+        entry: &ExpenseEntry,
+    ) -> Result<Vec<DuplicatePair>, ApplicationError> {
+        Ok(self
+            .duplicate_candidates(ledger_id, entry)?
+            .into_iter()
+            .filter(|pair| !pair.duplicate_of.is_redacted())
+            .collect())
+    }
+
+    // candidate pairs among the entries matching the filter for a member to review
+    pub fn duplicates(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<DuplicatePair>, ApplicationError> {
+        let entries = self.list(ledger_id, filter)?;
+        Ok(duplicate_pairs(
+            entries
+                .into_iter()
+                .filter(|entry| !entry.is_redacted())
+                .collect(),
+        ))
+    }
+
+    // what the categorisation rules would change on the entries matching the filter, entries
+    // the member may not see in full are left out
+    pub fn categorisations(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<Categorisation>, ApplicationError> {
+        let rules = self.categorisation_rule_service.list(ledger_id)?;
+        let entries = self.list(ledger_id, filter)?;
+        let mut categorisations = Vec::new();
+        for entry in entries.into_iter().filter(|entry| !entry.is_redacted()) {
+            let categorised_entry = categorise(&rules, &entry)?;
+            let changed = categorised_entry.expense_type() != entry.expense_type()
+                || categorised_entry.description() != entry.description()
+                || categorised_entry.tags() != entry.tags();
+            if changed {
+                categorisations.push(Categorisation {
+                    before: entry,
+                    after: categorised_entry,
+                });
+            }
+        }
+        Ok(categorisations)
     }
 }
@@ -1,20 +1,49 @@
+use std::collections::HashMap;
+
 use uuid::Uuid;
 
-use crate::domain::expense_type::ExpenseType;
-use crate::service::application_error::{ApplicationError, ApplicationErrorType};
-
-use crate::test_util::test_utility::TEST_VALID_UUID;
-
-pub fn get(id: Uuid) -> Result<ExpenseType, ApplicationError> {
-    match id {
-        id if id == TEST_VALID_UUID => Ok(ExpenseType::new(
-            String::from("Food"),
-            String::from("Expenses related to food and dining"),
-        )
-        .unwrap()),
-        _ => Err(ApplicationError {
-            error_type: ApplicationErrorType::NotFound,
-            message: String::from("Expense type not found."),
-        }),
+use crate::domain::expense_type::{ExpenseType, ExpenseTypeHierarchy, ExpenseTypeTotal};
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::trash::TrashItem;
+use crate::service::application_error::ApplicationError;
+use crate::service::expense_entry::ExpenseEntryFilter;
+use crate::service::expense_type::ExpenseTypeService;
+
+impl ExpenseTypeService {
+    pub fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<ExpenseType, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.get(ledger_id, id)
+    }
+
+    pub fn list(&self, ledger_id: Uuid) -> Result<Vec<ExpenseType>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.list(ledger_id)
+    }
+
+    pub fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.trash(ledger_id)
+    }
+
+    // spent amount per expense type of the matching entries, rolled up to the parent types
+    pub fn report(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<ExpenseTypeTotal>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let entries = self.expense_entry_read_repo.find(ledger_id, filter)?;
+
+        let mut amounts: HashMap<Uuid, f64> = HashMap::new();
+        for entry in &entries {
+            let amount: f64 = entry.cost_shares().iter().map(|share| share.amount).sum();
+            *amounts.entry(entry.expense_type()).or_default() += amount;
+        }
+
+        Ok(ExpenseTypeHierarchy::new(self.read_repo.list(ledger_id)?).roll_up(&amounts))
     }
 }
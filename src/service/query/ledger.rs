@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::domain::expense_entry::redact_snapshot;
+use crate::domain::ledger::Ledger;
+use crate::domain::ledger_member::{LedgerMember, LedgerRole};
+use crate::domain::outbox::{ChangeEvent, ChangedEntity};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::ledger::{LedgerService, requesting_user};
+
+impl LedgerService {
+    // authorized first, so users cannot tell unknown ledgers from those they may not access
+    pub fn get(&self, id: Uuid) -> Result<Ledger, ApplicationError> {
+        self.authorize(id, LedgerRole::Viewer)?;
+        self.read_repo.get(id)
+    }
+
+    // users only see the ledgers they are a member of
+    pub fn list(&self) -> Result<Vec<Ledger>, ApplicationError> {
+        match requesting_user() {
+            Some(user) => self.read_repo.list_of_member(&user),
+            None => self.read_repo.list(),
+        }
+    }
+
+    pub fn members(&self, ledger_id: Uuid) -> Result<Vec<LedgerMember>, ApplicationError> {
+        self.authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.members(ledger_id)
+    }
+
+    // fails unless the user behind the request holds at least the given role in the ledger
+    pub fn authorize(&self, ledger_id: Uuid, role: LedgerRole) -> Result<(), ApplicationError> {
+        self.authorize_user(ledger_id, requesting_user().as_deref(), role)
+    }
+
+    // the same for a user other than the one behind the request, no user may access every ledger
+    fn authorize_user(
+        &self,
+        ledger_id: Uuid,
+        user: Option<&str>,
+        role: LedgerRole,
+    ) -> Result<(), ApplicationError> {
+        let Some(user) = user else {
+            return Ok(());
+        };
+        match self.read_repo.role(ledger_id, user)? {
+            Some(granted) if granted >= role => Ok(()),
+            _ => Err(ApplicationError {
+                error_type: ApplicationErrorType::Forbidden,
+                message: String::from("Missing permission for this ledger."),
+            }),
+        }
+    }
+
+    // the cost bearers whose entries come back redacted to the user behind the request, those
+    // allowed to see every ledger are shown everything
+    pub fn hidden_cost_bearers(&self, ledger_id: Uuid) -> Result<HashSet<Uuid>, ApplicationError> {
+        self.hidden_cost_bearers_of(ledger_id, requesting_user().as_deref())
+    }
+
+    fn hidden_cost_bearers_of(
+        &self,
+        ledger_id: Uuid,
+        user: Option<&str>,
+    ) -> Result<HashSet<Uuid>, ApplicationError> {
+        match user {
+            Some(user) => Ok(self
+                .read_repo
+                .cost_bearers_hidden_from(ledger_id, user)?
+                .into_iter()
+                .collect()),
+            None => Ok(HashSet::new()),
+        }
+    }
+
+    // a change as the given user may see it, changes are pushed long after the user subscribed,
+    // so a user no longer a member of the ledger is refused
+    pub fn visible_change(
+        &self,
+        user: Option<&str>,
+        change: &ChangeEvent,
+    ) -> Result<ChangeEvent, ApplicationError> {
+        self.authorize_user(change.ledger_id, user, LedgerRole::Viewer)?;
+        let mut change = change.clone();
+        if change.entity == ChangedEntity::ExpenseEntry {
+            let hidden = self.hidden_cost_bearers_of(change.ledger_id, user)?;
+            redact_snapshot(change.data.as_mut(), &hidden);
+        }
+        Ok(change)
+    }
+
+    // the writes check this again in their transaction, checking ahead only tells which
+    // operation of a batch failed
+    pub fn verify_references(
+        &self,
+        ledger_id: Uuid,
+        cost_bearer_ids: &[Uuid],
+        expense_type_ids: &[Uuid],
+    ) -> Result<(), ApplicationError> {
+        self.read_repo
+            .verify_references(ledger_id, cost_bearer_ids, expense_type_ids)
+    }
+}
@@ -1,3 +1,16 @@
+pub mod api_key;
+pub mod attachment;
+pub mod audit;
+pub mod budget;
+pub mod categorisation_rule;
+pub mod change_feed;
 pub mod cost_bearer;
 pub mod expense_entry;
 pub mod expense_type;
+pub mod ledger;
+pub mod outbox;
+pub mod projection;
+pub mod recurring_expense;
+pub mod token;
+pub mod trash;
+pub mod webhook;
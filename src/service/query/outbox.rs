@@ -0,0 +1,12 @@
+use crate::domain::outbox::DeadLetter;
+use crate::service::application_error::ApplicationError;
+use crate::service::ledger::authorize_operator;
+use crate::service::outbox::OutboxService;
+
+impl OutboxService {
+    // the dead letters of every ledger
+    pub fn dead_letters(&self) -> Result<Vec<DeadLetter>, ApplicationError> {
+        authorize_operator()?;
+        self.read_repo.dead_letters()
+    }
+}
@@ -0,0 +1,49 @@
+use uuid::Uuid;
+
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::projection::{CostBearerBalance, ExpenseEntryRow, MonthlyTotal};
+use crate::service::application_error::ApplicationError;
+use crate::service::expense_entry::ExpenseEntryFilter;
+use crate::service::projection::ProjectionService;
+
+impl ProjectionService {
+    pub fn expense_entry_rows(
+        &self,
+        ledger_id: Uuid,
+        filter: &ExpenseEntryFilter,
+    ) -> Result<Vec<ExpenseEntryRow>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let rows = self.read_repo.expense_entry_rows(ledger_id, filter)?;
+        // the totals still include the redacted rows, so the views add up for every member
+        let hidden = self.ledger_service.hidden_cost_bearers(ledger_id)?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                match row
+                    .cost_shares
+                    .iter()
+                    .any(|share| hidden.contains(&share.cost_bearer_id))
+                {
+                    true => row.redact(),
+                    false => row,
+                }
+            })
+            .collect())
+    }
+
+    pub fn monthly_totals(&self, ledger_id: Uuid) -> Result<Vec<MonthlyTotal>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.monthly_totals(ledger_id)
+    }
+
+    pub fn cost_bearer_balances(
+        &self,
+        ledger_id: Uuid,
+    ) -> Result<Vec<CostBearerBalance>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.cost_bearer_balances(ledger_id)
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::recurring_expense::{
+    OccurrenceStatus, RecurringExpense, RecurringExpenseOccurrence,
+};
+use crate::domain::trash::TrashItem;
+use crate::service::application_error::ApplicationError;
+use crate::service::recurring_expense::RecurringExpenseService;
+
+// upper bound for a single preview request
+pub const MAX_PREVIEW_OCCURRENCES: usize = 366;
+
+impl RecurringExpenseService {
+    pub fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<RecurringExpense, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.get(ledger_id, id)
+    }
+
+    pub fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        self.read_repo.trash(ledger_id)
+    }
+
+    pub fn preview(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        from: NaiveDate,
+        count: usize,
+    ) -> Result<Vec<RecurringExpenseOccurrence>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let recurring_expense = self.read_repo.get(ledger_id, id)?;
+        let mut recorded = self.read_repo.occurrences(id)?;
+
+        let occurrences = recurring_expense
+            .schedule()
+            .occurrences_from(from)
+            .take(count.min(MAX_PREVIEW_OCCURRENCES))
+            .map(|occurrence_date| {
+                match recorded
+                    .iter()
+                    .position(|occurrence| occurrence.occurrence_date == occurrence_date)
+                {
+                    Some(index) => recorded.swap_remove(index),
+                    None => RecurringExpenseOccurrence {
+                        occurrence_date,
+                        status: OccurrenceStatus::Pending,
+                        override_template: None,
+                        expense_entry_id: None,
+                    },
+                }
+            })
+            .collect();
+
+        Ok(occurrences)
+    }
+}
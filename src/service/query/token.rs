@@ -0,0 +1,45 @@
+use jsonwebtoken::{Validation, decode, decode_header};
+
+use crate::domain::principal::Principal;
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::token::{TOKEN_ALGORITHMS, TokenService};
+
+// the claims a token needs, the rest is checked by the validation
+#[derive(serde::Deserialize)]
+struct TokenClaims {
+    sub: String,
+}
+
+impl TokenService {
+    // the user the token was issued to, the reason a token is rejected is not given away
+    pub fn authenticate(&self, token: Option<&str>) -> Result<Principal, ApplicationError> {
+        let unauthorized = || ApplicationError {
+            error_type: ApplicationErrorType::Unauthorized,
+            message: String::from("Missing or invalid bearer token."),
+        };
+        // bearer tokens are only accepted once issuer and audience are configured
+        if self.issuer.is_empty() || self.audience.is_empty() {
+            return Err(unauthorized());
+        }
+        let token = token.ok_or_else(unauthorized)?;
+        let header = decode_header(token).map_err(|_| unauthorized())?;
+        if !TOKEN_ALGORITHMS.contains(&header.alg) {
+            return Err(unauthorized());
+        }
+        let key = self
+            .key_repo
+            .decoding_key(header.alg, header.kid.as_deref())
+            .ok_or_else(unauthorized)?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        let claims = decode::<TokenClaims>(token, &key, &validation)
+            .map_err(|_| unauthorized())?
+            .claims;
+        if claims.sub.trim().is_empty() {
+            return Err(unauthorized());
+        }
+        Ok(Principal::user(&claims.sub))
+    }
+}
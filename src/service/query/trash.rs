@@ -0,0 +1,21 @@
+use uuid::Uuid;
+
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::trash::TrashItem;
+use crate::service::application_error::ApplicationError;
+use crate::service::trash::TrashService;
+
+impl TrashService {
+    // oldest deletions first, they are the next to be purged
+    pub fn list(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Viewer)?;
+        let mut items = self.expense_entry_service.trash(ledger_id)?;
+        items.extend(self.cost_bearer_service.trash(ledger_id)?);
+        items.extend(self.expense_type_service.trash(ledger_id)?);
+        items.extend(self.budget_service.trash(ledger_id)?);
+        items.extend(self.recurring_expense_service.trash(ledger_id)?);
+        items.sort_by_key(|item| item.deleted_at);
+        Ok(items)
+    }
+}
@@ -0,0 +1,30 @@
+use uuid::Uuid;
+
+use crate::domain::ledger_member::LedgerRole;
+use crate::domain::webhook::{WebhookDelivery, WebhookSubscription};
+use crate::service::application_error::ApplicationError;
+use crate::service::webhook::WebhookService;
+
+// subscriptions hand the ledger's data to other systems, so only its owners manage them
+impl WebhookService {
+    pub fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<WebhookSubscription, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.read_repo.get(ledger_id, id)
+    }
+
+    pub fn list(&self, ledger_id: Uuid) -> Result<Vec<WebhookSubscription>, ApplicationError> {
+        self.ledger_service
+            .authorize(ledger_id, LedgerRole::Owner)?;
+        self.read_repo.list(ledger_id)
+    }
+
+    pub fn deliveries(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+    ) -> Result<Vec<WebhookDelivery>, ApplicationError> {
+        self.get(ledger_id, id)?;
+        self.read_repo.deliveries(id)
+    }
+}
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::domain::expense_entry::ExpenseEntry;
+use crate::domain::recurring_expense::{
+    ExpenseEntryTemplate, Recurrence, RecurringExpense, RecurringExpenseOccurrence,
+    RecurringExpenseValidationError, Schedule,
+};
+use crate::domain::trash::TrashItem;
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::expense_entry::{ExpenseEntryNew, ExpenseEntryService};
+use crate::service::ledger::LedgerService;
+use crate::service::request_context::RequestContext;
+
+// raw unvalidated user input DTO
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ScheduleNew {
+    #[serde(flatten)]
+    pub recurrence: Recurrence,
+    pub interval: Option<u32>,
+    pub starts_on: NaiveDate,
+    pub ends_on: Option<NaiveDate>,
+}
+
+// raw unvalidated user input DTO, the expense date of the template is taken from the schedule
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct RecurringExpenseNew {
+    pub template: ExpenseEntryNew,
+    pub schedule: ScheduleNew,
+}
+
+impl TryFrom<ExpenseEntryNew> for ExpenseEntryTemplate {
+    type Error = RecurringExpenseValidationError;
+    fn try_from(value: ExpenseEntryNew) -> Result<Self, RecurringExpenseValidationError> {
+        Ok(ExpenseEntryTemplate::new(
+            value.cost_shares,
+            value.expense_type,
+            value.description,
+        )?)
+    }
+}
+
+impl TryFrom<RecurringExpenseNew> for RecurringExpense {
+    type Error = RecurringExpenseValidationError;
+    fn try_from(value: RecurringExpenseNew) -> Result<Self, RecurringExpenseValidationError> {
+        let template = ExpenseEntryTemplate::try_from(value.template)?;
+        let schedule = Schedule::new(
+            value.schedule.recurrence,
+            value.schedule.interval.unwrap_or(1),
+            value.schedule.starts_on,
+            value.schedule.ends_on,
+        )?;
+        Ok(RecurringExpense::new(template, schedule))
+    }
+}
+
+impl From<RecurringExpenseValidationError> for ApplicationError {
+    fn from(err: RecurringExpenseValidationError) -> Self {
+        match err {
+            RecurringExpenseValidationError::MalformedTemplate(err) => ApplicationError::from(err),
+            RecurringExpenseValidationError::ZeroInterval => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid interval.".to_string(),
+            },
+            RecurringExpenseValidationError::InvalidDayOfMonth => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid day of month.".to_string(),
+            },
+            RecurringExpenseValidationError::InvalidDate => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid date.".to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RecurringExpenseService {
+    pub(in crate::service) read_repo: Arc<dyn RecurringExpenseReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn RecurringExpenseWritePort + Send + Sync>,
+    pub(in crate::service) expense_entry_service: Arc<ExpenseEntryService>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+}
+
+impl RecurringExpenseService {
+    pub fn new(
+        read_repo: Arc<dyn RecurringExpenseReadPort + Send + Sync>,
+        write_repo: Arc<dyn RecurringExpenseWritePort + Send + Sync>,
+        expense_entry_service: Arc<ExpenseEntryService>,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        RecurringExpenseService {
+            read_repo,
+            write_repo,
+            expense_entry_service,
+            ledger_service,
+        }
+    }
+}
+
+// books all due occurrences right away and then once every period
+pub fn spawn_recurring_expense_booking(
+    service: Arc<RecurringExpenseService>,
+    period: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let service = service.clone();
+            // the repositories block, keep them off the async workers
+            let booked = tokio::task::spawn_blocking(move || {
+                // one request id for all entries booked by this run
+                RequestContext::system()
+                    .sync_scope(|| service.book_due_occurrences(Utc::now().date_naive()))
+            })
+            .await;
+            if let Ok(Err(err)) = booked {
+                eprintln!("Booking recurring expenses failed: {}", err.message);
+            }
+        }
+    })
+}
+
+pub trait RecurringExpenseReadPort {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<RecurringExpense, ApplicationError>;
+    fn list(&self, ledger_id: Uuid) -> Result<Vec<RecurringExpense>, ApplicationError>;
+    // only occurrences that were skipped, overridden or booked are stored
+    fn occurrences(&self, id: Uuid) -> Result<Vec<RecurringExpenseOccurrence>, ApplicationError>;
+    fn occurrences_from(
+        &self,
+        id: Uuid,
+        from: NaiveDate,
+    ) -> Result<Vec<RecurringExpenseOccurrence>, ApplicationError>;
+    fn last_booked_occurrence(&self, id: Uuid) -> Result<Option<NaiveDate>, ApplicationError>;
+    fn trash(&self, ledger_id: Uuid) -> Result<Vec<TrashItem>, ApplicationError>;
+}
+
+pub trait RecurringExpenseWritePort {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        recurring_expense: RecurringExpense,
+    ) -> Result<RecurringExpense, ApplicationError>;
+    // moves the recurring expense to the trash, nothing is booked for it while it is there
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+    fn restore(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+    // removes recurring expenses of the ledger deleted before the given time for good, returns their ids
+    fn purge(
+        &self,
+        ledger_id: Uuid,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, ApplicationError>;
+    // stores a skip or override, fails if the occurrence has already been booked
+    fn set_occurrence(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        occurrence: RecurringExpenseOccurrence,
+    ) -> Result<(), ApplicationError>;
+    // creates the occurrence's expense entry and marks the occurrence booked in one transaction,
+    // returns false and writes nothing if the occurrence was changed since it was read
+    fn book_occurrence(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        occurrence: &RecurringExpenseOccurrence,
+        entry: &ExpenseEntry,
+    ) -> Result<bool, ApplicationError>;
+}
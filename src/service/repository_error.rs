@@ -1,7 +1,9 @@
+#[allow(dead_code)]
 pub enum RepositoryErrorType {
     NotFound,
     ValidationFailed,
     // this can be extended
 }
 
+#[allow(dead_code)]
 pub struct RepositoryError {}
@@ -0,0 +1,58 @@
+use std::future::Future;
+
+use uuid::Uuid;
+
+use crate::domain::principal::Principal;
+
+tokio::task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+// who triggered the current change and as part of which request, set by the api layer
+#[derive(Clone)]
+pub struct RequestContext {
+    pub actor: String,
+    pub request_id: String,
+    // who authenticated the request, none without authentication and for background jobs
+    pub principal: Option<Principal>,
+}
+
+impl RequestContext {
+    pub fn new(actor: Option<String>, request_id: Option<String>) -> Self {
+        RequestContext {
+            actor: actor.unwrap_or_else(|| String::from("anonymous")),
+            request_id: request_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            principal: None,
+        }
+    }
+
+    // the principal takes the place of the unverified actor
+    pub fn authenticated(self, principal: Principal) -> Self {
+        RequestContext {
+            actor: principal.actor(),
+            principal: Some(principal),
+            ..self
+        }
+    }
+
+    // changes made by background jobs instead of requests
+    pub fn system() -> Self {
+        RequestContext::new(Some(String::from("system")), None)
+    }
+
+    // the context of the request being handled, outside of one everything is done by the system
+    pub fn current() -> Self {
+        REQUEST_CONTEXT
+            .try_with(RequestContext::clone)
+            .unwrap_or_else(|_| RequestContext::system())
+    }
+
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        REQUEST_CONTEXT.scope(self, future).await
+    }
+
+    // for blocking work that runs outside of the request's task
+    pub fn sync_scope<R>(self, f: impl FnOnce() -> R) -> R {
+        REQUEST_CONTEXT.sync_scope(self, f)
+    }
+}
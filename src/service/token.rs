@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use jsonwebtoken::{Algorithm, DecodingKey};
+
+// signature algorithms tokens are accepted with, everything else is rejected before verifying
+pub const TOKEN_ALGORITHMS: [Algorithm; 3] = [Algorithm::HS256, Algorithm::RS256, Algorithm::EdDSA];
+
+// validates the bearer tokens issued by the sso, only tokens for this api from the configured
+// issuer are accepted
+#[derive(Clone)]
+pub struct TokenService {
+    pub(in crate::service) key_repo: Arc<dyn TokenKeyPort + Send + Sync>,
+    pub(in crate::service) issuer: String,
+    pub(in crate::service) audience: String,
+}
+
+impl TokenService {
+    pub fn new(
+        key_repo: Arc<dyn TokenKeyPort + Send + Sync>,
+        issuer: String,
+        audience: String,
+    ) -> Self {
+        TokenService {
+            key_repo,
+            issuer,
+            audience,
+        }
+    }
+}
+
+pub trait TokenKeyPort {
+    // the key tokens signed with the algorithm are verified with, the key id picks one of
+    // several keys
+    fn decoding_key(&self, algorithm: Algorithm, key_id: Option<&str>) -> Option<DecodingKey>;
+}
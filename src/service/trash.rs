@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+
+use crate::service::budget::BudgetService;
+use crate::service::cost_bearer::CostBearerService;
+use crate::service::expense_entry::ExpenseEntryService;
+use crate::service::expense_type::ExpenseTypeService;
+use crate::service::ledger::LedgerService;
+use crate::service::recurring_expense::RecurringExpenseService;
+use crate::service::request_context::RequestContext;
+
+// default time soft deleted entities stay restorable
+pub const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+
+// the trash spans all soft deletable entities, each service keeps its own part of it
+#[derive(Clone)]
+pub struct TrashService {
+    pub(in crate::service) expense_entry_service: Arc<ExpenseEntryService>,
+    pub(in crate::service) cost_bearer_service: Arc<CostBearerService>,
+    pub(in crate::service) expense_type_service: Arc<ExpenseTypeService>,
+    pub(in crate::service) budget_service: Arc<BudgetService>,
+    pub(in crate::service) recurring_expense_service: Arc<RecurringExpenseService>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+}
+
+impl TrashService {
+    pub fn new(
+        expense_entry_service: Arc<ExpenseEntryService>,
+        cost_bearer_service: Arc<CostBearerService>,
+        expense_type_service: Arc<ExpenseTypeService>,
+        budget_service: Arc<BudgetService>,
+        recurring_expense_service: Arc<RecurringExpenseService>,
+        ledger_service: Arc<LedgerService>,
+    ) -> Self {
+        TrashService {
+            expense_entry_service,
+            cost_bearer_service,
+            expense_type_service,
+            budget_service,
+            recurring_expense_service,
+            ledger_service,
+        }
+    }
+}
+
+// purges everything that has been in the trash longer than the retention on every tick
+pub fn spawn_trash_purge(
+    service: Arc<TrashService>,
+    period: Duration,
+    retention: chrono::Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let service = service.clone();
+            // the repositories block, keep them off the async workers
+            let purged = tokio::task::spawn_blocking(move || {
+                RequestContext::system().sync_scope(|| service.purge(Utc::now() - retention))
+            })
+            .await;
+            if let Ok(Err(err)) = purged {
+                eprintln!("Purging the trash failed: {}", err.message);
+            }
+        }
+    })
+}
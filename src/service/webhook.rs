@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::domain::webhook::{WebhookDelivery, WebhookSubscription, WebhookValidationError};
+use crate::service::application_error::{ApplicationError, ApplicationErrorType};
+use crate::service::ledger::LedgerService;
+
+// raw unvalidated user input DTO
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct WebhookSubscriptionNew {
+    pub url: String,
+    pub events: Vec<String>,
+    pub secret: String,
+}
+
+impl From<WebhookValidationError> for ApplicationError {
+    fn from(err: WebhookValidationError) -> Self {
+        match err {
+            WebhookValidationError::InvalidUrl => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid url.".to_string(),
+            },
+            WebhookValidationError::PrivateAddress => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json with url of a private address.".to_string(),
+            },
+            WebhookValidationError::InvalidEvent => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid events.".to_string(),
+            },
+            WebhookValidationError::MissingSecret => ApplicationError {
+                error_type: ApplicationErrorType::ValidationFailed,
+                message: "Json without valid secret.".to_string(),
+            },
+        }
+    }
+}
+
+// posts the published changes of a ledger to the urls subscribed to it, retries are left to the
+// outbox
+#[derive(Clone)]
+pub struct WebhookService {
+    pub(in crate::service) read_repo: Arc<dyn WebhookReadPort + Send + Sync>,
+    pub(in crate::service) write_repo: Arc<dyn WebhookWritePort + Send + Sync>,
+    pub(in crate::service) sender: Arc<dyn WebhookSenderPort + Send + Sync>,
+    pub(in crate::service) ledger_service: Arc<LedgerService>,
+    // whether subscriptions may point at loopback or private addresses, e.g. on a home network
+    pub(in crate::service) private_targets: bool,
+}
+
+impl WebhookService {
+    pub fn new(
+        read_repo: Arc<dyn WebhookReadPort + Send + Sync>,
+        write_repo: Arc<dyn WebhookWritePort + Send + Sync>,
+        sender: Arc<dyn WebhookSenderPort + Send + Sync>,
+        ledger_service: Arc<LedgerService>,
+        private_targets: bool,
+    ) -> Self {
+        WebhookService {
+            read_repo,
+            write_repo,
+            sender,
+            ledger_service,
+            private_targets,
+        }
+    }
+}
+
+pub trait WebhookReadPort {
+    fn get(&self, ledger_id: Uuid, id: Uuid) -> Result<WebhookSubscription, ApplicationError>;
+    fn list(&self, ledger_id: Uuid) -> Result<Vec<WebhookSubscription>, ApplicationError>;
+    // all attempts for the subscription, latest first
+    fn deliveries(&self, subscription_id: Uuid) -> Result<Vec<WebhookDelivery>, ApplicationError>;
+    // attempts made so far for the event and whether one of them succeeded
+    fn attempts(
+        &self,
+        subscription_id: Uuid,
+        event_id: Uuid,
+    ) -> Result<(u32, bool), ApplicationError>;
+}
+
+pub trait WebhookWritePort {
+    fn insert(
+        &self,
+        ledger_id: Uuid,
+        subscription: WebhookSubscription,
+    ) -> Result<WebhookSubscription, ApplicationError>;
+    // removes the subscription together with its delivery log
+    fn delete(&self, ledger_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+    fn log_delivery(&self, delivery: &WebhookDelivery) -> Result<(), ApplicationError>;
+}
+
+pub trait WebhookSenderPort {
+    // posts the body and returns the response status, errors mean no response was received
+    fn send(
+        &self,
+        url: &str,
+        headers: &[(&str, String)],
+        body: Vec<u8>,
+    ) -> Result<u16, ApplicationError>;
+}